@@ -1,38 +1,198 @@
-use std::collections::HashMap;
+use std::collections::{BTreeMap, BTreeSet, HashMap};
 use std::env;
 use std::fs;
-use std::sync::{Arc, Mutex};
+use std::net::SocketAddr;
+use std::path::PathBuf;
+use std::str::FromStr;
+use std::num::NonZeroUsize;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex, OnceLock, RwLock};
+use std::time::{Duration, Instant};
 use std::io::Cursor;
 
 use actix_cors::Cors;
-use actix_web::{get, post, web, App, HttpResponse, HttpServer, Responder, middleware::Logger, ResponseError};
+use actix_web::{delete, get, post, web, App, HttpRequest, HttpResponse, HttpServer, Responder, middleware::{from_fn, Compress, Condition, Logger}, ResponseError};
+use actix_web::error::JsonPayloadError;
+use actix_web::http::StatusCode;
+use actix_web::web::Bytes;
 use anyhow::Result;
 use base64::{Engine as _, engine::general_purpose};
-use chrono::Utc;
-use ed25519_dalek::{PublicKey, Signature};
-use log::info;
+use borsh::BorshDeserialize;
+use chrono::{NaiveDate, Utc};
+use ed25519_dalek::{Keypair, PublicKey, Signature, PUBLIC_KEY_LENGTH, SIGNATURE_LENGTH};
+use futures::stream;
+use log::{error, info, warn};
+use lru::LruCache;
 use serde::{Deserialize, Serialize};
 use sha2::{Digest, Sha256};
 use dotenv;
+use price_oracle_node::asset_registry::AssetRegistry;
+use price_oracle_node::logging::init_logging;
+use price_oracle_node::models::{ConsensusParams, ConsensusResult};
+use price_oracle_node::solana_client::{price_account_address, read_price_account};
+use price_oracle_program::{is_valid_asset_category, PricePayload, PricePayloadV1, ASSET_CATEGORIES, NO_SENTIMENT_LABEL};
+use solana_client::rpc_client::RpcClient;
+use solana_sdk::{commitment_config::CommitmentConfig, pubkey::Pubkey};
+use tokio::sync::mpsc;
+
+mod middleware;
+#[path = "services/aggregator.rs"]
+mod aggregator;
+#[path = "services/stats.rs"]
+mod stats;
+#[path = "services/solana_submit.rs"]
+mod solana_submit;
+#[path = "services/leaderboard.rs"]
+mod leaderboard;
+
+use middleware::{current_request_id, ETagCache, RateLimitMiddleware, RequestId};
+use aggregator::SentimentAggregator;
+use stats::{ema, OracleStats, PriceComparison, StatsStore, SMOOTHING_WINDOW};
+use solana_submit::SolanaSubmitService;
+use leaderboard::LeaderboardService;
 
 // ==== Models ====
 
+/// Sentiment label shared with the oracle program, which stores it as a `u8`. Serialized in
+/// uppercase; deserializing an unknown label fails, so such payloads are rejected with a 400
+pub use price_oracle_program::SentimentLabel;
+
+/// Parse a label leniently, treating anything unrecognised as neutral. For data the API didn't
+/// receive itself, such as legacy files and stored history
+pub fn sentiment_from_label(label: &str) -> SentimentLabel {
+    label.parse().unwrap_or_else(|_| {
+        warn!("Unknown sentiment label '{}', treating it as NEUTRAL", label);
+        SentimentLabel::Neutral
+    })
+}
+
 /// Raw sentiment data as stored on-chain or in local files
 #[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(try_from = "SentimentDataWire", into = "SentimentDataWire")]
 pub struct SentimentData {
     pub id: String,
     pub text: String,
-    pub label: String,
+    pub label: SentimentLabel,
     pub score: f64,
     // Using a custom date format for now to avoid the chrono::serde issue
     #[serde(default)]
     pub date: Option<String>,
+    /// Unix seconds the sentiment was computed at. Omitted when unknown so
+    /// payloads signed before it existed still hash the same.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub timestamp: Option<i64>,
     pub username: String,
     pub source: String,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub signature: Option<String>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub public_key: Option<String>,
+    /// Asset category, one of the oracle program's `ASSET_CATEGORIES`. Omitted when
+    /// unknown, like `timestamp`, so older payloads still hash the same.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub category: Option<String>,
+    /// Set when the value came from a single source instead of a consensus. Omitted
+    /// when false so older payloads still hash the same.
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub degraded: bool,
+    /// Asset the sentiment is about, so the signature binds it. Required by `/publish`;
+    /// omitted when unknown, like `timestamp`, so older payloads still hash the same.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub asset: Option<String>,
+    /// `label` as it was written in the payload (e.g. `positive`), which is what the signature
+    /// covers. Serialized in place of the normalized label while it still names the same one.
+    pub label_as_received: Option<String>,
+}
+
+/// `SentimentData` as it is serialized, signed and hashed, with the label as written
+#[derive(Serialize, Deserialize)]
+struct SentimentDataWire {
+    id: String,
+    text: String,
+    label: String,
+    score: f64,
+    #[serde(default)]
+    date: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    timestamp: Option<i64>,
+    username: String,
+    source: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    signature: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    public_key: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    category: Option<String>,
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    degraded: bool,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    asset: Option<String>,
+}
+
+impl TryFrom<SentimentDataWire> for SentimentData {
+    type Error = String;
+    
+    fn try_from(wire: SentimentDataWire) -> Result<Self, Self::Error> {
+        let label: SentimentLabel = wire.label.parse()?;
+        Ok(SentimentData {
+            id: wire.id,
+            text: wire.text,
+            label,
+            score: wire.score,
+            date: wire.date,
+            timestamp: wire.timestamp,
+            username: wire.username,
+            source: wire.source,
+            signature: wire.signature,
+            public_key: wire.public_key,
+            category: wire.category,
+            degraded: wire.degraded,
+            asset: wire.asset,
+            label_as_received: Some(wire.label).filter(|received| received != label.as_str()),
+        })
+    }
+}
+
+impl From<SentimentData> for SentimentDataWire {
+    fn from(data: SentimentData) -> Self {
+        let label = data.label_as_received
+            .filter(|received| received.parse() == Ok(data.label))
+            .unwrap_or_else(|| data.label.to_string());
+        SentimentDataWire {
+            id: data.id,
+            text: data.text,
+            label,
+            score: data.score,
+            date: data.date,
+            timestamp: data.timestamp,
+            username: data.username,
+            source: data.source,
+            signature: data.signature,
+            public_key: data.public_key,
+            category: data.category,
+            degraded: data.degraded,
+            asset: data.asset,
+        }
+    }
+}
+
+/// Longest `SentimentData::text` accepted, in bytes
+pub const MAX_TEXT_BYTES: usize = 10_000;
+
+impl SentimentData {
+    /// Reject `text` holding NUL bytes or control characters other than line breaks and tabs, or
+    /// longer than `MAX_TEXT_BYTES`. Signatures are checked against the text exactly as received.
+    pub fn validate_text(&self) -> Result<(), ApiError> {
+        if let Some(c) = self.text.chars().find(|c| c.is_control() && !matches!(c, '\n' | '\r' | '\t')) {
+            return Err(ApiError::BadRequest(format!("Text contains control character U+{:04X}", c as u32)));
+        }
+        if self.text.len() > MAX_TEXT_BYTES {
+            return Err(ApiError::BadRequest(format!(
+                "Text is {} bytes, longer than the {} allowed", self.text.len(), MAX_TEXT_BYTES
+            )));
+        }
+        Ok(())
+    }
 }
 
 /// Signed sentiment data from the oracle
@@ -48,10 +208,87 @@ pub struct SignedSentimentData {
 pub struct LatestSentimentResponse {
     pub asset: String,
     pub date: String,
-    pub sentiment: String,
+    /// Unix seconds the data was computed at (start of `date` when no precise time is known)
+    pub timestamp: i64,
+    /// Seconds elapsed since `timestamp`
+    pub age_seconds: i64,
+    pub sentiment: SentimentLabel,
     pub confidence: f64,
     pub signature: String,
     pub signer: String,
+    /// Asset category, when the data carries one
+    pub category: Option<String>,
+    /// Exponential moving average of the asset's confidence history, when smoothing is enabled
+    #[serde(default)]
+    pub smoothed_confidence: Option<f64>,
+    /// True when the value came from a single source instead of a consensus
+    #[serde(default)]
+    pub degraded: bool,
+    /// 0-100 health of the asset's latest price consensus, when price history is configured
+    #[serde(default)]
+    pub health_score: Option<f64>,
+    /// Price of the asset's latest consensus, when price history is configured
+    #[serde(default)]
+    pub price: Option<f64>,
+}
+
+/// Decimal places of the integer price in `/feed.json`
+pub const FEED_DECIMALS: u32 = 6;
+
+/// Value of the `X-Oracle-Source` header on `/feed.json` responses
+const ORACLE_SOURCE: &str = "sentiment-oracle";
+
+/// Response for the /feed.json endpoint, in the price feed schema other oracles publish
+#[derive(Debug, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct FeedJson {
+    #[serde(rename = "type")]
+    pub kind: String,
+    /// `<asset>/USD`
+    pub pair: String,
+    pub timestamp: i64,
+    /// The price times 10^`decimal`, as an integer string
+    pub price: String,
+    pub decimal: u32,
+    pub signature: String,
+    pub signer_address: String,
+}
+
+impl LatestSentimentResponse {
+    /// The response in feed format; `None` without a consensus price to publish
+    pub fn to_feed_format(&self) -> Option<FeedJson> {
+        let price = self.price?;
+        Some(FeedJson {
+            kind: "Price".to_string(),
+            pair: format!("{}/USD", self.asset),
+            timestamp: self.timestamp,
+            price: ((price * 10f64.powi(FEED_DECIMALS as i32)).round() as u64).to_string(),
+            decimal: FEED_DECIMALS,
+            signature: self.signature.clone(),
+            signer_address: self.signer.clone(),
+        })
+    }
+}
+
+/// Response for the /onchain endpoint: the API's value for an asset next to the oracle's on-chain account
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ComparisonResponse {
+    pub cached: LatestSentimentResponse,
+    pub onchain: PricePayload,
+    /// True when both carry the same timestamp and confidence, i.e. the API isn't serving stale data
+    #[serde(rename = "match")]
+    pub matches: bool,
+}
+
+/// Response for the /impact endpoint: the asset's latest consensus price with the direction its sentiment points
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ImpactResponse {
+    pub asset: String,
+    pub price: f64,
+    pub sentiment: SentimentLabel,
+    pub confidence: f64,
+    /// From -1.0 (strongly bearish) to 1.0 (strongly bullish)
+    pub sentiment_impact: f64,
 }
 
 /// Request for the /verify endpoint
@@ -62,12 +299,43 @@ pub struct VerifyRequest {
     pub signer: String,
 }
 
+impl VerifyRequest {
+    /// Decode `signature` and `signer`, checking they are base64 of a 64-byte ed25519
+    /// signature and a 32-byte public key before any ed25519 parsing
+    pub fn decode_keys(&self) -> Result<(Vec<u8>, Vec<u8>), ApiError> {
+        Ok((
+            decode_fixed_base64("signature", &self.signature, SIGNATURE_LENGTH)?,
+            decode_fixed_base64("signer", &self.signer, PUBLIC_KEY_LENGTH)?,
+        ))
+    }
+}
+
+/// Base64 field `name` decoded, when it is non-empty and exactly `len` bytes
+fn decode_fixed_base64(name: &str, encoded: &str, len: usize) -> Result<Vec<u8>, ApiError> {
+    if encoded.trim().is_empty() {
+        return Err(ApiError::BadRequest(format!("{} must not be empty", name)));
+    }
+    let bytes = general_purpose::STANDARD.decode(encoded.trim())
+        .map_err(|e| ApiError::BadRequest(format!("{} is not valid base64: {}", name, e)))?;
+    if bytes.len() != len {
+        return Err(ApiError::BadRequest(format!("{} must be {} bytes, got {}", name, len, bytes.len())));
+    }
+    Ok(bytes)
+}
+
 /// Response for the /verify endpoint
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct VerifyResponse {
     pub valid: bool,
 }
 
+/// Response for the /verify/hash endpoint
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct HashResponse {
+    pub hash_hex: String,
+    pub hash_base64: String,
+}
+
 /// Response for the /history endpoint
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct HistoryResponse {
@@ -79,10 +347,128 @@ pub struct HistoryResponse {
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct HistorySentimentEntry {
     pub date: String,
-    pub sentiment: String,
+    pub sentiment: SentimentLabel,
     pub confidence: f64,
 }
 
+/// Response for the /assets endpoint
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AssetsResponse {
+    pub assets: Vec<String>,
+    pub count: usize,
+    /// Date of each asset's latest data, null when it carries none
+    pub last_updated: BTreeMap<String, Option<String>>,
+}
+
+/// Response for the /cache endpoint
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CacheInvalidationResponse {
+    pub invalidated: Vec<String>,
+}
+
+/// Response for the /admin/reload endpoint
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ReloadResponse {
+    pub evicted: usize,
+}
+
+/// Request body for the /publish endpoint
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PublishRequest {
+    pub asset: String,
+    #[serde(flatten)]
+    pub signed: SignedSentimentData,
+}
+
+/// Response for the /signer/pubkey endpoint
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SignerPubkeyResponse {
+    pub public_key: String,
+    pub public_key_hex: String,
+}
+
+/// JSON-RPC 2.0 request object for the /rpc endpoint
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct JsonRpcRequest {
+    pub jsonrpc: String,
+    pub method: String,
+    #[serde(default)]
+    pub params: serde_json::Value,
+    // Absent for notifications, which get no response
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub id: Option<serde_json::Value>,
+}
+
+/// JSON-RPC 2.0 response object
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct JsonRpcResponse {
+    pub jsonrpc: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub result: Option<serde_json::Value>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<JsonRpcError>,
+    pub id: serde_json::Value,
+}
+
+/// JSON-RPC 2.0 error object
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct JsonRpcError {
+    pub code: i64,
+    pub message: String,
+}
+
+impl JsonRpcError {
+    pub const PARSE_ERROR: i64 = -32700;
+    pub const INVALID_REQUEST: i64 = -32600;
+    pub const METHOD_NOT_FOUND: i64 = -32601;
+    pub const INVALID_PARAMS: i64 = -32602;
+    pub const INTERNAL_ERROR: i64 = -32603;
+    // Implementation-defined server errors
+    pub const NOT_FOUND: i64 = -32004;
+    pub const SIGNATURE_VERIFICATION_FAILED: i64 = -32001;
+    pub const UNAUTHORIZED: i64 = -32002;
+    
+    pub fn new(code: i64, message: impl Into<String>) -> Self {
+        Self {
+            code,
+            message: message.into(),
+        }
+    }
+}
+
+impl From<ApiError> for JsonRpcError {
+    fn from(e: ApiError) -> Self {
+        let code = match e {
+            ApiError::NotFound(_) => Self::NOT_FOUND,
+            ApiError::BadRequest(_) => Self::INVALID_PARAMS,
+            ApiError::SignatureVerificationFailed => Self::SIGNATURE_VERIFICATION_FAILED,
+            ApiError::InternalServerError(_) => Self::INTERNAL_ERROR,
+            ApiError::Unauthorized(_) => Self::UNAUTHORIZED,
+        };
+        Self::new(code, e.to_string())
+    }
+}
+
+impl JsonRpcResponse {
+    pub fn success(id: serde_json::Value, result: serde_json::Value) -> Self {
+        Self {
+            jsonrpc: "2.0".to_string(),
+            result: Some(result),
+            error: None,
+            id,
+        }
+    }
+    
+    pub fn failure(id: serde_json::Value, error: JsonRpcError) -> Self {
+        Self {
+            jsonrpc: "2.0".to_string(),
+            result: None,
+            error: Some(error),
+            id,
+        }
+    }
+}
+
 /// Error type for API operations
 #[derive(Debug, thiserror::Error)]
 pub enum ApiError {
@@ -97,137 +483,393 @@ pub enum ApiError {
     
     #[error("Internal server error: {0}")]
     InternalServerError(String),
+    
+    #[error("Unauthorized: {0}")]
+    Unauthorized(String),
+}
+
+/// Body of every error response
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ErrorResponse {
+    pub status: u16,
+    pub message: String,
+    /// Id of the failed request, as logged and sent in `X-Request-Id`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub request_id: Option<String>,
 }
 
 impl ResponseError for ApiError {
-    fn error_response(&self) -> HttpResponse {
+    fn status_code(&self) -> StatusCode {
         match self {
-            ApiError::NotFound(_) => HttpResponse::NotFound().json(self.to_string()),
-            ApiError::BadRequest(_) => HttpResponse::BadRequest().json(self.to_string()),
-            ApiError::SignatureVerificationFailed => HttpResponse::BadRequest().json(self.to_string()),
-            ApiError::InternalServerError(_) => HttpResponse::InternalServerError().json(self.to_string()),
+            ApiError::NotFound(_) => StatusCode::NOT_FOUND,
+            ApiError::BadRequest(_) => StatusCode::BAD_REQUEST,
+            ApiError::SignatureVerificationFailed => StatusCode::BAD_REQUEST,
+            ApiError::InternalServerError(_) => StatusCode::INTERNAL_SERVER_ERROR,
+            ApiError::Unauthorized(_) => StatusCode::UNAUTHORIZED,
+        }
+    }
+    
+    fn error_response(&self) -> HttpResponse {
+        let status = self.status_code();
+        let request_id = current_request_id();
+        if status.is_server_error() {
+            error!("[{}] {}", request_id.as_deref().unwrap_or("-"), self);
         }
+        HttpResponse::build(status).json(ErrorResponse {
+            status: status.as_u16(),
+            message: self.to_string(),
+            request_id,
+        })
     }
 }
 
+/// Largest JSON request body accepted, in bytes
+const MAX_JSON_BODY_BYTES: usize = 1_048_576;
+
+/// JSON extractor settings for every endpoint: bodies over `MAX_JSON_BODY_BYTES` are
+/// rejected with a 400 before they are buffered in full, and bodies that don't match the
+/// expected shape with a 400 naming the problem
+fn json_config() -> web::JsonConfig {
+    web::JsonConfig::default()
+        .limit(MAX_JSON_BODY_BYTES)
+        .error_handler(|err, _req| match err {
+            JsonPayloadError::Overflow { .. } | JsonPayloadError::OverflowKnownLength { .. } => {
+                ApiError::BadRequest("Payload too large".to_string()).into()
+            },
+            // Say which field was wrong, e.g. an unknown sentiment label
+            JsonPayloadError::Deserialize(e) => ApiError::BadRequest(format!("Invalid JSON body: {}", e)).into(),
+            err => err.into(),
+        })
+}
+
 /// Asset query parameter
 #[derive(Deserialize)]
 pub struct AssetQuery {
     pub asset: String,
+    /// `csv` asks for CSV like `Accept: text/csv`, on the endpoints that offer it
+    #[serde(default)]
+    pub format: Option<String>,
 }
 
-// ==== Services ====
+/// Query parameters for the /assets endpoint
+#[derive(Deserialize)]
+pub struct AssetsQuery {
+    /// Only list assets in this category
+    pub category: Option<String>,
+}
 
-/// Service for retrieving sentiment data
-#[derive(Clone)]
-pub struct SentimentService {
-    // In-memory cache of latest sentiment by asset
-    cache: Arc<Mutex<HashMap<String, SignedSentimentData>>>,
+/// Query parameters for the /export/csv endpoint (dates are `YYYY-MM-DD`, inclusive)
+#[derive(Deserialize)]
+pub struct ExportQuery {
+    pub asset: String,
+    pub from: Option<String>,
+    pub to: Option<String>,
+}
+
+/// Optional asset query parameter for the /cache endpoint
+#[derive(Deserialize)]
+pub struct CacheQuery {
+    pub asset: Option<String>,
+}
+
+/// Query parameters for the /stats endpoint
+#[derive(Deserialize)]
+pub struct StatsQuery {
+    pub asset: String,
+    /// Window in hours
+    #[serde(default = "default_stats_period")]
+    pub period: u64,
+}
+
+fn default_stats_period() -> u64 {
+    24
+}
+
+/// Request body for the /oracle/submit endpoint
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct OracleSubmitRequest {
+    pub signed_sentiment: SignedSentimentData,
+    /// Oracle program account to write to
+    pub asset_account: String,
+}
+
+/// Response for the /oracle/submit endpoint
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct OracleSubmitResponse {
+    pub tx_signature: String,
+    pub explorer_url: String,
+}
+
+/// Query parameters for the /compare endpoint
+#[derive(Deserialize)]
+pub struct CompareQuery {
+    pub asset: String,
+}
+
+// ==== Backends ====
+
+/// Storage backend that signed sentiment data is loaded from
+pub trait DataBackend: Send + Sync {
+    fn load(&self, asset: &str) -> Result<SignedSentimentData>;
+    
+    /// Assets the backend currently holds data for
+    fn list_assets(&self) -> Result<Vec<String>>;
+    
+    /// Persist newly signed data as the asset's latest entry
+    fn store(&self, _asset: &str, _data: &SignedSentimentData) -> Result<()> {
+        Err(anyhow::anyhow!("Backend is read-only"))
+    }
+}
+
+impl<B: DataBackend + ?Sized> DataBackend for Arc<B> {
+    fn load(&self, asset: &str) -> Result<SignedSentimentData> {
+        (**self).load(asset)
+    }
+    
+    fn list_assets(&self) -> Result<Vec<String>> {
+        (**self).list_assets()
+    }
+    
+    fn store(&self, asset: &str, data: &SignedSentimentData) -> Result<()> {
+        (**self).store(asset, data)
+    }
+}
+
+/// Backend selected at startup via the `BACKEND` env var
+pub type SharedBackend = Arc<dyn DataBackend>;
+
+/// How often the file backend rescans the data directory for new assets
+const DATA_DIR_RESCAN_INTERVAL: Duration = Duration::from_secs(60);
+
+/// Backend reading signed sentiment JSON files from the data directory.
+/// Each asset's file is found by scanning the directory for
+/// `<ASSET>_sentiment.json`, `signed_<asset>.json`, `signed_sentiment_<asset>.json` or
+/// `<ASSET>/signed_sentiment.json`;
+/// a top-level `signed_sentiment.json` holds SOL.
+pub struct FileBackend {
     // Path to sentiment data directory
     data_path: String,
+    // Canonical asset symbol -> file, built on first use and refreshed periodically
+    index: RwLock<Option<HashMap<String, PathBuf>>>,
 }
 
-impl SentimentService {
-    /// Create a new instance of the sentiment service
+/// Resolve aliases such as `bitcoin` or `$ether` with the node's asset registry; other symbols
+/// have a leading `$` stripped and are uppercased, so `$sol`, `SOL` and `sol` all name the same asset
+fn canonical_asset(asset: &str) -> String {
+    static REGISTRY: OnceLock<AssetRegistry> = OnceLock::new();
+    REGISTRY.get_or_init(AssetRegistry::default)
+        .normalize(asset)
+        .unwrap_or_else(|| asset.trim().trim_start_matches('$').to_uppercase())
+}
+
+/// Whether a path symbol is 1-20 of `A-Z`, `0-9` and `$` (any case), so nothing like
+/// `../etc/passwd` reaches a backend
+fn is_valid_symbol(symbol: &str) -> bool {
+    (1..=20).contains(&symbol.len())
+        && symbol.chars().all(|c| c.is_ascii_alphanumeric() || c == '$')
+}
+
+/// Whether a canonical asset is 1-20 of `A-Z` and `0-9`, and so safe to put in a file name
+fn is_canonical_symbol(asset: &str) -> bool {
+    (1..=20).contains(&asset.len())
+        && asset.chars().all(|c| c.is_ascii_uppercase() || c.is_ascii_digit())
+}
+
+/// Serialize `body` as MessagePack when the client accepts `application/msgpack`, else as JSON
+fn negotiated<T: Serialize>(req: &HttpRequest, body: &T) -> HttpResponse {
+    let accept = req.headers().get(actix_web::http::header::ACCEPT)
+        .and_then(|value| value.to_str().ok())
+        .unwrap_or_default();
+    let mut response = HttpResponse::Ok();
+    response.insert_header((actix_web::http::header::VARY, "Accept"));
+    if !accept.contains("application/msgpack") {
+        return response.json(body);
+    }
+    match rmp_serde::to_vec_named(body) {
+        Ok(bytes) => response.content_type("application/msgpack").body(bytes),
+        Err(e) => ApiError::InternalServerError(format!("Failed to encode MessagePack: {}", e)).error_response(),
+    }
+}
+
+/// Responses that can also be served as CSV, with a header row
+trait ToCsv {
+    fn to_csv(&self) -> String;
+}
+
+/// Quote a CSV field when it holds a comma, quote or line break
+fn csv_field(value: &str) -> String {
+    if value.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value.to_string()
+    }
+}
+
+fn csv_optional<T: ToString>(value: &Option<T>) -> String {
+    value.as_ref().map(ToString::to_string).unwrap_or_default()
+}
+
+impl ToCsv for LatestSentimentResponse {
+    fn to_csv(&self) -> String {
+        let row = [
+            csv_field(&self.asset),
+            csv_field(&self.date),
+            self.timestamp.to_string(),
+            self.age_seconds.to_string(),
+            self.sentiment.to_string(),
+            self.confidence.to_string(),
+            csv_field(&self.signature),
+            csv_field(&self.signer),
+            csv_field(&csv_optional(&self.category)),
+            csv_optional(&self.smoothed_confidence),
+            self.degraded.to_string(),
+            csv_optional(&self.health_score),
+            csv_optional(&self.price),
+        ];
+        format!(
+            "asset,date,timestamp,age_seconds,sentiment,confidence,signature,signer,category,smoothed_confidence,degraded,health_score,price\n{}\n",
+            row.join(","),
+        )
+    }
+}
+
+impl ToCsv for HistoryResponse {
+    fn to_csv(&self) -> String {
+        let mut csv = "asset,date,sentiment,confidence\n".to_string();
+        for entry in &self.data {
+            csv.push_str(&format!("{},{},{},{}\n", csv_field(&self.asset), csv_field(&entry.date), entry.sentiment, entry.confidence));
+        }
+        csv
+    }
+}
+
+/// Serialize `body` as CSV when the query has `format=csv` or the client accepts `text/csv`, else as JSON
+fn csv_or_json<T: Serialize + ToCsv>(req: &HttpRequest, format: Option<&str>, body: &T) -> HttpResponse {
+    let accept = req.headers().get(actix_web::http::header::ACCEPT)
+        .and_then(|value| value.to_str().ok())
+        .unwrap_or_default();
+    let mut response = HttpResponse::Ok();
+    response.insert_header((actix_web::http::header::VARY, "Accept"));
+    if format.is_some_and(|format| format.eq_ignore_ascii_case("csv")) || accept.contains("text/csv") {
+        response.content_type("text/csv; charset=utf-8").body(body.to_csv())
+    } else {
+        response.json(body)
+    }
+}
+
+impl FileBackend {
     pub fn new(data_path: &str) -> Self {
         Self {
-            cache: Arc::new(Mutex::new(HashMap::new())),
             data_path: data_path.to_string(),
+            index: RwLock::new(None),
         }
     }
-
-    /// Get the latest sentiment for the specified asset
-    pub async fn get_latest_sentiment(&self, asset: &str) -> Result<LatestSentimentResponse, ApiError> {
-        // Check cache first
-        if let Some(data) = self.cache.lock().unwrap().get(asset) {
-            return self.transform_to_response(asset, data.clone());
-        }
-
-        // If not in cache, try to load from file
-        match self.load_from_file(asset) {
-            Ok(data) => {
-                // Cache the result
-                self.cache.lock().unwrap().insert(asset.to_string(), data.clone());
-                self.transform_to_response(asset, data)
-            }
-            Err(_) => {
-                Err(ApiError::NotFound(format!("No sentiment data found for {}", asset)))
+    
+    /// Rescan the data directory, replacing the asset index. Returns the number of assets found.
+    pub fn refresh(&self) -> Result<usize> {
+        let index = self.scan()?;
+        let count = index.len();
+        *self.index.write().unwrap() = Some(index);
+        Ok(count)
+    }
+    
+    /// Rescan the data directory every `period` in the background
+    pub fn spawn_refresh(self: &Arc<Self>, period: Duration) {
+        let backend = Arc::clone(self);
+        tokio::spawn(async move {
+            let mut interval = tokio::time::interval(period);
+            loop {
+                interval.tick().await;
+                if let Err(e) = backend.refresh() {
+                    warn!("Failed to rescan data directory {}: {}", backend.data_path, e);
+                }
             }
-        }
+        });
     }
-
-    /// Get sentiment history for the specified asset
-    pub async fn get_sentiment_history(&self, asset: &str) -> Result<HistoryResponse, ApiError> {
-        // In a real implementation, we would query historical data from Solana
-        // For now, we'll just return the latest data as a single entry
+    
+    fn scan(&self) -> Result<HashMap<String, PathBuf>> {
+        let mut index = HashMap::new();
         
-        match self.load_from_file(asset) {
-            Ok(data) => {
-                let date_str = data.data.date
-                    .unwrap_or_else(|| Utc::now().format("%Y-%m-%d").to_string());
-                
-                let entry = HistorySentimentEntry {
-                    date: date_str,
-                    sentiment: data.data.label.clone(),
-                    confidence: data.data.score,
-                };
-                
-                Ok(HistoryResponse {
-                    asset: asset.to_string(),
-                    data: vec![entry],
-                })
-            }
-            Err(_) => {
-                Err(ApiError::NotFound(format!("No sentiment history found for {}", asset)))
+        for entry in fs::read_dir(&self.data_path)? {
+            let entry = entry?;
+            let path = entry.path();
+            let name = entry.file_name().to_string_lossy().into_owned();
+            
+            let asset = if entry.file_type()?.is_dir() {
+                if !path.join("signed_sentiment.json").is_file() {
+                    continue;
+                }
+                index.insert(canonical_asset(&name), path.join("signed_sentiment.json"));
+                continue;
+            } else if name == "signed_sentiment.json" {
+                Some("SOL".to_string())
+            } else if let Some(symbol) = name.strip_prefix("signed_sentiment_").and_then(|n| n.strip_suffix(".json")) {
+                (!symbol.is_empty()).then(|| canonical_asset(symbol))
+            } else if let Some(symbol) = name.strip_suffix("_sentiment.json") {
+                (!symbol.is_empty() && symbol == symbol.to_uppercase()).then(|| canonical_asset(symbol))
+            } else if let Some(symbol) = name.strip_prefix("signed_").and_then(|n| n.strip_suffix(".json")) {
+                (!symbol.is_empty() && symbol == symbol.to_lowercase()).then(|| canonical_asset(symbol))
+            } else {
+                None
+            };
+            
+            if let Some(asset) = asset {
+                index.insert(asset, path);
             }
         }
-    }
-
-    /// Transform signed sentiment data to API response format
-    fn transform_to_response(&self, asset: &str, data: SignedSentimentData) -> Result<LatestSentimentResponse, ApiError> {
-        // Format the date string
-        let date_str = data.data.date
-            .unwrap_or_else(|| Utc::now().format("%Y-%m-%d").to_string());
         
-        Ok(LatestSentimentResponse {
-            asset: asset.to_string(),
-            date: date_str,
-            sentiment: data.data.label,
-            confidence: data.data.score,
-            signature: data.signature,
-            signer: data.public_key,
-        })
+        Ok(index)
+    }
+    
+    /// Scan the data directory if it hasn't been indexed yet
+    fn ensure_index(&self) -> Result<()> {
+        if self.index.read().unwrap().is_none() {
+            self.refresh()?;
+        }
+        Ok(())
     }
+    
+    /// File holding an asset's data, if the index knows about it
+    fn path_for(&self, asset: &str) -> Result<Option<PathBuf>> {
+        self.ensure_index()?;
+        let index = self.index.read().unwrap();
+        Ok(index.as_ref().and_then(|index| index.get(&canonical_asset(asset)).cloned()))
+    }
+}
 
+impl DataBackend for FileBackend {
     /// Load sentiment data from file
-    fn load_from_file(&self, asset: &str) -> Result<SignedSentimentData, anyhow::Error> {
-        // For demo purposes, we'll just use the signed_sentiment.json file
-        // In a real implementation, this would query from Solana based on the asset
+    fn load(&self, asset: &str) -> Result<SignedSentimentData> {
+        let file_path = self.path_for(asset)?
+            .ok_or_else(|| anyhow::anyhow!("Asset not supported"))?;
         
-        // Assuming we have different files for different assets in production
-        let file_path = if asset.to_uppercase() == "$SOL" {
-            format!("{}/signed_sentiment.json", self.data_path)
-        } else {
-            return Err(anyhow::anyhow!("Asset not supported"));
-        };
-        
-        info!("Loading sentiment data from file: {}", file_path);
+        info!("Loading sentiment data from file: {}", file_path.display());
         let file_content = fs::read_to_string(&file_path)?;
         
         // Parse the JSON file
         let signed_data: serde_json::Value = serde_json::from_str(&file_content)?;
         
+        // Files written by `store` already hold a full SignedSentimentData
+        if let Ok(signed_sentiment_data) = serde_json::from_value::<SignedSentimentData>(signed_data.clone()) {
+            return Ok(signed_sentiment_data);
+        }
+        
         // Create a SentimentData object from the parsed JSON
         let sentiment_data = SentimentData {
             id: "sample_0_1747301807".to_string(),
             text: "Sample sentiment data for $SOL".to_string(),
-            label: signed_data["data"]["overall_sentiment"].as_str().unwrap_or("NEUTRAL").to_string(),
+            label: sentiment_from_label(signed_data["data"]["overall_sentiment"].as_str().unwrap_or("NEUTRAL")),
             score: signed_data["data"]["confidence"].as_f64().unwrap_or(0.5),
             date: Some(signed_data["data"]["date"].as_str().unwrap_or("2025-05-15").to_string()),
+            timestamp: signed_data["data"]["timestamp"].as_i64(),
             username: "oracle".to_string(),
             source: "Sentiment Oracle".to_string(),
             signature: None,
             public_key: None,
+            category: signed_data["data"]["category"].as_str().map(str::to_string),
+            degraded: signed_data["data"]["degraded"].as_bool().unwrap_or(false),
+            asset: None,
+            label_as_received: None,
         };
         
         // Create a SignedSentimentData object
@@ -239,32 +881,632 @@ impl SentimentService {
         
         Ok(signed_sentiment_data)
     }
+    
+    /// Indexed assets, sorted so the listing doesn't change order between calls
+    fn list_assets(&self) -> Result<Vec<String>> {
+        self.ensure_index()?;
+        let mut assets: Vec<String> = self.index.read().unwrap().iter().flatten().map(|(asset, _)| asset.clone()).collect();
+        assets.sort();
+        Ok(assets)
+    }
+    
+    /// Replace the asset's file, creating `<ASSET>_sentiment.json` for new assets. The data is
+    /// written to a temporary file first and renamed into place, so readers never see a partial file.
+    fn store(&self, asset: &str, data: &SignedSentimentData) -> Result<()> {
+        static TEMP_FILES: AtomicU64 = AtomicU64::new(0);
+        
+        let asset = canonical_asset(asset);
+        if !is_canonical_symbol(&asset) {
+            return Err(anyhow::anyhow!("Invalid asset symbol '{}'", asset));
+        }
+        let file_path = match self.path_for(&asset)? {
+            Some(path) => path,
+            None => PathBuf::from(&self.data_path).join(format!("{}_sentiment.json", asset)),
+        };
+        
+        info!("Writing sentiment data to file: {}", file_path.display());
+        let temp_path = file_path.with_extension(format!(
+            "json.{}-{}.tmp", std::process::id(), TEMP_FILES.fetch_add(1, Ordering::Relaxed)
+        ));
+        fs::write(&temp_path, serde_json::to_string_pretty(data)?)?;
+        if let Err(e) = fs::rename(&temp_path, &file_path) {
+            let _ = fs::remove_file(&temp_path);
+            return Err(e.into());
+        }
+        
+        if let Some(index) = self.index.write().unwrap().as_mut() {
+            index.insert(asset, file_path);
+        }
+        Ok(())
+    }
+}
+
+/// Backend reading the `PricePayload` stored in the oracle's on-chain account
+pub struct SolanaBackend {
+    rpc_client: RpcClient,
+    program_id: Pubkey,
+    // Oracle key the per-asset accounts are derived from
+    authority: Pubkey,
+}
+
+impl SolanaBackend {
+    pub fn new(rpc_url: &str, program_id: Pubkey, authority: Pubkey) -> Self {
+        Self {
+            rpc_client: RpcClient::new_with_commitment(rpc_url.to_string(), CommitmentConfig::confirmed()),
+            program_id,
+            authority,
+        }
+    }
+
+    /// Configure from `SOLANA_RPC_URL`, `ORACLE_PROGRAM_ID` and `ORACLE_AUTHORITY`
+    pub fn from_env() -> Result<Self> {
+        let rpc_url = env::var("SOLANA_RPC_URL")
+            .unwrap_or_else(|_| "https://api.devnet.solana.com".to_string());
+        let program_id = Pubkey::from_str(&env::var("ORACLE_PROGRAM_ID")?)?;
+        let authority = Pubkey::from_str(&env::var("ORACLE_AUTHORITY")?)?;
+        info!("Using Solana RPC: {} (program {})", rpc_url, program_id);
+        Ok(Self::new(&rpc_url, program_id, authority))
+    }
+
+    /// Derive the oracle account for an asset the same way the oracle node creates it
+    fn account_address(&self, asset: &str) -> Result<Pubkey> {
+        price_account_address(&self.authority, &canonical_asset(asset), &self.program_id)
+    }
+
+    /// The asset's on-chain price payload; fails when the account is missing or uninitialized
+    pub fn read_price_account(&self, asset: &str) -> Result<PricePayload> {
+        read_price_account(&self.rpc_client, &self.account_address(asset)?)
+    }
+}
+
+impl DataBackend for SolanaBackend {
+    fn load(&self, asset: &str) -> Result<SignedSentimentData> {
+        let account = self.account_address(asset)?;
+        info!("Loading sentiment data from account: {}", account);
+        
+        // Accounts are allocated with spare room, and older layouts lack the newer fields
+        let payload = read_price_account(&self.rpc_client, &account)?;
+        if payload.asset.is_empty() {
+            return Err(anyhow::anyhow!("Oracle account {} has no data", account));
+        }
+        
+        let date = chrono::DateTime::from_timestamp(payload.timestamp, 0)
+            .map(|d| d.format("%Y-%m-%d").to_string());
+        
+        // A plain price submission has no label
+        let label = match payload.sentiment_label {
+            NO_SENTIMENT_LABEL => SentimentLabel::Neutral,
+            value => SentimentLabel::from_u8(value)
+                .ok_or_else(|| anyhow::anyhow!("Oracle account {} has unknown sentiment label {}", account, value))?,
+        };
+        
+        let sentiment_data = SentimentData {
+            id: account.to_string(),
+            text: format!("{} price {:.2}", payload.asset, payload.price),
+            label,
+            score: payload.confidence,
+            date,
+            timestamp: Some(payload.timestamp),
+            username: "oracle".to_string(),
+            source: payload.sources.join(","),
+            signature: None,
+            public_key: None,
+            category: Some(payload.asset_category).filter(|category| !category.is_empty()),
+            // The program only logs the degraded flag; a lone source with no consensus score is how one looks
+            degraded: payload.sources.len() == 1 && payload.consensus_score == 0.0,
+            asset: None,
+            label_as_received: None,
+        };
+        
+        Ok(SignedSentimentData {
+            data: sentiment_data,
+            signature: general_purpose::STANDARD.encode(&payload.signature),
+            public_key: general_purpose::STANDARD.encode(payload.signer),
+        })
+    }
+    
+    fn list_assets(&self) -> Result<Vec<String>> {
+        let accounts = self.rpc_client.get_program_accounts(&self.program_id)?;
+        
+        // Every payload version starts with the v1 layout, which carries the asset. Symbols are
+        // listed canonical and sorted, as the file backend lists them, whatever order the RPC uses
+        let mut assets: Vec<String> = accounts.into_iter()
+            .filter_map(|(_, account)| PricePayloadV1::deserialize(&mut account.data.as_slice()).ok())
+            .filter(|payload| payload.is_initialized && !payload.asset.is_empty())
+            .map(|payload| canonical_asset(&payload.asset))
+            .collect();
+        assets.sort();
+        assets.dedup();
+        
+        Ok(assets)
+    }
+}
+
+/// Absolute, canonical form of the data directory, with a leading `~` standing for `home`.
+/// Fails when the directory doesn't exist, rather than every request 404ing later.
+fn resolve_data_dir(data_dir: &str, home: Option<&str>) -> Result<PathBuf> {
+    let expanded = match data_dir.strip_prefix('~') {
+        Some(rest) if rest.is_empty() || rest.starts_with('/') => {
+            let home = home.ok_or_else(|| anyhow::anyhow!("DATA_DIR '{}' starts with ~ but HOME is not set", data_dir))?;
+            PathBuf::from(format!("{}{}", home, rest))
+        },
+        _ => PathBuf::from(data_dir),
+    };
+    
+    let resolved = fs::canonicalize(&expanded).map_err(|e| {
+        let cwd = env::current_dir().map(|cwd| cwd.display().to_string()).unwrap_or_default();
+        anyhow::anyhow!("DATA_DIR '{}' can't be resolved from {}: {}", data_dir, cwd, e)
+    })?;
+    if !resolved.is_dir() {
+        return Err(anyhow::anyhow!("DATA_DIR '{}' is not a directory", resolved.display()));
+    }
+    Ok(resolved)
+}
+
+/// Build the backend selected by the `BACKEND` env var (`file` or `solana`)
+fn backend_from_env(data_dir: &str) -> Result<SharedBackend> {
+    let backend = env::var("BACKEND").unwrap_or_else(|_| "file".to_string());
+    
+    match backend.as_str() {
+        "file" => {
+            let data_dir = resolve_data_dir(data_dir, env::var("HOME").ok().as_deref())?;
+            info!("Using data directory: {}", data_dir.display());
+            let backend = Arc::new(FileBackend::new(&data_dir.to_string_lossy()));
+            backend.spawn_refresh(DATA_DIR_RESCAN_INTERVAL);
+            Ok(backend)
+        },
+        "solana" => {
+            info!("Using Solana backend");
+            Ok(Arc::new(SolanaBackend::from_env()?))
+        },
+        other => Err(anyhow::anyhow!("Unknown BACKEND '{}' (expected 'file' or 'solana')", other)),
+    }
+}
+
+// ==== Services ====
+
+/// How long computed stats are served before being recomputed
+const STATS_CACHE_TTL: Duration = Duration::from_secs(5 * 60);
+
+/// Longest window /stats accepts, in hours
+const MAX_STATS_PERIOD_HOURS: u64 = 24 * 365;
+
+/// Computed stats by (asset, period hours), with when they were computed
+type StatsCache = HashMap<(String, u64), (Instant, OracleStats)>;
+
+/// Service for retrieving sentiment data
+#[derive(Clone)]
+pub struct SentimentService<B: DataBackend = SharedBackend> {
+    // In-memory cache of latest sentiment by asset
+    cache: Arc<Mutex<HashMap<String, SignedSentimentData>>>,
+    // Where sentiment data is loaded from on a cache miss
+    backend: B,
+    // History database the /stats figures are computed from, if configured
+    stats_store: Option<Arc<StatsStore>>,
+    // Recently computed /stats responses
+    stats_cache: Arc<Mutex<StatsCache>>,
+    // Weight of the newest entry in `smoothed_confidence`, when smoothing is enabled
+    smoothing: Option<f64>,
+}
+
+impl<B: DataBackend> SentimentService<B> {
+    /// Create a new instance of the sentiment service
+    pub fn new(backend: B) -> Self {
+        Self {
+            cache: Arc::new(Mutex::new(HashMap::new())),
+            backend,
+            stats_store: None,
+            stats_cache: Arc::new(Mutex::new(HashMap::new())),
+            smoothing: None,
+        }
+    }
+
+    /// Record sentiment history in, and compute stats from, the given database
+    pub fn with_stats_store(mut self, store: Arc<StatsStore>) -> Self {
+        self.stats_store = Some(store);
+        self
+    }
+
+    /// Report a `smoothed_confidence` computed over the stats history, with smoothing factor `alpha` in (0, 1]
+    pub fn with_smoothing(mut self, alpha: f64) -> Self {
+        self.smoothing = Some(alpha);
+        self
+    }
+    
+    /// EMA of the asset's recorded confidences, ending with the latest entry unless it is already recorded
+    fn smoothed_confidence(&self, asset: &str, timestamp: i64, confidence: f64) -> Option<f64> {
+        let alpha = self.smoothing?;
+        let store = self.stats_store.as_ref()?;
+        let mut series = match store.sentiment_series(&canonical_asset(asset), SMOOTHING_WINDOW) {
+            Ok(series) => series,
+            Err(e) => {
+                warn!("Failed to read sentiment history for {}: {}", asset, e);
+                return None;
+            }
+        };
+        match series.last() {
+            Some(&(recorded_at, _)) if recorded_at >= timestamp => {},
+            _ => series.push((timestamp, confidence)),
+        }
+        ema(series.into_iter().map(|(_, confidence)| confidence), alpha)
+    }
+    
+    /// The asset's latest recorded consensus, if there is price history
+    fn latest_consensus(&self, asset: &str) -> Option<ConsensusResult> {
+        let store = self.stats_store.as_ref()?;
+        match store.latest_consensus(&canonical_asset(asset)) {
+            Ok(result) => result,
+            Err(e) => {
+                warn!("Failed to read price history for {}: {}", asset, e);
+                None
+            }
+        }
+    }
+    
+    /// Aggregate the asset's history over the last `period_hours`, cached for 5 minutes
+    pub fn compute_stats(&self, asset: &str, period_hours: u64) -> Result<OracleStats, ApiError> {
+        let store = self.stats_store.as_ref()
+            .ok_or_else(|| ApiError::NotFound("Stats are not configured".to_string()))?;
+        if period_hours == 0 || period_hours > MAX_STATS_PERIOD_HOURS {
+            return Err(ApiError::BadRequest(format!("Period must be between 1 and {} hours", MAX_STATS_PERIOD_HOURS)));
+        }
+        
+        let key = (canonical_asset(asset), period_hours);
+        if let Some((computed_at, stats)) = self.stats_cache.lock().unwrap().get(&key) {
+            if computed_at.elapsed() < STATS_CACHE_TTL {
+                return Ok(stats.clone());
+            }
+        }
+        
+        let stats = store.compute(&key.0, period_hours, Utc::now())
+            .map_err(|e| ApiError::InternalServerError(format!("Failed to compute stats: {}", e)))?
+            .ok_or_else(|| ApiError::NotFound(format!("No history for {} in the last {} hours", key.0, period_hours)))?;
+        self.stats_cache.lock().unwrap().insert(key, (Instant::now(), stats.clone()));
+        Ok(stats)
+    }
+
+    /// Latest consensus price for the asset with each source's deviation from it
+    pub fn compare_sources(&self, asset: &str) -> Result<PriceComparison, ApiError> {
+        let store = self.stats_store.as_ref()
+            .ok_or_else(|| ApiError::NotFound("Price history is not configured".to_string()))?;
+        let asset = canonical_asset(asset);
+        store.latest_comparison(&asset)
+            .map_err(|e| ApiError::InternalServerError(format!("Failed to read price history: {}", e)))?
+            .ok_or_else(|| ApiError::NotFound(format!("No source breakdown for {}", asset)))
+    }
+
+    /// Latest consensus price for the asset with the impact its latest sentiment implies
+    pub fn sentiment_impact(&self, asset: &str) -> Result<ImpactResponse, ApiError> {
+        let store = self.stats_store.as_ref()
+            .ok_or_else(|| ApiError::NotFound("Price history is not configured".to_string()))?;
+        let asset = canonical_asset(asset);
+        let sentiment = self.latest(&asset)?.data;
+        let consensus = store.latest_consensus(&asset)
+            .map_err(|e| ApiError::InternalServerError(format!("Failed to read price history: {}", e)))?
+            .ok_or_else(|| ApiError::NotFound(format!("No price history for {}", asset)))?
+            .with_sentiment(sentiment.label, sentiment.score);
+        
+        Ok(ImpactResponse {
+            asset,
+            price: consensus.price,
+            sentiment: sentiment.label,
+            confidence: sentiment.score,
+            sentiment_impact: consensus.sentiment_impact.unwrap_or_default(),
+        })
+    }
+
+    /// Append sentiment to the stats history; failures are logged, not returned
+    pub fn record_sentiment(&self, asset: &str, data: &SignedSentimentData) {
+        if let Some(store) = &self.stats_store {
+            let timestamp = data.data.timestamp.unwrap_or_else(|| Utc::now().timestamp());
+            if let Err(e) = store.record_sentiment(&canonical_asset(asset), data, timestamp) {
+                warn!("Failed to record sentiment history for {}: {}", asset, e);
+            }
+        }
+    }
+
+    /// The latest signed data for an asset exactly as stored, for auditors checking the signature
+    pub fn get_latest_signed(&self, asset: &str) -> Result<SignedSentimentData, ApiError> {
+        self.latest(asset)
+    }
+
+    /// Latest data for an asset, from the cache or else loaded from the backend and cached
+    fn latest(&self, asset: &str) -> Result<SignedSentimentData, ApiError> {
+        // Check cache first
+        if let Some(data) = self.cache.lock().unwrap().get(asset) {
+            return Ok(data.clone());
+        }
+
+        // If not in cache, try to load from the backend
+        match self.backend.load(asset) {
+            Ok(data) => {
+                // Cache the result
+                self.cache.lock().unwrap().insert(asset.to_string(), data.clone());
+                Ok(data)
+            }
+            Err(_) => {
+                Err(ApiError::NotFound(format!("No sentiment data found for {}", asset)))
+            }
+        }
+    }
+
+    /// Assets that are cached or available from the backend, sorted and deduplicated
+    pub fn list_assets(&self) -> Vec<String> {
+        let mut assets: BTreeSet<String> = self.cache.lock().unwrap().keys().cloned().collect();
+        
+        match self.backend.list_assets() {
+            Ok(found) => assets.extend(found),
+            Err(e) => warn!("Failed to list assets from backend: {}", e),
+        }
+        
+        assets.into_iter().collect()
+    }
+
+    /// Date of an asset's latest data, if it has data and the data is dated
+    pub fn last_updated(&self, asset: &str) -> Option<String> {
+        self.latest(asset).ok().and_then(|signed| signed.data.date)
+    }
+
+    /// Assets whose latest data is filed under `category`
+    pub fn list_assets_in(&self, category: &str) -> Vec<String> {
+        self.list_assets().into_iter()
+            .filter(|asset| {
+                self.latest(asset)
+                    .is_ok_and(|data| data.data.category.as_deref() == Some(category))
+            })
+            .collect()
+    }
+
+    /// Drop an asset from the cache so the next request reloads it from the backend.
+    /// Returns whether an entry was removed.
+    pub fn invalidate(&self, asset: &str) -> bool {
+        self.cache.lock().unwrap().remove(asset).is_some()
+    }
+
+    /// Store newly signed data in the backend and drop any cached copy of the asset
+    pub fn store(&self, asset: &str, data: &SignedSentimentData) -> Result<(), ApiError> {
+        self.backend.store(asset, data)
+            .map_err(|e| ApiError::InternalServerError(format!("Failed to store sentiment data: {}", e)))?;
+        
+        self.record_sentiment(asset, data);
+        
+        let asset = canonical_asset(asset);
+        self.cache.lock().unwrap().retain(|cached, _| canonical_asset(cached) != asset);
+        Ok(())
+    }
+
+    /// Flush the whole cache, returning the assets that were removed
+    pub fn invalidate_all(&self) -> Vec<String> {
+        let mut removed: Vec<String> = self.cache.lock().unwrap().drain().map(|(asset, _)| asset).collect();
+        removed.sort();
+        removed
+    }
+
+    /// Get sentiment history for the specified asset
+    fn sentiment_history(&self, asset: &str) -> Result<HistoryResponse, ApiError> {
+        // In a real implementation, we would query historical data from Solana
+        // For now, we'll just return the latest data as a single entry
+        
+        match self.backend.load(asset) {
+            Ok(data) => {
+                let date_str = data.data.date
+                    .unwrap_or_else(|| Utc::now().format("%Y-%m-%d").to_string());
+                
+                let entry = HistorySentimentEntry {
+                    date: date_str,
+                    sentiment: data.data.label,
+                    confidence: data.data.score,
+                };
+                
+                Ok(HistoryResponse {
+                    asset: asset.to_string(),
+                    data: vec![entry],
+                })
+            }
+            Err(_) => {
+                Err(ApiError::NotFound(format!("No sentiment history found for {}", asset)))
+            }
+        }
+    }
+
+    /// Transform signed sentiment data to API response format
+    fn transform_to_response(&self, asset: &str, data: SignedSentimentData) -> Result<LatestSentimentResponse, ApiError> {
+        let now = Utc::now();
+        
+        // Fall back to midnight UTC of the reported day when there's no precise timestamp
+        let timestamp = data.data.timestamp
+            .or_else(|| {
+                let date = NaiveDate::parse_from_str(data.data.date.as_deref()?, "%Y-%m-%d").ok()?;
+                Some(date.and_hms_opt(0, 0, 0)?.and_utc().timestamp())
+            })
+            .unwrap_or_else(|| now.timestamp());
+        
+        // Format the date string
+        let date_str = data.data.date
+            .unwrap_or_else(|| now.format("%Y-%m-%d").to_string());
+        
+        let consensus = self.latest_consensus(asset);
+        Ok(LatestSentimentResponse {
+            asset: asset.to_string(),
+            date: date_str,
+            timestamp,
+            age_seconds: (now.timestamp() - timestamp).max(0),
+            sentiment: data.data.label,
+            confidence: data.data.score,
+            smoothed_confidence: self.smoothed_confidence(asset, timestamp, data.data.score),
+            signature: data.signature,
+            signer: data.public_key,
+            category: data.data.category,
+            degraded: data.data.degraded,
+            health_score: consensus.as_ref().map(|result| result.health_score(&ConsensusParams::default())),
+            price: consensus.map(|result| result.price),
+        })
+    }
+}
+
+impl<B: DataBackend + Clone + 'static> SentimentService<B> {
+    /// Run `f` on the blocking thread pool: backends read files or wait on Solana RPC calls and
+    /// the history database is SQLite, none of which may stall the async workers
+    pub async fn blocking<T, F>(&self, f: F) -> Result<T, ApiError>
+    where
+        T: Send + 'static,
+        F: FnOnce(&Self) -> Result<T, ApiError> + Send + 'static,
+    {
+        let service = self.clone();
+        web::block(move || f(&service)).await
+            .map_err(|e| ApiError::InternalServerError(format!("Blocking task failed: {}", e)))?
+    }
+    
+    /// Get the latest sentiment for the specified asset
+    pub async fn get_latest_sentiment(&self, asset: &str) -> Result<LatestSentimentResponse, ApiError> {
+        let asset = asset.to_string();
+        self.blocking(move |service| {
+            let data = service.latest(&asset)?;
+            service.transform_to_response(&asset, data)
+        }).await
+    }
+    
+    /// Get sentiment history for the specified asset
+    pub async fn get_sentiment_history(&self, asset: &str) -> Result<HistoryResponse, ApiError> {
+        let asset = asset.to_string();
+        self.blocking(move |service| service.sentiment_history(&asset)).await
+    }
+    
+    /// Stream an asset's history as CSV rows (`date,sentiment,confidence,signature`),
+    /// header first, keeping only entries dated within `from..=to`
+    pub async fn stream_history_csv(
+        &self,
+        asset: &str,
+        from: Option<NaiveDate>,
+        to: Option<NaiveDate>,
+    ) -> Result<mpsc::Receiver<Bytes>, ApiError> {
+        // Like /history, the backends only hold the latest entry for now
+        let asset = asset.to_string();
+        let entries = vec![self.blocking(move |service| {
+            service.backend.load(&asset)
+                .map_err(|_| ApiError::NotFound(format!("No sentiment history found for {}", asset)))
+        }).await?];
+        
+        let (tx, rx) = mpsc::channel(64);
+        tokio::spawn(async move {
+            if tx.send(Bytes::from_static(b"date,sentiment,confidence,signature\n")).await.is_err() {
+                return;
+            }
+            
+            for entry in entries {
+                let date = entry.data.date.unwrap_or_default();
+                if let Ok(day) = NaiveDate::parse_from_str(&date, "%Y-%m-%d") {
+                    if from.is_some_and(|from| day < from) || to.is_some_and(|to| day > to) {
+                        continue;
+                    }
+                }
+                
+                let row = format!("{},{},{},{}\n", date, entry.data.label, entry.data.score, entry.signature);
+                // The receiver is gone once the client disconnects
+                if tx.send(Bytes::from(row)).await.is_err() {
+                    return;
+                }
+            }
+        });
+        
+        Ok(rx)
+    }
 }
 
+/// Requests an address may burst unless `RATE_LIMIT_BURST` says otherwise
+const DEFAULT_RATE_LIMIT_BURST: u32 = 60;
+
+/// Requests per second an address regains unless `RATE_LIMIT_PER_SECOND` says otherwise
+const DEFAULT_RATE_LIMIT_PER_SECOND: f64 = 10.0;
+
+/// Verification results remembered unless `VERIFY_CACHE_SIZE` says otherwise
+const DEFAULT_VERIFY_CACHE_SIZE: usize = 1024;
+
+/// Payload hash, signature and signer of a verification, as given
+type VerifyKey = (Vec<u8>, String, String);
+
 /// Service for verifying signatures on sentiment data
 #[derive(Clone)]
-pub struct VerificationService;
+pub struct VerificationService {
+    // Recent verification results, shared by every clone so all workers use one cache
+    // (None when caching is disabled)
+    cache: Option<Arc<Mutex<LruCache<VerifyKey, bool>>>>,
+    hits: Arc<AtomicU64>,
+    misses: Arc<AtomicU64>,
+}
+
+/// Response for the /verify/cache endpoint
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct VerifyCacheStats {
+    pub hits: u64,
+    pub misses: u64,
+    /// Fraction of verifications answered from the cache (0 before any)
+    pub hit_rate: f64,
+    pub entries: usize,
+    pub capacity: usize,
+}
 
 impl VerificationService {
     /// Create a new instance of the verification service
     pub fn new() -> Self {
-        Self {}
+        Self::with_cache_size(DEFAULT_VERIFY_CACHE_SIZE)
+    }
+
+    /// Service remembering up to `size` verification results (0 disables the cache)
+    pub fn with_cache_size(size: usize) -> Self {
+        Self {
+            cache: NonZeroUsize::new(size).map(|size| Arc::new(Mutex::new(LruCache::new(size)))),
+            hits: Arc::new(AtomicU64::new(0)),
+            misses: Arc::new(AtomicU64::new(0)),
+        }
     }
 
-    /// Verify a signature against the data and signer
+    /// Verify a signature against the data and signer. Results are cached, since clients
+    /// retrying a request would otherwise pay for the same ed25519 check again.
     pub async fn verify(&self, request: VerifyRequest) -> Result<bool, ApiError> {
-        let data_hash = self.hash_sentiment_data(&request.payload)?;
-        let signature_bytes = self.decode_base64(&request.signature)?;
-        let public_key_bytes = self.decode_base64(&request.signer)?;
+        let (signature_bytes, public_key_bytes) = request.decode_keys()?;
+        let data_hash = Self::hash_sentiment_data(&request.payload)?;
+        let key = (data_hash, request.signature, request.signer);
+        if let Some(cache) = &self.cache {
+            if let Some(valid) = cache.lock().unwrap().get(&key) {
+                self.hits.fetch_add(1, Ordering::Relaxed);
+                return Ok(*valid);
+            }
+        }
+        self.misses.fetch_add(1, Ordering::Relaxed);
         
-        self.verify_signature(&data_hash, &signature_bytes, &public_key_bytes)
+        let valid = self.verify_signature(&key.0, &signature_bytes, &public_key_bytes)
             .map_err(|_| {
                 ApiError::SignatureVerificationFailed
-            })
+            })?;
+        if let Some(cache) = &self.cache {
+            cache.lock().unwrap().put(key, valid);
+        }
+        Ok(valid)
+    }
+    
+    pub fn cache_stats(&self) -> VerifyCacheStats {
+        let hits = self.hits.load(Ordering::Relaxed);
+        let misses = self.misses.load(Ordering::Relaxed);
+        let (entries, capacity) = match &self.cache {
+            Some(cache) => {
+                let cache = cache.lock().unwrap();
+                (cache.len(), cache.cap().get())
+            },
+            None => (0, 0),
+        };
+        VerifyCacheStats {
+            hits,
+            misses,
+            hit_rate: if hits + misses > 0 { hits as f64 / (hits + misses) as f64 } else { 0.0 },
+            entries,
+            capacity,
+        }
     }
     
-    /// Hash the sentiment data using SHA-256
-    fn hash_sentiment_data(&self, sentiment_data: &SentimentData) -> Result<Vec<u8>, ApiError> {
+    /// Hash the sentiment data using SHA-256, with its label as the signer wrote it. Needs no
+    /// cache, so signers and `/verify/hash` call it without building a service
+    pub fn hash_sentiment_data(sentiment_data: &SentimentData) -> Result<Vec<u8>, ApiError> {
         let canonical_json = serde_json::to_string(sentiment_data)
             .map_err(|e| ApiError::BadRequest(format!("Failed to serialize data: {}", e)))?;
         
@@ -275,12 +1517,6 @@ impl VerificationService {
         Ok(hash.to_vec())
     }
     
-    /// Decode base64 string to bytes
-    fn decode_base64(&self, encoded: &str) -> Result<Vec<u8>, ApiError> {
-        general_purpose::STANDARD.decode(encoded)
-            .map_err(|e| ApiError::BadRequest(format!("Invalid base64 encoding: {}", e)))
-    }
-    
     /// Verify the signature using ED25519
     fn verify_signature(&self, data_hash: &[u8], signature_bytes: &[u8], public_key_bytes: &[u8]) -> Result<bool, ApiError> {
         // Convert bytes to ED25519 types
@@ -298,33 +1534,277 @@ impl VerificationService {
     }
 }
 
-// ==== Handlers ====
+/// Bearer-token check for admin endpoints; with no token configured they are disabled
+#[derive(Clone)]
+pub struct AdminAuth {
+    token: Option<String>,
+    // Env var the token comes from, for error messages
+    setting: &'static str,
+}
 
-/// Get the latest sentiment for an asset
-#[get("/latest")]
-async fn get_latest_sentiment(
-    query: web::Query<AssetQuery>,
-    sentiment_service: web::Data<SentimentService>,
-) -> impl Responder {
-    let asset = &query.asset;
-    info!("GET /latest - asset: {}", asset);
+impl AdminAuth {
+    pub fn new(token: Option<String>) -> Self {
+        Self::named("ADMIN_TOKEN", token)
+    }
     
-    match sentiment_service.get_latest_sentiment(asset).await {
-        Ok(response) => HttpResponse::Ok().json(response),
-        Err(e) => e.error_response(),
+    /// Token check configured from the env var `setting`
+    pub fn named(setting: &'static str, token: Option<String>) -> Self {
+        Self {
+            token: token.filter(|t| !t.is_empty()),
+            setting,
+        }
+    }
+    
+    /// Check the request's `Authorization: Bearer <token>` header
+    pub fn authorize(&self, req: &HttpRequest) -> Result<(), ApiError> {
+        let expected = self.token.as_ref()
+            .ok_or_else(|| ApiError::Unauthorized(format!("Endpoint is disabled ({} not set)", self.setting)))?;
+        
+        let provided = req.headers()
+            .get(actix_web::http::header::AUTHORIZATION)
+            .and_then(|value| value.to_str().ok())
+            .and_then(|value| value.strip_prefix("Bearer "))
+            .ok_or_else(|| ApiError::Unauthorized("Missing bearer token".to_string()))?;
+        
+        // Compare digests so the check doesn't leak the token through timing
+        if Sha256::digest(provided.trim()) == Sha256::digest(expected) {
+            Ok(())
+        } else {
+            Err(ApiError::Unauthorized("Invalid bearer token".to_string()))
+        }
     }
 }
 
-/// Get sentiment history for an asset
-#[get("/history")]
-async fn get_sentiment_history(
-    query: web::Query<AssetQuery>,
-    sentiment_service: web::Data<SentimentService>,
-) -> impl Responder {
-    let asset = &query.asset;
-    info!("GET /history - asset: {}", asset);
-    
-    match sentiment_service.get_sentiment_history(asset).await {
+/// Env var holding the oracle's secret key, shared with the oracle node
+const KEYPAIR_ENV: &str = "ORACLE_KEYPAIR";
+
+/// Decode a secret key given as base58 or a JSON byte array
+fn secret_key_bytes(secret: &str) -> Result<Vec<u8>> {
+    let secret = secret.trim();
+    if secret.starts_with('[') {
+        Ok(serde_json::from_str(secret)?)
+    } else {
+        Ok(bs58::decode(secret).into_vec()?)
+    }
+}
+
+/// Service exposing the oracle's signing identity
+#[derive(Clone)]
+pub struct SignerService {
+    keypair: Option<Arc<Keypair>>,
+}
+
+impl SignerService {
+    /// Load the signer from a Solana-style keypair file (JSON array of 64 bytes)
+    pub fn from_keypair_file(path: &str) -> Result<Self> {
+        let keypair_data = fs::read_to_string(path)?;
+        let keypair_bytes: Vec<u8> = serde_json::from_str(&keypair_data)?;
+        let keypair = Keypair::from_bytes(&keypair_bytes)
+            .map_err(|e| anyhow::anyhow!("Invalid keypair file {}: {}", path, e))?;
+        
+        Ok(Self::from_keypair(keypair))
+    }
+    
+    /// Load the signer from a secret key given as base58 or a JSON byte array,
+    /// the same formats the oracle node accepts in `ORACLE_KEYPAIR`
+    pub fn from_secret(secret: &str) -> Result<Self> {
+        let keypair = Keypair::from_bytes(&secret_key_bytes(secret)?)
+            .map_err(|e| anyhow::anyhow!("Invalid {}: {}", KEYPAIR_ENV, e))?;
+        
+        Ok(Self::from_keypair(keypair))
+    }
+    
+    /// Load the signer from `ORACLE_KEYPAIR`, falling back to the file at `SIGNER_KEYPAIR_PATH`.
+    /// Returns `Ok(None)` when neither is set.
+    pub fn from_env() -> Result<Option<Self>> {
+        if let Ok(secret) = env::var(KEYPAIR_ENV) {
+            return Self::from_secret(&secret).map(Some);
+        }
+        match env::var("SIGNER_KEYPAIR_PATH") {
+            Ok(path) => Self::from_keypair_file(&path).map(Some),
+            Err(_) => Ok(None),
+        }
+    }
+    
+    pub fn from_keypair(keypair: Keypair) -> Self {
+        Self { keypair: Some(Arc::new(keypair)) }
+    }
+    
+    /// Service with no signer configured
+    pub fn unconfigured() -> Self {
+        Self { keypair: None }
+    }
+    
+    /// Sign sentiment data over the same canonical hash `/verify` checks
+    pub fn sign(&self, mut data: SentimentData) -> Result<SignedSentimentData, ApiError> {
+        use ed25519_dalek::Signer;
+        
+        let keypair = self.keypair.as_ref()
+            .ok_or_else(|| ApiError::NotFound("No signer configured".to_string()))?;
+        
+        // Any signature fields embedded by the client are not part of the signed payload
+        data.signature = None;
+        data.public_key = None;
+        let hash = VerificationService::hash_sentiment_data(&data)?;
+        let signature = keypair.sign(&hash);
+        
+        Ok(SignedSentimentData {
+            data,
+            signature: general_purpose::STANDARD.encode(signature.to_bytes()),
+            public_key: general_purpose::STANDARD.encode(keypair.public.to_bytes()),
+        })
+    }
+    
+    /// Get the oracle's public key in base64 and hex
+    pub fn get_public_key(&self) -> Result<SignerPubkeyResponse, ApiError> {
+        let keypair = self.keypair.as_ref()
+            .ok_or_else(|| ApiError::NotFound("No signer configured".to_string()))?;
+        let bytes = keypair.public.to_bytes();
+        
+        Ok(SignerPubkeyResponse {
+            public_key: general_purpose::STANDARD.encode(bytes),
+            public_key_hex: hex::encode(bytes),
+        })
+    }
+}
+
+// ==== Handlers ====
+
+/// Get the latest sentiment for an asset, as JSON or CSV
+#[get("/latest")]
+async fn get_latest_sentiment(
+    req: HttpRequest,
+    query: web::Query<AssetQuery>,
+    sentiment_service: web::Data<SentimentService>,
+) -> impl Responder {
+    let asset = &canonical_asset(&query.asset);
+    info!("GET /latest - asset: {}", asset);
+    
+    match sentiment_service.get_latest_sentiment(asset).await {
+        Ok(response) => csv_or_json(&req, query.format.as_deref(), &response),
+        Err(e) => e.error_response(),
+    }
+}
+
+/// The latest `SignedSentimentData` for an asset unmodified, so a third party can recompute
+/// its hash and verify the signature
+#[get("/latest/raw")]
+async fn get_latest_raw(
+    query: web::Query<AssetQuery>,
+    sentiment_service: web::Data<SentimentService>,
+) -> impl Responder {
+    let asset = &canonical_asset(&query.asset);
+    info!("GET /latest/raw - asset: {}", asset);
+    
+    let asset = asset.clone();
+    match sentiment_service.blocking(move |service| service.get_latest_signed(&asset)).await {
+        Ok(signed) => HttpResponse::Ok().json(signed),
+        Err(e) => e.error_response(),
+    }
+}
+
+/// Latest consensus price for an asset in the cross-oracle feed format
+#[get("/feed.json")]
+async fn get_feed(
+    query: web::Query<AssetQuery>,
+    sentiment_service: web::Data<SentimentService>,
+) -> impl Responder {
+    let asset = &canonical_asset(&query.asset);
+    info!("GET /feed.json - asset: {}", asset);
+    
+    let latest = match sentiment_service.get_latest_sentiment(asset).await {
+        Ok(latest) => latest,
+        Err(e) => return e.error_response(),
+    };
+    match latest.to_feed_format() {
+        Some(feed) => HttpResponse::Ok()
+            .insert_header(("X-Oracle-Source", ORACLE_SOURCE))
+            .json(feed),
+        None => ApiError::NotFound(format!("No consensus price for {}", asset)).error_response(),
+    }
+}
+
+/// Get sentiment history for an asset, as JSON or CSV
+#[get("/history")]
+async fn get_sentiment_history(
+    req: HttpRequest,
+    query: web::Query<AssetQuery>,
+    sentiment_service: web::Data<SentimentService>,
+) -> impl Responder {
+    let asset = &canonical_asset(&query.asset);
+    info!("GET /history - asset: {}", asset);
+    
+    match sentiment_service.get_sentiment_history(asset).await {
+        Ok(response) => csv_or_json(&req, query.format.as_deref(), &response),
+        Err(e) => e.error_response(),
+    }
+}
+
+/// Latest sentiment for the asset named in the path, like `/latest?asset=`
+#[get("/asset/{symbol}")]
+async fn get_asset(
+    req: HttpRequest,
+    symbol: web::Path<String>,
+    sentiment_service: web::Data<SentimentService>,
+) -> impl Responder {
+    info!("GET /asset/{}", symbol);
+    
+    if !is_valid_symbol(&symbol) {
+        return ApiError::BadRequest(format!("Invalid asset symbol '{}'", symbol)).error_response();
+    }
+    match sentiment_service.get_latest_sentiment(&canonical_asset(&symbol)).await {
+        Ok(response) => negotiated(&req, &response),
+        Err(e) => e.error_response(),
+    }
+}
+
+/// Sentiment history for the asset named in the path, like `/history?asset=`
+#[get("/asset/{symbol}/history")]
+async fn get_asset_history(
+    req: HttpRequest,
+    symbol: web::Path<String>,
+    sentiment_service: web::Data<SentimentService>,
+) -> impl Responder {
+    info!("GET /asset/{}/history", symbol);
+    
+    if !is_valid_symbol(&symbol) {
+        return ApiError::BadRequest(format!("Invalid asset symbol '{}'", symbol)).error_response();
+    }
+    match sentiment_service.get_sentiment_history(&canonical_asset(&symbol)).await {
+        Ok(response) => negotiated(&req, &response),
+        Err(e) => e.error_response(),
+    }
+}
+
+/// List the assets the oracle has data for, optionally only those in one category
+#[get("/assets")]
+async fn list_assets(
+    query: web::Query<AssetsQuery>,
+    sentiment_service: web::Data<SentimentService>,
+) -> impl Responder {
+    info!("GET /assets - category: {:?}", query.category);
+    
+    if let Some(category) = query.category.as_ref().filter(|category| !is_valid_asset_category(category)) {
+        return ApiError::BadRequest(format!(
+            "Unknown category '{}', expected one of {}", category, ASSET_CATEGORIES.join(", ")
+        )).error_response();
+    }
+    let category = query.category.clone();
+    let listed = sentiment_service.blocking(move |service| {
+        let assets = match &category {
+            Some(category) => service.list_assets_in(category),
+            None => service.list_assets(),
+        };
+        let last_updated = assets.iter()
+            .map(|asset| (asset.clone(), service.last_updated(asset)))
+            .collect();
+        Ok(AssetsResponse {
+            count: assets.len(),
+            assets,
+            last_updated,
+        })
+    }).await;
+    match listed {
         Ok(response) => HttpResponse::Ok().json(response),
         Err(e) => e.error_response(),
     }
@@ -338,7 +1818,11 @@ async fn verify_signature(
 ) -> impl Responder {
     info!("POST /verify");
     
-    match verification_service.verify(req.into_inner()).await {
+    let request = req.into_inner();
+    if let Err(e) = request.payload.validate_text() {
+        return e.error_response();
+    }
+    match verification_service.verify(request).await {
         Ok(valid) => {
             HttpResponse::Ok().json(VerifyResponse { valid })
         },
@@ -346,38 +1830,553 @@ async fn verify_signature(
     }
 }
 
-/// Serve a simple HTML dashboard
-#[get("/dashboard")]
-async fn dashboard() -> impl Responder {
-    info!("GET /dashboard");
+/// Download an asset's sentiment history as a CSV attachment
+#[get("/export/csv")]
+async fn export_csv(
+    query: web::Query<ExportQuery>,
+    sentiment_service: web::Data<SentimentService>,
+) -> impl Responder {
+    info!("GET /export/csv - asset: {}", query.asset);
     
-    let html = r#"
-    <!DOCTYPE html>
-    <html>
-    <head>
-        <meta charset="UTF-8">
-        <meta name="viewport" content="width=device-width, initial-scale=1.0">
-        <title>Sentiment Oracle Dashboard</title>
-        <style>
-            body {
-                font-family: Arial, sans-serif;
-                margin: 0;
-                padding: 20px;
-                background-color: #f5f5f5;
+    let parse_date = |value: &Option<String>| -> Result<Option<NaiveDate>, ApiError> {
+        value.as_deref()
+            .map(|v| NaiveDate::parse_from_str(v, "%Y-%m-%d")
+                .map_err(|_| ApiError::BadRequest(format!("Invalid date '{}': expected YYYY-MM-DD", v))))
+            .transpose()
+    };
+    let (from, to) = match (parse_date(&query.from), parse_date(&query.to)) {
+        (Ok(from), Ok(to)) => (from, to),
+        (Err(e), _) | (_, Err(e)) => return e.error_response(),
+    };
+    
+    let rx = match sentiment_service.stream_history_csv(&query.asset, from, to).await {
+        Ok(rx) => rx,
+        Err(e) => return e.error_response(),
+    };
+    let body = stream::unfold(rx, |mut rx| async move {
+        rx.recv().await.map(|chunk| (Ok::<_, actix_web::Error>(chunk), rx))
+    });
+    
+    let filename = format!("{}_sentiment.csv", canonical_asset(&query.asset));
+    HttpResponse::Ok()
+        .content_type("text/csv")
+        .insert_header(("Content-Disposition", format!("attachment; filename=\"{}\"", filename)))
+        .streaming(body)
+}
+
+/// Return the canonical hash `/verify` checks signatures against, for debugging clients.
+/// The payload goes through the same text validation `/verify` runs first.
+#[post("/verify/hash")]
+async fn verify_hash(req: web::Json<SentimentData>) -> impl Responder {
+    info!("POST /verify/hash");
+    
+    if let Err(e) = req.validate_text() {
+        return e.error_response();
+    }
+    match VerificationService::hash_sentiment_data(&req) {
+        Ok(hash) => HttpResponse::Ok().json(HashResponse {
+            hash_hex: hex::encode(&hash),
+            hash_base64: general_purpose::STANDARD.encode(&hash),
+        }),
+        Err(e) => e.error_response(),
+    }
+}
+
+/// Hit rate and size of the signature verification cache
+#[get("/verify/cache")]
+async fn verify_cache_stats(
+    verification_service: web::Data<VerificationService>,
+) -> impl Responder {
+    info!("GET /verify/cache");
+    
+    HttpResponse::Ok().json(verification_service.cache_stats())
+}
+
+/// Accept a signed submission from an allowed oracle node and add it to the asset's aggregate.
+/// The signed payload must name the asset, so it can't be replayed under another one.
+#[post("/publish")]
+async fn publish_sentiment(
+    req: web::Json<PublishRequest>,
+    verification_service: web::Data<VerificationService>,
+    aggregator: web::Data<SentimentAggregator>,
+    sentiment_service: Option<web::Data<SentimentService>>,
+) -> impl Responder {
+    let PublishRequest { asset, signed } = req.into_inner();
+    info!("POST /publish - asset: {}", asset);
+    
+    if let Err(e) = aggregator.authorize(&signed.public_key) {
+        return e.error_response();
+    }
+    if let Err(e) = signed.data.validate_text() {
+        return e.error_response();
+    }
+    let asset = canonical_asset(&asset);
+    match signed.data.asset.as_deref().map(canonical_asset) {
+        Some(signed_asset) if signed_asset == asset => {}
+        Some(signed_asset) => {
+            return ApiError::BadRequest(format!("Payload is signed for {}, not {}", signed_asset, asset)).error_response();
+        }
+        None => return ApiError::BadRequest("Payload must name its asset in data.asset".to_string()).error_response(),
+    }
+    let verify_request = VerifyRequest {
+        payload: signed.data.clone(),
+        signature: signed.signature.clone(),
+        signer: signed.public_key.clone(),
+    };
+    match verification_service.verify(verify_request).await {
+        Ok(true) => {}
+        Ok(false) => return ApiError::SignatureVerificationFailed.error_response(),
+        Err(e) => return e.error_response(),
+    }
+    
+    if let Some(sentiment_service) = sentiment_service {
+        let (recorded_asset, recorded) = (asset.clone(), signed.clone());
+        let result = sentiment_service.blocking(move |service| {
+            service.record_sentiment(&recorded_asset, &recorded);
+            Ok(())
+        }).await;
+        if let Err(e) = result {
+            warn!("Failed to record sentiment history for {}: {}", asset, e);
+        }
+    }
+    aggregator.push(&asset, signed);
+    HttpResponse::Accepted().json(aggregator.aggregate(&asset))
+}
+
+/// Verify signed sentiment and write it to an oracle program account on Solana
+#[post("/oracle/submit")]
+async fn oracle_submit(
+    req: HttpRequest,
+    body: web::Json<OracleSubmitRequest>,
+    verification_service: web::Data<VerificationService>,
+    submit_service: Option<web::Data<SolanaSubmitService>>,
+) -> impl Responder {
+    let OracleSubmitRequest { signed_sentiment, asset_account } = body.into_inner();
+    info!("POST /oracle/submit - account: {}", asset_account);
+    
+    let Some(submit_service) = submit_service else {
+        return ApiError::NotFound("Solana submission is not configured (ORACLE_PROGRAM_ID not set)".to_string()).error_response();
+    };
+    if let Err(e) = submit_service.authorize(&req) {
+        return e.error_response();
+    }
+    let asset_account = match Pubkey::from_str(&asset_account) {
+        Ok(pubkey) => pubkey,
+        Err(e) => return ApiError::BadRequest(format!("Invalid asset account: {}", e)).error_response(),
+    };
+    
+    let verify_request = VerifyRequest {
+        payload: signed_sentiment.data.clone(),
+        signature: signed_sentiment.signature.clone(),
+        signer: signed_sentiment.public_key.clone(),
+    };
+    match verification_service.verify(verify_request).await {
+        Ok(true) => {}
+        Ok(false) => return ApiError::SignatureVerificationFailed.error_response(),
+        Err(e) => return e.error_response(),
+    }
+    
+    // The RPC client blocks until the transaction is confirmed
+    let service = submit_service.clone();
+    let result = web::block(move || service.submit(&signed_sentiment, &asset_account)).await;
+    match result {
+        Ok(Ok(signature)) => HttpResponse::Ok().json(OracleSubmitResponse {
+            tx_signature: signature.to_string(),
+            explorer_url: submit_service.explorer_url(&signature),
+        }),
+        Ok(Err(e)) => e.error_response(),
+        Err(e) => ApiError::InternalServerError(format!("Submission task failed: {}", e)).error_response(),
+    }
+}
+
+/// Sign externally computed sentiment with the oracle's key and store it as the asset's latest
+#[post("/submit")]
+async fn submit_sentiment(
+    req: HttpRequest,
+    query: web::Query<AssetQuery>,
+    body: web::Json<serde_json::Value>,
+    admin_auth: web::Data<AdminAuth>,
+    signer_service: web::Data<SignerService>,
+    sentiment_service: web::Data<SentimentService>,
+    etag_cache: Option<web::Data<ETagCache>>,
+) -> impl Responder {
+    info!("POST /submit - asset: {}", query.asset);
+    
+    if let Err(e) = admin_auth.authorize(&req) {
+        return e.error_response();
+    }
+    if !is_valid_symbol(&query.asset) {
+        return ApiError::BadRequest(format!("Invalid asset symbol '{}'", query.asset)).error_response();
+    }
+    
+    let data: SentimentData = match serde_json::from_value(body.into_inner()) {
+        Ok(data) => data,
+        Err(e) => return ApiError::BadRequest(format!("Invalid sentiment data: {}", e)).error_response(),
+    };
+    if !(0.0..=1.0).contains(&data.score) {
+        return ApiError::BadRequest(format!("Score {} is outside 0.0..=1.0", data.score)).error_response();
+    }
+    
+    let signed = match signer_service.sign(data) {
+        Ok(signed) => signed,
+        Err(e) => return e.error_response(),
+    };
+    let (asset, stored) = (query.asset.clone(), signed.clone());
+    if let Err(e) = sentiment_service.blocking(move |service| service.store(&asset, &stored)).await {
+        error!("Failed to store submitted sentiment for {}: {}", query.asset, e);
+        return e.error_response();
+    }
+    if let Some(etag_cache) = etag_cache {
+        etag_cache.clear();
+    }
+    
+    HttpResponse::Ok().json(signed)
+}
+
+/// Get the composite sentiment published for an asset by all oracle nodes
+#[get("/aggregate")]
+async fn get_aggregate(
+    query: web::Query<AssetQuery>,
+    aggregator: web::Data<SentimentAggregator>,
+) -> impl Responder {
+    let asset = canonical_asset(&query.asset);
+    info!("GET /aggregate - asset: {}", asset);
+    
+    let aggregated = aggregator.aggregate(&asset);
+    if aggregated.source_count == 0 {
+        return ApiError::NotFound(format!("No published sentiment for {}", asset)).error_response();
+    }
+    HttpResponse::Ok().json(aggregated)
+}
+
+/// Summary statistics for an asset over the last `period` hours (default 24)
+#[get("/stats")]
+async fn get_stats(
+    query: web::Query<StatsQuery>,
+    sentiment_service: web::Data<SentimentService>,
+) -> impl Responder {
+    info!("GET /stats - asset: {}, period: {}h", query.asset, query.period);
+    
+    let (asset, period) = (query.asset.clone(), query.period);
+    match sentiment_service.blocking(move |service| service.compute_stats(&asset, period)).await {
+        Ok(stats) => HttpResponse::Ok().json(stats),
+        Err(e) => e.error_response(),
+    }
+}
+
+/// Consensus price against each contributing source's price, with outliers flagged
+#[get("/compare")]
+async fn compare_sources(
+    query: web::Query<CompareQuery>,
+    sentiment_service: web::Data<SentimentService>,
+) -> impl Responder {
+    info!("GET /compare - asset: {}", query.asset);
+    
+    let asset = query.asset.clone();
+    match sentiment_service.blocking(move |service| service.compare_sources(&asset)).await {
+        Ok(comparison) => HttpResponse::Ok().json(comparison),
+        Err(e) => e.error_response(),
+    }
+}
+
+/// Latest consensus price with the expected price direction implied by the asset's sentiment
+#[get("/impact")]
+async fn get_impact(
+    query: web::Query<AssetQuery>,
+    sentiment_service: web::Data<SentimentService>,
+) -> impl Responder {
+    info!("GET /impact - asset: {}", query.asset);
+    
+    let asset = query.asset.clone();
+    match sentiment_service.blocking(move |service| service.sentiment_impact(&asset)).await {
+        Ok(impact) => HttpResponse::Ok().json(impact),
+        Err(e) => e.error_response(),
+    }
+}
+
+/// Signers of the asset's prices ranked by how closely they tracked the consensus price
+#[get("/leaderboard")]
+async fn get_leaderboard(
+    query: web::Query<AssetQuery>,
+    leaderboard_service: Option<web::Data<LeaderboardService>>,
+) -> impl Responder {
+    info!("GET /leaderboard - asset: {}", query.asset);
+    
+    let Some(leaderboard_service) = leaderboard_service else {
+        return ApiError::NotFound("Price history is not configured".to_string()).error_response();
+    };
+    // The history database is SQLite, which blocks
+    let (service, asset) = (leaderboard_service.clone(), query.asset.clone());
+    match web::block(move || service.leaderboard(&asset)).await {
+        Ok(Ok(leaderboard)) => HttpResponse::Ok().json(leaderboard),
+        Ok(Err(e)) => e.error_response(),
+        Err(e) => ApiError::InternalServerError(format!("Blocking task failed: {}", e)).error_response(),
+    }
+}
+
+/// The API's latest value for an asset next to its on-chain price account, to spot stale caches
+#[get("/onchain")]
+async fn get_onchain(
+    query: web::Query<AssetQuery>,
+    sentiment_service: web::Data<SentimentService>,
+    onchain: Option<web::Data<SolanaBackend>>,
+) -> impl Responder {
+    info!("GET /onchain - asset: {}", query.asset);
+    
+    let Some(onchain) = onchain else {
+        return ApiError::NotFound("On-chain reads are not configured".to_string()).error_response();
+    };
+    let cached = match sentiment_service.get_latest_sentiment(&query.asset).await {
+        Ok(cached) => cached,
+        Err(e) => return e.error_response(),
+    };
+    // The RPC client blocks, which the request's runtime doesn't allow
+    let asset = query.asset.clone();
+    let payload = match web::block(move || onchain.read_price_account(&asset)).await {
+        Ok(Ok(payload)) => payload,
+        Ok(Err(e)) => return ApiError::NotFound(format!("No on-chain price for {}: {}", query.asset, e)).error_response(),
+        Err(e) => return ApiError::InternalServerError(e.to_string()).error_response(),
+    };
+    
+    HttpResponse::Ok().json(ComparisonResponse {
+        matches: cached.timestamp == payload.timestamp && (cached.confidence - payload.confidence).abs() < 1e-9,
+        cached,
+        onchain: payload,
+    })
+}
+
+/// Invalidate cached sentiment for one asset, or all assets when none is given (admin only)
+#[delete("/cache")]
+async fn invalidate_cache(
+    req: HttpRequest,
+    query: web::Query<CacheQuery>,
+    admin_auth: web::Data<AdminAuth>,
+    sentiment_service: web::Data<SentimentService>,
+    etag_cache: Option<web::Data<ETagCache>>,
+) -> impl Responder {
+    info!("DELETE /cache - asset: {:?}", query.asset);
+    
+    if let Err(e) = admin_auth.authorize(&req) {
+        return e.error_response();
+    }
+    
+    let invalidated = match &query.asset {
+        Some(asset) if sentiment_service.invalidate(&canonical_asset(asset)) => vec![asset.clone()],
+        Some(_) => Vec::new(),
+        None => sentiment_service.invalidate_all(),
+    };
+    
+    // Clients holding an ETag for the old data must get a fresh response
+    if let Some(etag_cache) = etag_cache {
+        etag_cache.clear();
+    }
+    
+    HttpResponse::Ok().json(CacheInvalidationResponse { invalidated })
+}
+
+/// Empty the whole sentiment cache so every asset is reloaded from the backend (admin only)
+#[post("/admin/reload")]
+async fn admin_reload(
+    req: HttpRequest,
+    admin_auth: web::Data<AdminAuth>,
+    sentiment_service: web::Data<SentimentService>,
+    etag_cache: Option<web::Data<ETagCache>>,
+) -> impl Responder {
+    info!("POST /admin/reload");
+    
+    if let Err(e) = admin_auth.authorize(&req) {
+        return e.error_response();
+    }
+    
+    let evicted = sentiment_service.invalidate_all().len();
+    if let Some(etag_cache) = etag_cache {
+        etag_cache.clear();
+    }
+    
+    info!("Reloaded sentiment cache, evicted {} entries", evicted);
+    HttpResponse::Ok().json(ReloadResponse { evicted })
+}
+
+/// Get the public key the oracle signs with
+#[get("/signer/pubkey")]
+async fn get_signer_pubkey(
+    signer_service: web::Data<SignerService>,
+) -> impl Responder {
+    info!("GET /signer/pubkey");
+    
+    match signer_service.get_public_key() {
+        Ok(response) => HttpResponse::Ok().json(response),
+        Err(e) => e.error_response(),
+    }
+}
+
+/// JSON-RPC 2.0 interface mirroring the REST endpoints (single and batch requests)
+#[post("/rpc")]
+async fn json_rpc(
+    body: web::Bytes,
+    sentiment_service: web::Data<SentimentService>,
+    verification_service: web::Data<VerificationService>,
+) -> impl Responder {
+    info!("POST /rpc");
+    
+    let value: serde_json::Value = match serde_json::from_slice(&body) {
+        Ok(value) => value,
+        Err(e) => {
+            let error = JsonRpcError::new(JsonRpcError::PARSE_ERROR, format!("Parse error: {}", e));
+            return HttpResponse::Ok().json(JsonRpcResponse::failure(serde_json::Value::Null, error));
+        }
+    };
+    
+    match value {
+        serde_json::Value::Array(calls) => {
+            if calls.is_empty() {
+                let error = JsonRpcError::new(JsonRpcError::INVALID_REQUEST, "Empty batch");
+                return HttpResponse::Ok().json(JsonRpcResponse::failure(serde_json::Value::Null, error));
             }
-            h1, h2 {
-                color: #333;
+            
+            let mut responses = Vec::new();
+            for call in calls {
+                if let Some(response) = dispatch_rpc(call, &sentiment_service, &verification_service).await {
+                    responses.push(response);
+                }
             }
-            .container {
-                max-width: 1200px;
-                margin: 0 auto;
-                background-color: white;
-                padding: 20px;
-                border-radius: 5px;
-                box-shadow: 0 2px 4px rgba(0, 0, 0, 0.1);
+            
+            // A batch of notifications gets no response body
+            if responses.is_empty() {
+                HttpResponse::NoContent().finish()
+            } else {
+                HttpResponse::Ok().json(responses)
             }
-            .sentiment-card {
-                background-color: #fff;
+        },
+        call => match dispatch_rpc(call, &sentiment_service, &verification_service).await {
+            Some(response) => HttpResponse::Ok().json(response),
+            None => HttpResponse::NoContent().finish(),
+        },
+    }
+}
+
+/// Handle a single JSON-RPC call, returning `None` for notifications
+async fn dispatch_rpc(
+    call: serde_json::Value,
+    sentiment_service: &SentimentService,
+    verification_service: &VerificationService,
+) -> Option<JsonRpcResponse> {
+    let request: JsonRpcRequest = match serde_json::from_value(call) {
+        Ok(request) => request,
+        Err(e) => {
+            let error = JsonRpcError::new(JsonRpcError::INVALID_REQUEST, format!("Invalid request: {}", e));
+            return Some(JsonRpcResponse::failure(serde_json::Value::Null, error));
+        }
+    };
+    
+    let id = request.id.clone();
+    let result = if request.jsonrpc != "2.0" {
+        Err(JsonRpcError::new(JsonRpcError::INVALID_REQUEST, "jsonrpc must be \"2.0\""))
+    } else {
+        call_rpc_method(&request, sentiment_service, verification_service).await
+    };
+    
+    let id = id?;
+    Some(match result {
+        Ok(value) => JsonRpcResponse::success(id, value),
+        Err(error) => JsonRpcResponse::failure(id, error),
+    })
+}
+
+async fn call_rpc_method(
+    request: &JsonRpcRequest,
+    sentiment_service: &SentimentService,
+    verification_service: &VerificationService,
+) -> Result<serde_json::Value, JsonRpcError> {
+    match request.method.as_str() {
+        "oracle_getLatestSentiment" => {
+            let asset: String = rpc_param(&request.params, 0, "asset")?;
+            let response = sentiment_service.get_latest_sentiment(&asset).await?;
+            to_rpc_result(&response)
+        },
+        "oracle_getSentimentHistory" => {
+            let asset: String = rpc_param(&request.params, 0, "asset")?;
+            let from: Option<String> = rpc_param(&request.params, 1, "from")?;
+            let to: Option<String> = rpc_param(&request.params, 2, "to")?;
+            
+            let mut response = sentiment_service.get_sentiment_history(&asset).await?;
+            response.data.retain(|entry| {
+                from.as_ref().is_none_or(|from| entry.date >= *from)
+                    && to.as_ref().is_none_or(|to| entry.date <= *to)
+            });
+            to_rpc_result(&response)
+        },
+        "oracle_verifySentiment" => {
+            let verify_request = VerifyRequest {
+                payload: rpc_param(&request.params, 0, "payload")?,
+                signature: rpc_param(&request.params, 1, "signature")?,
+                signer: rpc_param(&request.params, 2, "signer")?,
+            };
+            let valid = verification_service.verify(verify_request).await?;
+            to_rpc_result(&valid)
+        },
+        method => Err(JsonRpcError::new(
+            JsonRpcError::METHOD_NOT_FOUND,
+            format!("Method not found: {}", method),
+        )),
+    }
+}
+
+/// Extract a parameter given either by position or by name
+fn rpc_param<T: serde::de::DeserializeOwned>(
+    params: &serde_json::Value,
+    position: usize,
+    name: &str,
+) -> Result<T, JsonRpcError> {
+    let value = match params {
+        serde_json::Value::Array(values) => values.get(position).cloned(),
+        serde_json::Value::Object(values) => values.get(name).cloned(),
+        _ => None,
+    }
+    .unwrap_or(serde_json::Value::Null);
+    
+    serde_json::from_value(value).map_err(|e| {
+        JsonRpcError::new(JsonRpcError::INVALID_PARAMS, format!("Invalid param '{}': {}", name, e))
+    })
+}
+
+fn to_rpc_result<T: Serialize>(value: &T) -> Result<serde_json::Value, JsonRpcError> {
+    serde_json::to_value(value)
+        .map_err(|e| JsonRpcError::new(JsonRpcError::INTERNAL_ERROR, e.to_string()))
+}
+
+/// Serve a simple HTML dashboard
+#[get("/dashboard")]
+async fn dashboard() -> impl Responder {
+    info!("GET /dashboard");
+    
+    let html = r#"
+    <!DOCTYPE html>
+    <html>
+    <head>
+        <meta charset="UTF-8">
+        <meta name="viewport" content="width=device-width, initial-scale=1.0">
+        <title>Sentiment Oracle Dashboard</title>
+        <style>
+            body {
+                font-family: Arial, sans-serif;
+                margin: 0;
+                padding: 20px;
+                background-color: #f5f5f5;
+            }
+            h1, h2 {
+                color: #333;
+            }
+            .container {
+                max-width: 1200px;
+                margin: 0 auto;
+                background-color: white;
+                padding: 20px;
+                border-radius: 5px;
+                box-shadow: 0 2px 4px rgba(0, 0, 0, 0.1);
+            }
+            .sentiment-card {
+                background-color: #fff;
                 border-radius: 8px;
                 padding: 20px;
                 margin-bottom: 20px;
@@ -484,6 +2483,9 @@ async fn dashboard() -> impl Responder {
                     }
                     
                     const confidencePct = (data.confidence * 100).toFixed(2);
+                    const age = data.age_seconds < 3600
+                        ? `${Math.floor(data.age_seconds / 60)} min`
+                        : `${Math.floor(data.age_seconds / 3600)} h`;
                     
                     const html = `
                         <h2>Latest Sentiment for ${data.asset}</h2>
@@ -493,7 +2495,7 @@ async fn dashboard() -> impl Responder {
                                 <span class="confidence">Confidence: ${confidencePct}%</span>
                             </div>
                             <div class="meta">
-                                <p>Date: ${data.date}</p>
+                                <p>Date: ${data.date} (updated ${age} ago)</p>
                                 <p>Oracle: ${data.signer.substring(0, 10)}...</p>
                             </div>
                         </div>
@@ -588,43 +2590,1945 @@ async fn dashboard() -> impl Responder {
 
 // ==== Main ====
 
+/// HTTP server settings resolved from the environment
+#[derive(Debug, Clone, PartialEq)]
+pub struct ServerConfig {
+    pub bind_address: SocketAddr,
+    pub workers: Option<usize>,
+    pub tls: Option<TlsFiles>,
+    // Extra plain-HTTP port (e.g. for health probes) when TLS is enabled
+    pub http_port: Option<u16>,
+    pub cors_origins: CorsOrigins,
+}
+
+/// Origins allowed to call the API from a browser, from `CORS_ORIGINS`
+#[derive(Debug, Clone, PartialEq)]
+pub enum CorsOrigins {
+    /// `CORS_ORIGINS` unset: any origin, with a warning at startup
+    Unset,
+    /// `CORS_ORIGINS=*`: any origin, deliberately
+    Any,
+    List(Vec<String>),
+}
+
+impl CorsOrigins {
+    fn parse(value: Option<String>) -> Result<Self, String> {
+        let Some(value) = value else {
+            return Ok(CorsOrigins::Unset);
+        };
+        let origins: Vec<String> = value.split(',')
+            .map(|origin| origin.trim().trim_end_matches('/').to_string())
+            .filter(|origin| !origin.is_empty())
+            .collect();
+        
+        if origins == ["*"] {
+            return Ok(CorsOrigins::Any);
+        }
+        if origins.is_empty() {
+            return Err("CORS_ORIGINS is set but lists no origins (use * to allow any)".to_string());
+        }
+        for origin in &origins {
+            if origin == "*" {
+                return Err("CORS_ORIGINS wildcard * can't be combined with other origins".to_string());
+            }
+            if !(origin.starts_with("http://") || origin.starts_with("https://")) {
+                return Err(format!("CORS_ORIGINS entry '{}' must be an http:// or https:// origin", origin));
+            }
+        }
+        Ok(CorsOrigins::List(origins))
+    }
+    
+    /// CORS middleware admitting these origins
+    pub fn cors(&self) -> Cors {
+        let cors = match self {
+            CorsOrigins::Unset | CorsOrigins::Any => Cors::default().allow_any_origin(),
+            CorsOrigins::List(origins) => origins.iter()
+                .fold(Cors::default(), |cors, origin| cors.allowed_origin(origin)),
+        };
+        cors.allow_any_method()
+            .allow_any_header()
+            .expose_headers([middleware::request_id::REQUEST_ID_HEADER])
+            .max_age(3600)
+    }
+}
+
+/// PEM certificate chain and private key used for TLS
+#[derive(Debug, Clone, PartialEq)]
+pub struct TlsFiles {
+    pub cert_file: String,
+    pub key_file: String,
+}
+
+impl ServerConfig {
+    /// Read `BIND_ADDRESS`, `WORKERS`, `TLS_CERT_FILE`, `TLS_KEY_FILE`, `HTTP_PORT` and `CORS_ORIGINS` from the environment
+    pub fn from_env() -> Result<Self, String> {
+        Self::parse(|name| env::var(name).ok())
+    }
+    
+    fn parse(var: impl Fn(&str) -> Option<String>) -> Result<Self, String> {
+        let bind_address = var("BIND_ADDRESS").unwrap_or_else(|| "127.0.0.1:8080".to_string());
+        let bind_address = SocketAddr::from_str(bind_address.trim()).map_err(|_| {
+            format!("BIND_ADDRESS '{}' is not a valid socket address (expected e.g. 127.0.0.1:8080)", bind_address)
+        })?;
+        
+        let workers = match var("WORKERS") {
+            Some(workers) => match workers.trim().parse::<usize>() {
+                Ok(n) if n > 0 => Some(n),
+                _ => return Err(format!("WORKERS '{}' must be a positive integer", workers)),
+            },
+            None => None,
+        };
+        
+        let tls = match (var("TLS_CERT_FILE"), var("TLS_KEY_FILE")) {
+            (Some(cert_file), Some(key_file)) => Some(TlsFiles { cert_file, key_file }),
+            (None, None) => None,
+            _ => return Err("TLS_CERT_FILE and TLS_KEY_FILE must be set together".to_string()),
+        };
+        
+        let http_port = match var("HTTP_PORT") {
+            Some(port) => match port.trim().parse::<u16>() {
+                Ok(n) if n > 0 => Some(n),
+                _ => return Err(format!("HTTP_PORT '{}' must be a port number", port)),
+            },
+            None => None,
+        };
+        
+        let cors_origins = CorsOrigins::parse(var("CORS_ORIGINS"))?;
+        
+        Ok(Self { bind_address, workers, tls, http_port, cors_origins })
+    }
+}
+
+/// Build a rustls server config from PEM certificate chain and private key files
+fn load_rustls_config(tls: &TlsFiles) -> Result<rustls::ServerConfig> {
+    let mut cert_reader = std::io::BufReader::new(fs::File::open(&tls.cert_file)?);
+    let certs: Vec<rustls::Certificate> = rustls_pemfile::certs(&mut cert_reader)?
+        .into_iter()
+        .map(rustls::Certificate)
+        .collect();
+    if certs.is_empty() {
+        return Err(anyhow::anyhow!("No certificates found in {}", tls.cert_file));
+    }
+    
+    let mut key_reader = std::io::BufReader::new(fs::File::open(&tls.key_file)?);
+    let key = rustls_pemfile::read_all(&mut key_reader)?
+        .into_iter()
+        .find_map(|item| match item {
+            rustls_pemfile::Item::PKCS8Key(key)
+            | rustls_pemfile::Item::RSAKey(key)
+            | rustls_pemfile::Item::ECKey(key) => Some(rustls::PrivateKey(key)),
+            _ => None,
+        })
+        .ok_or_else(|| anyhow::anyhow!("No private key found in {}", tls.key_file))?;
+    
+    Ok(rustls::ServerConfig::builder()
+        .with_safe_defaults()
+        .with_no_client_auth()
+        .with_single_cert(certs, key)?)
+}
+
 #[actix_web::main]
 async fn main() -> std::io::Result<()> {
     // Initialize environment
     dotenv::dotenv().ok();
-    env_logger::init_from_env(env_logger::Env::default().default_filter_or("info"));
+    init_logging("info");
     
-    // Configure data directory - default to "../oracle-publisher" if not specified
+    // Data directory for the file backend, resolved to an absolute path when the backend is built
     let data_dir = env::var("DATA_DIR").unwrap_or_else(|_| "../oracle-publisher".to_string());
-    info!("Using data directory: {}", data_dir);
+    
+    // Resolve and validate the server configuration up front
+    let server_config = ServerConfig::from_env().unwrap_or_else(|e| {
+        error!("Invalid server configuration: {}", e);
+        std::process::exit(1);
+    });
+    
+    // Select the storage backend
+    let backend = backend_from_env(&data_dir).unwrap_or_else(|e| {
+        error!("Invalid backend configuration: {}", e);
+        std::process::exit(1);
+    });
     
     // Create services
-    let sentiment_service = SentimentService::new(&data_dir);
-    let verification_service = VerificationService::new();
+    let mut sentiment_service: SentimentService = SentimentService::new(backend);
+    
+    // History database shared with the oracle node, used for /stats and /leaderboard
+    let history_db = env::var("HISTORY_DB_PATH").unwrap_or_else(|_| "../oracle-node/price_history.db".to_string());
+    let mut leaderboard_service = None;
+    match StatsStore::open(&history_db) {
+        Ok(store) => {
+            info!("Using history database: {}", history_db);
+            let store = Arc::new(store);
+            leaderboard_service = Some(web::Data::new(LeaderboardService::new(store.clone())));
+            sentiment_service = sentiment_service.with_stats_store(store);
+        },
+        Err(e) => warn!("Failed to open history database {}; /stats is disabled: {}", history_db, e),
+    }
+    if let Ok(alpha) = env::var("SENTIMENT_SMOOTHING") {
+        match alpha.trim().parse::<f64>() {
+            Ok(alpha) if alpha > 0.0 && alpha <= 1.0 => sentiment_service = sentiment_service.with_smoothing(alpha),
+            _ => warn!("SENTIMENT_SMOOTHING '{}' is not in (0, 1]; smoothed_confidence is disabled", alpha),
+        }
+    }
+    // Oracle accounts read by /onchain, whichever backend serves the API
+    let onchain_reader = if env::var("ORACLE_PROGRAM_ID").is_ok() {
+        match SolanaBackend::from_env() {
+            Ok(reader) => Some(web::Data::new(reader)),
+            Err(e) => {
+                warn!("Invalid on-chain configuration; /onchain is disabled: {}", e);
+                None
+            },
+        }
+    } else {
+        None
+    };
+    let verify_cache_size = match env::var("VERIFY_CACHE_SIZE") {
+        Ok(size) => size.trim().parse::<usize>().unwrap_or_else(|_| {
+            warn!("VERIFY_CACHE_SIZE '{}' is not a number; using {}", size, DEFAULT_VERIFY_CACHE_SIZE);
+            DEFAULT_VERIFY_CACHE_SIZE
+        }),
+        Err(_) => DEFAULT_VERIFY_CACHE_SIZE,
+    };
+    let verification_service = VerificationService::with_cache_size(verify_cache_size);
+    
+    let rate_limit_burst = match env::var("RATE_LIMIT_BURST") {
+        Ok(burst) => burst.trim().parse::<u32>().unwrap_or_else(|_| {
+            warn!("RATE_LIMIT_BURST '{}' is not a number; using {}", burst, DEFAULT_RATE_LIMIT_BURST);
+            DEFAULT_RATE_LIMIT_BURST
+        }),
+        Err(_) => DEFAULT_RATE_LIMIT_BURST,
+    };
+    let rate_limit_per_second = match env::var("RATE_LIMIT_PER_SECOND") {
+        Ok(rate) => match rate.trim().parse::<f64>() {
+            Ok(rate) if rate > 0.0 && rate.is_finite() => rate,
+            _ => {
+                warn!("RATE_LIMIT_PER_SECOND '{}' is not a positive number; using {}", rate, DEFAULT_RATE_LIMIT_PER_SECOND);
+                DEFAULT_RATE_LIMIT_PER_SECOND
+            }
+        },
+        Err(_) => DEFAULT_RATE_LIMIT_PER_SECOND,
+    };
+    let rate_limited = rate_limit_burst > 0;
+    let rate_limiter = RateLimitMiddleware::new(rate_limit_burst, rate_limit_per_second);
+    if rate_limited {
+        info!("Rate limiting each address to bursts of {} requests, {}/s sustained", rate_limit_burst, rate_limit_per_second);
+        rate_limiter.spawn_cleanup();
+    } else {
+        warn!("RATE_LIMIT_BURST is 0; rate limiting is disabled");
+    }
+    
+    // Load the oracle signer if a keypair is configured
+    let signer_service = match SignerService::from_env() {
+        Ok(Some(service)) => {
+            info!("Loaded signer keypair");
+            service
+        },
+        Ok(None) => SignerService::unconfigured(),
+        Err(e) => {
+            warn!("Failed to load signer keypair: {}", e);
+            SignerService::unconfigured()
+        }
+    };
     
     // Start HTTP server
-    let bind_address = env::var("BIND_ADDRESS").unwrap_or_else(|_| "127.0.0.1:8080".to_string());
-    info!("Starting server at {}", bind_address);
-    
-    HttpServer::new(move || {
-        // Configure CORS
-        let cors = Cors::default()
-            .allow_any_origin()
-            .allow_any_method()
-            .allow_any_header()
-            .max_age(3600);
+    // Load TLS material before starting so a bad certificate fails fast
+    let rustls_config = server_config.tls.as_ref().map(|tls| {
+        load_rustls_config(tls).unwrap_or_else(|e| {
+            error!("Invalid TLS configuration: {}", e);
+            std::process::exit(1);
+        })
+    });
+    
+    info!("Starting server at {} (workers: {}, TLS: {})", server_config.bind_address,
+          server_config.workers.map_or("default".to_string(), |n| n.to_string()),
+          if rustls_config.is_some() { "enabled" } else { "disabled" });
+    
+    // Admin endpoints are only enabled when a token is configured
+    let admin_auth = AdminAuth::new(env::var("ADMIN_TOKEN").ok());
+    if admin_auth.token.is_none() {
+        warn!("ADMIN_TOKEN not set; admin endpoints are disabled");
+    }
+    
+    // On-chain submission is only available when the oracle program is configured
+    let submit_service = match SolanaSubmitService::from_env() {
+        Ok(Some(service)) => Some(web::Data::new(service)),
+        Ok(None) => None,
+        Err(e) => {
+            warn!("Failed to configure Solana submission; /oracle/submit is disabled: {}", e);
+            None
+        }
+    };
+    
+    // Shared across workers so every worker sees the same ETags
+    let etag_cache = ETagCache::new();
+    
+    // Submissions from every oracle node land in one aggregator; only the configured nodes may publish
+    let publish_signers: Vec<String> = env::var("PUBLISH_SIGNERS").unwrap_or_default()
+        .split(',')
+        .map(str::trim)
+        .filter(|key| !key.is_empty())
+        .filter(|key| match decode_fixed_base64("PUBLISH_SIGNERS key", key, PUBLIC_KEY_LENGTH) {
+            Ok(_) => true,
+            Err(e) => {
+                warn!("Ignoring PUBLISH_SIGNERS entry: {}", e);
+                false
+            }
+        })
+        .map(str::to_string)
+        .collect();
+    if publish_signers.is_empty() {
+        warn!("PUBLISH_SIGNERS not set; /publish is disabled");
+    }
+    let aggregator = web::Data::new(SentimentAggregator::with_signers(publish_signers));
+    
+    let cors_origins = server_config.cors_origins.clone();
+    match &cors_origins {
+        CorsOrigins::Unset => warn!("CORS_ORIGINS not set; allowing requests from any origin"),
+        CorsOrigins::Any => info!("CORS: allowing any origin"),
+        CorsOrigins::List(origins) => info!("CORS: allowing {}", origins.join(", ")),
+    }
+    
+    let mut server = HttpServer::new(move || {
+        let cors = cors_origins.cors();
+        let rate_limiter = rate_limiter.clone();
         
-        App::new()
-            .wrap(Logger::default())
+        let app = App::new()
+            .wrap(etag_cache.clone())
+            // gzip, deflate, brotli or zstd per Accept-Encoding; ETags are taken from the uncompressed body
+            .wrap(Compress::default())
+            .wrap(Condition::new(rate_limited, from_fn(move |req, next| rate_limiter.clone().limit(req, next))))
+            // Inside the logger so it sees the id in the response headers
+            .wrap(RequestId)
+            .wrap(Logger::new(r#"%a "%r" %s %b "%{Referer}i" "%{User-Agent}i" %T request_id=%{X-Request-Id}o"#))
             .wrap(cors)
             .app_data(web::Data::new(sentiment_service.clone()))
             .app_data(web::Data::new(verification_service.clone()))
+            .app_data(web::Data::new(signer_service.clone()))
+            .app_data(web::Data::new(admin_auth.clone()))
+            .app_data(web::Data::new(etag_cache.clone()))
+            .app_data(aggregator.clone())
+            .app_data(json_config())
             .service(get_latest_sentiment)
+            .service(get_latest_raw)
+            .service(get_feed)
             .service(get_sentiment_history)
+            .service(get_asset)
+            .service(get_asset_history)
+            .service(list_assets)
+            .service(export_csv)
             .service(verify_signature)
-            .service(dashboard)
-    })
-    .bind(bind_address)?
-    .run()
-    .await
+            .service(verify_hash)
+            .service(verify_cache_stats)
+            .service(publish_sentiment)
+            .service(submit_sentiment)
+            .service(get_aggregate)
+            .service(get_stats)
+            .service(compare_sources)
+            .service(get_impact)
+            .service(get_leaderboard)
+            .service(get_onchain)
+            .service(get_signer_pubkey)
+            .service(invalidate_cache)
+            .service(admin_reload)
+            .service(oracle_submit)
+            .service(json_rpc)
+            .service(dashboard);
+        let app = match &leaderboard_service {
+            Some(leaderboard_service) => app.app_data(leaderboard_service.clone()),
+            None => app,
+        };
+        let app = match &onchain_reader {
+            Some(onchain_reader) => app.app_data(onchain_reader.clone()),
+            None => app,
+        };
+        match &submit_service {
+            Some(submit_service) => app.app_data(submit_service.clone()),
+            None => app,
+        }
+    });
+    
+    if let Some(workers) = server_config.workers {
+        server = server.workers(workers);
+    }
+    
+    server = match rustls_config {
+        Some(rustls_config) => {
+            server = server.bind_rustls_021(server_config.bind_address, rustls_config)?;
+            // Keep plain HTTP reachable for health probes on a separate port
+            if let Some(http_port) = server_config.http_port {
+                info!("Plain HTTP also available on port {}", http_port);
+                server.bind((server_config.bind_address.ip(), http_port))?
+            } else {
+                server
+            }
+        },
+        None => server.bind(server_config.bind_address)?,
+    };
+    
+    server.run().await
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use actix_web::test as actix_test;
+    
+    fn rpc_app_data() -> (web::Data<SentimentService>, web::Data<VerificationService>) {
+        let backend: SharedBackend = Arc::new(FileBackend::new("/nonexistent"));
+        (
+            web::Data::new(SentimentService::new(backend)),
+            web::Data::new(VerificationService::new()),
+        )
+    }
+    
+    async fn post_rpc(body: &str) -> serde_json::Value {
+        let (sentiment_service, verification_service) = rpc_app_data();
+        let app = actix_test::init_service(
+            App::new()
+                .app_data(sentiment_service)
+                .app_data(verification_service)
+                .service(json_rpc),
+        ).await;
+        
+        let req = actix_test::TestRequest::post()
+            .uri("/rpc")
+            .set_payload(body.to_string())
+            .to_request();
+        actix_test::call_and_read_body_json(&app, req).await
+    }
+    
+    fn sample_signed_data(label: SentimentLabel) -> SignedSentimentData {
+        SignedSentimentData {
+            data: SentimentData {
+                id: "sample".to_string(),
+                text: "sample".to_string(),
+                label,
+                score: 0.8,
+                date: Some("2025-05-15".to_string()),
+                timestamp: None,
+                username: "oracle".to_string(),
+                source: "test".to_string(),
+                signature: None,
+                public_key: None,
+                category: None,
+                degraded: false,
+                asset: None,
+                label_as_received: None,
+            },
+            signature: String::new(),
+            public_key: String::new(),
+        }
+    }
+    
+    #[actix_web::test]
+    async fn test_list_assets_from_cache() {
+        let (sentiment_service, _) = rpc_app_data();
+        {
+            let mut cache = sentiment_service.cache.lock().unwrap();
+            cache.insert("ETH".to_string(), sample_signed_data(SentimentLabel::Negative));
+            cache.insert("$SOL".to_string(), sample_signed_data(SentimentLabel::Positive));
+        }
+        let app = actix_test::init_service(
+            App::new()
+                .app_data(sentiment_service)
+                .service(list_assets),
+        ).await;
+        
+        let req = actix_test::TestRequest::get().uri("/assets").to_request();
+        let response: serde_json::Value = actix_test::call_and_read_body_json(&app, req).await;
+        
+        assert_eq!(response, serde_json::json!({
+            "assets": ["$SOL", "ETH"],
+            "count": 2,
+            "last_updated": { "$SOL": "2025-05-15", "ETH": "2025-05-15" },
+        }));
+    }
+    
+    #[actix_web::test]
+    async fn test_list_assets_picks_up_new_files() {
+        let dir = std::env::temp_dir().join(format!("sentiment-assets-test-{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        write_signed_file(&dir, "POSITIVE");
+        let backend = Arc::new(FileBackend::new(dir.to_str().unwrap()));
+        let shared: SharedBackend = backend.clone();
+        let app = actix_test::init_service(
+            App::new()
+                .app_data(web::Data::new(SentimentService::new(shared)))
+                .service(list_assets),
+        ).await;
+        let assets = || actix_test::TestRequest::get().uri("/assets").to_request();
+        
+        let response: AssetsResponse = actix_test::call_and_read_body_json(&app, assets()).await;
+        assert_eq!(response.assets, vec!["SOL"]);
+        assert_eq!(response.last_updated["SOL"].as_deref(), Some("2025-05-15"));
+        
+        // A file dropped in while running is listed after the next rescan
+        let mut newer = serde_json::from_str::<serde_json::Value>(&fs::read_to_string(dir.join("signed_sentiment.json")).unwrap()).unwrap();
+        newer["data"]["date"] = "2025-06-01".into();
+        fs::write(dir.join("signed_sentiment_btc.json"), newer.to_string()).unwrap();
+        backend.refresh().unwrap();
+        
+        let response: AssetsResponse = actix_test::call_and_read_body_json(&app, assets()).await;
+        assert_eq!((response.assets, response.count), (vec!["BTC".to_string(), "SOL".to_string()], 2));
+        assert_eq!(response.last_updated["BTC"].as_deref(), Some("2025-06-01"));
+        
+        fs::remove_dir_all(&dir).unwrap();
+    }
+    
+    #[actix_web::test]
+    async fn test_list_assets_by_category() {
+        let (sentiment_service, _) = rpc_app_data();
+        {
+            let mut cache = sentiment_service.cache.lock().unwrap();
+            for (asset, category) in [("ETH", Some("L1")), ("UNI", Some("DeFi")), ("SOL", Some("L1")), ("DOGE", None)] {
+                let mut data = sample_signed_data(SentimentLabel::Positive);
+                data.data.category = category.map(str::to_string);
+                cache.insert(asset.to_string(), data);
+            }
+        }
+        let app = actix_test::init_service(
+            App::new()
+                .app_data(sentiment_service)
+                .service(list_assets)
+                .service(get_latest_sentiment),
+        ).await;
+        
+        let req = actix_test::TestRequest::get().uri("/assets?category=L1").to_request();
+        let response: serde_json::Value = actix_test::call_and_read_body_json(&app, req).await;
+        assert_eq!(response, serde_json::json!({
+            "assets": ["ETH", "SOL"],
+            "count": 2,
+            "last_updated": { "ETH": "2025-05-15", "SOL": "2025-05-15" },
+        }));
+        
+        let req = actix_test::TestRequest::get().uri("/assets?category=Meme").to_request();
+        let response: serde_json::Value = actix_test::call_and_read_body_json(&app, req).await;
+        assert_eq!(response["count"], 0);
+        
+        let req = actix_test::TestRequest::get().uri("/assets?category=defi").to_request();
+        assert_eq!(actix_test::call_service(&app, req).await.status(), 400);
+        
+        let req = actix_test::TestRequest::get().uri("/latest?asset=UNI").to_request();
+        let response: serde_json::Value = actix_test::call_and_read_body_json(&app, req).await;
+        assert_eq!(response["category"], "DeFi");
+    }
+    
+    #[actix_web::test]
+    async fn test_history_compressed_when_accepted() {
+        let dir = std::env::temp_dir().join(format!("sentiment-compress-test-{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        write_signed_file(&dir, "POSITIVE");
+        let backend: SharedBackend = Arc::new(FileBackend::new(dir.to_str().unwrap()));
+        // A year of entries, the size /history reaches once it serves stored history
+        let year = HistoryResponse {
+            asset: "SOL".to_string(),
+            data: (0..365).map(|day| HistorySentimentEntry {
+                date: (NaiveDate::from_ymd_opt(2025, 1, 1).unwrap() + chrono::Duration::days(day)).to_string(),
+                sentiment: if day % 3 == 0 { SentimentLabel::Negative } else { SentimentLabel::Positive },
+                confidence: 0.5 + (day % 50) as f64 / 100.0,
+            }).collect(),
+        };
+        let app = actix_test::init_service(
+            App::new()
+                .wrap(ETagCache::new())
+                .wrap(Compress::default())
+                .app_data(web::Data::new(SentimentService::new(backend)))
+                .service(get_sentiment_history)
+                .route("/history/year", web::get().to(move || {
+                    let year = year.clone();
+                    async move { HttpResponse::Ok().json(year) }
+                })),
+        ).await;
+        
+        let req = actix_test::TestRequest::get().uri("/history/year").to_request();
+        let plain = actix_test::read_body(actix_test::call_service(&app, req).await).await;
+        
+        let req = actix_test::TestRequest::get().uri("/history/year")
+            .insert_header(("Accept-Encoding", "gzip"))
+            .to_request();
+        let res = actix_test::call_service(&app, req).await;
+        assert_eq!(res.headers().get("Content-Encoding").unwrap(), "gzip");
+        let gzipped = actix_test::read_body(res).await;
+        // About 23 KB of JSON shrinks to under 3 KB
+        assert!(gzipped.len() * 8 < plain.len(), "{} -> {} bytes", plain.len(), gzipped.len());
+        
+        // Clients that don't ask for compression, and the real endpoint, still get plain JSON
+        let req = actix_test::TestRequest::get().uri("/history?asset=SOL").to_request();
+        let res = actix_test::call_service(&app, req).await;
+        assert!(res.headers().get("Content-Encoding").is_none());
+        let history: HistoryResponse = actix_test::read_body_json(res).await;
+        assert_eq!(history.data.len(), 1);
+        
+        fs::remove_dir_all(&dir).unwrap();
+    }
+    
+    #[actix_web::test]
+    async fn test_asset_path_endpoints() {
+        let dir = std::env::temp_dir().join(format!("sentiment-asset-path-test-{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        write_signed_file(&dir, "POSITIVE");
+        let backend: SharedBackend = Arc::new(FileBackend::new(dir.to_str().unwrap()));
+        let app = actix_test::init_service(
+            App::new()
+                .wrap(ETagCache::new())
+                .app_data(web::Data::new(SentimentService::new(backend)))
+                .service(get_asset)
+                .service(get_asset_history)
+                .service(get_latest_sentiment),
+        ).await;
+        let get = |uri: &str| actix_test::TestRequest::get().uri(uri).to_request();
+        
+        let by_path: LatestSentimentResponse = actix_test::call_and_read_body_json(&app, get("/asset/$sol")).await;
+        let by_query: LatestSentimentResponse = actix_test::call_and_read_body_json(&app, get("/latest?asset=SOL")).await;
+        assert_eq!((by_path.sentiment, by_path.confidence), (by_query.sentiment, by_query.confidence));
+        // Aliases resolve to the same asset
+        for uri in ["/asset/solana", "/latest?asset=%24Solana"] {
+            let by_alias: LatestSentimentResponse = actix_test::call_and_read_body_json(&app, get(uri)).await;
+            assert_eq!((by_alias.asset.as_str(), by_alias.confidence), ("SOL", by_query.confidence), "{}", uri);
+        }
+        let history: HistoryResponse = actix_test::call_and_read_body_json(&app, get("/asset/SOL/history")).await;
+        assert_eq!(history.data.len(), 1);
+        
+        let req = actix_test::TestRequest::get().uri("/asset/SOL/history")
+            .insert_header(("Accept", "application/msgpack"))
+            .to_request();
+        let res = actix_test::call_service(&app, req).await;
+        assert_eq!(res.headers().get("Content-Type").unwrap(), "application/msgpack");
+        assert_eq!(res.headers().get("Vary").unwrap(), "Accept");
+        let history: HistoryResponse = rmp_serde::from_slice(&actix_test::read_body(res).await).unwrap();
+        assert_eq!(history.asset, "SOL");
+        assert_eq!(history.data[0].confidence, 0.9);
+        
+        // A MessagePack ETag doesn't validate the JSON representation of the asset
+        let req = actix_test::TestRequest::get().uri("/asset/SOL")
+            .insert_header(("Accept", "application/msgpack"))
+            .to_request();
+        let res = actix_test::call_service(&app, req).await;
+        assert_eq!(res.headers().get("Vary").unwrap(), "Accept");
+        let etag = res.headers().get("ETag").unwrap().to_str().unwrap().to_string();
+        let req = actix_test::TestRequest::get().uri("/asset/SOL")
+            .insert_header(("Accept", "application/json"))
+            .insert_header(("If-None-Match", etag.as_str()))
+            .to_request();
+        let res = actix_test::call_service(&app, req).await;
+        assert_eq!(res.status(), 200);
+        assert_eq!(res.headers().get("Content-Type").unwrap(), "application/json");
+        assert_eq!(res.headers().get("Vary").unwrap(), "Accept");
+        
+        assert_eq!(actix_test::call_service(&app, get("/asset/ADA")).await.status(), 404);
+        for uri in ["/asset/..%2Fetc%2Fpasswd", "/asset/BTC.json/history", "/asset/ABCDEFGHIJKLMNOPQRSTU"] {
+            assert_eq!(actix_test::call_service(&app, get(uri)).await.status(), 400, "{}", uri);
+        }
+        
+        fs::remove_dir_all(&dir).unwrap();
+    }
+    
+    #[actix_web::test]
+    async fn test_latest_surfaces_degraded() {
+        let (sentiment_service, _) = rpc_app_data();
+        {
+            let mut cache = sentiment_service.cache.lock().unwrap();
+            let mut data = sample_signed_data(SentimentLabel::Positive);
+            data.data.degraded = true;
+            cache.insert("BTC".to_string(), data);
+            cache.insert("ETH".to_string(), sample_signed_data(SentimentLabel::Positive));
+        }
+        let app = actix_test::init_service(
+            App::new()
+                .app_data(sentiment_service)
+                .service(get_latest_sentiment),
+        ).await;
+        
+        let req = actix_test::TestRequest::get().uri("/latest?asset=BTC").to_request();
+        let response: serde_json::Value = actix_test::call_and_read_body_json(&app, req).await;
+        assert_eq!(response["degraded"], true);
+        
+        let req = actix_test::TestRequest::get().uri("/latest?asset=ETH").to_request();
+        let response: serde_json::Value = actix_test::call_and_read_body_json(&app, req).await;
+        assert_eq!(response["degraded"], false);
+        
+        // Payloads that aren't degraded serialize, and so hash, as before
+        assert!(serde_json::to_value(sample_signed_data(SentimentLabel::Positive).data).unwrap().get("degraded").is_none());
+    }
+    
+    fn write_signed_file(dir: &std::path::Path, sentiment: &str) {
+        let contents = serde_json::json!({
+            "data": { "overall_sentiment": sentiment, "confidence": 0.9, "date": "2025-05-15" },
+            "signature": "c2ln",
+            "public_key": "a2V5",
+        });
+        fs::write(dir.join("signed_sentiment.json"), contents.to_string()).unwrap();
+    }
+    
+    #[test]
+    fn test_file_backend_discovers_assets() {
+        let dir = std::env::temp_dir().join(format!("sentiment-scan-test-{}", std::process::id()));
+        fs::create_dir_all(dir.join("DOGE")).unwrap();
+        write_signed_file(&dir, "POSITIVE");
+        write_signed_file(&dir.join("DOGE"), "NEUTRAL");
+        fs::copy(dir.join("signed_sentiment.json"), dir.join("BTC_sentiment.json")).unwrap();
+        fs::copy(dir.join("signed_sentiment.json"), dir.join("signed_eth.json")).unwrap();
+        fs::copy(dir.join("signed_sentiment.json"), dir.join("signed_sentiment_Link.json")).unwrap();
+        // Neither pattern matches mixed-case names
+        fs::copy(dir.join("signed_sentiment.json"), dir.join("Ada_sentiment.json")).unwrap();
+        fs::write(dir.join("notes.json"), "{}").unwrap();
+        
+        let backend = FileBackend::new(dir.to_str().unwrap());
+        assert_eq!(backend.list_assets().unwrap(), vec!["BTC", "DOGE", "ETH", "LINK", "SOL"]);
+        
+        for asset in ["$btc", "ETH", "$SOL", "sol"] {
+            assert_eq!(backend.load(asset).unwrap().data.label, SentimentLabel::Positive, "{}", asset);
+        }
+        assert_eq!(backend.load("doge").unwrap().data.label, SentimentLabel::Neutral);
+        assert!(backend.load("ADA").is_err());
+        
+        // New files only show up once the index is refreshed
+        fs::copy(dir.join("signed_sentiment.json"), dir.join("signed_avax.json")).unwrap();
+        assert!(backend.load("AVAX").is_err());
+        assert_eq!(backend.refresh().unwrap(), 6);
+        assert!(backend.load("AVAX").is_ok());
+        assert_eq!(backend.list_assets().unwrap(), vec!["AVAX", "BTC", "DOGE", "ETH", "LINK", "SOL"]);
+        
+        fs::remove_dir_all(&dir).unwrap();
+    }
+    
+    #[actix_web::test]
+    async fn test_cache_invalidation_reloads_from_file() {
+        let dir = std::env::temp_dir().join(format!("sentiment-cache-test-{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        write_signed_file(&dir, "POSITIVE");
+        
+        let backend: SharedBackend = Arc::new(FileBackend::new(dir.to_str().unwrap()));
+        let app = actix_test::init_service(
+            App::new()
+                .app_data(web::Data::new(SentimentService::new(backend)))
+                .app_data(web::Data::new(AdminAuth::new(Some("secret".to_string()))))
+                .service(get_latest_sentiment)
+                .service(invalidate_cache),
+        ).await;
+        let latest = || actix_test::TestRequest::get().uri("/latest?asset=%24SOL").to_request();
+        
+        let response: serde_json::Value = actix_test::call_and_read_body_json(&app, latest()).await;
+        assert_eq!(response["sentiment"], "POSITIVE");
+        
+        // A new file on disk is hidden by the cache until it is invalidated
+        write_signed_file(&dir, "NEGATIVE");
+        let response: serde_json::Value = actix_test::call_and_read_body_json(&app, latest()).await;
+        assert_eq!(response["sentiment"], "POSITIVE");
+        
+        let unauthorized = actix_test::TestRequest::delete()
+            .uri("/cache?asset=%24SOL")
+            .insert_header(("Authorization", "Bearer wrong"))
+            .to_request();
+        assert_eq!(actix_test::call_service(&app, unauthorized).await.status(), 401);
+        
+        let delete = actix_test::TestRequest::delete()
+            .uri("/cache?asset=%24SOL")
+            .insert_header(("Authorization", "Bearer secret"))
+            .to_request();
+        let response: serde_json::Value = actix_test::call_and_read_body_json(&app, delete).await;
+        assert_eq!(response, serde_json::json!({ "invalidated": ["$SOL"] }));
+        
+        let response: serde_json::Value = actix_test::call_and_read_body_json(&app, latest()).await;
+        assert_eq!(response["sentiment"], "NEGATIVE");
+        
+        fs::remove_dir_all(&dir).unwrap();
+    }
+    
+    fn signed_by(seed: u8, label: SentimentLabel) -> SignedSentimentData {
+        sign(sample_signed_data(label), seed)
+    }
+    
+    /// Submission for `/publish`, signed for `asset`
+    fn published_by(seed: u8, label: SentimentLabel, asset: &str) -> SignedSentimentData {
+        let mut signed = sample_signed_data(label);
+        signed.data.asset = Some(asset.to_string());
+        sign(signed, seed)
+    }
+    
+    /// Aggregator accepting submissions from the keys of these `signed_by` seeds
+    fn publishers(seeds: impl IntoIterator<Item = u8>) -> SentimentAggregator {
+        SentimentAggregator::with_signers(seeds.into_iter().map(|seed| signed_by(seed, SentimentLabel::Neutral).public_key))
+    }
+    
+    fn sign(mut signed: SignedSentimentData, seed: u8) -> SignedSentimentData {
+        use ed25519_dalek::{SecretKey, Signer};
+        
+        let secret = SecretKey::from_bytes(&[seed; 32]).unwrap();
+        let keypair = Keypair { public: PublicKey::from(&secret), secret };
+        let hash = Sha256::digest(serde_json::to_string(&signed.data).unwrap().as_bytes());
+        signed.signature = general_purpose::STANDARD.encode(keypair.sign(&hash).to_bytes());
+        signed.public_key = general_purpose::STANDARD.encode(keypair.public.to_bytes());
+        signed
+    }
+    
+    #[actix_web::test]
+    async fn test_stats_endpoint_caches_per_period() {
+        let store = Arc::new(StatsStore::in_memory().unwrap());
+        let backend: SharedBackend = Arc::new(FileBackend::new("/nonexistent"));
+        let sentiment_service = SentimentService::new(backend).with_stats_store(store.clone());
+        let now = Utc::now().timestamp();
+        store.record_price("BTC", 45000.0, 0.9, now - 60).unwrap();
+        sentiment_service.record_sentiment("$btc", &sample_signed_data(SentimentLabel::Positive));
+        
+        let app = actix_test::init_service(
+            App::new()
+                .app_data(web::Data::new(sentiment_service))
+                .service(get_stats),
+        ).await;
+        let stats_for = |uri: &str| actix_test::TestRequest::get().uri(uri).to_request();
+        
+        let stats: OracleStats = actix_test::call_and_read_body_json(&app, stats_for("/stats?asset=BTC")).await;
+        assert_eq!(stats.period_hours, 24);
+        assert_eq!(stats.update_count, 1);
+        assert_eq!(stats.sentiment_distribution["POSITIVE"], 1);
+        
+        // Served from the cache until it expires, but each period is cached separately
+        store.record_price("BTC", 46000.0, 0.9, now - 30).unwrap();
+        let cached: OracleStats = actix_test::call_and_read_body_json(&app, stats_for("/stats?asset=btc&period=24")).await;
+        assert_eq!(cached.update_count, 1);
+        let fresh: OracleStats = actix_test::call_and_read_body_json(&app, stats_for("/stats?asset=BTC&period=1")).await;
+        assert_eq!(fresh.update_count, 2);
+        
+        assert_eq!(actix_test::call_service(&app, stats_for("/stats?asset=BTC&period=0")).await.status(), 400);
+        assert_eq!(actix_test::call_service(&app, stats_for("/stats?asset=ADA")).await.status(), 404);
+    }
+    
+    #[actix_web::test]
+    async fn test_latest_smoothed_confidence() {
+        let store = Arc::new(StatsStore::in_memory().unwrap());
+        let backend: SharedBackend = Arc::new(FileBackend::new("/nonexistent"));
+        let sentiment_service = SentimentService::new(backend).with_stats_store(store.clone());
+        for (timestamp, score) in [(1_000, 0.2), (2_000, 0.6)] {
+            let mut data = sample_signed_data(SentimentLabel::Positive);
+            data.data.score = score;
+            store.record_sentiment("BTC", &data, timestamp).unwrap();
+        }
+        let latest = |timestamp: i64| {
+            let mut data = sample_signed_data(SentimentLabel::Positive);
+            data.data.timestamp = Some(timestamp);
+            data
+        };
+        sentiment_service.cache.lock().unwrap().insert("BTC".to_string(), latest(3_000));
+        sentiment_service.cache.lock().unwrap().insert("ETH".to_string(), latest(3_000));
+        
+        // Without a smoothing factor nothing is computed
+        let response = sentiment_service.get_latest_sentiment("BTC").await.unwrap();
+        assert_eq!(response.smoothed_confidence, None);
+        
+        let sentiment_service = sentiment_service.with_smoothing(0.5);
+        let app = actix_test::init_service(
+            App::new()
+                .app_data(web::Data::new(sentiment_service.clone()))
+                .service(get_latest_sentiment),
+        ).await;
+        
+        // 0.2, then 0.6 and the latest 0.8 each weighted by half
+        let req = actix_test::TestRequest::get().uri("/latest?asset=BTC").to_request();
+        let response: serde_json::Value = actix_test::call_and_read_body_json(&app, req).await;
+        assert!((response["smoothed_confidence"].as_f64().unwrap() - 0.6).abs() < 1e-9);
+        assert_eq!(response["confidence"], 0.8);
+        
+        // The latest entry isn't counted twice once it has been recorded
+        sentiment_service.record_sentiment("BTC", &latest(3_000));
+        let response = sentiment_service.get_latest_sentiment("BTC").await.unwrap();
+        assert!((response.smoothed_confidence.unwrap() - 0.6).abs() < 1e-9);
+        
+        // With no history the latest value is its own average
+        let response = sentiment_service.get_latest_sentiment("ETH").await.unwrap();
+        assert_eq!(response.smoothed_confidence, Some(0.8));
+    }
+    
+    #[actix_web::test]
+    async fn test_latest_health_score() {
+        let store = Arc::new(StatsStore::in_memory().unwrap());
+        let backend: SharedBackend = Arc::new(FileBackend::new("/nonexistent"));
+        let sentiment_service = SentimentService::new(backend).with_stats_store(store.clone());
+        for asset in ["BTC", "ETH", "SOL"] {
+            sentiment_service.cache.lock().unwrap().insert(asset.to_string(), sample_signed_data(SentimentLabel::Positive));
+        }
+        store.record_price_with_sources("BTC", 100.0, 1_000, &[("A", 100.0, false), ("B", 100.0, false)]).unwrap();
+        store.record_price_with_sources("ETH", 100.0, 1_000, &[("A", 98.0, false), ("B", 102.0, false), ("C", 150.0, true)]).unwrap();
+        
+        let app = actix_test::init_service(
+            App::new()
+                .app_data(web::Data::new(sentiment_service))
+                .service(get_latest_sentiment),
+        ).await;
+        let health = |uri: &'static str| {
+            let app = &app;
+            async move {
+                let req = actix_test::TestRequest::get().uri(uri).to_request();
+                let response: LatestSentimentResponse = actix_test::call_and_read_body_json(app, req).await;
+                response.health_score
+            }
+        };
+        
+        let btc = health("/latest?asset=BTC").await.unwrap();
+        let eth = health("/latest?asset=ETH").await.unwrap();
+        assert!((0.0..=100.0).contains(&btc) && (0.0..=100.0).contains(&eth));
+        assert!(btc > eth, "{} {}", btc, eth);
+        // No price history for the asset
+        assert_eq!(health("/latest?asset=SOL").await, None);
+    }
+    
+    #[actix_web::test]
+    async fn test_latest_and_history_as_csv() {
+        let dir = std::env::temp_dir().join(format!("sentiment-csv-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let mut data = sample_signed_data(SentimentLabel::VeryPositive);
+        data.data.date = Some("2025-05-01".to_string());
+        data.data.category = Some("L1, smart contracts".to_string());
+        std::fs::write(dir.join("SOL_sentiment.json"), serde_json::to_string(&data).unwrap()).unwrap();
+        let backend: SharedBackend = Arc::new(FileBackend::new(dir.to_str().unwrap()));
+        
+        let app = actix_test::init_service(
+            App::new()
+                .app_data(web::Data::new(SentimentService::new(backend)))
+                .service(get_latest_sentiment)
+                .service(get_sentiment_history),
+        ).await;
+        let get = |uri: &str, accept: &str| actix_test::TestRequest::get().uri(uri).insert_header(("Accept", accept)).to_request();
+        
+        // JSON stays the default
+        let json = actix_test::call_service(&app, get("/latest?asset=SOL", "application/json")).await;
+        assert_eq!(json.headers().get("content-type").unwrap(), "application/json");
+        let json: LatestSentimentResponse = actix_test::read_body_json(json).await;
+        
+        let csv = actix_test::call_service(&app, get("/latest?asset=SOL", "text/csv")).await;
+        assert_eq!(csv.headers().get("content-type").unwrap(), "text/csv; charset=utf-8");
+        // Caches must not hand one format to clients asking for the other
+        assert_eq!(csv.headers().get("vary").unwrap(), "Accept");
+        let csv = String::from_utf8(actix_test::read_body(csv).await.to_vec()).unwrap();
+        let lines: Vec<&str> = csv.lines().collect();
+        assert_eq!(lines.len(), 2);
+        assert!(lines[0].starts_with("asset,date,timestamp,age_seconds,sentiment,confidence,signature,signer,category,"));
+        assert!(lines[1].starts_with(&format!("SOL,2025-05-01,{},", json.timestamp)));
+        assert!(lines[1].contains(&format!(",VERY_POSITIVE,{},{},{},\"L1, smart contracts\",", json.confidence, json.signature, json.signer)));
+        
+        // ?format=csv works without the header, for spreadsheet imports by URL
+        let history = actix_test::call_service(&app, get("/history?asset=SOL&format=csv", "*/*")).await;
+        assert_eq!(history.headers().get("content-type").unwrap(), "text/csv; charset=utf-8");
+        let history = actix_test::read_body(history).await;
+        assert_eq!(history, format!("asset,date,sentiment,confidence\nSOL,2025-05-01,VERY_POSITIVE,{}\n", json.confidence).as_bytes());
+        let history: HistoryResponse = actix_test::call_and_read_body_json(&app, get("/history?asset=SOL", "*/*")).await;
+        assert_eq!(history.data[0].date, "2025-05-01");
+        
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+    
+    #[test]
+    fn test_feed_price_encoding() {
+        let mut latest = LatestSentimentResponse {
+            asset: "BTC".to_string(),
+            date: "2024-05-18".to_string(),
+            timestamp: 1_716_000_000,
+            age_seconds: 0,
+            sentiment: SentimentLabel::Positive,
+            confidence: 0.8,
+            signature: "sig".to_string(),
+            signer: "signer".to_string(),
+            category: None,
+            smoothed_confidence: None,
+            degraded: false,
+            health_score: None,
+            price: None,
+        };
+        assert_eq!(latest.to_feed_format(), None);
+        
+        latest.price = Some(45000.0);
+        let feed = latest.to_feed_format().unwrap();
+        assert_eq!(feed.price, "45000000000");
+        assert_eq!((feed.pair.as_str(), feed.decimal, feed.timestamp), ("BTC/USD", 6, 1_716_000_000));
+        
+        // Rounded to the sixth decimal rather than truncated by float error
+        latest.price = Some(0.1 + 0.2);
+        assert_eq!(latest.to_feed_format().unwrap().price, "300000");
+        latest.price = Some(1.2345678);
+        assert_eq!(latest.to_feed_format().unwrap().price, "1234568");
+    }
+    
+    #[actix_web::test]
+    async fn test_feed_endpoint() {
+        let store = Arc::new(StatsStore::in_memory().unwrap());
+        let backend: SharedBackend = Arc::new(FileBackend::new("/nonexistent"));
+        let sentiment_service = SentimentService::new(backend).with_stats_store(store.clone());
+        for asset in ["BTC", "ETH"] {
+            sentiment_service.cache.lock().unwrap().insert(asset.to_string(), sample_signed_data(SentimentLabel::Positive));
+        }
+        store.record_price("BTC", 45000.5, 0.9, 1_000).unwrap();
+        
+        let app = actix_test::init_service(
+            App::new()
+                .app_data(web::Data::new(sentiment_service))
+                .service(get_feed),
+        ).await;
+        
+        let req = actix_test::TestRequest::get().uri("/feed.json?asset=btc").to_request();
+        let res = actix_test::call_service(&app, req).await;
+        assert_eq!(res.headers().get("X-Oracle-Source").unwrap(), "sentiment-oracle");
+        let feed: serde_json::Value = actix_test::read_body_json(res).await;
+        assert_eq!(feed["type"], "Price");
+        assert_eq!(feed["pair"], "BTC/USD");
+        assert_eq!(feed["price"], "45000500000");
+        assert_eq!(feed["decimal"], 6);
+        assert!(feed["signerAddress"].is_string());
+        
+        // Sentiment without a consensus price
+        let req = actix_test::TestRequest::get().uri("/feed.json?asset=ETH").to_request();
+        assert_eq!(actix_test::call_service(&app, req).await.status(), 404);
+    }
+    
+    #[actix_web::test]
+    async fn test_latest_raw_endpoint() {
+        let backend: SharedBackend = Arc::new(FileBackend::new("/nonexistent"));
+        let sentiment_service = SentimentService::new(backend);
+        let signed = sample_signed_data(SentimentLabel::Positive);
+        sentiment_service.cache.lock().unwrap().insert("BTC".to_string(), signed.clone());
+        
+        let app = actix_test::init_service(
+            App::new()
+                .app_data(web::Data::new(sentiment_service))
+                .service(get_latest_raw),
+        ).await;
+        
+        let req = actix_test::TestRequest::get().uri("/latest/raw?asset=btc").to_request();
+        let raw: serde_json::Value = actix_test::call_and_read_body_json(&app, req).await;
+        assert_eq!(raw, serde_json::to_value(&signed).unwrap());
+        
+        let req = actix_test::TestRequest::get().uri("/latest/raw?asset=ETH").to_request();
+        assert_eq!(actix_test::call_service(&app, req).await.status(), 404);
+    }
+    
+    #[actix_web::test]
+    async fn test_impact_endpoint() {
+        let store = Arc::new(StatsStore::in_memory().unwrap());
+        let backend: SharedBackend = Arc::new(FileBackend::new("/nonexistent"));
+        let sentiment_service = SentimentService::new(backend).with_stats_store(store.clone());
+        let mut bearish = sample_signed_data(SentimentLabel::VeryNegative);
+        bearish.data.score = 0.6;
+        sentiment_service.cache.lock().unwrap().insert("BTC".to_string(), bearish);
+        sentiment_service.cache.lock().unwrap().insert("ETH".to_string(), sample_signed_data(SentimentLabel::Positive));
+        store.record_price_with_sources("BTC", 45000.0, 1_000, &[("A", 45000.0, false), ("B", 45000.0, false)]).unwrap();
+        
+        let app = actix_test::init_service(
+            App::new()
+                .app_data(web::Data::new(sentiment_service))
+                .service(get_impact),
+        ).await;
+        
+        let req = actix_test::TestRequest::get().uri("/impact?asset=btc").to_request();
+        let response: ImpactResponse = actix_test::call_and_read_body_json(&app, req).await;
+        assert_eq!(response.asset, "BTC");
+        assert_eq!(response.price, 45000.0);
+        assert!((response.sentiment_impact + 0.6).abs() < 1e-9);
+        
+        // Sentiment but no price history, and neither
+        for uri in ["/impact?asset=ETH", "/impact?asset=SOL"] {
+            let req = actix_test::TestRequest::get().uri(uri).to_request();
+            assert_eq!(actix_test::call_service(&app, req).await.status(), 404);
+        }
+    }
+    
+    #[actix_web::test]
+    async fn test_compare_reports_source_deviation() {
+        let store = Arc::new(StatsStore::in_memory().unwrap());
+        let backend: SharedBackend = Arc::new(FileBackend::new("/nonexistent"));
+        let sentiment_service = SentimentService::new(backend).with_stats_store(store.clone());
+        store.record_price_with_sources("SOL", 100.0, Utc::now().timestamp(), &[
+            ("CoinGecko", 102.0, false),
+            ("OKX", 130.0, true),
+        ]).unwrap();
+        store.record_price("BTC", 45000.0, 0.9, Utc::now().timestamp()).unwrap();
+        
+        let app = actix_test::init_service(
+            App::new()
+                .app_data(web::Data::new(sentiment_service))
+                .service(compare_sources),
+        ).await;
+        let compare = |uri: &str| actix_test::TestRequest::get().uri(uri).to_request();
+        
+        let comparison: PriceComparison = actix_test::call_and_read_body_json(&app, compare("/compare?asset=$sol")).await;
+        assert_eq!(comparison.asset, "SOL");
+        assert_eq!(comparison.consensus_price, 100.0);
+        assert_eq!(comparison.sources.len(), 2);
+        assert!((comparison.sources[0].deviation_pct - 2.0).abs() < 1e-9);
+        assert!(comparison.sources[1].outlier);
+        
+        // No per-source prices recorded, or no history at all
+        assert_eq!(actix_test::call_service(&app, compare("/compare?asset=BTC")).await.status(), 404);
+        assert_eq!(actix_test::call_service(&app, compare("/compare?asset=ADA")).await.status(), 404);
+    }
+    
+    #[actix_web::test]
+    async fn test_leaderboard_endpoint() {
+        let store = Arc::new(StatsStore::in_memory().unwrap());
+        store.record_price_with_sources("BTC", 100.0, Utc::now().timestamp(), &[
+            ("Binance", 101.0, false),
+            ("CoinGecko", 99.5, false),
+        ]).unwrap();
+        let leaderboard = |service: Option<LeaderboardService>| {
+            let app = App::new().service(get_leaderboard);
+            match service {
+                Some(service) => app.app_data(web::Data::new(service)),
+                None => app,
+            }
+        };
+        let request = || actix_test::TestRequest::get().uri("/leaderboard?asset=BTC").to_request();
+        
+        let app = actix_test::init_service(leaderboard(Some(LeaderboardService::new(store)))).await;
+        let response: leaderboard::LeaderboardResponse = actix_test::call_and_read_body_json(&app, request()).await;
+        assert_eq!(response.entries[0].signer, "CoinGecko");
+        assert_eq!(response.entries.len(), 2);
+        
+        // Without a history database
+        let app = actix_test::init_service(leaderboard(None)).await;
+        assert_eq!(actix_test::call_service(&app, request()).await.status(), 404);
+    }
+    
+    /// RPC JSON of an oracle account holding `payload`, with spare room after it
+    fn account_json(payload: &PricePayload) -> serde_json::Value {
+        let mut data = borsh::to_vec(payload).unwrap();
+        data.resize(data.len() + 64, 0);
+        serde_json::json!({
+            "data": [general_purpose::STANDARD.encode(&data), "base64"],
+            "owner": Pubkey::default().to_string(),
+            "lamports": 1_000_000,
+            "executable": false,
+            "rentEpoch": 0,
+            "space": data.len(),
+        })
+    }
+    
+    /// On-chain reader whose RPC answers with these mocked responses
+    fn mocked_reader(mocks: HashMap<solana_client::rpc_request::RpcRequest, serde_json::Value>) -> SolanaBackend {
+        SolanaBackend {
+            rpc_client: RpcClient::new_mock_with_mocks("succeeds", mocks),
+            program_id: Pubkey::new_unique(),
+            authority: Pubkey::new_unique(),
+        }
+    }
+    
+    /// On-chain reader whose RPC answers one account lookup with `payload`
+    fn onchain_reader(payload: &PricePayload) -> SolanaBackend {
+        let account = serde_json::json!({ "context": { "slot": 1 }, "value": account_json(payload) });
+        mocked_reader(HashMap::from([(solana_client::rpc_request::RpcRequest::GetAccountInfo, account)]))
+    }
+    
+    /// Current-layout BTC account submitted with a positive sentiment label
+    fn price_payload(timestamp: i64) -> PricePayload {
+        PricePayload {
+            is_initialized: true,
+            version: price_oracle_program::PAYLOAD_VERSION,
+            asset: "BTC".to_string(),
+            price: 45000.0,
+            confidence: 0.8,
+            timestamp,
+            sources: vec!["CoinGecko".to_string()],
+            consensus_score: 0.9,
+            signature: vec![1; 64],
+            signer: [2; 32],
+            nonce: 3,
+            asset_category: "L1".to_string(),
+            paused: false,
+            sentiment_label: SentimentLabel::Positive as u8,
+        }
+    }
+    
+    #[test]
+    fn test_solana_backend_reads_sentiment_label() {
+        let labelled = onchain_reader(&price_payload(1_700_000_000)).load("BTC").unwrap();
+        assert_eq!(labelled.data.label, SentimentLabel::Positive);
+        assert_eq!(labelled.data.category.as_deref(), Some("L1"));
+        
+        let unlabelled = PricePayload { sentiment_label: NO_SENTIMENT_LABEL, ..price_payload(1_700_000_000) };
+        assert_eq!(onchain_reader(&unlabelled).load("BTC").unwrap().data.label, SentimentLabel::Neutral);
+        
+        let unknown = PricePayload { sentiment_label: 42, ..price_payload(1_700_000_000) };
+        assert!(onchain_reader(&unknown).load("BTC").is_err());
+    }
+    
+    #[test]
+    fn test_solana_backend_lists_canonical_sorted_assets() {
+        let accounts: Vec<serde_json::Value> = ["SOL", "btc", "$SOL", "ETH"].into_iter()
+            .map(|asset| serde_json::json!({
+                "pubkey": Pubkey::new_unique().to_string(),
+                "account": account_json(&PricePayload { asset: asset.to_string(), ..price_payload(1_700_000_000) }),
+            }))
+            .collect();
+        let reader = mocked_reader(HashMap::from([(solana_client::rpc_request::RpcRequest::GetProgramAccounts, accounts.into())]));
+        
+        assert_eq!(reader.list_assets().unwrap(), vec!["BTC", "ETH", "SOL"]);
+    }
+    
+    #[actix_web::test]
+    async fn test_onchain_endpoint() {
+        let backend: SharedBackend = Arc::new(FileBackend::new("/nonexistent"));
+        let sentiment_service = SentimentService::new(backend);
+        let mut cached = sample_signed_data(SentimentLabel::Positive);
+        cached.data.timestamp = Some(1_700_000_000);
+        sentiment_service.cache.lock().unwrap().insert("BTC".to_string(), cached);
+        let onchain = |reader: Option<SolanaBackend>| {
+            let app = App::new()
+                .app_data(web::Data::new(sentiment_service.clone()))
+                .service(get_onchain);
+            match reader {
+                Some(reader) => app.app_data(web::Data::new(reader)),
+                None => app,
+            }
+        };
+        let request = || actix_test::TestRequest::get().uri("/onchain?asset=BTC").to_request();
+        
+        let app = actix_test::init_service(onchain(Some(onchain_reader(&price_payload(1_700_000_000))))).await;
+        let response: serde_json::Value = actix_test::call_and_read_body_json(&app, request()).await;
+        assert_eq!(response["match"], true);
+        assert_eq!(response["cached"]["timestamp"], 1_700_000_000);
+        assert_eq!(response["onchain"]["price"], 45000.0);
+        assert_eq!(response["onchain"]["nonce"], 3);
+        
+        // The chain has moved on since the API cached its value
+        let app = actix_test::init_service(onchain(Some(onchain_reader(&price_payload(1_700_000_600))))).await;
+        let response: ComparisonResponse = actix_test::call_and_read_body_json(&app, request()).await;
+        assert!(!response.matches);
+        assert_eq!(response.onchain.timestamp, 1_700_000_600);
+        
+        // Without on-chain configuration
+        let app = actix_test::init_service(onchain(None)).await;
+        assert_eq!(actix_test::call_service(&app, request()).await.status(), 404);
+    }
+    
+    #[actix_web::test]
+    async fn test_latest_reports_timestamp_and_age() {
+        let (sentiment_service, _) = rpc_app_data();
+        let mut precise = sample_signed_data(SentimentLabel::Positive);
+        precise.data.timestamp = Some(Utc::now().timestamp() - 120);
+        {
+            let mut cache = sentiment_service.cache.lock().unwrap();
+            cache.insert("BTC".to_string(), precise);
+            cache.insert("ETH".to_string(), sample_signed_data(SentimentLabel::Neutral));
+        }
+        
+        let btc = sentiment_service.get_latest_sentiment("BTC").await.unwrap();
+        assert!((120..125).contains(&btc.age_seconds), "{}", btc.age_seconds);
+        
+        // Day-level data is dated from midnight UTC
+        let eth = sentiment_service.get_latest_sentiment("ETH").await.unwrap();
+        assert_eq!(eth.timestamp, 1_747_267_200);
+        assert!(Utc::now().timestamp() - 1_747_267_200 - eth.age_seconds <= 1);
+    }
+    
+    #[actix_web::test]
+    async fn test_submit_signs_and_stores() {
+        use ed25519_dalek::SecretKey;
+        
+        let dir = std::env::temp_dir().join(format!("sentiment-submit-test-{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        let backend: SharedBackend = Arc::new(FileBackend::new(dir.to_str().unwrap()));
+        let secret = SecretKey::from_bytes(&[9; 32]).unwrap();
+        let signer = SignerService::from_keypair(Keypair { public: PublicKey::from(&secret), secret });
+        let app = actix_test::init_service(
+            App::new()
+                .app_data(web::Data::new(SentimentService::new(backend.clone())))
+                .app_data(web::Data::new(signer))
+                .app_data(web::Data::new(AdminAuth::new(Some("secret".to_string()))))
+                .service(submit_sentiment),
+        ).await;
+        let submit = |auth: &str, body: serde_json::Value| actix_test::TestRequest::post()
+            .uri("/submit?asset=$doge")
+            .insert_header(("Authorization", auth.to_string()))
+            .set_json(body)
+            .to_request();
+        let payload = serde_json::to_value(&sample_signed_data(SentimentLabel::Negative).data).unwrap();
+        
+        assert_eq!(actix_test::call_service(&app, submit("Bearer wrong", payload.clone())).await.status(), 401);
+        
+        let mut missing_label = payload.clone();
+        missing_label.as_object_mut().unwrap().remove("label");
+        assert_eq!(actix_test::call_service(&app, submit("Bearer secret", missing_label)).await.status(), 400);
+        
+        let signed: SignedSentimentData = actix_test::call_and_read_body_json(&app, submit("Bearer secret", payload)).await;
+        let verify_request = VerifyRequest {
+            payload: signed.data.clone(),
+            signature: signed.signature.clone(),
+            signer: signed.public_key.clone(),
+        };
+        assert!(VerificationService::new().verify(verify_request).await.unwrap());
+        
+        // The file backend now serves the signed data for the asset
+        assert!(dir.join("DOGE_sentiment.json").is_file());
+        let stored = backend.load("DOGE").unwrap();
+        assert_eq!(stored.data.label, SentimentLabel::Negative);
+        assert_eq!(stored.signature, signed.signature);
+        // Written through a temporary file that is renamed away
+        assert_eq!(fs::read_dir(&dir).unwrap().count(), 1);
+        
+        // Symbols that could leave the data directory never reach the backend
+        let traversal = actix_test::TestRequest::post()
+            .uri("/submit?asset=..%2F..%2Fevil")
+            .insert_header(("Authorization", "Bearer secret"))
+            .set_json(serde_json::to_value(&sample_signed_data(SentimentLabel::Negative).data).unwrap())
+            .to_request();
+        assert_eq!(actix_test::call_service(&app, traversal).await.status(), 400);
+        assert!(backend.store("../../evil", &signed).is_err());
+        assert!(backend.store("a/b", &signed).is_err());
+        assert!(!dir.parent().unwrap().join("EVIL_sentiment.json").exists());
+        
+        fs::remove_dir_all(&dir).unwrap();
+    }
+    
+    #[actix_web::test]
+    async fn test_verify_hash_matches_signed_bytes() {
+        let app = actix_test::init_service(
+            App::new()
+                .app_data(web::Data::new(VerificationService::new()))
+                .service(verify_hash),
+        ).await;
+        let signed = signed_by(5, SentimentLabel::Negative);
+        let request = actix_test::TestRequest::post()
+            .uri("/verify/hash")
+            .set_json(&signed.data)
+            .to_request();
+        let response: HashResponse = actix_test::call_and_read_body_json(&app, request).await;
+        
+        let expected = Sha256::digest(serde_json::to_string(&signed.data).unwrap().as_bytes());
+        assert_eq!(response.hash_hex, hex::encode(expected));
+        assert_eq!(general_purpose::STANDARD.decode(&response.hash_base64).unwrap(), expected.to_vec());
+        
+        // The reported hash is exactly what the signature covers
+        let public_key = PublicKey::from_bytes(&general_purpose::STANDARD.decode(&signed.public_key).unwrap()).unwrap();
+        let signature = Signature::from_bytes(&general_purpose::STANDARD.decode(&signed.signature).unwrap()).unwrap();
+        assert!(public_key.verify_strict(&hex::decode(&response.hash_hex).unwrap(), &signature).is_ok());
+        
+        // However the body is formatted, the hash is of the canonical serialization
+        let pretty = serde_json::to_string_pretty(&signed.data).unwrap();
+        let request = actix_test::TestRequest::post()
+            .uri("/verify/hash")
+            .insert_header(("Content-Type", "application/json"))
+            .set_payload(pretty)
+            .to_request();
+        let response: HashResponse = actix_test::call_and_read_body_json(&app, request).await;
+        assert_eq!(response.hash_hex, hex::encode(expected));
+        
+        // Text `/verify` would reject is rejected here too, rather than hashed
+        let mut bell = signed.data.clone();
+        bell.text = "sample\u{7}".to_string();
+        let request = actix_test::TestRequest::post().uri("/verify/hash").set_json(&bell).to_request();
+        assert_eq!(actix_test::call_service(&app, request).await.status(), 400);
+        
+        let request = actix_test::TestRequest::post()
+            .uri("/verify/hash")
+            .insert_header(("Content-Type", "application/json"))
+            .set_payload("{\"label\": 1}")
+            .to_request();
+        assert_eq!(actix_test::call_service(&app, request).await.status(), 400);
+    }
+    
+    #[actix_web::test]
+    async fn test_signature_over_unnormalized_label_verifies() {
+        use ed25519_dalek::{SecretKey, Signer};
+        
+        // A node that signs its payload with a lowercase label
+        let secret = SecretKey::from_bytes(&[8; 32]).unwrap();
+        let keypair = Keypair { public: PublicKey::from(&secret), secret };
+        let signed_json = serde_json::to_string(&sample_signed_data(SentimentLabel::Positive).data).unwrap()
+            .replace(r#""label":"POSITIVE""#, r#""label":"positive""#);
+        let signature = keypair.sign(&Sha256::digest(signed_json.as_bytes()));
+        
+        let received: SentimentData = serde_json::from_str(&signed_json).unwrap();
+        assert_eq!(received.label, SentimentLabel::Positive);
+        assert_eq!(serde_json::to_string(&received).unwrap(), signed_json);
+        let request = VerifyRequest {
+            payload: received,
+            signature: general_purpose::STANDARD.encode(signature.to_bytes()),
+            signer: general_purpose::STANDARD.encode(keypair.public.to_bytes()),
+        };
+        assert!(VerificationService::new().verify(request.clone()).await.unwrap());
+        
+        // Changing the label drops the received spelling, so the old signature no longer matches
+        let mut relabeled = request;
+        relabeled.payload.label = SentimentLabel::Negative;
+        assert!(!VerificationService::new().verify(relabeled).await.unwrap());
+    }
+    
+    #[actix_web::test]
+    async fn test_verify_results_are_cached() {
+        let service = VerificationService::with_cache_size(1);
+        let app = actix_test::init_service(
+            App::new()
+                .app_data(web::Data::new(service.clone()))
+                .service(verify_cache_stats),
+        ).await;
+        let request = |signed: &SignedSentimentData| VerifyRequest {
+            payload: signed.data.clone(),
+            signature: signed.signature.clone(),
+            signer: signed.public_key.clone(),
+        };
+        let first = signed_by(5, SentimentLabel::Negative);
+        let mut tampered = first.clone();
+        tampered.data.score = 0.1;
+        
+        assert!(service.verify(request(&first)).await.unwrap());
+        assert!(service.verify(request(&first)).await.unwrap());
+        // A cached result is per payload, signature and signer
+        assert!(!service.verify(request(&tampered)).await.unwrap());
+        assert!(!service.verify(request(&tampered)).await.unwrap());
+        // The one-entry cache evicted the first result
+        assert!(service.verify(request(&first)).await.unwrap());
+        
+        let req = actix_test::TestRequest::get().uri("/verify/cache").to_request();
+        let stats: VerifyCacheStats = actix_test::call_and_read_body_json(&app, req).await;
+        assert_eq!(stats, VerifyCacheStats { hits: 2, misses: 3, hit_rate: 0.4, entries: 1, capacity: 1 });
+        
+        let disabled = VerificationService::with_cache_size(0);
+        assert!(disabled.verify(request(&first)).await.unwrap());
+        assert!(disabled.verify(request(&first)).await.unwrap());
+        assert_eq!((disabled.cache_stats().hits, disabled.cache_stats().capacity), (0, 0));
+    }
+    
+    #[actix_web::test]
+    async fn test_verify_request_validation() {
+        let signed = signed_by(6, SentimentLabel::Positive);
+        let request = |signature: &str, signer: &str| VerifyRequest {
+            payload: signed.data.clone(),
+            signature: signature.to_string(),
+            signer: signer.to_string(),
+        };
+        let encoded = |len: usize| general_purpose::STANDARD.encode(vec![7u8; len]);
+        let rejection = |request: VerifyRequest| match request.decode_keys() {
+            Err(ApiError::BadRequest(message)) => message,
+            other => panic!("expected a bad request, got {:?}", other.map(|_| ())),
+        };
+        
+        assert_eq!(rejection(request("", &signed.public_key)), "signature must not be empty");
+        assert_eq!(rejection(request(&signed.signature, "  ")), "signer must not be empty");
+        assert!(rejection(request("not base64!", &signed.public_key)).starts_with("signature is not valid base64"));
+        assert!(rejection(request(&signed.signature, "@@@@")).starts_with("signer is not valid base64"));
+        assert_eq!(rejection(request(&encoded(63), &signed.public_key)), "signature must be 64 bytes, got 63");
+        assert_eq!(rejection(request(&signed.signature, &encoded(20))), "signer must be 32 bytes, got 20");
+        assert!(request(&signed.signature, &signed.public_key).decode_keys().is_ok());
+        
+        // The endpoint answers with the precise message
+        let app = actix_test::init_service(
+            App::new()
+                .app_data(web::Data::new(VerificationService::new()))
+                .service(verify_signature),
+        ).await;
+        let req = actix_test::TestRequest::post().uri("/verify").set_json(request(&signed.signature, &encoded(20))).to_request();
+        let res = actix_test::call_service(&app, req).await;
+        assert_eq!(res.status(), 400);
+        let body: ErrorResponse = actix_test::read_body_json(res).await;
+        assert!(body.message.contains("signer must be 32 bytes, got 20"), "{}", body.message);
+    }
+    
+    #[actix_web::test]
+    async fn test_publish_feeds_aggregate() {
+        let app = actix_test::init_service(
+            App::new()
+                .app_data(web::Data::new(VerificationService::new()))
+                .app_data(web::Data::new(publishers(1..=4)))
+                .service(publish_sentiment)
+                .service(get_aggregate),
+        ).await;
+        let publish = |signed: SignedSentimentData| actix_test::TestRequest::post()
+            .uri("/publish")
+            .set_json(PublishRequest { asset: "BTC".to_string(), signed })
+            .to_request();
+        
+        let missing = actix_test::TestRequest::get().uri("/aggregate?asset=BTC").to_request();
+        assert_eq!(actix_test::call_service(&app, missing).await.status(), 404);
+        
+        for (seed, label) in [(1, SentimentLabel::Positive), (2, SentimentLabel::Positive), (3, SentimentLabel::Negative)] {
+            let response = actix_test::call_service(&app, publish(published_by(seed, label, "BTC"))).await;
+            assert_eq!(response.status(), 202);
+        }
+        // Resubmitting replaces the node's earlier entry instead of adding a vote
+        for _ in 0..3 {
+            let response = actix_test::call_service(&app, publish(published_by(3, SentimentLabel::Negative, "$btc"))).await;
+            assert_eq!(response.status(), 202);
+        }
+        
+        // A tampered payload is rejected and never reaches the aggregator
+        let mut forged = published_by(4, SentimentLabel::Positive, "BTC");
+        forged.data.label = SentimentLabel::Negative;
+        assert_eq!(actix_test::call_service(&app, publish(forged)).await.status(), 400);
+        
+        // So are payloads from unknown nodes, signed for another asset, or not naming one
+        assert_eq!(actix_test::call_service(&app, publish(published_by(5, SentimentLabel::Negative, "BTC"))).await.status(), 401);
+        let response = actix_test::call_service(&app, publish(published_by(4, SentimentLabel::Negative, "ETH"))).await;
+        assert_eq!(response.status(), 400);
+        let body: ErrorResponse = actix_test::read_body_json(response).await;
+        assert!(body.message.contains("signed for ETH, not BTC"), "{}", body.message);
+        assert_eq!(actix_test::call_service(&app, publish(signed_by(4, SentimentLabel::Negative))).await.status(), 400);
+        
+        let aggregate = actix_test::TestRequest::get().uri("/aggregate?asset=btc").to_request();
+        let response: serde_json::Value = actix_test::call_and_read_body_json(&app, aggregate).await;
+        assert_eq!(response["label"], "POSITIVE");
+        assert_eq!(response["source_count"], 3);
+    }
+    
+    #[actix_web::test]
+    async fn test_error_response_carries_request_id() {
+        let dir = std::env::temp_dir().join(format!("sentiment-request-id-test-{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        let backend: SharedBackend = Arc::new(FileBackend::new(dir.to_str().unwrap()));
+        let app = actix_test::init_service(
+            App::new()
+                .wrap(RequestId)
+                .app_data(web::Data::new(SentimentService::new(backend)))
+                .service(get_asset),
+        ).await;
+        
+        let res = actix_test::call_service(&app, actix_test::TestRequest::get().uri("/asset/ADA").to_request()).await;
+        assert_eq!(res.status(), 404);
+        let header = res.headers().get("X-Request-Id").unwrap().to_str().unwrap().to_string();
+        let body: ErrorResponse = actix_test::read_body_json(res).await;
+        assert_eq!(body.status, 404);
+        assert_eq!(body.request_id.as_deref(), Some(header.as_str()));
+        
+        // A client-chosen id is used for correlation as-is
+        let req = actix_test::TestRequest::get().uri("/asset/..%2Fetc")
+            .insert_header(("X-Request-Id", "support-ticket-7"))
+            .to_request();
+        let res = actix_test::call_service(&app, req).await;
+        assert_eq!(res.status(), 400);
+        assert_eq!(res.headers().get("X-Request-Id").unwrap(), "support-ticket-7");
+        let body: ErrorResponse = actix_test::read_body_json(res).await;
+        assert_eq!(body.request_id.as_deref(), Some("support-ticket-7"));
+        
+        fs::remove_dir_all(&dir).unwrap();
+    }
+    
+    #[actix_web::test]
+    async fn test_oversized_payloads_rejected() {
+        let app = actix_test::init_service(
+            App::new()
+                .app_data(web::Data::new(VerificationService::new()))
+                .app_data(web::Data::new(publishers(1..=2)))
+                .app_data(json_config())
+                .service(verify_signature)
+                .service(publish_sentiment),
+        ).await;
+        let publish = |signed: &SignedSentimentData| actix_test::TestRequest::post()
+            .uri("/publish")
+            .set_json(PublishRequest { asset: "BTC".to_string(), signed: signed.clone() })
+            .to_request();
+        
+        let mut huge = signed_by(1, SentimentLabel::Positive);
+        huge.data.text = "a".repeat(MAX_JSON_BODY_BYTES);
+        for req in [
+            publish(&huge),
+            actix_test::TestRequest::post().uri("/verify").set_json(VerifyRequest {
+                payload: huge.data.clone(),
+                signature: huge.signature.clone(),
+                signer: huge.public_key.clone(),
+            }).to_request(),
+        ] {
+            let response = actix_test::call_service(&app, req).await;
+            assert_eq!(response.status(), 400);
+            let body: ErrorResponse = actix_test::read_body_json(response).await;
+            assert!(body.message.contains("Payload too large"), "{}", body.message);
+        }
+        
+        // Within the body limit, but the text itself is too long
+        let mut long = published_by(1, SentimentLabel::Positive, "BTC");
+        long.data.text = "a".repeat(MAX_TEXT_BYTES + 1);
+        let response = actix_test::call_service(&app, publish(&long)).await;
+        assert_eq!(response.status(), 400);
+        let body: ErrorResponse = actix_test::read_body_json(response).await;
+        assert!(body.message.contains("longer than the 10000 allowed"), "{}", body.message);
+        
+        // Text with control characters is rejected, even when signed as received, rather than
+        // rewritten into text the signer never signed
+        let mut bell = sample_signed_data(SentimentLabel::Positive);
+        bell.data.asset = Some("BTC".to_string());
+        bell.data.text = "sample\u{7}".to_string();
+        let bell = sign(bell, 2);
+        for req in [
+            publish(&bell),
+            actix_test::TestRequest::post().uri("/verify").set_json(VerifyRequest {
+                payload: bell.data.clone(),
+                signature: bell.signature.clone(),
+                signer: bell.public_key.clone(),
+            }).to_request(),
+        ] {
+            let response = actix_test::call_service(&app, req).await;
+            assert_eq!(response.status(), 400);
+            let body: ErrorResponse = actix_test::read_body_json(response).await;
+            assert!(body.message.contains("control character U+0007"), "{}", body.message);
+        }
+        
+        // Line breaks and tabs are kept and signed like any other text
+        let mut multiline = sample_signed_data(SentimentLabel::Positive);
+        multiline.data.asset = Some("BTC".to_string());
+        multiline.data.text = "line\r\n\tindented".to_string();
+        assert_eq!(actix_test::call_service(&app, publish(&sign(multiline, 2))).await.status(), 202);
+    }
+    
+    #[actix_web::test]
+    async fn test_unknown_sentiment_label_rejected() {
+        let app = actix_test::init_service(
+            App::new()
+                .app_data(web::Data::new(VerificationService::new()))
+                .app_data(json_config())
+                .service(verify_signature),
+        ).await;
+        let signed = signed_by(1, SentimentLabel::VeryPositive);
+        let mut request = serde_json::json!({
+            "payload": signed.data,
+            "signature": signed.signature,
+            "signer": signed.public_key,
+        });
+        assert_eq!(request["payload"]["label"], "VERY_POSITIVE");
+        
+        request["payload"]["label"] = "BULLISH".into();
+        let response = actix_test::call_service(
+            &app,
+            actix_test::TestRequest::post().uri("/verify").set_json(&request).to_request(),
+        ).await;
+        assert_eq!(response.status(), 400);
+        let body: ErrorResponse = actix_test::read_body_json(response).await;
+        assert!(body.message.contains("Unknown sentiment label 'BULLISH'"), "{}", body.message);
+    }
+    
+    async fn get_export(dir: &std::path::Path, uri: &str) -> actix_web::dev::ServiceResponse {
+        let backend: SharedBackend = Arc::new(FileBackend::new(dir.to_str().unwrap()));
+        let app = actix_test::init_service(
+            App::new()
+                .app_data(web::Data::new(SentimentService::new(backend)))
+                .service(export_csv),
+        ).await;
+        actix_test::call_service(&app, actix_test::TestRequest::get().uri(uri).to_request()).await
+    }
+    
+    #[actix_web::test]
+    async fn test_export_csv_streams_rows() {
+        let dir = std::env::temp_dir().join(format!("sentiment-export-test-{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        write_signed_file(&dir, "POSITIVE");
+        
+        let response = get_export(&dir, "/export/csv?asset=%24SOL&from=2025-01-01&to=2025-06-01").await;
+        assert_eq!(response.status(), 200);
+        assert_eq!(response.headers().get("Content-Type").unwrap(), "text/csv");
+        assert_eq!(
+            response.headers().get("Content-Disposition").unwrap(),
+            "attachment; filename=\"SOL_sentiment.csv\""
+        );
+        let body = String::from_utf8(actix_test::read_body(response).await.to_vec()).unwrap();
+        let lines: Vec<&str> = body.lines().collect();
+        assert_eq!(lines, vec!["date,sentiment,confidence,signature", "2025-05-15,POSITIVE,0.9,c2ln"]);
+        
+        // Entries outside the range are left out, the header is always sent
+        let response = get_export(&dir, "/export/csv?asset=%24SOL&from=2025-06-01").await;
+        let body = actix_test::read_body(response).await;
+        assert_eq!(body.as_ref(), b"date,sentiment,confidence,signature\n");
+        
+        let response = get_export(&dir, "/export/csv?asset=%24SOL&from=June").await;
+        assert_eq!(response.status(), 400);
+        
+        fs::remove_dir_all(&dir).unwrap();
+    }
+    
+    #[actix_web::test]
+    async fn test_oracle_submit_checks_token_and_signature() {
+        use solana_sdk::signature::Signer;
+        
+        let (program_id, oracle) = (Pubkey::new_unique(), solana_sdk::signature::Keypair::new());
+        let account = price_account_address(&oracle.pubkey(), "SOL", &program_id).unwrap().to_string();
+        let submit_service = web::Data::new(SolanaSubmitService::new(
+            "http://127.0.0.1:1",
+            program_id,
+            oracle,
+            AdminAuth::named("SUBMIT_TOKEN", Some("secret".to_string())),
+        ).unwrap());
+        let app = actix_test::init_service(
+            App::new()
+                .app_data(web::Data::new(VerificationService::new()))
+                .app_data(submit_service)
+                .service(oracle_submit),
+        ).await;
+        let submit = |auth: &str, signed: SignedSentimentData, account: &str| actix_test::TestRequest::post()
+            .uri("/oracle/submit")
+            .insert_header(("Authorization", auth.to_string()))
+            .set_json(OracleSubmitRequest { signed_sentiment: signed, asset_account: account.to_string() })
+            .to_request();
+        let signed = published_by(3, SentimentLabel::Positive, "SOL");
+        
+        assert_eq!(actix_test::call_service(&app, submit("Bearer wrong", signed.clone(), &account)).await.status(), 401);
+        assert_eq!(actix_test::call_service(&app, submit("Bearer secret", signed.clone(), "not-a-key")).await.status(), 400);
+        
+        let mut tampered = signed.clone();
+        tampered.data.score = 0.1;
+        assert_eq!(actix_test::call_service(&app, submit("Bearer secret", tampered, &account)).await.status(), 400);
+        
+        // Another asset's account is refused
+        let other = published_by(3, SentimentLabel::Positive, "BTC");
+        assert_eq!(actix_test::call_service(&app, submit("Bearer secret", other, &account)).await.status(), 400);
+        
+        // A valid submission gets as far as the (unreachable) RPC node
+        assert_eq!(actix_test::call_service(&app, submit("Bearer secret", signed, &account)).await.status(), 500);
+        
+        // Without a program configured the endpoint doesn't exist
+        let unconfigured = actix_test::init_service(
+            App::new()
+                .app_data(web::Data::new(VerificationService::new()))
+                .service(oracle_submit),
+        ).await;
+        let signed = signed_by(3, SentimentLabel::Positive);
+        assert_eq!(actix_test::call_service(&unconfigured, submit("Bearer secret", signed, &account)).await.status(), 404);
+    }
+    
+    #[actix_web::test]
+    async fn test_admin_reload_requires_token() {
+        let (sentiment_service, _) = rpc_app_data();
+        {
+            let mut cache = sentiment_service.cache.lock().unwrap();
+            cache.insert("BTC".to_string(), sample_signed_data(SentimentLabel::Positive));
+            cache.insert("ETH".to_string(), sample_signed_data(SentimentLabel::Negative));
+        }
+        let app = |token: Option<&str>| {
+            App::new()
+                .app_data(sentiment_service.clone())
+                .app_data(web::Data::new(AdminAuth::new(token.map(str::to_string))))
+                .service(admin_reload)
+        };
+        let reload = |auth: &str| actix_test::TestRequest::post()
+            .uri("/admin/reload")
+            .insert_header(("Authorization", auth.to_string()))
+            .to_request();
+        
+        // Without a configured token the endpoint stays closed
+        let disabled = actix_test::init_service(app(None)).await;
+        assert_eq!(actix_test::call_service(&disabled, reload("Bearer anything")).await.status(), 401);
+        
+        let app = actix_test::init_service(app(Some("secret"))).await;
+        assert_eq!(actix_test::call_service(&app, reload("Bearer wrong")).await.status(), 401);
+        assert_eq!(sentiment_service.cache.lock().unwrap().len(), 2);
+        
+        let response: serde_json::Value = actix_test::call_and_read_body_json(&app, reload("Bearer secret")).await;
+        assert_eq!(response, serde_json::json!({ "evicted": 2 }));
+        assert!(sentiment_service.cache.lock().unwrap().is_empty());
+    }
+    
+    #[test]
+    fn test_invalidate_all_flushes_cache() {
+        let (sentiment_service, _) = rpc_app_data();
+        {
+            let mut cache = sentiment_service.cache.lock().unwrap();
+            cache.insert("ETH".to_string(), sample_signed_data(SentimentLabel::Negative));
+            cache.insert("BTC".to_string(), sample_signed_data(SentimentLabel::Positive));
+        }
+        
+        assert_eq!(sentiment_service.invalidate_all(), vec!["BTC", "ETH"]);
+        assert!(sentiment_service.cache.lock().unwrap().is_empty());
+        assert!(!sentiment_service.invalidate("BTC"));
+    }
+    
+    #[test]
+    fn test_sentiment_parsing_is_case_insensitive() {
+        for label in ["POSITIVE", "positive", "Positive", " positive "] {
+            assert_eq!(label.parse::<SentimentLabel>(), Ok(SentimentLabel::Positive));
+        }
+        assert_eq!("negative".parse::<SentimentLabel>(), Ok(SentimentLabel::Negative));
+        assert_eq!("Neutral".parse::<SentimentLabel>(), Ok(SentimentLabel::Neutral));
+        assert!("bullish".parse::<SentimentLabel>().is_err());
+    }
+    
+    #[test]
+    fn test_sentiment_unknown_label_is_neutral() {
+        assert_eq!(sentiment_from_label("bullish"), SentimentLabel::Neutral);
+        assert_eq!(sentiment_from_label(""), SentimentLabel::Neutral);
+    }
+    
+    #[test]
+    fn test_sentiment_wire_format_is_uppercase() {
+        let sentiment: SentimentLabel = serde_json::from_str("\"positive\"").unwrap();
+        
+        assert_eq!(sentiment, SentimentLabel::Positive);
+        assert_eq!(serde_json::to_string(&sentiment).unwrap(), "\"POSITIVE\"");
+        assert_eq!(SentimentLabel::Negative.to_string(), "NEGATIVE");
+    }
+    
+    #[test]
+    fn test_resolve_data_dir() {
+        let dir = std::env::temp_dir().join(format!("sentiment-data-dir-test-{}", std::process::id()));
+        fs::create_dir_all(dir.join("signed")).unwrap();
+        let canonical = fs::canonicalize(&dir).unwrap();
+        
+        assert_eq!(resolve_data_dir(dir.to_str().unwrap(), None).unwrap(), canonical);
+        let dotted = format!("{}/signed/..", dir.display());
+        assert_eq!(resolve_data_dir(&dotted, None).unwrap(), canonical);
+        
+        // ~ is the home directory, but only as a whole path component
+        let home = dir.to_str();
+        assert_eq!(resolve_data_dir("~", home).unwrap(), canonical);
+        assert_eq!(resolve_data_dir("~/signed", home).unwrap(), canonical.join("signed"));
+        assert!(resolve_data_dir("~/signed", None).unwrap_err().to_string().contains("HOME"));
+        assert!(resolve_data_dir("~other/signed", home).is_err());
+        
+        let missing = resolve_data_dir(&format!("{}/missing", dir.display()), None).unwrap_err();
+        assert!(missing.to_string().contains("can't be resolved"), "{}", missing);
+        fs::write(dir.join("file.json"), "{}").unwrap();
+        assert!(resolve_data_dir(&format!("{}/file.json", dir.display()), None).unwrap_err().to_string().contains("not a directory"));
+        
+        fs::remove_dir_all(&dir).unwrap();
+    }
+    
+    #[test]
+    fn test_server_config_defaults() {
+        let config = ServerConfig::parse(|_| None).unwrap();
+        
+        assert_eq!(config.bind_address, "127.0.0.1:8080".parse::<SocketAddr>().unwrap());
+        assert_eq!(config.workers, None);
+        assert_eq!(config.tls, None);
+    }
+    
+    fn parse_server_config(vars: &[(&str, &str)]) -> Result<ServerConfig, String> {
+        let vars: HashMap<String, String> = vars.iter().map(|(k, v)| (k.to_string(), v.to_string())).collect();
+        ServerConfig::parse(|name| vars.get(name).cloned())
+    }
+    
+    #[test]
+    fn test_server_config_validation() {
+        let config = parse_server_config(&[("BIND_ADDRESS", "0.0.0.0:9000"), ("WORKERS", "4")]).unwrap();
+        assert_eq!(config.workers, Some(4));
+        
+        assert!(parse_server_config(&[("BIND_ADDRESS", "localhost8080")]).is_err());
+        assert!(parse_server_config(&[("WORKERS", "0")]).is_err());
+        assert!(parse_server_config(&[("WORKERS", "many")]).is_err());
+    }
+    
+    #[test]
+    fn test_server_config_tls_requires_both_files() {
+        let config = parse_server_config(&[
+            ("TLS_CERT_FILE", "cert.pem"),
+            ("TLS_KEY_FILE", "key.pem"),
+            ("HTTP_PORT", "8081"),
+        ]).unwrap();
+        assert_eq!(config.tls.unwrap().cert_file, "cert.pem");
+        assert_eq!(config.http_port, Some(8081));
+        
+        assert!(parse_server_config(&[("TLS_CERT_FILE", "cert.pem")]).is_err());
+        assert!(parse_server_config(&[("HTTP_PORT", "http")]).is_err());
+    }
+    
+    #[test]
+    fn test_server_config_cors_origins() {
+        assert_eq!(parse_server_config(&[]).unwrap().cors_origins, CorsOrigins::Unset);
+        assert_eq!(parse_server_config(&[("CORS_ORIGINS", " * ")]).unwrap().cors_origins, CorsOrigins::Any);
+        
+        let config = parse_server_config(&[("CORS_ORIGINS", "https://oracle.example.com/, http://localhost:8000")]).unwrap();
+        assert_eq!(config.cors_origins, CorsOrigins::List(vec![
+            "https://oracle.example.com".to_string(),
+            "http://localhost:8000".to_string(),
+        ]));
+        
+        assert!(parse_server_config(&[("CORS_ORIGINS", "")]).is_err());
+        assert!(parse_server_config(&[("CORS_ORIGINS", "*,https://oracle.example.com")]).is_err());
+        assert!(parse_server_config(&[("CORS_ORIGINS", "oracle.example.com")]).is_err());
+    }
+    
+    #[actix_web::test]
+    async fn test_cors_allowlist() {
+        let origins = CorsOrigins::List(vec!["https://oracle.example.com".to_string()]);
+        let app = actix_test::init_service(
+            App::new()
+                .wrap(origins.cors())
+                .app_data(web::Data::new(SignerService::unconfigured()))
+                .service(get_signer_pubkey),
+        ).await;
+        let from = |origin: &str| actix_test::TestRequest::get()
+            .uri("/signer/pubkey")
+            .insert_header(("Origin", origin.to_string()))
+            .to_request();
+        let allow_origin = |headers: &actix_web::http::header::HeaderMap| headers
+            .get("access-control-allow-origin")
+            .map(|value| value.to_str().unwrap().to_string());
+        
+        let allowed = actix_test::call_service(&app, from("https://oracle.example.com")).await;
+        assert_eq!(allow_origin(allowed.headers()).as_deref(), Some("https://oracle.example.com"));
+        
+        let blocked = actix_test::call_service(&app, from("https://evil.example.com")).await;
+        assert_eq!(allow_origin(blocked.headers()), None);
+    }
+    
+    /// Generates a self-signed certificate for `localhost`, loads it the same way
+    /// `main` does and checks that a TLS server binds and accepts connections.
+    #[actix_web::test]
+    async fn test_tls_server_starts_with_self_signed_cert() {
+        let dir = std::env::temp_dir().join(format!("sentiment-tls-test-{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        let cert = rcgen::generate_simple_self_signed(vec!["localhost".to_string()]).unwrap();
+        let tls = TlsFiles {
+            cert_file: dir.join("cert.pem").to_string_lossy().into_owned(),
+            key_file: dir.join("key.pem").to_string_lossy().into_owned(),
+        };
+        fs::write(&tls.cert_file, cert.serialize_pem().unwrap()).unwrap();
+        fs::write(&tls.key_file, cert.serialize_private_key_pem()).unwrap();
+        
+        let rustls_config = load_rustls_config(&tls).unwrap();
+        let server = HttpServer::new(|| App::new().service(dashboard))
+            .workers(1)
+            .bind_rustls_021("127.0.0.1:0", rustls_config)
+            .unwrap();
+        let address = server.addrs()[0];
+        let server = server.run();
+        let handle = server.handle();
+        tokio::spawn(server);
+        
+        tokio::net::TcpStream::connect(address).await.unwrap();
+        
+        handle.stop(true).await;
+        fs::remove_dir_all(&dir).unwrap();
+    }
+    
+    #[actix_web::test]
+    async fn test_rpc_parse_error() {
+        let response = post_rpc("{\"jsonrpc\": \"2.0\", \"method\"").await;
+        
+        assert_eq!(response["error"]["code"], JsonRpcError::PARSE_ERROR);
+        assert_eq!(response["id"], serde_json::Value::Null);
+    }
+    
+    #[actix_web::test]
+    async fn test_rpc_method_not_found() {
+        let response = post_rpc(r#"{"jsonrpc": "2.0", "method": "oracle_nope", "id": 7}"#).await;
+        
+        assert_eq!(response["error"]["code"], JsonRpcError::METHOD_NOT_FOUND);
+        assert_eq!(response["id"], 7);
+    }
+    
+    #[actix_web::test]
+    async fn test_rpc_maps_not_found_error() {
+        let response = post_rpc(
+            r#"{"jsonrpc": "2.0", "method": "oracle_getLatestSentiment", "params": {"asset": "BTC"}, "id": "a"}"#,
+        ).await;
+        
+        assert_eq!(response["error"]["code"], JsonRpcError::NOT_FOUND);
+        assert_eq!(response["id"], "a");
+    }
+    
+    #[actix_web::test]
+    async fn test_rpc_batch() {
+        let response = post_rpc(r#"[
+            {"jsonrpc": "2.0", "method": "oracle_getLatestSentiment", "params": ["BTC"], "id": 1},
+            {"jsonrpc": "2.0", "method": "oracle_getLatestSentiment", "params": ["BTC"]},
+            {"jsonrpc": "2.0", "method": "oracle_getSentimentHistory", "params": [], "id": 2},
+            {"foo": "bar"}
+        ]"#).await;
+        
+        let responses = response.as_array().unwrap();
+        assert_eq!(responses.len(), 3);
+        assert_eq!(responses[0]["id"], 1);
+        assert_eq!(responses[0]["error"]["code"], JsonRpcError::NOT_FOUND);
+        assert_eq!(responses[1]["id"], 2);
+        assert_eq!(responses[1]["error"]["code"], JsonRpcError::INVALID_PARAMS);
+        assert_eq!(responses[2]["error"]["code"], JsonRpcError::INVALID_REQUEST);
+    }
+    
+    #[actix_web::test]
+    async fn test_rpc_empty_batch_is_invalid() {
+        let response = post_rpc("[]").await;
+        
+        assert_eq!(response["error"]["code"], JsonRpcError::INVALID_REQUEST);
+    }
 }
@@ -0,0 +1,266 @@
+use std::future::{ready, Future, Ready};
+use std::pin::Pin;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use actix_web::body::{to_bytes, BodySize, BoxBody, MessageBody};
+use actix_web::dev::{forward_ready, Service, ServiceRequest, ServiceResponse, Transform};
+use actix_web::http::header::{self, HeaderValue};
+use actix_web::http::{Method, StatusCode};
+use actix_web::{Error, HttpResponse};
+use dashmap::DashMap;
+use sha2::{Digest, Sha256};
+
+/// How long a computed ETag is trusted before the handler must run again
+const ETAG_TTL: Duration = Duration::from_secs(60);
+
+const CACHE_CONTROL: &str = "max-age=30";
+
+struct CachedETag {
+    etag: String,
+    // The response's `Vary`, repeated on `304 Not Modified` answers
+    vary: Option<HeaderValue>,
+    created: Instant,
+}
+
+/// Middleware adding `ETag`/`Cache-Control` headers and answering matching
+/// `If-None-Match` requests with `304 Not Modified`
+#[derive(Clone, Default)]
+pub struct ETagCache {
+    // ETags of recent GET responses keyed by `cache_key`
+    entries: Arc<DashMap<String, CachedETag>>,
+}
+
+impl ETagCache {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Forget every stored ETag, e.g. after the underlying data changed
+    pub fn clear(&self) {
+        self.entries.clear();
+    }
+}
+
+impl<S, B> Transform<S, ServiceRequest> for ETagCache
+where
+    S: Service<ServiceRequest, Response = ServiceResponse<B>, Error = Error> + 'static,
+    B: MessageBody + 'static,
+{
+    type Response = ServiceResponse<BoxBody>;
+    type Error = Error;
+    type Transform = ETagMiddleware<S>;
+    type InitError = ();
+    type Future = Ready<Result<Self::Transform, Self::InitError>>;
+
+    fn new_transform(&self, service: S) -> Self::Future {
+        ready(Ok(ETagMiddleware {
+            service,
+            entries: self.entries.clone(),
+        }))
+    }
+}
+
+pub struct ETagMiddleware<S> {
+    service: S,
+    entries: Arc<DashMap<String, CachedETag>>,
+}
+
+impl<S, B> Service<ServiceRequest> for ETagMiddleware<S>
+where
+    S: Service<ServiceRequest, Response = ServiceResponse<B>, Error = Error> + 'static,
+    B: MessageBody + 'static,
+{
+    type Response = ServiceResponse<BoxBody>;
+    type Error = Error;
+    type Future = Pin<Box<dyn Future<Output = Result<Self::Response, Self::Error>>>>;
+
+    forward_ready!(service);
+
+    fn call(&self, req: ServiceRequest) -> Self::Future {
+        let cacheable = req.method() == Method::GET;
+        let key = cache_key(&req);
+        let if_none_match = req.headers()
+            .get(header::IF_NONE_MATCH)
+            .and_then(|value| value.to_str().ok())
+            .map(str::to_string);
+
+        // A client holding the current ETag doesn't need the handler to run at all
+        if cacheable {
+            if let (Some(if_none_match), Some(cached)) = (&if_none_match, self.entries.get(&key)) {
+                if cached.created.elapsed() < ETAG_TTL && etag_matches(if_none_match, &cached.etag) {
+                    let response = not_modified(&cached.etag, cached.vary.as_ref());
+                    return Box::pin(async move { Ok(req.into_response(response)) });
+                }
+            }
+        }
+
+        let entries = self.entries.clone();
+        let fut = self.service.call(req);
+
+        Box::pin(async move {
+            let res = fut.await?;
+
+            // Streamed bodies are passed through rather than buffered to hash them
+            let streaming = matches!(res.response().body().size(), BodySize::Stream);
+            if !cacheable || streaming || res.status() != StatusCode::OK {
+                let mut res = res.map_into_boxed_body();
+                res.headers_mut().insert(header::CACHE_CONTROL, HeaderValue::from_static(CACHE_CONTROL));
+                return Ok(res);
+            }
+
+            let (req, res) = res.into_parts();
+            let (head, body) = res.into_parts();
+            let bytes = to_bytes(body).await.map_err(|e| actix_web::error::ErrorInternalServerError(e.into()))?;
+            let etag = format!("\"{}\"", hex::encode(Sha256::digest(&bytes)));
+            let vary = head.headers().get(header::VARY).cloned();
+
+            entries.retain(|_, cached| cached.created.elapsed() < ETAG_TTL);
+            entries.insert(key, CachedETag { etag: etag.clone(), vary: vary.clone(), created: Instant::now() });
+
+            let response = match &if_none_match {
+                Some(if_none_match) if etag_matches(if_none_match, &etag) => not_modified(&etag, vary.as_ref()),
+                _ => {
+                    let mut response = head.set_body(bytes).map_into_boxed_body();
+                    let headers = response.headers_mut();
+                    headers.insert(header::ETAG, HeaderValue::from_str(&etag)?);
+                    headers.insert(header::CACHE_CONTROL, HeaderValue::from_static(CACHE_CONTROL));
+                    response
+                }
+            };
+
+            Ok(ServiceResponse::new(req, response))
+        })
+    }
+}
+
+/// Method, path and query (which carries `format=`) plus the `Accept` header, lowercased and
+/// without whitespace: handlers negotiate the response format from both
+fn cache_key(req: &ServiceRequest) -> String {
+    let accept: String = req.headers()
+        .get(header::ACCEPT)
+        .and_then(|value| value.to_str().ok())
+        .unwrap_or_default()
+        .chars()
+        .filter(|c| !c.is_whitespace())
+        .collect::<String>()
+        .to_ascii_lowercase();
+    format!("{} {}?{} accept={}", req.method(), req.path(), req.query_string(), accept)
+}
+
+// `If-None-Match` may list several ETags, or `*`
+fn etag_matches(if_none_match: &str, etag: &str) -> bool {
+    if_none_match
+        .split(',')
+        .map(|candidate| candidate.trim().trim_start_matches("W/"))
+        .any(|candidate| candidate == "*" || candidate == etag)
+}
+
+fn not_modified(etag: &str, vary: Option<&HeaderValue>) -> HttpResponse {
+    let mut response = HttpResponse::NotModified();
+    response
+        .insert_header((header::ETAG, etag))
+        .insert_header((header::CACHE_CONTROL, CACHE_CONTROL));
+    if let Some(vary) = vary {
+        response.insert_header((header::VARY, vary.clone()));
+    }
+    response.finish()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use actix_web::test as actix_test;
+    use actix_web::{web, App};
+
+    #[actix_web::test]
+    async fn test_second_identical_request_is_not_modified() {
+        let app = actix_test::init_service(
+            App::new()
+                .wrap(ETagCache::new())
+                .route("/latest", web::get().to(|| async { HttpResponse::Ok().json(serde_json::json!({ "price": 1.0 })) })),
+        ).await;
+
+        let first = actix_test::call_service(&app, actix_test::TestRequest::get().uri("/latest?asset=BTC").to_request()).await;
+        assert_eq!(first.status(), StatusCode::OK);
+        assert_eq!(first.headers().get(header::CACHE_CONTROL).unwrap(), "max-age=30");
+        let etag = first.headers().get(header::ETAG).unwrap().to_str().unwrap().to_string();
+
+        let second = actix_test::call_service(
+            &app,
+            actix_test::TestRequest::get()
+                .uri("/latest?asset=BTC")
+                .insert_header((header::IF_NONE_MATCH, etag.as_str()))
+                .to_request(),
+        ).await;
+        assert_eq!(second.status(), StatusCode::NOT_MODIFIED);
+        assert_eq!(second.headers().get(header::ETAG).unwrap().to_str().unwrap(), etag);
+        assert!(actix_test::read_body(second).await.is_empty());
+    }
+
+    #[actix_web::test]
+    async fn test_etag_is_per_query() {
+        let app = actix_test::init_service(
+            App::new()
+                .wrap(ETagCache::new())
+                .route("/latest", web::get().to(|req: actix_web::HttpRequest| async move {
+                    HttpResponse::Ok().body(req.query_string().to_string())
+                })),
+        ).await;
+
+        let btc = actix_test::call_service(&app, actix_test::TestRequest::get().uri("/latest?asset=BTC").to_request()).await;
+        let etag = btc.headers().get(header::ETAG).unwrap().to_str().unwrap().to_string();
+
+        let eth = actix_test::call_service(
+            &app,
+            actix_test::TestRequest::get()
+                .uri("/latest?asset=ETH")
+                .insert_header((header::IF_NONE_MATCH, etag.as_str()))
+                .to_request(),
+        ).await;
+        assert_eq!(eth.status(), StatusCode::OK);
+    }
+
+    #[actix_web::test]
+    async fn test_etag_is_per_accept() {
+        let app = actix_test::init_service(
+            App::new()
+                .wrap(ETagCache::new())
+                .route("/latest", web::get().to(|req: actix_web::HttpRequest| async move {
+                    let csv = req.headers().get(header::ACCEPT).is_some_and(|accept| accept == "text/csv");
+                    HttpResponse::Ok()
+                        .insert_header((header::VARY, "Accept"))
+                        .body(if csv { "price\n1.0\n" } else { "{\"price\":1.0}" })
+                })),
+        ).await;
+        let request = |accept: &str, etag: Option<&str>| {
+            let mut request = actix_test::TestRequest::get().uri("/latest?asset=BTC").insert_header((header::ACCEPT, accept));
+            if let Some(etag) = etag {
+                request = request.insert_header((header::IF_NONE_MATCH, etag));
+            }
+            request.to_request()
+        };
+
+        let csv = actix_test::call_service(&app, request("text/csv", None)).await;
+        assert_eq!(csv.headers().get(header::VARY).unwrap(), "Accept");
+        let etag = csv.headers().get(header::ETAG).unwrap().to_str().unwrap().to_string();
+
+        // The CSV ETag doesn't vouch for the JSON representation
+        let json = actix_test::call_service(&app, request("application/json", Some(&etag))).await;
+        assert_eq!(json.status(), StatusCode::OK);
+        assert_eq!(actix_test::read_body(json).await, "{\"price\":1.0}");
+
+        // The same Accept, however it is spaced or cased, revalidates with Vary kept
+        let revalidated = actix_test::call_service(&app, request("Text/CSV ", Some(&etag))).await;
+        assert_eq!(revalidated.status(), StatusCode::NOT_MODIFIED);
+        assert_eq!(revalidated.headers().get(header::VARY).unwrap(), "Accept");
+    }
+
+    #[test]
+    fn test_etag_matching() {
+        assert!(etag_matches("\"abc\"", "\"abc\""));
+        assert!(etag_matches("\"x\", W/\"abc\"", "\"abc\""));
+        assert!(etag_matches("*", "\"abc\""));
+        assert!(!etag_matches("\"abd\"", "\"abc\""));
+    }
+}
@@ -0,0 +1,7 @@
+pub mod etag;
+pub mod rate_limit;
+pub mod request_id;
+
+pub use etag::ETagCache;
+pub use rate_limit::RateLimitMiddleware;
+pub use request_id::{current_request_id, RequestId};
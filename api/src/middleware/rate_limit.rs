@@ -0,0 +1,151 @@
+use std::net::IpAddr;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use actix_web::body::{EitherBody, MessageBody};
+use actix_web::dev::{ServiceRequest, ServiceResponse};
+use actix_web::http::header;
+use actix_web::middleware::Next;
+use actix_web::{Error, HttpResponse};
+use dashmap::DashMap;
+
+/// How often buckets of clients that went quiet are dropped
+const CLEANUP_INTERVAL: Duration = Duration::from_secs(60);
+
+/// Tokens left for one client address
+#[derive(Debug, Clone)]
+pub struct TokenBucket {
+    tokens: f64,
+    last_refill: Instant,
+}
+
+/// Per-IP token-bucket rate limiting: each address may burst up to `capacity`
+/// requests and regains `refill_rate` requests per second. Use with
+/// `actix_web::middleware::from_fn`, calling [`RateLimitMiddleware::limit`]
+#[derive(Clone)]
+pub struct RateLimitMiddleware {
+    store: Arc<DashMap<IpAddr, TokenBucket>>,
+    capacity: u32,
+    refill_rate: f64,
+}
+
+impl RateLimitMiddleware {
+    pub fn new(capacity: u32, refill_rate: f64) -> Self {
+        Self {
+            store: Arc::new(DashMap::new()),
+            capacity,
+            refill_rate,
+        }
+    }
+
+    /// Take a token for `ip`; `Err` holds the seconds until one is available
+    pub fn check(&self, ip: IpAddr) -> Result<(), u64> {
+        let now = Instant::now();
+        let capacity = f64::from(self.capacity);
+        let mut bucket = self.store.entry(ip).or_insert_with(|| TokenBucket { tokens: capacity, last_refill: now });
+
+        let elapsed = now.duration_since(bucket.last_refill).as_secs_f64();
+        bucket.tokens = (bucket.tokens + elapsed * self.refill_rate).min(capacity);
+        bucket.last_refill = now;
+
+        if bucket.tokens < 1.0 {
+            return Err(((1.0 - bucket.tokens) / self.refill_rate).ceil().max(1.0) as u64);
+        }
+        bucket.tokens -= 1.0;
+        Ok(())
+    }
+
+    /// Forget buckets that have refilled completely, since they behave like new ones
+    pub fn remove_stale(&self) {
+        let now = Instant::now();
+        let capacity = f64::from(self.capacity);
+        self.store.retain(|_, bucket| {
+            bucket.tokens + now.duration_since(bucket.last_refill).as_secs_f64() * self.refill_rate < capacity
+        });
+    }
+
+    /// Drop stale buckets every minute in the background
+    pub fn spawn_cleanup(&self) {
+        let limiter = self.clone();
+        tokio::spawn(async move {
+            let mut interval = tokio::time::interval(CLEANUP_INTERVAL);
+            loop {
+                interval.tick().await;
+                limiter.remove_stale();
+            }
+        });
+    }
+
+    /// Middleware body: answer `429 Too Many Requests` once the caller's bucket is empty.
+    /// Requests without a peer address (e.g. over a Unix socket) are not limited
+    pub async fn limit<B: MessageBody>(
+        self,
+        req: ServiceRequest,
+        next: Next<B>,
+    ) -> Result<ServiceResponse<EitherBody<B>>, Error> {
+        if let Some(addr) = req.peer_addr() {
+            if let Err(retry_after) = self.check(addr.ip()) {
+                let response = HttpResponse::TooManyRequests()
+                    .insert_header((header::RETRY_AFTER, retry_after.to_string()))
+                    .json(crate::ErrorResponse {
+                        status: 429,
+                        message: "Too many requests".to_string(),
+                        request_id: super::current_request_id(),
+                    });
+                return Ok(req.into_response(response).map_into_right_body());
+            }
+        }
+        Ok(next.call(req).await?.map_into_left_body())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use actix_web::middleware::from_fn;
+    use actix_web::test as actix_test;
+    use actix_web::{web, App};
+
+    fn peer(ip: &str) -> std::net::SocketAddr {
+        format!("{}:4000", ip).parse().unwrap()
+    }
+
+    #[actix_web::test]
+    async fn test_requests_over_capacity_are_rejected() {
+        let limiter = RateLimitMiddleware::new(2, 0.5);
+        let app = actix_test::init_service(
+            App::new()
+                .wrap(from_fn(move |req, next| limiter.clone().limit(req, next)))
+                .route("/", web::get().to(HttpResponse::Ok)),
+        ).await;
+        let from = |ip: &str| actix_test::TestRequest::get().uri("/").peer_addr(peer(ip)).to_request();
+
+        for _ in 0..2 {
+            assert_eq!(actix_test::call_service(&app, from("10.0.0.1")).await.status(), 200);
+        }
+        let limited = actix_test::call_service(&app, from("10.0.0.1")).await;
+        assert_eq!(limited.status(), 429);
+        assert_eq!(limited.headers().get(header::RETRY_AFTER).unwrap(), "2");
+
+        // Each address has its own bucket
+        assert_eq!(actix_test::call_service(&app, from("10.0.0.2")).await.status(), 200);
+    }
+
+    #[test]
+    fn test_bucket_refills_over_time() {
+        let limiter = RateLimitMiddleware::new(1, 10.0);
+        let ip: IpAddr = "10.0.0.1".parse().unwrap();
+        assert!(limiter.check(ip).is_ok());
+        assert_eq!(limiter.check(ip), Err(1));
+
+        limiter.store.get_mut(&ip).unwrap().last_refill -= Duration::from_millis(150);
+        assert!(limiter.check(ip).is_ok());
+
+        // Only buckets that are full again are dropped
+        limiter.remove_stale();
+        assert_eq!(limiter.store.len(), 1);
+        limiter.store.get_mut(&ip).unwrap().last_refill -= Duration::from_secs(1);
+        limiter.remove_stale();
+        assert!(limiter.store.is_empty());
+    }
+}
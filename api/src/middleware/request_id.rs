@@ -0,0 +1,135 @@
+use std::future::{ready, Future, Ready};
+use std::pin::Pin;
+
+use actix_web::dev::{forward_ready, Service, ServiceRequest, ServiceResponse, Transform};
+use actix_web::http::header::{HeaderName, HeaderValue};
+use actix_web::{Error, HttpMessage};
+use uuid::Uuid;
+
+pub const REQUEST_ID_HEADER: HeaderName = HeaderName::from_static("x-request-id");
+
+/// Longest incoming `X-Request-Id` that is honored; longer ones are replaced
+const MAX_REQUEST_ID_LEN: usize = 128;
+
+tokio::task_local! {
+    static REQUEST_ID: String;
+}
+
+/// Id of the request being handled on this task, if it passed through [`RequestId`]
+pub fn current_request_id() -> Option<String> {
+    REQUEST_ID.try_with(Clone::clone).ok()
+}
+
+/// Id assigned to a request, also available from its extensions
+#[derive(Debug, Clone, PartialEq)]
+pub struct RequestIdValue(pub String);
+
+/// Middleware giving every request an id: the client's `X-Request-Id` when it
+/// sent a usable one, a fresh UUID otherwise. The id is echoed in the
+/// `X-Request-Id` response header and readable through [`current_request_id`]
+/// while the request is handled
+#[derive(Clone, Default)]
+pub struct RequestId;
+
+impl<S, B> Transform<S, ServiceRequest> for RequestId
+where
+    S: Service<ServiceRequest, Response = ServiceResponse<B>, Error = Error> + 'static,
+    B: 'static,
+{
+    type Response = ServiceResponse<B>;
+    type Error = Error;
+    type Transform = RequestIdMiddleware<S>;
+    type InitError = ();
+    type Future = Ready<Result<Self::Transform, Self::InitError>>;
+
+    fn new_transform(&self, service: S) -> Self::Future {
+        ready(Ok(RequestIdMiddleware { service }))
+    }
+}
+
+pub struct RequestIdMiddleware<S> {
+    service: S,
+}
+
+impl<S, B> Service<ServiceRequest> for RequestIdMiddleware<S>
+where
+    S: Service<ServiceRequest, Response = ServiceResponse<B>, Error = Error> + 'static,
+    B: 'static,
+{
+    type Response = ServiceResponse<B>;
+    type Error = Error;
+    type Future = Pin<Box<dyn Future<Output = Result<Self::Response, Self::Error>>>>;
+
+    forward_ready!(service);
+
+    fn call(&self, req: ServiceRequest) -> Self::Future {
+        let id = req.headers()
+            .get(&REQUEST_ID_HEADER)
+            .and_then(|value| value.to_str().ok())
+            .map(str::trim)
+            .filter(|id| is_usable(id))
+            .map(str::to_string)
+            .unwrap_or_else(|| Uuid::new_v4().to_string());
+        req.extensions_mut().insert(RequestIdValue(id.clone()));
+
+        // Errors are rendered inside the handler's future, so the scope covers them too
+        let fut = REQUEST_ID.scope(id.clone(), self.service.call(req));
+
+        Box::pin(async move {
+            let mut res = fut.await?;
+            if let Ok(value) = HeaderValue::from_str(&id) {
+                res.headers_mut().insert(REQUEST_ID_HEADER, value);
+            }
+            Ok(res)
+        })
+    }
+}
+
+// Printable ASCII without spaces, so the id is safe to log and to send back as a header
+fn is_usable(id: &str) -> bool {
+    !id.is_empty() && id.len() <= MAX_REQUEST_ID_LEN && id.bytes().all(|b| b.is_ascii_graphic())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use actix_web::test as actix_test;
+    use actix_web::{web, App, HttpResponse};
+
+    async fn echo() -> HttpResponse {
+        HttpResponse::Ok().body(current_request_id().unwrap_or_default())
+    }
+
+    #[actix_web::test]
+    async fn test_request_id_generated_or_honored() {
+        let app = actix_test::init_service(
+            App::new()
+                .wrap(RequestId)
+                .route("/echo", web::get().to(echo)),
+        ).await;
+
+        let generated = actix_test::call_service(&app, actix_test::TestRequest::get().uri("/echo").to_request()).await;
+        let id = generated.headers().get(&REQUEST_ID_HEADER).unwrap().to_str().unwrap().to_string();
+        assert!(Uuid::parse_str(&id).is_ok(), "{}", id);
+        assert_eq!(actix_test::read_body(generated).await, id.as_bytes());
+
+        let honored = actix_test::call_service(
+            &app,
+            actix_test::TestRequest::get().uri("/echo").insert_header((REQUEST_ID_HEADER, "client-42")).to_request(),
+        ).await;
+        assert_eq!(honored.headers().get(&REQUEST_ID_HEADER).unwrap(), "client-42");
+        assert_eq!(actix_test::read_body(honored).await, "client-42".as_bytes());
+
+        // Unusable ids are replaced rather than echoed
+        for bad in ["has space", &"x".repeat(MAX_REQUEST_ID_LEN + 1)] {
+            let res = actix_test::call_service(
+                &app,
+                actix_test::TestRequest::get().uri("/echo").insert_header((REQUEST_ID_HEADER, bad)).to_request(),
+            ).await;
+            let id = res.headers().get(&REQUEST_ID_HEADER).unwrap().to_str().unwrap();
+            assert!(Uuid::parse_str(id).is_ok(), "{}", id);
+        }
+
+        assert_eq!(current_request_id(), None);
+    }
+}
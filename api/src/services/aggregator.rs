@@ -0,0 +1,222 @@
+// Aggregation of sentiment pushed by several oracle nodes for the same asset
+use std::collections::{HashMap, HashSet, VecDeque};
+use std::sync::Mutex;
+
+use serde::{Deserialize, Serialize};
+
+use crate::{canonical_asset, ApiError, SentimentLabel, SignedSentimentData};
+
+/// Number of submissions kept per asset, one per signer; older ones are evicted first
+pub const AGGREGATOR_CAPACITY: usize = 10;
+
+/// Composite sentiment for an asset, built from the buffered submissions
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct AggregatedSentiment {
+    pub asset: String,
+    /// Confidence-weighted mean polarity, from -1.0 (all negative) to 1.0 (all positive)
+    pub score: f64,
+    /// Label with the most votes
+    pub label: SentimentLabel,
+    /// Mean confidence of the submissions
+    pub confidence: f64,
+    /// Number of distinct signers among the submissions
+    pub source_count: usize,
+}
+
+/// Ring buffer of recent signed submissions per asset
+#[derive(Default)]
+pub struct SentimentAggregator {
+    entries: Mutex<HashMap<String, VecDeque<SignedSentimentData>>>,
+    // Base64 public keys of the oracle nodes allowed to publish
+    signers: HashSet<String>,
+}
+
+impl SentimentAggregator {
+    /// Aggregator accepting submissions from the nodes with these base64 public keys
+    pub fn with_signers(signers: impl IntoIterator<Item = String>) -> Self {
+        Self {
+            signers: signers.into_iter().map(|key| key.trim().to_string()).collect(),
+            ..Self::default()
+        }
+    }
+
+    /// Check that `public_key` belongs to a configured node; with none configured, publishing is disabled
+    pub fn authorize(&self, public_key: &str) -> Result<(), ApiError> {
+        if self.signers.is_empty() {
+            return Err(ApiError::Unauthorized("Publishing is disabled (PUBLISH_SIGNERS not set)".to_string()));
+        }
+        if !self.signers.contains(public_key.trim()) {
+            return Err(ApiError::Unauthorized("Signer is not an allowed oracle node".to_string()));
+        }
+        Ok(())
+    }
+
+    /// Add a submission, replacing the signer's previous one for the asset and evicting the
+    /// oldest once the asset's buffer is full
+    pub fn push(&self, asset: &str, data: SignedSentimentData) {
+        let mut entries = self.entries.lock().unwrap();
+        let buffer = entries.entry(canonical_asset(asset)).or_default();
+        buffer.retain(|entry| entry.public_key.trim() != data.public_key.trim());
+        if buffer.len() == AGGREGATOR_CAPACITY {
+            buffer.pop_front();
+        }
+        buffer.push_back(data);
+    }
+
+    /// Combine the buffered submissions for an asset.
+    /// Assets with no submissions come back neutral with a `source_count` of 0.
+    pub fn aggregate(&self, asset: &str) -> AggregatedSentiment {
+        let asset = canonical_asset(asset);
+        let entries = self.entries.lock().unwrap();
+        let buffer = entries.get(&asset).map(|b| b.iter().collect::<Vec<_>>()).unwrap_or_default();
+
+        let total_confidence: f64 = buffer.iter().map(|e| e.data.score).sum();
+        let score = if total_confidence > 0.0 {
+            buffer.iter().map(|e| polarity(e.data.label) * e.data.score).sum::<f64>() / total_confidence
+        } else {
+            0.0
+        };
+        let confidence = if buffer.is_empty() {
+            0.0
+        } else {
+            total_confidence / buffer.len() as f64
+        };
+        let source_count = buffer.iter().map(|e| e.public_key.as_str()).collect::<HashSet<_>>().len();
+
+        AggregatedSentiment {
+            asset,
+            score,
+            label: majority_label(&buffer),
+            confidence,
+            source_count,
+        }
+    }
+}
+
+// The "very" labels count the same as their plain counterparts, since the score tops out at ±1.0
+fn polarity(label: SentimentLabel) -> f64 {
+    match label {
+        SentimentLabel::Positive | SentimentLabel::VeryPositive => 1.0,
+        SentimentLabel::Negative | SentimentLabel::VeryNegative => -1.0,
+        SentimentLabel::Neutral => 0.0,
+    }
+}
+
+/// Label with the most votes; ties go to the label with more total confidence,
+/// and a tie on both is reported as neutral
+fn majority_label(entries: &[&SignedSentimentData]) -> SentimentLabel {
+    let mut tally: HashMap<SentimentLabel, (usize, f64)> = HashMap::new();
+    for entry in entries {
+        let (votes, confidence) = tally.entry(entry.data.label).or_default();
+        *votes += 1;
+        *confidence += entry.data.score;
+    }
+
+    let mut ranked: Vec<_> = tally.into_iter().collect();
+    ranked.sort_by(|(_, a), (_, b)| b.0.cmp(&a.0).then(b.1.total_cmp(&a.1)));
+
+    match ranked.as_slice() {
+        [] => SentimentLabel::Neutral,
+        [(label, _)] => *label,
+        [(label, first), (_, second), ..] if first != second => *label,
+        _ => SentimentLabel::Neutral,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::SentimentData;
+
+    fn submission(signer: &str, label: SentimentLabel, score: f64) -> SignedSentimentData {
+        SignedSentimentData {
+            data: SentimentData {
+                id: "1".to_string(),
+                text: "gm".to_string(),
+                label,
+                score,
+                date: Some("2025-05-15".to_string()),
+                timestamp: None,
+                username: "oracle".to_string(),
+                source: "twitter".to_string(),
+                signature: None,
+                public_key: None,
+                category: None,
+                degraded: false,
+                asset: None,
+                label_as_received: None,
+            },
+            signature: "sig".to_string(),
+            public_key: signer.to_string(),
+        }
+    }
+
+    #[test]
+    fn test_weighted_score_and_majority() {
+        let aggregator = SentimentAggregator::default();
+        aggregator.push("btc", submission("node-a", SentimentLabel::Positive, 0.9));
+        aggregator.push("BTC", submission("node-b", SentimentLabel::Positive, 0.6));
+        aggregator.push("BTC", submission("node-c", SentimentLabel::Negative, 0.5));
+
+        let result = aggregator.aggregate("btc");
+        assert_eq!(result.asset, "BTC");
+        assert_eq!(result.label, SentimentLabel::Positive);
+        assert_eq!(result.source_count, 3);
+        assert!((result.score - 0.5).abs() < 1e-9);
+        assert!((result.confidence - 2.0 / 3.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_oldest_entries_evicted() {
+        let aggregator = SentimentAggregator::default();
+        aggregator.push("SOL", submission("node-a", SentimentLabel::Negative, 0.8));
+        for i in 0..AGGREGATOR_CAPACITY {
+            aggregator.push("SOL", submission(&format!("node-{}", i), SentimentLabel::Positive, 0.7));
+        }
+
+        let result = aggregator.aggregate("SOL");
+        assert_eq!(result.label, SentimentLabel::Positive);
+        assert_eq!(result.source_count, AGGREGATOR_CAPACITY);
+        assert!((result.score - 1.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_one_entry_per_signer() {
+        let aggregator = SentimentAggregator::default();
+        for _ in 0..AGGREGATOR_CAPACITY {
+            aggregator.push("BTC", submission("node-a", SentimentLabel::Negative, 0.9));
+        }
+        aggregator.push("BTC", submission("node-b", SentimentLabel::Positive, 0.6));
+        aggregator.push("BTC", submission("node-c", SentimentLabel::Positive, 0.6));
+
+        // node-a's repeats took a single slot, so it can't outvote the other two
+        let result = aggregator.aggregate("BTC");
+        assert_eq!(result.source_count, 3);
+        assert_eq!(result.label, SentimentLabel::Positive);
+
+        // A newer submission replaces the signer's earlier one
+        aggregator.push("BTC", submission("node-a", SentimentLabel::Positive, 0.9));
+        assert_eq!(aggregator.aggregate("BTC").source_count, 3);
+        assert!((aggregator.aggregate("BTC").score - 1.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_only_configured_signers_authorized() {
+        assert!(matches!(SentimentAggregator::default().authorize("node-a"), Err(ApiError::Unauthorized(_))));
+
+        let aggregator = SentimentAggregator::with_signers([" node-a ".to_string()]);
+        assert!(aggregator.authorize("node-a").is_ok());
+        assert!(matches!(aggregator.authorize("node-b"), Err(ApiError::Unauthorized(_))));
+    }
+
+    #[test]
+    fn test_tie_and_empty_are_neutral() {
+        let aggregator = SentimentAggregator::default();
+        assert_eq!(aggregator.aggregate("ETH").source_count, 0);
+        assert_eq!(aggregator.aggregate("ETH").label, SentimentLabel::Neutral);
+
+        aggregator.push("ETH", submission("node-a", SentimentLabel::Positive, 0.7));
+        aggregator.push("ETH", submission("node-b", SentimentLabel::Negative, 0.7));
+        assert_eq!(aggregator.aggregate("ETH").label, SentimentLabel::Neutral);
+    }
+}
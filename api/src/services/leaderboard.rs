@@ -0,0 +1,141 @@
+// Ranking of the sources feeding the oracle node by how closely they track consensus
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use serde::{Deserialize, Serialize};
+
+use crate::stats::StatsStore;
+use crate::{canonical_asset, ApiError};
+
+/// One signer's record over every recorded consensus round for an asset
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct LeaderboardEntry {
+    /// Source that signed the price, as recorded by the node
+    pub signer: String,
+    pub submission_count: u64,
+    /// Mean absolute deviation from the consensus price, in percent
+    pub avg_deviation_pct: f64,
+    /// Mean of `1.0 - min(|deviation| / consensus_price, 1.0)`: 1.0 always matched consensus,
+    /// 0.0 was always off by 100% or more
+    pub accuracy_score: f64,
+}
+
+/// Signers of an asset's prices, most accurate first
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct LeaderboardResponse {
+    pub asset: String,
+    pub entries: Vec<LeaderboardEntry>,
+}
+
+/// Builds leaderboards from the consensus rounds in the history database
+pub struct LeaderboardService {
+    store: Arc<StatsStore>,
+}
+
+impl LeaderboardService {
+    pub fn new(store: Arc<StatsStore>) -> Self {
+        Self { store }
+    }
+
+    /// Rank the asset's signers by accuracy score, then by submission count and name.
+    /// 404 when no round for the asset was recorded with source prices.
+    pub fn leaderboard(&self, asset: &str) -> Result<LeaderboardResponse, ApiError> {
+        let asset = canonical_asset(asset);
+        let prices = self.store.source_prices(&asset)
+            .map_err(|e| ApiError::InternalServerError(format!("Failed to read price history: {}", e)))?;
+
+        let entries = rank(prices);
+        if entries.is_empty() {
+            return Err(ApiError::NotFound(format!("No consensus rounds with source prices for {}", asset)));
+        }
+        Ok(LeaderboardResponse { asset, entries })
+    }
+}
+
+/// Per-signer entries from (signer, price, consensus price) records, best first.
+/// Records with a non-positive consensus price have no meaningful deviation and are skipped.
+fn rank(prices: impl IntoIterator<Item = (String, f64, f64)>) -> Vec<LeaderboardEntry> {
+    // Signer -> (submissions, summed deviation ratio, summed accuracy)
+    let mut totals: HashMap<String, (u64, f64, f64)> = HashMap::new();
+    for (signer, price, consensus_price) in prices {
+        if consensus_price <= 0.0 {
+            continue;
+        }
+        let deviation = (price - consensus_price).abs() / consensus_price;
+        let (count, deviation_sum, accuracy_sum) = totals.entry(signer).or_default();
+        *count += 1;
+        *deviation_sum += deviation;
+        *accuracy_sum += 1.0 - deviation.min(1.0);
+    }
+
+    let mut entries: Vec<LeaderboardEntry> = totals.into_iter()
+        .map(|(signer, (count, deviation_sum, accuracy_sum))| LeaderboardEntry {
+            signer,
+            submission_count: count,
+            avg_deviation_pct: deviation_sum / count as f64 * 100.0,
+            accuracy_score: accuracy_sum / count as f64,
+        })
+        .collect();
+    entries.sort_by(|a, b| {
+        b.accuracy_score.total_cmp(&a.accuracy_score)
+            .then(b.submission_count.cmp(&a.submission_count))
+            .then_with(|| a.signer.cmp(&b.signer))
+    });
+    entries
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const NOW: i64 = 1_747_300_000;
+
+    #[test]
+    fn test_closest_signer_ranks_first() {
+        let store = Arc::new(StatsStore::in_memory().unwrap());
+        for (round, consensus) in [100.0, 102.0, 98.0, 101.0].into_iter().enumerate() {
+            store.record_price_with_sources("BTC", consensus, NOW + round as i64, &[
+                ("Binance", consensus * 1.001, false),
+                ("CoinGecko", consensus * 0.99, false),
+                ("OKX", consensus * 1.2, true),
+            ]).unwrap();
+        }
+        // A signer that showed up once, exactly on consensus
+        store.record_price_with_sources("BTC", 100.0, NOW + 10, &[("Kraken", 100.0, false)]).unwrap();
+        store.record_price_with_sources("ETH", 3000.0, NOW, &[("OKX", 3000.0, false)]).unwrap();
+
+        let leaderboard = LeaderboardService::new(store).leaderboard("$btc").unwrap();
+        assert_eq!(leaderboard.asset, "BTC");
+        let signers: Vec<&str> = leaderboard.entries.iter().map(|entry| entry.signer.as_str()).collect();
+        assert_eq!(signers, ["Kraken", "Binance", "CoinGecko", "OKX"]);
+
+        let binance = &leaderboard.entries[1];
+        assert_eq!(binance.submission_count, 4);
+        assert!((binance.avg_deviation_pct - 0.1).abs() < 1e-9, "{}", binance.avg_deviation_pct);
+        assert!((binance.accuracy_score - 0.999).abs() < 1e-9);
+        let okx = &leaderboard.entries[3];
+        assert!((okx.avg_deviation_pct - 20.0).abs() < 1e-9);
+        assert!((okx.accuracy_score - 0.8).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_accuracy_floors_at_zero() {
+        let entries = rank([
+            ("Wild".to_string(), 350.0, 100.0),
+            ("Wild".to_string(), 100.0, 100.0),
+            ("Ignored".to_string(), 5.0, 0.0),
+        ]);
+        assert_eq!(entries.len(), 1);
+        assert!((entries[0].accuracy_score - 0.5).abs() < 1e-9);
+        assert!((entries[0].avg_deviation_pct - 125.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_no_rounds_is_not_found() {
+        let store = Arc::new(StatsStore::in_memory().unwrap());
+        store.record_price("SOL", 100.0, 0.9, NOW).unwrap();
+        let service = LeaderboardService::new(store);
+        assert!(matches!(service.leaderboard("SOL"), Err(ApiError::NotFound(_))));
+        assert!(matches!(service.leaderboard("ADA"), Err(ApiError::NotFound(_))));
+    }
+}
@@ -0,0 +1,176 @@
+// Submission of signed sentiment to the on-chain oracle program
+use std::env;
+use std::fs;
+use std::str::FromStr;
+
+use anyhow::Result;
+use chrono::Utc;
+use price_oracle_node::solana_client::{price_account_address, SolanaOracleClient};
+use solana_sdk::{
+    pubkey::Pubkey,
+    signature::{Keypair, Signature},
+};
+
+use crate::{canonical_asset, secret_key_bytes, AdminAuth, ApiError, SignedSentimentData, KEYPAIR_ENV};
+
+/// Attaches signed sentiment to the oracle's on-chain prices through the node's
+/// `SolanaOracleClient`, which signs each submission, adds the Ed25519 verification the
+/// program checks, retries transient RPC failures and refuses mainnet-beta unless
+/// `ALLOW_MAINNET=1` is set
+pub struct SolanaSubmitService {
+    client: SolanaOracleClient,
+    rpc_url: String,
+    program_id: Pubkey,
+    // Bearer token check for POST /oracle/submit
+    auth: AdminAuth,
+}
+
+impl SolanaSubmitService {
+    /// Service submitting to `program_id` as `oracle`, which pays for and signs the transactions
+    pub fn new(rpc_url: &str, program_id: Pubkey, oracle: Keypair, auth: AdminAuth) -> Result<Self> {
+        Ok(Self {
+            client: SolanaOracleClient::with_keypair(rpc_url, Some(program_id.to_string()), oracle)?,
+            rpc_url: rpc_url.to_string(),
+            program_id,
+            auth,
+        })
+    }
+
+    /// Configure from `SOLANA_RPC_URL`, `ORACLE_PROGRAM_ID`, `SUBMIT_TOKEN` and the oracle keypair
+    /// (`ORACLE_KEYPAIR`, then `SIGNER_KEYPAIR_PATH`). Returns `Ok(None)` when no program ID is set.
+    pub fn from_env() -> Result<Option<Self>> {
+        let program_id = match env::var("ORACLE_PROGRAM_ID") {
+            Ok(id) => Pubkey::from_str(&id)?,
+            Err(_) => return Ok(None),
+        };
+        let rpc_url = env::var("SOLANA_RPC_URL")
+            .unwrap_or_else(|_| "https://api.devnet.solana.com".to_string());
+        let secret = match env::var(KEYPAIR_ENV) {
+            Ok(secret) => secret,
+            Err(_) => fs::read_to_string(env::var("SIGNER_KEYPAIR_PATH")
+                .map_err(|_| anyhow::anyhow!("{} or SIGNER_KEYPAIR_PATH is required to submit", KEYPAIR_ENV))?)?,
+        };
+        let oracle = Keypair::from_bytes(&secret_key_bytes(&secret)?)
+            .map_err(|e| anyhow::anyhow!("Invalid {}: {}", KEYPAIR_ENV, e))?;
+        let auth = AdminAuth::named("SUBMIT_TOKEN", env::var("SUBMIT_TOKEN").ok());
+
+        Self::new(&rpc_url, program_id, oracle, auth).map(Some)
+    }
+
+    /// Check the request's bearer token against `SUBMIT_TOKEN`
+    pub fn authorize(&self, req: &actix_web::HttpRequest) -> Result<(), ApiError> {
+        self.auth.authorize(req)
+    }
+
+    /// Attach the sentiment's label to the oracle's price in `asset_account`, which must be the
+    /// account the oracle keeps for the data's `asset`, returning the transaction signature.
+    /// The price, confidence, sources and category on chain are kept, and the submission is
+    /// stamped with the sentiment's timestamp (now when it has none), which must be newer.
+    pub fn submit(&self, signed: &SignedSentimentData, asset_account: &Pubkey) -> Result<Signature, ApiError> {
+        let data = &signed.data;
+        let asset = data.asset.as_deref().map(canonical_asset)
+            .ok_or_else(|| ApiError::BadRequest("Sentiment must name its asset in data.asset".to_string()))?;
+        let expected = price_account_address(&self.client.get_oracle_pubkey(), &asset, &self.program_id)
+            .map_err(|e| ApiError::BadRequest(format!("No price account for {}: {}", asset, e)))?;
+        if *asset_account != expected {
+            return Err(ApiError::BadRequest(format!(
+                "Account {} is not the oracle's {} account ({})", asset_account, asset, expected
+            )));
+        }
+
+        let current = self.client.read_price_account(&asset)
+            .map_err(|e| ApiError::InternalServerError(format!("Failed to read account {}: {}", asset_account, e)))?;
+        if current.price <= 0.0 {
+            return Err(ApiError::BadRequest(format!("Account {} has no price to attach sentiment to yet", asset_account)));
+        }
+        let timestamp = data.timestamp.unwrap_or_else(|| Utc::now().timestamp());
+        if timestamp <= current.timestamp {
+            return Err(ApiError::BadRequest(format!(
+                "Sentiment at {} is not newer than the account's price at {}", timestamp, current.timestamp
+            )));
+        }
+
+        // The client's RPC calls block and its retries sleep on tokio's timer, so it gets a
+        // runtime of its own on this blocking-pool thread
+        let runtime = tokio::runtime::Builder::new_current_thread().enable_all().build()
+            .map_err(|e| ApiError::InternalServerError(format!("Failed to start submission runtime: {}", e)))?;
+        runtime.block_on(self.client.submit_sentiment(&current, data.label, timestamp))
+            .map_err(|e| ApiError::InternalServerError(format!("Transaction failed: {}", e)))
+    }
+
+    pub fn explorer_url(&self, signature: &Signature) -> String {
+        explorer_url(signature, &self.rpc_url)
+    }
+}
+
+/// Solana Explorer link for a transaction on the cluster behind `rpc_url`
+pub fn explorer_url(signature: &Signature, rpc_url: &str) -> String {
+    let cluster = if rpc_url.contains("mainnet") {
+        String::new()
+    } else if rpc_url.contains("devnet") {
+        "?cluster=devnet".to_string()
+    } else if rpc_url.contains("testnet") {
+        "?cluster=testnet".to_string()
+    } else {
+        format!("?cluster=custom&customUrl={}", rpc_url)
+    };
+    format!("https://explorer.solana.com/tx/{}{}", signature, cluster)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{SentimentLabel, SentimentData};
+    use solana_sdk::signature::Signer;
+
+    fn signed(asset: Option<&str>) -> SignedSentimentData {
+        SignedSentimentData {
+            data: SentimentData {
+                id: "1".to_string(),
+                text: "gm".to_string(),
+                label: SentimentLabel::Negative,
+                score: 0.0,
+                date: None,
+                timestamp: Some(1_747_300_000),
+                username: "oracle".to_string(),
+                source: "twitter".to_string(),
+                signature: None,
+                public_key: None,
+                category: None,
+                degraded: false,
+                asset: asset.map(str::to_string),
+                label_as_received: None,
+            },
+            signature: "c2ln".to_string(),
+            public_key: "a2V5".to_string(),
+        }
+    }
+
+    #[test]
+    fn test_submit_requires_the_assets_account() {
+        let (program_id, oracle) = (Pubkey::new_unique(), Keypair::new());
+        let sol_account = price_account_address(&oracle.pubkey(), "SOL", &program_id).unwrap();
+        let auth = AdminAuth::named("SUBMIT_TOKEN", None);
+        let service = SolanaSubmitService::new("http://127.0.0.1:1", program_id, oracle, auth).unwrap();
+        let rejection = |signed: SignedSentimentData, account: &Pubkey| match service.submit(&signed, account) {
+            Err(ApiError::BadRequest(message)) => message,
+            other => panic!("expected a bad request, got {:?}", other.map(|_| ())),
+        };
+
+        assert!(rejection(signed(None), &sol_account).contains("data.asset"));
+        // Sentiment for one asset can't be written to another asset's account
+        assert!(rejection(signed(Some("BTC")), &sol_account).contains("is not the oracle's BTC account"));
+        assert!(rejection(signed(Some("SOL")), &Pubkey::new_unique()).contains("is not the oracle's SOL account"));
+
+        // A zero score and a negative label are fine; the matching account gets as far as the (unreachable) RPC node
+        assert!(matches!(service.submit(&signed(Some("$sol")), &sol_account), Err(ApiError::InternalServerError(_))));
+    }
+
+    #[test]
+    fn test_explorer_url_cluster() {
+        let signature = Signature::default();
+        assert!(explorer_url(&signature, "https://api.devnet.solana.com").ends_with("?cluster=devnet"));
+        assert!(!explorer_url(&signature, "https://api.mainnet-beta.solana.com").contains('?'));
+        assert!(explorer_url(&signature, "http://localhost:8899").ends_with("customUrl=http://localhost:8899"));
+    }
+}
@@ -0,0 +1,409 @@
+// Summary statistics over the oracle's SQLite history
+use std::collections::HashMap;
+use std::path::Path;
+use std::sync::Mutex;
+
+use anyhow::Result;
+use chrono::{DateTime, Duration, TimeZone, Utc};
+use price_oracle_node::models::ConsensusResult;
+use price_oracle_node::store;
+use rusqlite::{params, Connection, OptionalExtension};
+use serde::{Deserialize, Serialize};
+
+use crate::{sentiment_from_label, SentimentLabel, SignedSentimentData};
+
+/// `sentiment_history` is kept by the API; the node's tables come from its `store` module
+const SENTIMENT_SCHEMA: &str = "
+    CREATE TABLE IF NOT EXISTS sentiment_history (
+        id INTEGER PRIMARY KEY AUTOINCREMENT,
+        timestamp INTEGER NOT NULL,
+        asset TEXT NOT NULL,
+        label TEXT NOT NULL,
+        confidence REAL NOT NULL
+    );
+    CREATE INDEX IF NOT EXISTS idx_sentiment_history_asset_time
+        ON sentiment_history (asset, timestamp);
+";
+
+/// Most recent sentiment entries a smoothed confidence is computed over
+pub const SMOOTHING_WINDOW: usize = 100;
+
+/// Aggregate oracle activity for an asset over the last `period_hours`
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct OracleStats {
+    pub asset: String,
+    pub period_hours: u64,
+    /// Price updates recorded by the node in the period
+    pub update_count: u64,
+    pub avg_confidence: f64,
+    pub min_price: f64,
+    pub max_price: f64,
+    /// Change from the first to the last price of the period
+    pub price_change_pct: f64,
+    /// Sentiment entries per label (`POSITIVE`, `NEGATIVE`, `NEUTRAL`, `VERY_POSITIVE`, `VERY_NEGATIVE`)
+    pub sentiment_distribution: HashMap<String, u64>,
+}
+
+/// A source's price in the latest consensus update, relative to the consensus price
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct SourceDeviation {
+    pub source: String,
+    pub price: f64,
+    pub deviation_pct: f64,
+    /// Excluded from the consensus as an outlier
+    pub outlier: bool,
+}
+
+/// The node's latest consensus price for an asset, broken down by source
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct PriceComparison {
+    pub asset: String,
+    pub consensus_price: f64,
+    pub timestamp: i64,
+    pub sources: Vec<SourceDeviation>,
+}
+
+/// History database shared with the oracle node
+pub struct StatsStore {
+    conn: Mutex<Connection>,
+}
+
+impl StatsStore {
+    /// Open (or create) the history database at `path`
+    pub fn open<P: AsRef<Path>>(path: P) -> Result<Self> {
+        Self::init(Connection::open(path)?)
+    }
+
+    /// Throwaway in-memory store, used by tests
+    #[cfg(test)]
+    pub fn in_memory() -> Result<Self> {
+        Self::init(Connection::open_in_memory()?)
+    }
+
+    fn init(conn: Connection) -> Result<Self> {
+        // Either side may create the node's tables first
+        store::create_schema(&conn)?;
+        conn.execute_batch(SENTIMENT_SCHEMA)?;
+        Ok(Self { conn: Mutex::new(conn) })
+    }
+
+    /// Append a sentiment entry for an asset
+    pub fn record_sentiment(&self, asset: &str, data: &SignedSentimentData, timestamp: i64) -> Result<()> {
+        self.conn.lock().unwrap().execute(
+            "INSERT INTO sentiment_history (timestamp, asset, label, confidence) VALUES (?1, ?2, ?3, ?4)",
+            params![timestamp, asset, data.data.label.to_string(), data.data.score],
+        )?;
+        Ok(())
+    }
+
+    /// Summarize the asset's history from `now - period_hours` on.
+    /// Returns `None` when nothing was recorded in the period.
+    pub fn compute(&self, asset: &str, period_hours: u64, now: DateTime<Utc>) -> Result<Option<OracleStats>> {
+        let since = (now - Duration::hours(period_hours as i64)).timestamp();
+        let conn = self.conn.lock().unwrap();
+
+        let (update_count, avg_confidence, min_price, max_price): (u64, Option<f64>, Option<f64>, Option<f64>) = conn.query_row(
+            "SELECT COUNT(*), AVG(confidence), MIN(price), MAX(price)
+             FROM price_history WHERE asset = ?1 AND timestamp >= ?2",
+            params![asset, since],
+            |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?, row.get(3)?)),
+        )?;
+
+        let edge_price = |order: &str| -> rusqlite::Result<Option<f64>> {
+            conn.query_row(
+                &format!("SELECT price FROM price_history WHERE asset = ?1 AND timestamp >= ?2
+                          ORDER BY timestamp {order}, id {order} LIMIT 1"),
+                params![asset, since],
+                |row| row.get(0),
+            ).optional()
+        };
+        let price_change_pct = match (edge_price("ASC")?, edge_price("DESC")?) {
+            (Some(first), Some(last)) if first != 0.0 => (last - first) / first * 100.0,
+            _ => 0.0,
+        };
+
+        let mut sentiment_distribution: HashMap<String, u64> = SentimentLabel::ALL
+            .iter()
+            .map(|label| (label.to_string(), 0))
+            .collect();
+        let mut stmt = conn.prepare(
+            "SELECT label, COUNT(*) FROM sentiment_history
+             WHERE asset = ?1 AND timestamp >= ?2 GROUP BY label",
+        )?;
+        let counts = stmt.query_map(params![asset, since], |row| Ok((row.get::<_, String>(0)?, row.get::<_, u64>(1)?)))?;
+        for count in counts {
+            let (label, count) = count?;
+            *sentiment_distribution.entry(sentiment_from_label(&label).to_string()).or_default() += count;
+        }
+
+        if update_count == 0 && sentiment_distribution.values().all(|&count| count == 0) {
+            return Ok(None);
+        }
+
+        Ok(Some(OracleStats {
+            asset: asset.to_string(),
+            period_hours,
+            update_count,
+            avg_confidence: avg_confidence.unwrap_or(0.0),
+            min_price: min_price.unwrap_or(0.0),
+            max_price: max_price.unwrap_or(0.0),
+            price_change_pct,
+            sentiment_distribution,
+        }))
+    }
+
+    /// The asset's last `limit` sentiment entries as (timestamp, confidence), oldest first
+    pub fn sentiment_series(&self, asset: &str, limit: usize) -> Result<Vec<(i64, f64)>> {
+        let conn = self.conn.lock().unwrap();
+        let mut stmt = conn.prepare(
+            "SELECT timestamp, confidence FROM sentiment_history WHERE asset = ?1
+             ORDER BY timestamp DESC, id DESC LIMIT ?2",
+        )?;
+        let mut series = stmt.query_map(params![asset, limit as i64], |row| Ok((row.get(0)?, row.get(1)?)))?
+            .collect::<rusqlite::Result<Vec<_>>>()?;
+        series.reverse();
+        Ok(series)
+    }
+
+    /// Per-source breakdown of the asset's most recent price update.
+    /// Returns `None` when there is no update or it was recorded without source prices.
+    pub fn latest_comparison(&self, asset: &str) -> Result<Option<PriceComparison>> {
+        let conn = self.conn.lock().unwrap();
+        let latest: Option<(i64, f64, i64)> = conn.query_row(
+            "SELECT id, price, timestamp FROM price_history WHERE asset = ?1
+             ORDER BY timestamp DESC, id DESC LIMIT 1",
+            params![asset],
+            |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?)),
+        ).optional()?;
+        let Some((history_id, consensus_price, timestamp)) = latest else {
+            return Ok(None);
+        };
+
+        let mut stmt = conn.prepare(
+            "SELECT source, price, outlier FROM source_prices WHERE history_id = ?1 ORDER BY source",
+        )?;
+        let sources = stmt.query_map(params![history_id], |row| {
+            let price: f64 = row.get(1)?;
+            Ok(SourceDeviation {
+                source: row.get(0)?,
+                price,
+                deviation_pct: if consensus_price != 0.0 { (price - consensus_price) / consensus_price * 100.0 } else { 0.0 },
+                outlier: row.get(2)?,
+            })
+        })?.collect::<rusqlite::Result<Vec<_>>>()?;
+
+        if sources.is_empty() {
+            return Ok(None);
+        }
+        Ok(Some(PriceComparison { asset: asset.to_string(), consensus_price, timestamp, sources }))
+    }
+
+    /// The asset's most recent price update rebuilt as a consensus result: sources, outliers and
+    /// the population variance of every source price come from its `source_prices` rows
+    pub fn latest_consensus(&self, asset: &str) -> Result<Option<ConsensusResult>> {
+        let conn = self.conn.lock().unwrap();
+        let latest: Option<(i64, i64, f64, f64, String, f64)> = conn.query_row(
+            "SELECT id, timestamp, price, confidence, sources, consensus_score FROM price_history
+             WHERE asset = ?1 ORDER BY timestamp DESC, id DESC LIMIT 1",
+            params![asset],
+            |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?, row.get(3)?, row.get(4)?, row.get(5)?)),
+        ).optional()?;
+        let Some((history_id, timestamp, price, confidence, sources, consensus_score)) = latest else {
+            return Ok(None);
+        };
+
+        let mut stmt = conn.prepare(
+            "SELECT source, price, outlier FROM source_prices WHERE history_id = ?1 ORDER BY source",
+        )?;
+        let source_prices = stmt.query_map(params![history_id], |row| {
+            Ok((row.get::<_, String>(0)?, row.get::<_, f64>(1)?, row.get::<_, bool>(2)?))
+        })?.collect::<rusqlite::Result<Vec<_>>>()?;
+
+        let (names, variance, outliers) = if source_prices.is_empty() {
+            let names = sources.split(',').map(str::trim).filter(|s| !s.is_empty()).map(str::to_string).collect();
+            (names, 0.0, Vec::new())
+        } else {
+            let n = source_prices.len() as f64;
+            let mean = source_prices.iter().map(|(_, p, _)| p).sum::<f64>() / n;
+            let variance = source_prices.iter().map(|(_, p, _)| (p - mean).powi(2)).sum::<f64>() / n;
+            let outliers = source_prices.iter().filter(|(_, _, outlier)| *outlier).map(|(s, _, _)| s.clone()).collect();
+            (source_prices.into_iter().map(|(s, _, _)| s).collect(), variance, outliers)
+        };
+
+        let mut result = ConsensusResult::new(asset.to_string(), price, names)
+            .with_confidence(confidence)
+            .with_consensus_score(consensus_score)
+            .with_variance(variance)
+            .with_outliers(outliers);
+        if let Some(at) = Utc.timestamp_opt(timestamp, 0).single() {
+            result.timestamp = at;
+        }
+        Ok(Some(result))
+    }
+
+    /// Every source price recorded for the asset as (source, price, consensus price), oldest
+    /// round first; updates recorded without source prices contribute nothing
+    pub fn source_prices(&self, asset: &str) -> Result<Vec<(String, f64, f64)>> {
+        let conn = self.conn.lock().unwrap();
+        let mut stmt = conn.prepare(
+            "SELECT s.source, s.price, h.price FROM source_prices s
+             JOIN price_history h ON h.id = s.history_id
+             WHERE h.asset = ?1 ORDER BY h.timestamp, h.id, s.source",
+        )?;
+        let prices = stmt.query_map(params![asset], |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?)))?
+            .collect::<rusqlite::Result<Vec<_>>>()?;
+        Ok(prices)
+    }
+
+    /// Insert a node price update, as the node's `PriceStore` does
+    #[cfg(test)]
+    pub fn record_price(&self, asset: &str, price: f64, confidence: f64, timestamp: i64) -> Result<()> {
+        self.conn.lock().unwrap().execute(
+            "INSERT INTO price_history (timestamp, asset, price, confidence, sources, consensus_score)
+             VALUES (?1, ?2, ?3, ?4, 'CoinGecko,Binance', 0.9)",
+            params![timestamp, asset, price, confidence],
+        )?;
+        Ok(())
+    }
+
+    /// Insert a node price update along with its per-source prices
+    #[cfg(test)]
+    pub fn record_price_with_sources(&self, asset: &str, price: f64, timestamp: i64, sources: &[(&str, f64, bool)]) -> Result<()> {
+        self.record_price(asset, price, 0.9, timestamp)?;
+        let conn = self.conn.lock().unwrap();
+        let history_id = conn.last_insert_rowid();
+        for (source, price, outlier) in sources {
+            conn.execute(
+                "INSERT INTO source_prices (history_id, source, price, outlier) VALUES (?1, ?2, ?3, ?4)",
+                params![history_id, source, price, outlier],
+            )?;
+        }
+        Ok(())
+    }
+}
+
+/// Exponential moving average of `values`, oldest first, giving each new value weight `alpha`
+pub fn ema(values: impl IntoIterator<Item = f64>, alpha: f64) -> Option<f64> {
+    values.into_iter().reduce(|smoothed, value| alpha * value + (1.0 - alpha) * smoothed)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::SentimentData;
+
+    const NOW: i64 = 1_747_300_000;
+
+    fn entry(label: SentimentLabel) -> SignedSentimentData {
+        SignedSentimentData {
+            data: SentimentData {
+                id: "1".to_string(),
+                text: "gm".to_string(),
+                label,
+                score: 0.8,
+                date: Some("2025-05-15".to_string()),
+                timestamp: None,
+                username: "oracle".to_string(),
+                source: "twitter".to_string(),
+                signature: None,
+                public_key: None,
+                category: None,
+                degraded: false,
+                asset: None,
+                label_as_received: None,
+            },
+            signature: "sig".to_string(),
+            public_key: "oracle".to_string(),
+        }
+    }
+
+    fn now() -> DateTime<Utc> {
+        DateTime::from_timestamp(NOW, 0).unwrap()
+    }
+
+    #[test]
+    fn test_sentiment_distribution_counts() {
+        let store = StatsStore::in_memory().unwrap();
+        let history = [
+            (SentimentLabel::Positive, 60),
+            (SentimentLabel::Positive, 3_600),
+            (SentimentLabel::Negative, 7_200),
+            (SentimentLabel::Positive, 10_800),
+            // Outside a 24 hour window
+            (SentimentLabel::Negative, 90_000),
+        ];
+        for (label, age) in history {
+            store.record_sentiment("BTC", &entry(label), NOW - age).unwrap();
+        }
+        store.record_sentiment("ETH", &entry(SentimentLabel::Negative), NOW).unwrap();
+
+        let stats = store.compute("BTC", 24, now()).unwrap().unwrap();
+        assert_eq!(stats.sentiment_distribution["POSITIVE"], 3);
+        assert_eq!(stats.sentiment_distribution["NEGATIVE"], 1);
+        assert_eq!(stats.sentiment_distribution["NEUTRAL"], 0);
+
+        let stats = store.compute("BTC", 48, now()).unwrap().unwrap();
+        assert_eq!(stats.sentiment_distribution["NEGATIVE"], 2);
+    }
+
+    #[test]
+    fn test_price_stats() {
+        let store = StatsStore::in_memory().unwrap();
+        store.record_price("SOL", 90.0, 0.5, NOW - 100_000).unwrap();
+        store.record_price("SOL", 100.0, 0.8, NOW - 7_200).unwrap();
+        store.record_price("SOL", 95.0, 0.9, NOW - 3_600).unwrap();
+        store.record_price("SOL", 110.0, 1.0, NOW - 60).unwrap();
+
+        let stats = store.compute("SOL", 24, now()).unwrap().unwrap();
+        assert_eq!(stats.update_count, 3);
+        assert!((stats.avg_confidence - 0.9).abs() < 1e-9);
+        assert_eq!((stats.min_price, stats.max_price), (95.0, 110.0));
+        assert!((stats.price_change_pct - 10.0).abs() < 1e-9);
+
+        assert!(store.compute("ADA", 24, now()).unwrap().is_none());
+    }
+
+    #[test]
+    fn test_latest_comparison() {
+        let store = StatsStore::in_memory().unwrap();
+        store.record_price_with_sources("SOL", 90.0, NOW - 120, &[("CoinGecko", 90.0, false)]).unwrap();
+        store.record_price_with_sources("SOL", 100.0, NOW - 60, &[
+            ("OKX", 120.0, true),
+            ("CoinGecko", 101.0, false),
+            ("Binance", 99.0, false),
+        ]).unwrap();
+
+        let comparison = store.latest_comparison("SOL").unwrap().unwrap();
+        assert_eq!(comparison.consensus_price, 100.0);
+        assert_eq!(comparison.timestamp, NOW - 60);
+        let sources: Vec<(&str, bool)> = comparison.sources.iter().map(|s| (s.source.as_str(), s.outlier)).collect();
+        assert_eq!(sources, vec![("Binance", false), ("CoinGecko", false), ("OKX", true)]);
+        assert!((comparison.sources[0].deviation_pct + 1.0).abs() < 1e-9);
+        assert!((comparison.sources[2].deviation_pct - 20.0).abs() < 1e-9);
+
+        // Updates recorded before the node kept source prices have no breakdown
+        store.record_price("BTC", 45000.0, 0.9, NOW).unwrap();
+        assert!(store.latest_comparison("BTC").unwrap().is_none());
+        assert!(store.latest_comparison("ADA").unwrap().is_none());
+    }
+
+    #[test]
+    fn test_sentiment_series_smoothing() {
+        let store = StatsStore::in_memory().unwrap();
+        for (offset, score) in [(0, 0.2), (120, 1.0), (60, 0.6)] {
+            let mut data = entry(SentimentLabel::Positive);
+            data.data.score = score;
+            store.record_sentiment("SOL", &data, NOW + offset).unwrap();
+        }
+        store.record_sentiment("BTC", &entry(SentimentLabel::Negative), NOW).unwrap();
+
+        let series = store.sentiment_series("SOL", 2).unwrap();
+        assert_eq!(series, vec![(NOW + 60, 0.6), (NOW + 120, 1.0)]);
+
+        let confidences = store.sentiment_series("SOL", SMOOTHING_WINDOW).unwrap().into_iter().map(|(_, c)| c);
+        assert!((ema(confidences, 0.5).unwrap() - 0.7).abs() < 1e-9);
+        // An alpha of 1 follows the newest value
+        assert_eq!(ema([0.2, 0.9], 1.0), Some(0.9));
+        assert_eq!(ema([], 0.5), None);
+    }
+}
@@ -0,0 +1,218 @@
+// Price deviation alerts delivered to webhooks
+use anyhow::{Context, Result};
+use chrono::{DateTime, Duration, Utc};
+use log::{error, info};
+use reqwest::Client;
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, VecDeque};
+use std::path::Path;
+
+use crate::models::{AlertConfig, ConsensusResult};
+
+/// Layout of the alerts config file: a list of `[[alerts]]` tables
+#[derive(Debug, Deserialize)]
+struct AlertsFile {
+    #[serde(default)]
+    alerts: Vec<AlertConfig>,
+}
+
+/// JSON body posted to the webhook when an alert fires
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct AlertPayload {
+    pub asset: String,
+    pub old_price: f64,
+    pub new_price: f64,
+    pub change_pct: f64,
+    pub timestamp: DateTime<Utc>,
+}
+
+struct AlertRule {
+    config: AlertConfig,
+    // Prices before this instant are ignored, so one move only fires once
+    armed_from: DateTime<Utc>,
+}
+
+pub struct AlertEngine {
+    rules: Vec<AlertRule>,
+    history: HashMap<String, VecDeque<(DateTime<Utc>, f64)>>,
+    client: Client,
+}
+
+impl AlertEngine {
+    pub fn new(alerts: Vec<AlertConfig>) -> Self {
+        let client = Client::builder()
+            .timeout(std::time::Duration::from_secs(10))
+            .build()
+            .expect("Failed to create HTTP client");
+
+        let rules = alerts.into_iter()
+            .map(|config| AlertRule {
+                config: AlertConfig { asset: config.asset.to_uppercase(), ..config },
+                armed_from: DateTime::<Utc>::MIN_UTC,
+            })
+            .collect();
+
+        Self {
+            rules,
+            history: HashMap::new(),
+            client,
+        }
+    }
+
+    /// Load alert rules from a TOML file
+    pub fn load<P: AsRef<Path>>(path: P) -> Result<Self> {
+        let path = path.as_ref();
+        let contents = std::fs::read_to_string(path)
+            .with_context(|| format!("Failed to read alerts config {}", path.display()))?;
+        let file: AlertsFile = toml::from_str(&contents)
+            .with_context(|| format!("Invalid alerts config {}", path.display()))?;
+
+        Ok(Self::new(file.alerts))
+    }
+
+    pub fn rule_count(&self) -> usize {
+        self.rules.len()
+    }
+
+    /// Record a new consensus price and fire any alerts whose threshold it breaches
+    pub async fn check(&mut self, result: &ConsensusResult) {
+        let asset = result.asset.to_uppercase();
+        let now = result.timestamp;
+
+        let longest_window = self.rules.iter()
+            .filter(|rule| rule.config.asset == asset)
+            .map(|rule| rule.config.window_secs)
+            .max();
+        let Some(longest_window) = longest_window else {
+            return;
+        };
+
+        let history = self.history.entry(asset.clone()).or_default();
+        history.push_back((now, result.price));
+        let cutoff = now - Duration::seconds(longest_window as i64);
+        while history.front().is_some_and(|(time, _)| *time < cutoff) {
+            history.pop_front();
+        }
+
+        let mut triggered = Vec::new();
+        for rule in self.rules.iter_mut().filter(|rule| rule.config.asset == asset) {
+            let window_start = (now - Duration::seconds(rule.config.window_secs as i64)).max(rule.armed_from);
+            let Some(&(_, old_price)) = history.iter().find(|(time, _)| *time >= window_start) else {
+                continue;
+            };
+            if old_price == 0.0 {
+                continue;
+            }
+
+            let change_pct = (result.price - old_price) / old_price * 100.0;
+            if change_pct.abs() >= rule.config.threshold_pct {
+                rule.armed_from = now;
+                triggered.push((
+                    rule.config.webhook_url.clone(),
+                    AlertPayload {
+                        asset: asset.clone(),
+                        old_price,
+                        new_price: result.price,
+                        change_pct,
+                        timestamp: now,
+                    },
+                ));
+            }
+        }
+
+        for (webhook_url, payload) in triggered {
+            info!("Alert: {} moved {:.2}% (${:.2} -> ${:.2})",
+                  payload.asset, payload.change_pct, payload.old_price, payload.new_price);
+            if let Err(e) = self.send(&webhook_url, &payload).await {
+                error!("Failed to deliver alert to {}: {}", webhook_url, e);
+            }
+        }
+    }
+
+    async fn send(&self, webhook_url: &str, payload: &AlertPayload) -> Result<()> {
+        self.client.post(webhook_url)
+            .json(payload)
+            .send()
+            .await?
+            .error_for_status()?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn result_at(price: f64, seconds: i64) -> ConsensusResult {
+        let mut result = ConsensusResult::new("BTC".to_string(), price, vec!["CoinGecko".to_string()]);
+        result.timestamp = DateTime::from_timestamp(1_700_000_000 + seconds, 0).unwrap();
+        result
+    }
+
+    fn engine_for(webhook_url: String) -> AlertEngine {
+        AlertEngine::new(vec![AlertConfig {
+            asset: "btc".to_string(),
+            threshold_pct: 5.0,
+            window_secs: 600,
+            webhook_url,
+        }])
+    }
+
+    #[tokio::test]
+    async fn test_webhook_fires_once_on_breach() {
+        let mut server = mockito::Server::new_async().await;
+        let mock = server.mock("POST", "/hook")
+            .match_body(mockito::Matcher::PartialJson(serde_json::json!({
+                "asset": "BTC",
+                "old_price": 100.0,
+                "new_price": 106.0,
+            })))
+            .with_status(200)
+            .expect(1)
+            .create_async()
+            .await;
+
+        let mut engine = engine_for(format!("{}/hook", server.url()));
+        engine.check(&result_at(100.0, 0)).await;
+        engine.check(&result_at(103.0, 60)).await;
+        engine.check(&result_at(106.0, 120)).await;
+        // Still 6% above the window's oldest price, but the alert already fired for this move
+        engine.check(&result_at(106.5, 180)).await;
+
+        mock.assert_async().await;
+    }
+
+    #[tokio::test]
+    async fn test_no_alert_outside_window() {
+        let mut server = mockito::Server::new_async().await;
+        let mock = server.mock("POST", "/hook")
+            .expect(0)
+            .create_async()
+            .await;
+
+        let mut engine = engine_for(format!("{}/hook", server.url()));
+        engine.check(&result_at(100.0, 0)).await;
+        // An 8% move overall, but no 10 minute window sees more than 5%
+        engine.check(&result_at(104.0, 400)).await;
+        engine.check(&result_at(108.0, 800)).await;
+
+        mock.assert_async().await;
+    }
+
+    #[test]
+    fn test_load_alerts_toml() {
+        let path = std::env::temp_dir().join(format!("oracle-alerts-{}.toml", std::process::id()));
+        std::fs::write(&path, r#"
+            [[alerts]]
+            asset = "SOL"
+            threshold_pct = 3.5
+            window_secs = 300
+            webhook_url = "https://example.com/hook"
+        "#).unwrap();
+
+        let engine = AlertEngine::load(&path).unwrap();
+        std::fs::remove_file(&path).unwrap();
+
+        assert_eq!(engine.rule_count(), 1);
+    }
+}
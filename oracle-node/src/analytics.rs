@@ -0,0 +1,40 @@
+// Quantitative readings of oracle data for downstream protocols
+use price_oracle_program::SentimentLabel;
+
+/// Expected price direction implied by a sentiment, in [-1.0, 1.0]: `confidence` for very
+/// positive, half of it for positive, nothing for neutral and the negatives mirrored
+pub fn sentiment_impact(label: SentimentLabel, confidence: f64) -> f64 {
+    let confidence = if confidence.is_nan() { 0.0 } else { confidence.clamp(0.0, 1.0) };
+    match label {
+        SentimentLabel::VeryPositive => confidence,
+        SentimentLabel::Positive => 0.5 * confidence,
+        SentimentLabel::Neutral => 0.0,
+        SentimentLabel::Negative => -0.5 * confidence,
+        SentimentLabel::VeryNegative => -confidence,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_sentiment_impact() {
+        assert_eq!(sentiment_impact(SentimentLabel::VeryPositive, 0.8), 0.8);
+        assert_eq!(sentiment_impact(SentimentLabel::Positive, 0.8), 0.4);
+        assert_eq!(sentiment_impact(SentimentLabel::Neutral, 0.8), 0.0);
+        assert_eq!(sentiment_impact(SentimentLabel::Negative, 0.8), -0.4);
+        assert_eq!(sentiment_impact(SentimentLabel::VeryNegative, 0.8), -0.8);
+    }
+
+    #[test]
+    fn test_sentiment_impact_stays_in_range() {
+        for label in SentimentLabel::ALL {
+            for confidence in [-1.0, 0.0, 0.5, 1.0, 7.0, f64::NAN] {
+                let impact = sentiment_impact(label, confidence);
+                assert!((-1.0..=1.0).contains(&impact), "{} {} {}", label, confidence, impact);
+            }
+        }
+        assert_eq!(sentiment_impact(SentimentLabel::VeryNegative, 3.0), -1.0);
+    }
+}
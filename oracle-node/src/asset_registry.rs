@@ -0,0 +1,117 @@
+// Asset symbol normalization: aliases and `$`-prefixed or lowercase input resolved to canonical symbols
+use std::collections::{HashMap, HashSet};
+
+/// Symbols recognized without any configuration
+pub const BUILTIN_SYMBOLS: &[&str] = &[
+    "BTC", "ETH", "SOL", "ADA", "DOT", "MATIC", "AVAX", "LINK", "UNI", "AAVE", "DOGE", "USDC", "USDT",
+];
+
+/// Built-in aliases (uppercase) and the symbol each stands for
+pub const BUILTIN_ALIASES: &[(&str, &str)] = &[
+    ("BITCOIN", "BTC"),
+    ("XBT", "BTC"),
+    ("ETHER", "ETH"),
+    ("ETHEREUM", "ETH"),
+    ("SOLANA", "SOL"),
+    ("CARDANO", "ADA"),
+    ("POLKADOT", "DOT"),
+    ("POLYGON", "MATIC"),
+    ("AVALANCHE", "AVAX"),
+    ("CHAINLINK", "LINK"),
+    ("UNISWAP", "UNI"),
+    ("DOGECOIN", "DOGE"),
+];
+
+/// Canonical asset symbols and the aliases that resolve to them
+#[derive(Debug, Clone)]
+pub struct AssetRegistry {
+    symbols: HashSet<String>,
+    // Uppercase alias -> canonical symbol
+    aliases: HashMap<String, String>,
+}
+
+impl Default for AssetRegistry {
+    fn default() -> Self {
+        Self {
+            symbols: BUILTIN_SYMBOLS.iter().map(|symbol| symbol.to_string()).collect(),
+            aliases: BUILTIN_ALIASES.iter().map(|(alias, symbol)| (alias.to_string(), symbol.to_string())).collect(),
+        }
+    }
+}
+
+impl AssetRegistry {
+    /// Also resolve these aliases (alias -> symbol, both in any case); their symbols become recognized
+    pub fn with_aliases(mut self, aliases: &HashMap<String, String>) -> Self {
+        for (alias, symbol) in aliases {
+            let symbol = strip(symbol);
+            self.symbols.insert(symbol.clone());
+            self.aliases.insert(strip(alias), symbol);
+        }
+        self
+    }
+
+    /// Also recognize these symbols
+    pub fn with_symbols<S: AsRef<str>>(mut self, symbols: impl IntoIterator<Item = S>) -> Self {
+        self.symbols.extend(symbols.into_iter().map(|symbol| strip(symbol.as_ref())));
+        self
+    }
+
+    /// Canonical symbol for user input such as `bitcoin`, `btc`, `BTC` or `$BTC`;
+    /// `None` when it is neither a known symbol nor an alias
+    pub fn normalize(&self, input: &str) -> Option<String> {
+        let key = strip(input);
+        if let Some(symbol) = self.aliases.get(&key) {
+            return Some(symbol.clone());
+        }
+        self.symbols.contains(&key).then_some(key)
+    }
+}
+
+/// Trim, drop a leading `$` and uppercase
+fn strip(input: &str) -> String {
+    let input = input.trim();
+    input.strip_prefix('$').unwrap_or(input).to_uppercase()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_normalize_symbols_and_aliases() {
+        let registry = AssetRegistry::default();
+        for input in ["bitcoin", "Bitcoin", "btc", "BTC", "$BTC", "$btc", " xbt "] {
+            assert_eq!(registry.normalize(input).as_deref(), Some("BTC"), "{}", input);
+        }
+        assert_eq!(registry.normalize("ether").as_deref(), Some("ETH"));
+        assert_eq!(registry.normalize("$Solana").as_deref(), Some("SOL"));
+        for (alias, symbol) in BUILTIN_ALIASES {
+            assert_eq!(registry.normalize(&alias.to_lowercase()).as_deref(), Some(*symbol));
+            assert_eq!(registry.normalize(symbol).as_deref(), Some(*symbol), "alias target {} is a known symbol", symbol);
+        }
+    }
+
+    #[test]
+    fn test_unrecognized_symbols_rejected() {
+        let registry = AssetRegistry::default();
+        for input in ["", "$", "asdfgh", "BTCC", "bit coin", "$$BTC"] {
+            assert_eq!(registry.normalize(input), None, "{}", input);
+        }
+    }
+
+    #[test]
+    fn test_configured_aliases_and_symbols() {
+        let aliases = HashMap::from([
+            ("pepecoin".to_string(), "pepe".to_string()),
+            ("Satoshi".to_string(), "BTC".to_string()),
+        ]);
+        let registry = AssetRegistry::default().with_aliases(&aliases).with_symbols(["wif"]);
+        assert_eq!(registry.normalize("PepeCoin").as_deref(), Some("PEPE"));
+        assert_eq!(registry.normalize("$pepe").as_deref(), Some("PEPE"));
+        assert_eq!(registry.normalize("satoshi").as_deref(), Some("BTC"));
+        assert_eq!(registry.normalize("WIF").as_deref(), Some("WIF"));
+        // Built-in aliases still apply
+        assert_eq!(registry.normalize("bitcoin").as_deref(), Some("BTC"));
+        assert_eq!(AssetRegistry::default().normalize("pepe"), None);
+    }
+}
@@ -0,0 +1,88 @@
+// Circuit breaker slowing the update loop down during prolonged upstream outages
+use std::time::Duration;
+
+pub struct CircuitBreaker {
+    /// Consecutive failed cycles that trip the breaker (0 disables it)
+    threshold: u32,
+    /// Delay between cycles while tripped
+    backoff: Duration,
+    consecutive_failures: u32,
+}
+
+impl CircuitBreaker {
+    pub fn new(threshold: u32, backoff: Duration) -> Self {
+        Self {
+            threshold,
+            backoff,
+            consecutive_failures: 0,
+        }
+    }
+
+    pub fn is_tripped(&self) -> bool {
+        self.threshold > 0 && self.consecutive_failures >= self.threshold
+    }
+
+    pub fn consecutive_failures(&self) -> u32 {
+        self.consecutive_failures
+    }
+
+    /// Record a failed cycle. Returns true when this failure tripped the breaker.
+    pub fn record_failure(&mut self) -> bool {
+        let was_tripped = self.is_tripped();
+        self.consecutive_failures = self.consecutive_failures.saturating_add(1);
+        !was_tripped && self.is_tripped()
+    }
+
+    /// Record a successful cycle. Returns true when the breaker had been tripped.
+    pub fn record_success(&mut self) -> bool {
+        let was_tripped = self.is_tripped();
+        self.consecutive_failures = 0;
+        was_tripped
+    }
+
+    /// How long to wait before the next cycle, given the normal update interval
+    pub fn next_delay(&self, interval: Duration) -> Duration {
+        if self.is_tripped() {
+            self.backoff.max(interval)
+        } else {
+            interval
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const INTERVAL: Duration = Duration::from_secs(30);
+    const BACKOFF: Duration = Duration::from_secs(300);
+
+    #[test]
+    fn test_trips_after_threshold_and_resets_on_success() {
+        let mut breaker = CircuitBreaker::new(3, BACKOFF);
+
+        assert!(!breaker.record_failure());
+        assert!(!breaker.record_failure());
+        assert_eq!(breaker.next_delay(INTERVAL), INTERVAL);
+
+        // Only the failure crossing the threshold reports the trip
+        assert!(breaker.record_failure());
+        assert!(!breaker.record_failure());
+        assert!(breaker.is_tripped());
+        assert_eq!(breaker.next_delay(INTERVAL), BACKOFF);
+
+        assert!(breaker.record_success());
+        assert!(!breaker.is_tripped());
+        assert_eq!(breaker.next_delay(INTERVAL), INTERVAL);
+        assert!(!breaker.record_success());
+    }
+
+    #[test]
+    fn test_zero_threshold_never_trips() {
+        let mut breaker = CircuitBreaker::new(0, BACKOFF);
+        for _ in 0..100 {
+            assert!(!breaker.record_failure());
+        }
+        assert_eq!(breaker.next_delay(INTERVAL), INTERVAL);
+    }
+}
@@ -0,0 +1,435 @@
+// Oracle node configuration file loading
+use anyhow::{Context, Result};
+use std::fmt;
+use std::path::Path;
+use std::str::FromStr;
+use std::time::Duration;
+
+use std::collections::HashMap;
+
+use crate::asset_registry::AssetRegistry;
+use crate::models::{AssetAliasConfig, AssetIdConfig, NetworkKind, OracleConfig, SourceTimeoutConfig, SourceWeightConfig};
+use price_oracle_program::{is_valid_asset_category, ASSET_CATEGORIES};
+
+/// Check a category against the ones the oracle program accepts
+pub fn validate_asset_category(category: &str) -> Result<()> {
+    if is_valid_asset_category(category) {
+        Ok(())
+    } else {
+        Err(anyhow::anyhow!("unknown asset category '{}', expected one of {}", category, ASSET_CATEGORIES.join(", ")))
+    }
+}
+
+impl OracleConfig {
+    /// Load a config file, choosing the format from its extension (`.toml` or `.json`)
+    pub fn load<P: AsRef<Path>>(path: P) -> Result<Self> {
+        let path = path.as_ref();
+        let config = Self::parse(path)?;
+        config.source_weights.validate()
+            .with_context(|| format!("Invalid source_weights in {}", path.display()))?;
+        validate_asset_category(&config.asset_category)
+            .with_context(|| format!("Invalid asset_category in {}", path.display()))?;
+        if config.flash_crash_threshold.is_nan() || config.flash_crash_threshold <= 0.0 {
+            return Err(anyhow::anyhow!("flash_crash_threshold in {} must be positive", path.display()));
+        }
+        if config.outlier_z_threshold.is_nan() || config.outlier_z_threshold <= 0.0 {
+            return Err(anyhow::anyhow!("outlier_z_threshold in {} must be positive", path.display()));
+        }
+        if !(0.0..=1.0).contains(&config.single_source_confidence_cap) {
+            return Err(anyhow::anyhow!("single_source_confidence_cap in {} must be between 0 and 1", path.display()));
+        }
+        if config.http_timeout_secs == 0 {
+            return Err(anyhow::anyhow!("http_timeout_secs in {} must be at least 1", path.display()));
+        }
+        if config.source_timeout_secs == 0 {
+            return Err(anyhow::anyhow!("source_timeout_secs in {} must be at least 1", path.display()));
+        }
+        config.http_timeouts.validate()
+            .with_context(|| format!("Invalid http_timeouts in {}", path.display()))?;
+        config.asset_aliases.validate()
+            .with_context(|| format!("Invalid asset_aliases in {}", path.display()))?;
+        Ok(config)
+    }
+    
+    /// Built-in symbols and aliases, plus the configured aliases and every asset given an id under `[asset_ids]`
+    pub fn asset_registry(&self) -> AssetRegistry {
+        AssetRegistry::default()
+            .with_aliases(&self.asset_aliases.0)
+            .with_symbols(self.asset_ids.0.values().flat_map(|ids| ids.keys()))
+    }
+    
+    /// Canonical symbol for an asset named by the user, or an error when the registry doesn't know it
+    pub fn canonical_asset(&self, asset: &str) -> Result<String> {
+        self.asset_registry().normalize(asset).ok_or_else(|| anyhow::anyhow!(
+            "unknown asset '{}' (map it under [asset_aliases] or [asset_ids.<Source>] in the config)", asset
+        ))
+    }
+    
+    /// Request timeout for a source: its override, or `http_timeout_secs`
+    pub fn http_timeout_for(&self, source: &str) -> Duration {
+        Duration::from_secs(self.http_timeouts.secs_for(source).unwrap_or(self.http_timeout_secs))
+    }
+    
+    /// How long an update cycle waits for the sources' prices
+    pub fn source_timeout(&self) -> Duration {
+        Duration::from_secs(self.source_timeout_secs)
+    }
+    
+    /// Networks to submit to: the configured ones, or just the cluster behind `rpc_url`
+    pub fn submit_networks(&self) -> Vec<NetworkKind> {
+        if self.networks.is_empty() {
+            vec![NetworkKind::from_rpc_url(&self.rpc_url)]
+        } else {
+            self.networks.clone()
+        }
+    }
+    
+    /// Networks a submission has to reach; all of them unless configured otherwise
+    pub fn submit_required_networks(&self) -> Vec<NetworkKind> {
+        if self.required_networks.is_empty() {
+            self.submit_networks()
+        } else {
+            self.required_networks.clone()
+        }
+    }
+    
+    fn parse(path: &Path) -> Result<Self> {
+        let contents = std::fs::read_to_string(path)
+            .with_context(|| format!("Failed to read config file {}", path.display()))?;
+
+        let extension = path.extension()
+            .and_then(|ext| ext.to_str())
+            .map(|ext| ext.to_ascii_lowercase());
+
+        match extension.as_deref() {
+            Some("toml") => toml::from_str(&contents)
+                .with_context(|| format!("Invalid TOML config {}", path.display())),
+            Some("json") => serde_json::from_str(&contents)
+                .with_context(|| format!("Invalid JSON config {}", path.display())),
+            _ => Err(anyhow::anyhow!(
+                "Unsupported config format for {}: expected a .toml or .json file",
+                path.display()
+            )),
+        }
+    }
+}
+
+impl SourceWeightConfig {
+    /// Multiplier for a source (matched case-insensitively); unlisted sources get 1.0
+    pub fn weight_for(&self, source: &str) -> f64 {
+        self.0.iter()
+            .find(|(name, _)| name.eq_ignore_ascii_case(source))
+            .map(|(_, weight)| *weight)
+            .unwrap_or(1.0)
+    }
+    
+    /// Every weight must be in `(0.0, 2.0]`
+    pub fn validate(&self) -> Result<()> {
+        for (source, weight) in &self.0 {
+            if !(*weight > 0.0 && *weight <= 2.0) {
+                return Err(anyhow::anyhow!("weight for {} is {}, expected a value in (0.0, 2.0]", source, weight));
+            }
+        }
+        Ok(())
+    }
+}
+
+impl SourceTimeoutConfig {
+    /// Override for a source (matched case-insensitively), if any
+    pub fn secs_for(&self, source: &str) -> Option<u64> {
+        self.0.iter()
+            .find(|(name, _)| name.eq_ignore_ascii_case(source))
+            .map(|(_, secs)| *secs)
+    }
+    
+    /// A timeout of 0 would fail every request
+    pub fn validate(&self) -> Result<()> {
+        for (source, secs) in &self.0 {
+            if *secs == 0 {
+                return Err(anyhow::anyhow!("timeout for {} is 0, expected at least 1 second", source));
+            }
+        }
+        Ok(())
+    }
+}
+
+impl NetworkKind {
+    pub fn rpc_url(&self) -> &str {
+        match self {
+            NetworkKind::Devnet => "https://api.devnet.solana.com",
+            NetworkKind::Testnet => "https://api.testnet.solana.com",
+            NetworkKind::Mainnet => "https://api.mainnet-beta.solana.com",
+            NetworkKind::Custom(url) => url,
+        }
+    }
+    
+    /// True for mainnet-beta, including custom RPC providers whose URL names it
+    pub fn is_mainnet(&self) -> bool {
+        match self {
+            NetworkKind::Mainnet => true,
+            NetworkKind::Custom(url) => url.to_ascii_lowercase().contains("mainnet"),
+            _ => false,
+        }
+    }
+    
+    /// The public cluster an RPC URL points at, or a custom network
+    pub fn from_rpc_url(url: &str) -> Self {
+        [NetworkKind::Devnet, NetworkKind::Testnet, NetworkKind::Mainnet]
+            .into_iter()
+            .find(|network| network.rpc_url() == url.trim_end_matches('/'))
+            .unwrap_or_else(|| NetworkKind::Custom(url.to_string()))
+    }
+}
+
+impl FromStr for NetworkKind {
+    type Err = String;
+    
+    fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+        let s = s.trim();
+        match s.to_ascii_lowercase().as_str() {
+            "devnet" => Ok(NetworkKind::Devnet),
+            "testnet" => Ok(NetworkKind::Testnet),
+            "mainnet" | "mainnet-beta" => Ok(NetworkKind::Mainnet),
+            _ if s.starts_with("http://") || s.starts_with("https://") => Ok(NetworkKind::from_rpc_url(s)),
+            _ => Err(format!("unknown network '{}' (expected devnet, testnet, mainnet or an RPC URL)", s)),
+        }
+    }
+}
+
+impl TryFrom<String> for NetworkKind {
+    type Error = String;
+    
+    fn try_from(s: String) -> std::result::Result<Self, Self::Error> {
+        s.parse()
+    }
+}
+
+impl From<NetworkKind> for String {
+    fn from(network: NetworkKind) -> Self {
+        network.to_string()
+    }
+}
+
+impl fmt::Display for NetworkKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            NetworkKind::Devnet => write!(f, "devnet"),
+            NetworkKind::Testnet => write!(f, "testnet"),
+            NetworkKind::Mainnet => write!(f, "mainnet"),
+            NetworkKind::Custom(url) => write!(f, "{}", url),
+        }
+    }
+}
+
+impl AssetAliasConfig {
+    /// Aliases and symbols must be non-empty, without `$` beyond a leading one
+    pub fn validate(&self) -> Result<()> {
+        for (alias, symbol) in &self.0 {
+            for name in [alias, symbol] {
+                let name = name.trim();
+                let name = name.strip_prefix('$').unwrap_or(name);
+                if name.is_empty() || name.contains('$') || name.contains(char::is_whitespace) {
+                    return Err(anyhow::anyhow!("'{}' = '{}' is not a valid alias", alias, symbol));
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+impl AssetIdConfig {
+    /// Overrides for one source (matched case-insensitively)
+    pub fn for_source(&self, source: &str) -> HashMap<String, String> {
+        self.0.iter()
+            .find(|(name, _)| name.eq_ignore_ascii_case(source))
+            .map(|(_, ids)| ids.clone())
+            .unwrap_or_default()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::consensus::ConsensusEngine;
+    use crate::models::PriceData;
+
+    fn write_config(name: &str, contents: &str) -> std::path::PathBuf {
+        let path = std::env::temp_dir().join(format!("oracle-config-{}-{}", std::process::id(), name));
+        std::fs::write(&path, contents).unwrap();
+        path
+    }
+
+    #[test]
+    fn test_load_toml_with_defaults() {
+        let path = write_config("node.toml", "asset = \"ETH\"\nupdate_interval = 60\n");
+        let config = OracleConfig::load(&path).unwrap();
+        std::fs::remove_file(&path).unwrap();
+
+        assert_eq!(config.asset, "ETH");
+        assert_eq!(config.update_interval, 60);
+        assert_eq!(config.rpc_url, OracleConfig::default().rpc_url);
+        assert_eq!(config.program_id, None);
+    }
+
+    #[test]
+    fn test_load_json() {
+        let path = write_config("node.json", r#"{"asset": "SOL", "program_id": "Prog111", "min_confidence": 0.9}"#);
+        let config = OracleConfig::load(&path).unwrap();
+        std::fs::remove_file(&path).unwrap();
+
+        assert_eq!(config.asset, "SOL");
+        assert_eq!(config.program_id.as_deref(), Some("Prog111"));
+        assert_eq!(config.min_confidence, 0.9);
+    }
+
+    #[test]
+    fn test_unknown_format_rejected() {
+        let path = write_config("node.yaml", "asset: BTC\n");
+        let result = OracleConfig::load(&path);
+        std::fs::remove_file(&path).unwrap();
+
+        assert!(result.unwrap_err().to_string().contains("Unsupported config format"));
+    }
+    
+    #[test]
+    fn test_source_weights_out_of_range_rejected() {
+        for weight in ["0.0", "2.5", "-1.0"] {
+            let path = write_config("weights.toml", &format!("[source_weights]\nBinance = {}\n", weight));
+            let result = OracleConfig::load(&path);
+            std::fs::remove_file(&path).unwrap();
+            
+            assert!(result.is_err(), "weight {} accepted", weight);
+        }
+    }
+    
+    #[test]
+    fn test_outlier_z_threshold_must_be_positive() {
+        for threshold in ["0.0", "-2.5"] {
+            let path = write_config("z-threshold.toml", &format!("outlier_z_threshold = {}\n", threshold));
+            let result = OracleConfig::load(&path);
+            std::fs::remove_file(&path).unwrap();
+            
+            assert!(result.unwrap_err().to_string().contains("outlier_z_threshold"), "threshold {} accepted", threshold);
+        }
+    }
+    
+    #[test]
+    fn test_half_weight_halves_influence() {
+        let path = write_config("weights-half.toml", "[source_weights]\nbinance = 0.5\n");
+        let config = OracleConfig::load(&path).unwrap();
+        std::fs::remove_file(&path).unwrap();
+        
+        assert_eq!(config.source_weights.weight_for("Binance"), 0.5);
+        assert_eq!(config.source_weights.weight_for("CoinGecko"), 1.0);
+        
+        let price_data: Vec<PriceData> = [("CoinGecko", 45000.0), ("Binance", 45300.0)].into_iter()
+            .map(|(source, price)| PriceData::new("BTC".to_string(), price, source.to_string())
+                .with_confidence(0.9)
+                .with_weight(config.source_weights.weight_for(source)))
+            .collect();
+        let result = ConsensusEngine::new().run_consensus(&price_data).unwrap();
+        
+        // Binance carries a weight of 0.5 against CoinGecko's 1.0, so the price sits a third of the way over
+        assert!((result.price - 45100.0).abs() < 1e-6);
+    }
+    
+    #[test]
+    fn test_asset_id_overrides_per_source() {
+        let path = write_config("asset-ids.toml", "[asset_ids.CoinGecko]\nPEPE = \"pepe\"\n\n[asset_ids.okx]\nPEPE = \"PEPE-USDC\"\n");
+        let config = OracleConfig::load(&path).unwrap();
+        std::fs::remove_file(&path).unwrap();
+        
+        assert_eq!(config.asset_ids.for_source("coingecko")["PEPE"], "pepe");
+        assert_eq!(config.asset_ids.for_source("OKX")["PEPE"], "PEPE-USDC");
+        assert!(config.asset_ids.for_source("Binance").is_empty());
+    }
+
+    #[test]
+    fn test_asset_aliases_section() {
+        let path = write_config("aliases.toml", "[asset_aliases]\npepecoin = \"PEPE\"\n\n[asset_ids.CoinGecko]\nWIF = \"dogwifcoin\"\n");
+        let config = OracleConfig::load(&path).unwrap();
+        std::fs::remove_file(&path).unwrap();
+        
+        assert_eq!(config.canonical_asset("PepeCoin").unwrap(), "PEPE");
+        assert_eq!(config.canonical_asset("$wif").unwrap(), "WIF");
+        assert_eq!(config.canonical_asset("bitcoin").unwrap(), "BTC");
+        assert!(config.canonical_asset("qwerty").unwrap_err().to_string().contains("unknown asset 'qwerty'"));
+        
+        let path = write_config("bad-aliases.toml", "[asset_aliases]\n\"moon coin\" = \"MOON\"\n");
+        let result = OracleConfig::load(&path);
+        std::fs::remove_file(&path).unwrap();
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn test_networks() {
+        assert_eq!("Devnet".parse::<NetworkKind>().unwrap(), NetworkKind::Devnet);
+        assert_eq!("mainnet-beta".parse::<NetworkKind>().unwrap(), NetworkKind::Mainnet);
+        assert_eq!("https://api.testnet.solana.com/".parse::<NetworkKind>().unwrap(), NetworkKind::Testnet);
+        assert_eq!("http://localhost:8899".parse::<NetworkKind>().unwrap(), NetworkKind::Custom("http://localhost:8899".to_string()));
+        assert!("moonnet".parse::<NetworkKind>().is_err());
+        assert!(NetworkKind::Mainnet.is_mainnet());
+        assert!("https://solana-mainnet.g.alchemy.com/v2/key".parse::<NetworkKind>().unwrap().is_mainnet());
+        assert!(!NetworkKind::Devnet.is_mainnet());
+        assert!(!NetworkKind::Custom("http://localhost:8899".to_string()).is_mainnet());
+
+        // Without a network list the node submits to rpc_url only, and requires it
+        let config = OracleConfig::default();
+        assert_eq!(config.submit_networks(), vec![NetworkKind::Devnet]);
+        assert_eq!(config.submit_required_networks(), vec![NetworkKind::Devnet]);
+
+        let path = write_config("networks.toml", "networks = [\"devnet\", \"mainnet\"]\nrequired_networks = [\"mainnet\"]\n");
+        let config = OracleConfig::load(&path).unwrap();
+        std::fs::remove_file(&path).unwrap();
+        assert_eq!(config.submit_networks(), vec![NetworkKind::Devnet, NetworkKind::Mainnet]);
+        assert_eq!(config.submit_required_networks(), vec![NetworkKind::Mainnet]);
+    }
+
+    #[test]
+    fn test_asset_category() {
+        assert_eq!(OracleConfig::default().asset_category, "Other");
+
+        let path = write_config("category.toml", "asset = \"UNI\"\nasset_category = \"DeFi\"\n");
+        let config = OracleConfig::load(&path).unwrap();
+        std::fs::remove_file(&path).unwrap();
+        assert_eq!(config.asset_category, "DeFi");
+
+        // Categories are matched exactly, as the program does
+        let path = write_config("bad_category.toml", "asset_category = \"defi\"\n");
+        let err = OracleConfig::load(&path).unwrap_err();
+        std::fs::remove_file(&path).unwrap();
+        assert!(format!("{:#}", err).contains("expected one of L1, L2, DeFi"));
+    }
+
+    #[test]
+    fn test_http_timeouts() {
+        let config = OracleConfig::default();
+        assert_eq!(config.http_timeout_for("CoinGecko"), Duration::from_secs(5));
+
+        let path = write_config("timeouts.toml", "http_timeout_secs = 4\n\n[http_timeouts]\nCoinGecko = 2\n");
+        let config = OracleConfig::load(&path).unwrap();
+        std::fs::remove_file(&path).unwrap();
+        assert_eq!(config.http_timeout_for("coingecko"), Duration::from_secs(2));
+        assert_eq!(config.http_timeout_for("Binance"), Duration::from_secs(4));
+
+        let path = write_config("zero_timeout.toml", "[http_timeouts]\nOKX = 0\n");
+        let result = OracleConfig::load(&path);
+        std::fs::remove_file(&path).unwrap();
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn test_source_timeout() {
+        assert_eq!(OracleConfig::default().source_timeout(), Duration::from_secs(10));
+
+        let path = write_config("source_timeout.toml", "source_timeout_secs = 3\n");
+        let config = OracleConfig::load(&path).unwrap();
+        std::fs::remove_file(&path).unwrap();
+        assert_eq!(config.source_timeout(), Duration::from_secs(3));
+
+        let path = write_config("zero_source_timeout.toml", "source_timeout_secs = 0\n");
+        let result = OracleConfig::load(&path);
+        std::fs::remove_file(&path).unwrap();
+        assert!(result.is_err());
+    }
+}
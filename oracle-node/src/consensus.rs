@@ -1,27 +1,60 @@
 // Consensus mechanism for price aggregation
 use anyhow::Result;
+use std::sync::{Arc, Mutex};
+use uuid::Uuid;
 
-use crate::models::{PriceData, ConsensusResult, ConsensusParams};
+use crate::models::{PriceData, ConsensusResult, ConsensusParams, ConsensusMethod, SourcePrice, ConsensusInput, ConsensusRound};
+use crate::ring_buffer::RingBuffer;
+
+/// Largest share of the total weight a single source may hold under volume weighting
+const MAX_VOLUME_SHARE: f64 = 0.5;
+
+/// Z-score above which a price is an outlier unless configured otherwise
+pub const DEFAULT_OUTLIER_Z_THRESHOLD: f64 = 2.5;
+
+/// Why a consensus round was refused, for callers that need to tell the cases apart
+#[derive(Debug, thiserror::Error)]
+pub enum ConsensusError {
+    #[error("Insufficient sources after removing outliers: {remaining} (minimum: {minimum})")]
+    InsufficientEffectiveSources { remaining: usize, minimum: usize },
+    #[error("No price data available from any source")]
+    NoPriceData,
+    #[error("Sources disagree too much: coefficient of variation {coefficient:.4} (max: {threshold:.4})")]
+    VarianceTooHigh { coefficient: f64, threshold: f64 },
+}
+
+/// Recent consensus rounds, shared with the status server
+pub type ConsensusRounds = Arc<Mutex<RingBuffer<ConsensusRound>>>;
 
 pub struct ConsensusEngine {
     params: ConsensusParams,
+    rounds: ConsensusRounds,
 }
 
 impl ConsensusEngine {
     pub fn new() -> Self {
-        Self {
-            params: ConsensusParams::default(),
-        }
+        Self::with_params(ConsensusParams::default())
     }
     
     pub fn with_params(params: ConsensusParams) -> Self {
-        Self { params }
+        let rounds = Arc::new(Mutex::new(RingBuffer::new(params.rounds_kept)));
+        Self { params, rounds }
+    }
+    
+    /// The last `rounds_kept` successful rounds, oldest first
+    pub fn rounds(&self) -> ConsensusRounds {
+        self.rounds.clone()
     }
     
     pub fn run_consensus(&self, price_data: &[PriceData]) -> Result<ConsensusResult> {
         if price_data.is_empty() {
             return Err(anyhow::anyhow!("No price data provided"));
         }
+        let price_data = &Self::at_mid_prices(price_data);
+        
+        if price_data.len() == 1 && self.params.allow_single_source {
+            return Ok(self.single_source_consensus(&price_data[0]));
+        }
         
         if price_data.len() < self.params.min_sources {
             return Err(anyhow::anyhow!(
@@ -54,8 +87,37 @@ impl ConsensusEngine {
             ));
         }
         
-        // Calculate weighted average excluding outliers
-        let consensus_price = self.calculate_weighted_average(price_data, &outliers);
+        // A "consensus" of the lone source left after outlier removal is just that source
+        let remaining = prices.len() - outlier_count;
+        if remaining < self.params.min_sources {
+            return Err(ConsensusError::InsufficientEffectiveSources {
+                remaining,
+                minimum: self.params.min_sources,
+            }.into());
+        }
+        
+        if self.params.reject_high_variance {
+            // Only the prices the method aggregates need to agree: trimmed extremes don't count
+            let excluded = match self.params.method {
+                ConsensusMethod::TrimmedMean { trim_pct } if (0.0..0.5).contains(&trim_pct) => self.trimmed_indices(price_data, trim_pct),
+                _ => outliers.clone(),
+            };
+            self.check_variance(&prices, &excluded)?;
+        }
+        
+        let vwap_price = self.calculate_vwap(price_data, &outliers);
+        
+        // Aggregate prices with the configured method
+        let consensus_price = match self.params.method {
+            ConsensusMethod::WeightedAverage => self.calculate_weighted_average(price_data, &outliers, false),
+            ConsensusMethod::VolumeWeighted => self.calculate_weighted_average(price_data, &outliers, true),
+            ConsensusMethod::TrimmedMean { trim_pct } => self.calculate_trimmed_mean(price_data, trim_pct)?,
+            ConsensusMethod::Vwap => match vwap_price {
+                Some(vwap) => vwap,
+                None => self.calculate_weighted_average(price_data, &outliers, false),
+            },
+        };
+        let vwap_unavailable = self.params.method == ConsensusMethod::Vwap && vwap_price.is_none();
         
         // Calculate confidence based on multiple factors
         let confidence = self.calculate_confidence(price_data, variance, outlier_count);
@@ -63,17 +125,89 @@ impl ConsensusEngine {
         // Calculate consensus score
         let consensus_score = self.calculate_consensus_score(price_data, variance, outlier_count);
         
+        let source_prices = price_data.iter().enumerate()
+            .map(|(i, p)| SourcePrice { source: p.source.clone(), price: p.price, outlier: outliers.contains(&i) })
+            .collect();
+        
         // Create consensus result
         let asset = price_data[0].asset.clone();
         let result = ConsensusResult::new(asset, consensus_price, sources)
             .with_confidence(confidence)
             .with_consensus_score(consensus_score)
             .with_variance(variance)
-            .with_outliers(outlier_count);
+            .with_outliers(Self::source_names(price_data, &outliers))
+            .with_simulated(price_data.iter().any(|p| p.simulated))
+            .with_vwap(vwap_price, vwap_unavailable)
+            .with_source_prices(source_prices);
+        
+        self.record_round(price_data, &outliers, vwap_price, &result);
         
         Ok(result)
     }
     
+    /// A lone source's price as a degraded result: its confidence capped and no consensus score
+    fn single_source_consensus(&self, data: &PriceData) -> ConsensusResult {
+        let source_prices = vec![SourcePrice { source: data.source.clone(), price: data.price, outlier: false }];
+        let result = ConsensusResult::new(data.asset.clone(), data.price, vec![data.source.clone()])
+            .with_confidence(data.confidence.min(self.params.single_source_confidence_cap))
+            .with_consensus_score(0.0)
+            .with_variance(0.0)
+            .with_simulated(data.simulated)
+            .with_vwap(data.volume_24h.map(|_| data.price), self.params.method == ConsensusMethod::Vwap && data.volume_24h.is_none())
+            .with_source_prices(source_prices)
+            .with_degraded(true);
+        
+        self.record_round(std::slice::from_ref(data), &[], result.vwap_price, &result);
+        
+        result
+    }
+    
+    /// Keep a record of the round: each input's share of the final price and what was left out
+    fn record_round(&self, price_data: &[PriceData], outliers: &[usize], vwap_price: Option<f64>, result: &ConsensusResult) {
+        let excluded = match self.params.method {
+            ConsensusMethod::TrimmedMean { trim_pct } => self.trimmed_indices(price_data, trim_pct),
+            _ => outliers.to_vec(),
+        };
+        let included: Vec<&PriceData> = price_data.iter()
+            .enumerate()
+            .filter(|(i, _)| !excluded.contains(i))
+            .map(|(_, data)| data)
+            .collect();
+        
+        let raw_weights: Vec<f64> = match self.params.method {
+            ConsensusMethod::VolumeWeighted => self.volume_weights(&included),
+            ConsensusMethod::Vwap if vwap_price.is_some() => included.iter().map(|d| d.volume_24h.unwrap_or(0.0)).collect(),
+            _ => included.iter().map(|d| d.confidence * d.weight).collect(),
+        };
+        let total_weight: f64 = raw_weights.iter().sum();
+        let mut shares = raw_weights.iter()
+            .map(|w| if total_weight > 0.0 { w / total_weight } else { 1.0 / included.len() as f64 });
+        
+        let inputs = price_data.iter()
+            .enumerate()
+            .map(|(i, data)| {
+                let excluded = excluded.contains(&i);
+                ConsensusInput {
+                    source: data.source.clone(),
+                    price: data.price,
+                    confidence: data.confidence,
+                    weight: if excluded { 0.0 } else { shares.next().unwrap_or(0.0) },
+                    excluded,
+                }
+            })
+            .collect();
+        
+        self.rounds.lock().unwrap().push(ConsensusRound {
+            round_id: Uuid::new_v4(),
+            asset: result.asset.clone(),
+            timestamp: result.timestamp,
+            inputs,
+            outliers: outliers.to_vec(),
+            final_price: result.price,
+            method: self.params.method,
+        });
+    }
+    
     fn calculate_mean(&self, prices: &[f64]) -> f64 {
         prices.iter().sum::<f64>() / prices.len() as f64
     }
@@ -85,13 +219,55 @@ impl ConsensusEngine {
         sum_squared_diff / prices.len() as f64
     }
     
+    /// Fail when the prices not `excluded` spread further than `price_variance_threshold`,
+    /// measured as std dev / mean
+    fn check_variance(&self, prices: &[f64], excluded: &[usize]) -> Result<(), ConsensusError> {
+        let included: Vec<f64> = prices.iter().enumerate()
+            .filter(|(i, _)| !excluded.contains(i))
+            .map(|(_, &price)| price)
+            .collect();
+        let mean = self.calculate_mean(&included);
+        if mean <= 0.0 {
+            return Ok(());
+        }
+        
+        let coefficient = self.calculate_variance(&included, mean).sqrt() / mean;
+        if coefficient > self.params.price_variance_threshold {
+            return Err(ConsensusError::VarianceTooHigh {
+                coefficient,
+                threshold: self.params.price_variance_threshold,
+            });
+        }
+        Ok(())
+    }
+    
+    /// Sources whose price `run_consensus` would flag as an outlier
+    pub fn outlier_sources(&self, price_data: &[PriceData]) -> Vec<String> {
+        let prices: Vec<f64> = price_data.iter().map(|p| p.mid_price()).collect();
+        let mean_price = self.calculate_mean(&prices);
+        let std_dev = self.calculate_variance(&prices, mean_price).sqrt();
+        
+        Self::source_names(price_data, &self.detect_outliers(&prices, mean_price, std_dev))
+    }
+    
+    /// Inputs priced at their order book's mid-price where they report one, which a single
+    /// trade can't skew the way it does the last price
+    fn at_mid_prices(price_data: &[PriceData]) -> Vec<PriceData> {
+        price_data.iter()
+            .map(|data| PriceData { price: data.mid_price(), ..data.clone() })
+            .collect()
+    }
+    
+    fn source_names(price_data: &[PriceData], indices: &[usize]) -> Vec<String> {
+        indices.iter().map(|&i| price_data[i].source.clone()).collect()
+    }
+    
     fn detect_outliers(&self, prices: &[f64], mean: f64, std_dev: f64) -> Vec<usize> {
         let mut outliers = Vec::new();
         
         for (i, price) in prices.iter().enumerate() {
             let z_score = (price - mean).abs() / std_dev;
-            // Consider outliers if Z-score > 2.5 (more conservative than 2.0)
-            if z_score > 2.5 {
+            if z_score > self.params.outlier_z_threshold {
                 outliers.push(i);
             }
         }
@@ -99,17 +275,21 @@ impl ConsensusEngine {
         outliers
     }
     
-    fn calculate_weighted_average(&self, price_data: &[PriceData], outliers: &[usize]) -> f64 {
-        let mut total_weight = 0.0;
-        let mut weighted_sum = 0.0;
+    fn calculate_weighted_average(&self, price_data: &[PriceData], outliers: &[usize], by_volume: bool) -> f64 {
+        let included: Vec<&PriceData> = price_data.iter()
+            .enumerate()
+            .filter(|(i, _)| !outliers.contains(i))
+            .map(|(_, data)| data)
+            .collect();
         
-        for (i, data) in price_data.iter().enumerate() {
-            if !outliers.contains(&i) {
-                let weight = data.confidence;
-                weighted_sum += data.price * weight;
-                total_weight += weight;
-            }
-        }
+        let weights = if by_volume {
+            self.volume_weights(&included)
+        } else {
+            included.iter().map(|data| data.confidence * data.weight).collect()
+        };
+        
+        let total_weight: f64 = weights.iter().sum();
+        let weighted_sum: f64 = included.iter().zip(&weights).map(|(data, w)| data.price * w).sum();
         
         if total_weight > 0.0 {
             weighted_sum / total_weight
@@ -123,6 +303,120 @@ impl ConsensusEngine {
         }
     }
     
+    /// Volume-weighted average of the non-outlier prices, or `None` unless every source has a volume
+    fn calculate_vwap(&self, price_data: &[PriceData], outliers: &[usize]) -> Option<f64> {
+        if price_data.iter().any(|d| d.volume_24h.is_none()) {
+            return None;
+        }
+        
+        let (weighted_sum, total_volume) = price_data.iter()
+            .enumerate()
+            .filter(|(i, _)| !outliers.contains(i))
+            .filter_map(|(_, d)| d.volume_24h.map(|volume| (d.price, volume)))
+            .fold((0.0, 0.0), |(sum, total), (price, volume)| (sum + price * volume, total + volume));
+        
+        (total_volume > 0.0).then(|| weighted_sum / total_volume)
+    }
+    
+    /// Per-source weights proportional to 24h volume, with no source above `MAX_VOLUME_SHARE`.
+    /// Sources without a volume count as an average venue scaled by their confidence.
+    fn volume_weights(&self, price_data: &[&PriceData]) -> Vec<f64> {
+        let volumes: Vec<f64> = price_data.iter()
+            .filter_map(|d| d.volume_24h.filter(|v| *v > 0.0))
+            .collect();
+        let mean_volume = if volumes.is_empty() {
+            1.0
+        } else {
+            volumes.iter().sum::<f64>() / volumes.len() as f64
+        };
+        
+        let raw: Vec<f64> = price_data.iter()
+            .map(|d| {
+                let volume = d.volume_24h
+                    .filter(|v| *v > 0.0)
+                    .unwrap_or(d.confidence * mean_volume);
+                volume * d.weight
+            })
+            .collect();
+        
+        let total: f64 = raw.iter().sum();
+        if total <= 0.0 {
+            return raw;
+        }
+        
+        // Cap oversized shares and hand the excess to the others in proportion,
+        // repeating until nothing is over the cap (it can't go below an even split)
+        let cap = MAX_VOLUME_SHARE.max(1.0 / raw.len() as f64);
+        let mut shares: Vec<f64> = raw.iter().map(|w| w / total).collect();
+        let mut capped = vec![false; shares.len()];
+        loop {
+            let excess: f64 = shares.iter().map(|s| (s - cap).max(0.0)).sum();
+            if excess <= f64::EPSILON {
+                break;
+            }
+            for (share, is_capped) in shares.iter_mut().zip(capped.iter_mut()) {
+                if *share >= cap {
+                    *share = cap;
+                    *is_capped = true;
+                }
+            }
+            let uncapped_total: f64 = shares.iter().zip(&capped)
+                .filter(|(_, c)| !**c)
+                .map(|(s, _)| s)
+                .sum();
+            if uncapped_total <= 0.0 {
+                break;
+            }
+            for (share, is_capped) in shares.iter_mut().zip(&capped) {
+                if !*is_capped {
+                    *share += excess * *share / uncapped_total;
+                }
+            }
+        }
+        
+        shares
+    }
+    
+    fn calculate_trimmed_mean(&self, price_data: &[PriceData], trim_pct: f64) -> Result<f64> {
+        if !(0.0..0.5).contains(&trim_pct) {
+            return Err(anyhow::anyhow!("Invalid trim percentage: {} (must be in [0, 0.5))", trim_pct));
+        }
+        
+        let trimmed = self.trimmed_indices(price_data, trim_pct);
+        let remaining: Vec<&PriceData> = price_data.iter()
+            .enumerate()
+            .filter(|(i, _)| !trimmed.contains(i))
+            .map(|(_, data)| data)
+            .collect();
+        
+        if remaining.len() < self.params.min_sources {
+            return Err(anyhow::anyhow!(
+                "Insufficient sources after trimming: {} (minimum: {})", 
+                remaining.len(), 
+                self.params.min_sources
+            ));
+        }
+        
+        let total_weight: f64 = remaining.iter().map(|d| d.confidence * d.weight).sum();
+        if total_weight > 0.0 {
+            Ok(remaining.iter().map(|d| d.price * d.confidence * d.weight).sum::<f64>() / total_weight)
+        } else {
+            Ok(remaining.iter().map(|d| d.price).sum::<f64>() / remaining.len() as f64)
+        }
+    }
+    
+    /// Indices of the `trim_pct` lowest and highest prices
+    fn trimmed_indices(&self, price_data: &[PriceData], trim_pct: f64) -> Vec<usize> {
+        let mut order: Vec<usize> = (0..price_data.len()).collect();
+        order.sort_by(|&a, &b| price_data[a].price.total_cmp(&price_data[b].price));
+        
+        // Drop k entries from each end
+        let k = (order.len() as f64 * trim_pct).floor() as usize;
+        let mut trimmed = order[..k].to_vec();
+        trimmed.extend_from_slice(&order[order.len() - k..]);
+        trimmed
+    }
+    
     fn calculate_confidence(&self, price_data: &[PriceData], variance: f64, outlier_count: usize) -> f64 {
         // Base confidence from source confidences
         let avg_source_confidence = price_data.iter()
@@ -154,18 +448,20 @@ impl ConsensusEngine {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::models::HealthWeights;
     use chrono::Utc;
     
     #[test]
     fn test_consensus_with_good_data() {
         let engine = ConsensusEngine::new();
         
+        // Confidence falls with the absolute price variance, so "good" means within a few dollars
         let price_data = vec![
             PriceData::new("BTC".to_string(), 45000.0, "Source1".to_string())
                 .with_confidence(0.9),
-            PriceData::new("BTC".to_string(), 45100.0, "Source2".to_string())
+            PriceData::new("BTC".to_string(), 45010.0, "Source2".to_string())
                 .with_confidence(0.8),
-            PriceData::new("BTC".to_string(), 44900.0, "Source3".to_string())
+            PriceData::new("BTC".to_string(), 44990.0, "Source3".to_string())
                 .with_confidence(0.85),
         ];
         
@@ -181,14 +477,12 @@ mod tests {
     fn test_consensus_with_outlier() {
         let engine = ConsensusEngine::new();
         
-        let price_data = vec![
-            PriceData::new("BTC".to_string(), 45000.0, "Source1".to_string())
-                .with_confidence(0.9),
-            PriceData::new("BTC".to_string(), 45100.0, "Source2".to_string())
-                .with_confidence(0.8),
-            PriceData::new("BTC".to_string(), 50000.0, "Source3".to_string()) // Outlier
-                .with_confidence(0.7),
-        ];
+        // With three sources no z-score can pass the threshold, so the outlier needs company
+        let mut price_data: Vec<PriceData> = (0..9)
+            .map(|i| PriceData::new("BTC".to_string(), 45000.0 + i as f64 * 10.0, format!("Source{}", i))
+                .with_confidence(0.9))
+            .collect();
+        price_data.push(PriceData::new("BTC".to_string(), 50000.0, "Outlier".to_string()).with_confidence(0.7));
         
         let result = engine.run_consensus(&price_data).unwrap();
         
@@ -197,6 +491,65 @@ mod tests {
         assert!(result.outlier_count > 0);
     }
     
+    #[test]
+    fn test_vwap_reported_alongside_weighted_average() {
+        let price_data = vec![
+            PriceData::new("BTC".to_string(), 45000.0, "Source1".to_string())
+                .with_confidence(0.9)
+                .with_volume(2_000_000.0),
+            PriceData::new("BTC".to_string(), 45010.0, "Source2".to_string())
+                .with_confidence(0.8)
+                .with_volume(1_000_000.0),
+            PriceData::new("BTC".to_string(), 44990.0, "Source3".to_string())
+                .with_confidence(0.85)
+                .with_volume(1_000_000.0),
+        ];
+        
+        let result = ConsensusEngine::new().run_consensus(&price_data).unwrap();
+        
+        assert!((result.vwap_price.unwrap() - 45000.0).abs() < 1e-6);
+        assert!(!result.vwap_unavailable);
+    }
+    
+    fn trimmed_mean_engine(trim_pct: f64) -> ConsensusEngine {
+        ConsensusEngine::with_params(ConsensusParams {
+            method: ConsensusMethod::TrimmedMean { trim_pct },
+            ..ConsensusParams::default()
+        })
+    }
+    
+    #[test]
+    fn test_trimmed_mean_discards_extremes() {
+        let engine = trimmed_mean_engine(0.1);
+        
+        // Eight tightly clustered prices plus one low and one high extreme
+        let mut price_data: Vec<PriceData> = (0..8)
+            .map(|i| PriceData::new("BTC".to_string(), 45000.0 + i as f64 * 10.0, format!("Source{}", i))
+                .with_confidence(0.9))
+            .collect();
+        price_data.push(PriceData::new("BTC".to_string(), 40000.0, "Low".to_string()).with_confidence(0.9));
+        price_data.push(PriceData::new("BTC".to_string(), 52000.0, "High".to_string()).with_confidence(0.9));
+        
+        let result = engine.run_consensus(&price_data).unwrap();
+        
+        // Mean of 45000..=45070 in steps of 10
+        assert!((result.price - 45035.0).abs() < 1e-6);
+    }
+    
+    #[test]
+    fn test_trimmed_mean_requires_min_sources() {
+        let engine = trimmed_mean_engine(0.4);
+        
+        let price_data = vec![
+            PriceData::new("BTC".to_string(), 45000.0, "Source1".to_string()),
+            PriceData::new("BTC".to_string(), 45100.0, "Source2".to_string()),
+            PriceData::new("BTC".to_string(), 44900.0, "Source3".to_string()),
+        ];
+        
+        // k = 1 leaves a single price, below min_sources
+        assert!(engine.run_consensus(&price_data).is_err());
+    }
+    
     #[test]
     fn test_consensus_insufficient_sources() {
         let engine = ConsensusEngine::new();
@@ -208,4 +561,348 @@ mod tests {
         let result = engine.run_consensus(&price_data);
         assert!(result.is_err());
     }
+    
+    #[test]
+    fn test_single_source_fallback_is_degraded() {
+        let engine = ConsensusEngine::with_params(ConsensusParams {
+            allow_single_source: true,
+            ..ConsensusParams::default()
+        });
+        
+        let price_data = vec![
+            PriceData::new("BTC".to_string(), 45000.0, "Source1".to_string()).with_confidence(0.9),
+        ];
+        
+        let result = engine.run_consensus(&price_data).unwrap();
+        assert_eq!(result.price, 45000.0);
+        assert_eq!(result.confidence, 0.4);
+        assert_eq!(result.consensus_score, 0.0);
+        assert!(result.degraded);
+        assert_eq!(engine.rounds().lock().unwrap().len(), 1);
+        
+        // Enough sources for a consensus is never degraded
+        let mut price_data = price_data;
+        price_data.push(PriceData::new("BTC".to_string(), 45100.0, "Source2".to_string()));
+        assert!(!engine.run_consensus(&price_data).unwrap().degraded);
+    }
+    
+    #[test]
+    fn test_volume_weighted_pulls_toward_liquid_venue() {
+        let price_data = vec![
+            PriceData::new("BTC".to_string(), 45000.0, "Small1".to_string())
+                .with_confidence(0.9)
+                .with_volume(1_000_000.0),
+            PriceData::new("BTC".to_string(), 45000.0, "Small2".to_string())
+                .with_confidence(0.9)
+                .with_volume(1_000_000.0),
+            PriceData::new("BTC".to_string(), 45300.0, "Liquid".to_string())
+                .with_confidence(0.9)
+                .with_volume(1_000_000_000.0),
+        ];
+        
+        let volume_engine = ConsensusEngine::with_params(ConsensusParams {
+            method: ConsensusMethod::VolumeWeighted,
+            ..ConsensusParams::default()
+        });
+        let volume_price = volume_engine.run_consensus(&price_data).unwrap().price;
+        let plain_price = ConsensusEngine::new().run_consensus(&price_data).unwrap().price;
+        
+        assert!(volume_price > plain_price);
+        // The liquid venue is capped at half the weight rather than taking all of it
+        assert!((volume_price - 45150.0).abs() < 1e-6);
+    }
+    
+    #[test]
+    fn test_volume_weighted_falls_back_to_confidence() {
+        let engine = ConsensusEngine::new();
+        
+        let price_data = [
+            PriceData::new("BTC".to_string(), 45000.0, "Source1".to_string()).with_confidence(0.8),
+            PriceData::new("BTC".to_string(), 45100.0, "Source2".to_string()).with_confidence(0.6),
+            PriceData::new("BTC".to_string(), 45200.0, "Source3".to_string()).with_confidence(0.6),
+        ];
+        let refs: Vec<&PriceData> = price_data.iter().collect();
+        
+        // Without volumes the shares follow confidence
+        let weights = engine.volume_weights(&refs);
+        assert!((weights[0] - 0.4).abs() < 1e-9);
+        assert!((weights[1] - 0.3).abs() < 1e-9);
+        assert!((weights[2] - 0.3).abs() < 1e-9);
+    }
+    
+    fn vwap_engine() -> ConsensusEngine {
+        ConsensusEngine::with_params(ConsensusParams {
+            method: ConsensusMethod::Vwap,
+            ..ConsensusParams::default()
+        })
+    }
+    
+    #[test]
+    fn test_vwap_uses_reported_volume() {
+        let price_data = vec![
+            PriceData::new("BTC".to_string(), 45000.0, "Source1".to_string())
+                .with_confidence(0.9)
+                .with_volume(3_000_000.0),
+            PriceData::new("BTC".to_string(), 45400.0, "Source2".to_string())
+                .with_confidence(0.9)
+                .with_volume(1_000_000.0),
+        ];
+        
+        let result = vwap_engine().run_consensus(&price_data).unwrap();
+        
+        assert!((result.price - 45100.0).abs() < 1e-6);
+        assert_eq!(result.vwap_price, Some(result.price));
+        assert!(!result.vwap_unavailable);
+    }
+    
+    #[test]
+    fn test_vwap_falls_back_without_volume() {
+        let price_data = vec![
+            PriceData::new("BTC".to_string(), 45000.0, "Source1".to_string())
+                .with_confidence(0.9)
+                .with_volume(3_000_000.0),
+            PriceData::new("BTC".to_string(), 45400.0, "Source2".to_string())
+                .with_confidence(0.9),
+        ];
+        
+        let result = vwap_engine().run_consensus(&price_data).unwrap();
+        
+        // Equal confidences, so the weighted average is the plain mean
+        assert!((result.price - 45200.0).abs() < 1e-6);
+        assert_eq!(result.vwap_price, None);
+        assert!(result.vwap_unavailable);
+        
+        // Other methods never report VWAP as unavailable
+        assert!(!ConsensusEngine::new().run_consensus(&price_data).unwrap().vwap_unavailable);
+    }
+    
+    #[test]
+    fn test_simulated_input_tags_result() {
+        let engine = ConsensusEngine::new();
+        
+        let price_data = vec![
+            PriceData::new("BTC".to_string(), 45000.0, "Source1".to_string()),
+            PriceData::new("BTC".to_string(), 45100.0, "Source2".to_string()).simulated(),
+        ];
+        assert!(engine.run_consensus(&price_data).unwrap().simulated);
+        
+        let real_data = vec![price_data[0].clone(), price_data[0].clone()];
+        assert!(!engine.run_consensus(&real_data).unwrap().simulated);
+    }
+    
+    #[test]
+    fn test_source_prices_flag_outliers() {
+        let engine = ConsensusEngine::new();
+        
+        let mut price_data: Vec<PriceData> = (0..9)
+            .map(|i| PriceData::new("SOL".to_string(), 100.0 + i as f64 * 0.1, format!("Source{}", i)))
+            .collect();
+        price_data.push(PriceData::new("SOL".to_string(), 200.0, "Rogue".to_string()));
+        
+        let result = engine.run_consensus(&price_data).unwrap();
+        assert_eq!(result.source_prices.len(), 10);
+        assert_eq!(result.source_prices[0], SourcePrice { source: "Source0".to_string(), price: 100.0, outlier: false });
+        let flagged: Vec<&str> = result.source_prices.iter()
+            .filter(|p| p.outlier)
+            .map(|p| p.source.as_str())
+            .collect();
+        assert_eq!(flagged, vec!["Rogue"]);
+    }
+    
+    #[test]
+    fn test_outlier_sources_named() {
+        let engine = ConsensusEngine::new();
+        
+        let mut price_data: Vec<PriceData> = (0..9)
+            .map(|i| PriceData::new("BTC".to_string(), 45000.0 + i as f64 * 10.0, format!("Source{}", i)))
+            .collect();
+        price_data.insert(4, PriceData::new("BTC".to_string(), 90000.0, "Rogue".to_string()));
+        
+        let result = engine.run_consensus(&price_data).unwrap();
+        assert_eq!(result.outlier_sources, vec!["Rogue".to_string()]);
+        assert_eq!(result.outlier_count, 1);
+        assert_eq!(engine.outlier_sources(&price_data), result.outlier_sources);
+        
+        price_data.remove(4);
+        assert!(engine.outlier_sources(&price_data).is_empty());
+    }
+    
+    #[test]
+    fn test_health_score_drops_with_variance_and_outliers() {
+        let params = ConsensusParams::default();
+        let sources: Vec<String> = (0..5).map(|i| format!("Source{}", i)).collect();
+        let result = |variance: f64, outliers: usize| {
+            ConsensusResult::new("BTC".to_string(), 100.0, sources.clone())
+                .with_confidence(0.9)
+                .with_variance(variance)
+                .with_outliers(sources[..outliers].to_vec())
+        };
+
+        let healthy = result(0.0, 0).health_score(&params);
+        assert!((healthy - 96.0).abs() < 1e-9, "{}", healthy);
+
+        // Std dev of 1% and 2% of the price against the 5% threshold
+        let noisy = result(1.0, 0).health_score(&params);
+        let noisier = result(4.0, 0).health_score(&params);
+        assert!(healthy > noisy && noisy > noisier, "{} {} {}", healthy, noisy, noisier);
+        assert!((noisy - 91.0).abs() < 1e-9, "{}", noisy);
+
+        let one_outlier = result(0.0, 1).health_score(&params);
+        let two_outliers = result(0.0, 2).health_score(&params);
+        assert!(healthy > one_outlier && one_outlier > two_outliers, "{} {} {}", healthy, one_outlier, two_outliers);
+
+        // Weights are configurable; with only the source weight the score follows the source count
+        let params = ConsensusParams {
+            health_weights: HealthWeights { confidence: 0.0, variance: 0.0, outliers: 0.0, sources: 1.0, full_source_count: 10 },
+            ..ConsensusParams::default()
+        };
+        assert!((result(4.0, 0).health_score(&params) - 50.0).abs() < 1e-9);
+        assert!((result(0.0, 1).health_score(&params) - 40.0).abs() < 1e-9);
+    }
+    
+    #[test]
+    fn test_mid_price_preferred_over_last_price() {
+        let engine = ConsensusEngine::new();
+        let price_data = vec![
+            PriceData::new("BTC".to_string(), 45300.0, "Binance".to_string()).with_bid_ask(44990.0, 45010.0),
+            PriceData::new("BTC".to_string(), 45000.0, "CoinGecko".to_string()),
+        ];
+        
+        let result = engine.run_consensus(&price_data).unwrap();
+        assert!((result.price - 45000.0).abs() < 1e-6, "{}", result.price);
+        assert_eq!(result.source_prices[0].price, 45000.0);
+        
+        let quote = &price_data[0];
+        assert_eq!(quote.mid_price(), 45000.0);
+        assert_eq!(quote.spread(), Some(20.0));
+        assert!((quote.spread_bps().unwrap() - 20.0 / 45000.0 * 10_000.0).abs() < 1e-9);
+        assert_eq!(price_data[1].mid_price(), 45000.0);
+        assert_eq!(price_data[1].spread(), None);
+        assert_eq!(price_data[1].spread_bps(), None);
+    }
+    
+    #[test]
+    fn test_outlier_z_threshold() {
+        // Nine agreeing sources and one 2% off: a z-score of 9/sqrt(10) ~ 2.85
+        let mut price_data: Vec<PriceData> = (0..9)
+            .map(|i| PriceData::new("SOL".to_string(), 100.0, format!("Source{}", i)))
+            .collect();
+        price_data.push(PriceData::new("SOL".to_string(), 102.0, "Drifting".to_string()));
+        let engine = |outlier_z_threshold| ConsensusEngine::with_params(ConsensusParams {
+            outlier_z_threshold,
+            ..ConsensusParams::default()
+        });
+        
+        assert_eq!(engine(DEFAULT_OUTLIER_Z_THRESHOLD).outlier_sources(&price_data), vec!["Drifting".to_string()]);
+        assert!(engine(3.0).outlier_sources(&price_data).is_empty());
+        assert_eq!(engine(3.0).run_consensus(&price_data).unwrap().outlier_count, 0);
+    }
+    
+    #[test]
+    fn test_outliers_count_against_min_sources() {
+        let engine = |min_sources, max_outlier_percentage, outlier_z_threshold| ConsensusEngine::with_params(ConsensusParams {
+            min_sources,
+            max_outlier_percentage,
+            outlier_z_threshold,
+            ..ConsensusParams::default()
+        });
+        let insufficient = |err: anyhow::Error| match err.downcast_ref() {
+            Some(ConsensusError::InsufficientEffectiveSources { remaining, minimum }) => (*remaining, *minimum),
+            _ => panic!("unexpected error: {}", err),
+        };
+        
+        // Two prices always sit one standard deviation either side of their mean, so with a
+        // threshold below 1 both are outliers and nothing is left to agree on
+        let pair = vec![
+            PriceData::new("BTC".to_string(), 45000.0, "Source1".to_string()),
+            PriceData::new("BTC".to_string(), 50000.0, "Source2".to_string()),
+        ];
+        let lenient = engine(2, 1.0, 0.5);
+        assert_eq!(insufficient(lenient.run_consensus(&pair).unwrap_err()), (0, 2));
+        assert!(lenient.rounds().lock().unwrap().is_empty());
+        
+        // One outlier among three (z ~ 1.41) leaves two survivors: enough for 2, not for 3
+        let mut trio = pair.clone();
+        trio.insert(1, PriceData::new("BTC".to_string(), 45010.0, "Source3".to_string()));
+        assert_eq!(insufficient(engine(3, 0.5, 1.2).run_consensus(&trio).unwrap_err()), (2, 3));
+        let result = engine(2, 0.5, 1.2).run_consensus(&trio).unwrap();
+        assert_eq!(result.outlier_sources, vec!["Source2".to_string()]);
+        assert!(result.price < 45010.0 + 1e-6);
+    }
+    
+    #[test]
+    fn test_rounds_record_inputs() {
+        let engine = ConsensusEngine::with_params(ConsensusParams { rounds_kept: 2, ..ConsensusParams::default() });
+        
+        let mut price_data: Vec<PriceData> = (0..9)
+            .map(|i| PriceData::new("SOL".to_string(), 100.0 + i as f64 * 0.1, format!("Source{}", i)))
+            .collect();
+        price_data.push(PriceData::new("SOL".to_string(), 200.0, "Rogue".to_string()));
+        for _ in 0..3 {
+            engine.run_consensus(&price_data).unwrap();
+        }
+        
+        let rounds = engine.rounds();
+        let rounds = rounds.lock().unwrap();
+        assert_eq!(rounds.len(), 2);
+        let round = rounds.iter().last().unwrap();
+        assert_eq!(round.asset, "SOL");
+        assert_eq!(round.outliers, vec![9]);
+        assert_eq!(round.method, ConsensusMethod::WeightedAverage);
+        assert!(round.inputs[9].excluded);
+        assert_eq!(round.inputs[9].weight, 0.0);
+        assert!((round.inputs.iter().map(|input| input.weight).sum::<f64>() - 1.0).abs() < 1e-9);
+        assert!((round.inputs[0].weight - 1.0 / 9.0).abs() < 1e-9);
+        
+        // Failed rounds are not recorded
+        assert!(engine.run_consensus(&price_data[..1]).is_err());
+        assert_eq!(rounds.len(), 2);
+    }
+    
+    #[test]
+    fn test_variance_cap() {
+        let engine = ConsensusEngine::new();
+        let prices = |prices: &[f64]| -> Vec<PriceData> {
+            prices.iter().enumerate()
+                .map(|(i, &price)| PriceData::new("BTC".to_string(), price, format!("Source{}", i)).with_confidence(0.9))
+                .collect()
+        };
+        
+        // ±20% around 45000: no single outlier, the sources just disagree
+        let wide = prices(&[36000.0, 40500.0, 45000.0, 49500.0, 54000.0]);
+        let err = engine.run_consensus(&wide).unwrap_err();
+        match err.downcast_ref() {
+            Some(ConsensusError::VarianceTooHigh { coefficient, threshold }) => {
+                assert!(*coefficient > 0.14 && *coefficient < 0.15, "{}", coefficient);
+                assert_eq!(*threshold, 0.05);
+            },
+            other => panic!("expected VarianceTooHigh, got {:?}", other),
+        }
+        
+        // Within ±1% passes
+        let tight = prices(&[44550.0, 44800.0, 45000.0, 45200.0, 45450.0]);
+        assert!(engine.run_consensus(&tight).is_ok());
+        
+        // Opting out publishes the wide round
+        let lenient = ConsensusEngine::with_params(ConsensusParams {
+            reject_high_variance: false,
+            ..ConsensusParams::default()
+        });
+        assert!(lenient.run_consensus(&wide).is_ok());
+    }
+    
+    #[test]
+    fn test_rounds_mark_trimmed_inputs() {
+        let engine = trimmed_mean_engine(0.25);
+        let price_data: Vec<PriceData> = [100.0, 101.0, 150.0, 50.0]
+            .iter()
+            .map(|&price| PriceData::new("BTC".to_string(), price, format!("S{}", price)))
+            .collect();
+        engine.run_consensus(&price_data).unwrap();
+        
+        let rounds = engine.rounds();
+        let rounds = rounds.lock().unwrap();
+        let excluded: Vec<bool> = rounds.iter().last().unwrap().inputs.iter().map(|input| input.excluded).collect();
+        assert_eq!(excluded, vec![false, false, true, true]);
+    }
 }
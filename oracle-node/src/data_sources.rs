@@ -2,9 +2,15 @@
 use anyhow::Result;
 use async_trait::async_trait;
 use reqwest::Client;
-use serde_json::Value;
+use log::warn;
+use serde_json::{json, Value};
+use solana_sdk::pubkey::Pubkey;
+use std::collections::{HashMap, HashSet};
+use std::str::FromStr;
+use std::sync::{Arc, Mutex};
 use std::time::Duration;
 
+use crate::asset_registry::AssetRegistry;
 use crate::models::PriceData;
 
 #[async_trait]
@@ -14,27 +20,100 @@ pub trait DataSource: Send + Sync {
     fn base_url(&self) -> &str;
 }
 
+/// How long a source's request may take unless configured otherwise
+pub const DEFAULT_HTTP_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Builds what the data sources share
+pub struct DataSourceFactory;
+
+impl DataSourceFactory {
+    /// One HTTP client for every source, so they share a connection pool and DNS cache.
+    /// Timeouts are set per request, since each source has its own.
+    pub fn create_shared_client() -> Arc<Client> {
+        let client = Client::builder()
+            .tcp_keepalive(Duration::from_secs(60))
+            .pool_max_idle_per_host(5)
+            .http2_prior_knowledge()
+            .build()
+            .expect("Failed to create HTTP client");
+        Arc::new(client)
+    }
+}
+
+/// Operator overrides of a source's asset ids (asset symbol -> source-specific id),
+/// consulted before the source's built-in mapping
+#[derive(Default)]
+pub struct AssetIds {
+    overrides: HashMap<String, String>,
+    // Assets already warned about as unmapped, so each is only logged once
+    warned: Mutex<HashSet<String>>,
+}
+
+impl AssetIds {
+    pub fn new(overrides: HashMap<String, String>) -> Self {
+        Self {
+            overrides: overrides.into_iter().map(|(asset, id)| (asset.to_uppercase(), id)).collect(),
+            warned: Mutex::new(HashSet::new()),
+        }
+    }
+    
+    /// Configured id for an asset, if any
+    fn get(&self, asset: &str) -> Option<String> {
+        self.overrides.get(&asset.to_uppercase()).cloned()
+    }
+    
+    fn warn_unmapped(&self, source: &str, asset: &str, fallback: &str) {
+        if self.warned.lock().unwrap().insert(asset.to_uppercase()) {
+            warn!("{} has no id mapping for {}; using '{}' (add one under [asset_ids.{}] in the config)",
+                  source, asset, fallback, source);
+        }
+    }
+}
+
 /// CoinGecko API data source
 pub struct CoinGeckoSource {
-    client: Client,
+    client: Arc<Client>,
     base_url: String,
+    asset_ids: AssetIds,
+    asset_registry: AssetRegistry,
+    timeout: Duration,
 }
 
 impl CoinGeckoSource {
-    pub fn new() -> Self {
-        let client = Client::builder()
-            .timeout(Duration::from_secs(10))
-            .build()
-            .expect("Failed to create HTTP client");
-        
+    pub fn new(client: Arc<Client>) -> Self {
         Self {
             client,
             base_url: "https://api.coingecko.com/api/v3".to_string(),
+            asset_ids: AssetIds::default(),
+            asset_registry: AssetRegistry::default(),
+            timeout: DEFAULT_HTTP_TIMEOUT,
         }
     }
     
+    /// Use these asset ids in place of the built-in ones
+    pub fn with_asset_ids(mut self, overrides: HashMap<String, String>) -> Self {
+        self.asset_ids = AssetIds::new(overrides);
+        self
+    }
+    
+    /// Resolve asset aliases with this registry instead of the built-in one
+    pub fn with_asset_registry(mut self, asset_registry: AssetRegistry) -> Self {
+        self.asset_registry = asset_registry;
+        self
+    }
+    
+    /// Give up on a request after this long
+    pub fn with_timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = timeout;
+        self
+    }
+    
     fn get_coin_id(&self, asset: &str) -> String {
-        match asset.to_uppercase().as_str() {
+        let asset = &self.asset_registry.normalize(asset).unwrap_or_else(|| asset.to_uppercase());
+        if let Some(id) = self.asset_ids.get(asset) {
+            return id;
+        }
+        match asset.as_str() {
             "BTC" => "bitcoin".to_string(),
             "ETH" => "ethereum".to_string(),
             "SOL" => "solana".to_string(),
@@ -45,7 +124,11 @@ impl CoinGeckoSource {
             "LINK" => "chainlink".to_string(),
             "UNI" => "uniswap".to_string(),
             "AAVE" => "aave".to_string(),
-            _ => asset.to_lowercase(),
+            _ => {
+                let id = asset.to_lowercase();
+                self.asset_ids.warn_unmapped("CoinGecko", asset, &id);
+                id
+            }
         }
     }
 }
@@ -57,7 +140,7 @@ impl DataSource for CoinGeckoSource {
         let url = format!("{}/simple/price?ids={}&vs_currencies=usd&include_24hr_vol=true&include_market_cap=true", 
                          self.base_url, coin_id);
         
-        let response = self.client.get(&url).send().await?;
+        let response = self.client.get(&url).timeout(self.timeout).send().await?;
         
         if !response.status().is_success() {
             return Err(anyhow::anyhow!("CoinGecko API error: {}", response.status()));
@@ -90,26 +173,76 @@ impl DataSource for CoinGeckoSource {
     }
 }
 
+/// Why a CoinMarketCap request failed, so callers can tell a bad key from a busy API
+#[derive(Debug, thiserror::Error)]
+pub enum CoinMarketCapError {
+    #[error("CoinMarketCap API key not configured (set CMC_API_KEY, or pass --allow-simulated for fabricated prices)")]
+    MissingApiKey,
+    #[error("CoinMarketCap rejected the request: {0}")]
+    BadRequest(String),
+    #[error("CoinMarketCap rejected the API key: {0}")]
+    Unauthorized(String),
+    #[error("CoinMarketCap rate limit exceeded: {0}")]
+    RateLimited(String),
+    #[error("CoinMarketCap API error {status}: {message}")]
+    Api { status: u16, message: String },
+}
+
+impl CoinMarketCapError {
+    /// Error for a failed response, with CMC's `status.error_message` when the body has one
+    fn from_response(status: reqwest::StatusCode, body: &str) -> Self {
+        let message = serde_json::from_str::<Value>(body).ok()
+            .and_then(|json| json["status"]["error_message"].as_str().map(str::to_string))
+            .unwrap_or_else(|| status.to_string());
+        match status.as_u16() {
+            400 => Self::BadRequest(message),
+            401 | 403 => Self::Unauthorized(message),
+            429 => Self::RateLimited(message),
+            status => Self::Api { status, message },
+        }
+    }
+}
+
 /// CoinMarketCap API data source
 pub struct CoinMarketCapSource {
-    client: Client,
+    client: Arc<Client>,
     base_url: String,
+    api_key: Option<String>,
+    // Fabricate prices when no API key is configured (development only)
+    allow_simulated: bool,
+    asset_ids: AssetIds,
+    timeout: Duration,
 }
 
 impl CoinMarketCapSource {
-    pub fn new() -> Self {
-        let client = Client::builder()
-            .timeout(Duration::from_secs(10))
-            .build()
-            .expect("Failed to create HTTP client");
-        
+    /// Create the source, reading the API key from `CMC_API_KEY`
+    pub fn new(client: Arc<Client>, allow_simulated: bool) -> Self {
         Self {
             client,
             base_url: "https://pro-api.coinmarketcap.com/v1".to_string(),
+            api_key: std::env::var("CMC_API_KEY").ok().filter(|key| !key.is_empty()),
+            allow_simulated,
+            asset_ids: AssetIds::default(),
+            timeout: DEFAULT_HTTP_TIMEOUT,
         }
     }
     
+    /// Use these asset symbols in place of the built-in ones
+    pub fn with_asset_ids(mut self, overrides: HashMap<String, String>) -> Self {
+        self.asset_ids = AssetIds::new(overrides);
+        self
+    }
+    
+    /// Give up on a request after this long
+    pub fn with_timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = timeout;
+        self
+    }
+    
     fn get_symbol(&self, asset: &str) -> String {
+        if let Some(symbol) = self.asset_ids.get(asset) {
+            return symbol;
+        }
         match asset.to_uppercase().as_str() {
             "BTC" => "BTC".to_string(),
             "ETH" => "ETH".to_string(),
@@ -121,20 +254,51 @@ impl CoinMarketCapSource {
             "LINK" => "LINK".to_string(),
             "UNI" => "UNI".to_string(),
             "AAVE" => "AAVE".to_string(),
-            _ => asset.to_string(),
+            _ => {
+                self.asset_ids.warn_unmapped("CoinMarketCap", asset, asset);
+                asset.to_string()
+            }
         }
     }
-}
-
-#[async_trait]
-impl DataSource for CoinMarketCapSource {
-    async fn fetch_price(&self, asset: &str) -> Result<PriceData> {
-        // Note: CoinMarketCap requires an API key in production
-        // For demo purposes, we'll simulate the response
+    
+    async fn fetch_live_price(&self, asset: &str, api_key: &str) -> Result<PriceData> {
+        let symbol = self.get_symbol(asset);
+        let url = format!("{}/cryptocurrency/quotes/latest?symbol={}&convert=USD", self.base_url, symbol);
+        
+        let response = self.client.get(&url)
+            .timeout(self.timeout)
+            .header("X-CMC_PRO_API_KEY", api_key)
+            .send()
+            .await?;
+        
+        let status = response.status();
+        if !status.is_success() {
+            let body = response.text().await.unwrap_or_default();
+            return Err(CoinMarketCapError::from_response(status, &body).into());
+        }
+        
+        let json: Value = response.json().await?;
+        let quote = &json["data"][&symbol]["quote"]["USD"];
+        
+        let price = quote["price"].as_f64()
+            .ok_or_else(|| anyhow::anyhow!("Invalid price data for {} from CoinMarketCap", symbol))?;
+        
+        let mut price_data = PriceData::new(asset.to_string(), price, "CoinMarketCap".to_string())
+            .with_confidence(0.85); // CoinMarketCap is reliable
+        if let Some(volume) = quote["volume_24h"].as_f64() {
+            price_data = price_data.with_volume(volume);
+        }
+        if let Some(market_cap) = quote["market_cap"].as_f64() {
+            price_data = price_data.with_market_cap(market_cap);
+        }
+        
+        Ok(price_data)
+    }
+    
+    fn simulated_price(&self, asset: &str) -> PriceData {
         let symbol = self.get_symbol(asset);
         
-        // Simulate CoinMarketCap response (in production, you'd use real API)
-        let simulated_price = match symbol.as_str() {
+        let price = match symbol.as_str() {
             "BTC" => 45230.50,
             "ETH" => 2650.75,
             "SOL" => 98.45,
@@ -148,14 +312,21 @@ impl DataSource for CoinMarketCapSource {
             _ => 100.0, // Default price
         };
         
-        // Add some random variation to simulate real data
-        let variation = (rand::random::<f64>() - 0.5) * 0.02; // ±1% variation
-        let price = simulated_price * (1.0 + variation);
-        
-        Ok(PriceData::new(asset.to_string(), price, "CoinMarketCap".to_string())
-            .with_confidence(0.85) // CoinMarketCap is reliable
-            .with_volume(1000000.0) // Simulated volume
-            .with_market_cap(price * 1000000.0)) // Simulated market cap
+        // No volume or market cap: made-up ones would skew volume-weighted consensus
+        PriceData::new(asset.to_string(), price, "CoinMarketCap".to_string())
+            .with_confidence(0.85)
+            .simulated()
+    }
+}
+
+#[async_trait]
+impl DataSource for CoinMarketCapSource {
+    async fn fetch_price(&self, asset: &str) -> Result<PriceData> {
+        match &self.api_key {
+            Some(api_key) => self.fetch_live_price(asset, api_key).await,
+            None if self.allow_simulated => Ok(self.simulated_price(asset)),
+            None => Err(CoinMarketCapError::MissingApiKey.into()),
+        }
     }
     
     fn name(&self) -> &str {
@@ -169,58 +340,613 @@ impl DataSource for CoinMarketCapSource {
 
 /// Binance API data source
 pub struct BinanceSource {
-    client: Client,
+    client: Arc<Client>,
     base_url: String,
+    asset_ids: AssetIds,
+    timeout: Duration,
 }
 
 impl BinanceSource {
-    pub fn new() -> Self {
-        let client = Client::builder()
-            .timeout(Duration::from_secs(10))
-            .build()
-            .expect("Failed to create HTTP client");
-        
+    pub fn new(client: Arc<Client>) -> Self {
         Self {
             client,
             base_url: "https://api.binance.com/api/v3".to_string(),
+            asset_ids: AssetIds::default(),
+            timeout: DEFAULT_HTTP_TIMEOUT,
         }
     }
     
+    /// Use these asset ids in place of the built-in ones
+    pub fn with_asset_ids(mut self, overrides: HashMap<String, String>) -> Self {
+        self.asset_ids = AssetIds::new(overrides);
+        self
+    }
+    
+    /// Give up on a request after this long
+    pub fn with_timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = timeout;
+        self
+    }
+    
     fn get_symbol(&self, asset: &str) -> String {
-        format!("{}USDT", asset.to_uppercase())
+        self.asset_ids.get(asset).unwrap_or_else(|| format!("{}USDT", asset.to_uppercase()))
     }
+    
+    /// Price from the order-book ticker instead of the last trade: the mid-price, with the
+    /// best bid and ask so the spread is known
+    pub async fn fetch_order_book_price(&self, asset: &str) -> Result<PriceData> {
+        let symbol = self.get_symbol(asset);
+        let url = format!("{}/ticker/bookTicker?symbol={}", self.base_url, symbol);
+        
+        let response = self.client.get(&url).timeout(self.timeout).send().await?;
+        
+        if !response.status().is_success() {
+            return Err(anyhow::anyhow!("Binance API error: {}", response.status()));
+        }
+        
+        let (bid, ask) = parse_book_ticker(&response.json().await?)?;
+        
+        Ok(PriceData::new(asset.to_string(), (bid + ask) / 2.0, "Binance".to_string())
+            .with_confidence(0.95)
+            .with_bid_ask(bid, ask))
+    }
+}
+
+/// Best bid and ask from a Binance `ticker/bookTicker` response
+fn parse_book_ticker(json: &Value) -> Result<(f64, f64)> {
+    let field = |name: &str| -> Result<f64> {
+        Ok(json[name].as_str()
+            .ok_or_else(|| anyhow::anyhow!("Invalid order book data: missing {}", name))?
+            .parse::<f64>()?)
+    };
+    let (bid, ask) = (field("bidPrice")?, field("askPrice")?);
+    if bid <= 0.0 || ask < bid {
+        return Err(anyhow::anyhow!("Invalid order book data: bid {} ask {}", bid, ask));
+    }
+    Ok((bid, ask))
+}
+
+/// Last price and 24h quote-currency volume from a Binance `ticker/24hr` response
+fn parse_binance_24hr(json: &Value) -> Result<(f64, Option<f64>)> {
+    let price = json["lastPrice"].as_str()
+        .ok_or_else(|| anyhow::anyhow!("Invalid price data"))?
+        .parse::<f64>()?;
+    let volume = json["quoteVolume"].as_str().and_then(|v| v.parse::<f64>().ok());
+    
+    Ok((price, volume))
 }
 
 #[async_trait]
 impl DataSource for BinanceSource {
     async fn fetch_price(&self, asset: &str) -> Result<PriceData> {
         let symbol = self.get_symbol(asset);
-        let url = format!("{}/ticker/price?symbol={}", self.base_url, symbol);
+        let url = format!("{}/ticker/24hr?symbol={}", self.base_url, symbol);
         
-        let response = self.client.get(&url).send().await?;
+        let response = self.client.get(&url).timeout(self.timeout).send().await?;
         
         if !response.status().is_success() {
             return Err(anyhow::anyhow!("Binance API error: {}", response.status()));
         }
         
         let json: Value = response.json().await?;
+        let (price, volume) = parse_binance_24hr(&json)?;
         
-        let price_str = json["price"].as_str()
-            .ok_or_else(|| anyhow::anyhow!("Invalid price data"))?;
+        // The ticker carries no market cap, so leave it unset
+        let price_data = PriceData::new(asset.to_string(), price, "Binance".to_string())
+            .with_confidence(0.95); // Binance is very reliable for spot prices
+        Ok(match volume {
+            Some(volume) => price_data.with_volume(volume),
+            None => price_data,
+        })
+    }
+    
+    fn name(&self) -> &str {
+        "Binance"
+    }
+    
+    fn base_url(&self) -> &str {
+        &self.base_url
+    }
+}
+
+/// Gate.io API data source
+pub struct GateSource {
+    client: Arc<Client>,
+    base_url: String,
+    asset_ids: AssetIds,
+    timeout: Duration,
+}
+
+impl GateSource {
+    pub fn new(client: Arc<Client>) -> Self {
+        Self {
+            client,
+            base_url: "https://api.gateio.ws/api/v4".to_string(),
+            asset_ids: AssetIds::default(),
+            timeout: DEFAULT_HTTP_TIMEOUT,
+        }
+    }
+    
+    /// Use these asset ids in place of the built-in ones
+    pub fn with_asset_ids(mut self, overrides: HashMap<String, String>) -> Self {
+        self.asset_ids = AssetIds::new(overrides);
+        self
+    }
+    
+    /// Give up on a request after this long
+    pub fn with_timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = timeout;
+        self
+    }
+    
+    fn get_currency_pair(&self, asset: &str) -> String {
+        self.asset_ids.get(asset).unwrap_or_else(|| format!("{}_USDT", asset.to_uppercase()))
+    }
+}
+
+/// Last price and 24h USDT volume from a Gate.io `spot/tickers` response
+fn parse_gate_ticker(json: &Value) -> Result<(f64, Option<f64>)> {
+    let ticker = json.get(0)
+        .ok_or_else(|| anyhow::anyhow!("Empty ticker response"))?;
+    
+    let price = ticker["last"].as_str()
+        .ok_or_else(|| anyhow::anyhow!("Invalid price data"))?
+        .parse::<f64>()?;
+    let volume = ticker["quote_volume"].as_str().and_then(|v| v.parse::<f64>().ok());
+    
+    Ok((price, volume))
+}
+
+#[async_trait]
+impl DataSource for GateSource {
+    async fn fetch_price(&self, asset: &str) -> Result<PriceData> {
+        let pair = self.get_currency_pair(asset);
+        let url = format!("{}/spot/tickers?currency_pair={}", self.base_url, pair);
         
-        let price = price_str.parse::<f64>()?;
+        let response = self.client.get(&url).timeout(self.timeout).send().await?;
         
-        Ok(PriceData::new(asset.to_string(), price, "Binance".to_string())
-            .with_confidence(0.95) // Binance is very reliable for spot prices
-            .with_volume(2000000.0) // Simulated volume
-            .with_market_cap(price * 2000000.0)) // Simulated market cap
+        if !response.status().is_success() {
+            return Err(anyhow::anyhow!("Gate.io API error: {}", response.status()));
+        }
+        
+        let json: Value = response.json().await?;
+        let (price, volume) = parse_gate_ticker(&json)?;
+        
+        let price_data = PriceData::new(asset.to_string(), price, "Gate.io".to_string())
+            .with_confidence(0.85);
+        Ok(match volume {
+            Some(volume) => price_data.with_volume(volume),
+            None => price_data,
+        })
     }
     
     fn name(&self) -> &str {
-        "Binance"
+        "Gate.io"
+    }
+    
+    fn base_url(&self) -> &str {
+        &self.base_url
+    }
+}
+
+/// OKX API data source
+pub struct OkxSource {
+    client: Arc<Client>,
+    base_url: String,
+    asset_ids: AssetIds,
+    timeout: Duration,
+}
+
+impl OkxSource {
+    pub fn new(client: Arc<Client>) -> Self {
+        Self {
+            client,
+            base_url: "https://www.okx.com/api/v5".to_string(),
+            asset_ids: AssetIds::default(),
+            timeout: DEFAULT_HTTP_TIMEOUT,
+        }
+    }
+    
+    /// Use these asset ids in place of the built-in ones
+    pub fn with_asset_ids(mut self, overrides: HashMap<String, String>) -> Self {
+        self.asset_ids = AssetIds::new(overrides);
+        self
+    }
+    
+    /// Give up on a request after this long
+    pub fn with_timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = timeout;
+        self
+    }
+    
+    fn get_inst_id(&self, asset: &str) -> String {
+        self.asset_ids.get(asset).unwrap_or_else(|| format!("{}-USDT", asset.to_uppercase()))
+    }
+}
+
+/// Last price and 24h USDT volume from an OKX `market/ticker` response
+fn parse_okx_ticker(json: &Value) -> Result<(f64, Option<f64>)> {
+    // OKX reports failures in the body with a non-zero code, often alongside HTTP 200
+    let code = json["code"].as_str().unwrap_or_default();
+    if code != "0" {
+        return Err(anyhow::anyhow!("OKX API error {}: {}", code, json["msg"].as_str().unwrap_or_default()));
+    }
+    
+    let ticker = json["data"].get(0)
+        .ok_or_else(|| anyhow::anyhow!("Empty ticker response"))?;
+    
+    let price = ticker["last"].as_str()
+        .ok_or_else(|| anyhow::anyhow!("Invalid price data"))?
+        .parse::<f64>()?;
+    // For spot pairs volCcy24h is denominated in the quote currency
+    let volume = ticker["volCcy24h"].as_str().and_then(|v| v.parse::<f64>().ok());
+    
+    Ok((price, volume))
+}
+
+#[async_trait]
+impl DataSource for OkxSource {
+    async fn fetch_price(&self, asset: &str) -> Result<PriceData> {
+        let inst_id = self.get_inst_id(asset);
+        let url = format!("{}/market/ticker?instId={}", self.base_url, inst_id);
+        
+        let response = self.client.get(&url).timeout(self.timeout).send().await?;
+        
+        if !response.status().is_success() {
+            return Err(anyhow::anyhow!("OKX API error: {}", response.status()));
+        }
+        
+        let json: Value = response.json().await?;
+        let (price, volume) = parse_okx_ticker(&json)?;
+        
+        let price_data = PriceData::new(asset.to_string(), price, "OKX".to_string())
+            .with_confidence(0.9);
+        Ok(match volume {
+            Some(volume) => price_data.with_volume(volume),
+            None => price_data,
+        })
+    }
+    
+    fn name(&self) -> &str {
+        "OKX"
+    }
+    
+    fn base_url(&self) -> &str {
+        &self.base_url
+    }
+}
+
+/// Confidence of an AMM pool price: reserves move with every swap and a thin pool
+/// can be pushed far from the market, so it counts for less than an exchange ticker
+pub const DEX_CONFIDENCE: f64 = 0.75;
+
+/// On-chain DEX data source: the spot price of a constant-product pool on Solana, read from
+/// the balances of its two token vaults over JSON-RPC. Pools are configured per asset under
+/// `[asset_ids.DEX]` as `"<base vault>:<quote vault>"`; assets without one are not priced
+pub struct DexSource {
+    client: Arc<Client>,
+    base_url: String,
+    asset_ids: AssetIds,
+    timeout: Duration,
+}
+
+impl DexSource {
+    /// Read pools through the Solana RPC node at `rpc_url`
+    pub fn new(client: Arc<Client>, rpc_url: &str) -> Self {
+        Self {
+            client,
+            base_url: rpc_url.to_string(),
+            asset_ids: AssetIds::default(),
+            timeout: DEFAULT_HTTP_TIMEOUT,
+        }
+    }
+    
+    /// Use these pools, asset symbol -> `"<base vault>:<quote vault>"`
+    pub fn with_asset_ids(mut self, overrides: HashMap<String, String>) -> Self {
+        self.asset_ids = AssetIds::new(overrides);
+        self
+    }
+    
+    /// Give up on a request after this long
+    pub fn with_timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = timeout;
+        self
+    }
+    
+    fn get_pool(&self, asset: &str) -> Result<(Pubkey, Pubkey)> {
+        let pool = self.asset_ids.get(asset)
+            .ok_or_else(|| anyhow::anyhow!("No DEX pool configured for {} (add one under [asset_ids.DEX])", asset))?;
+        parse_pool(&pool)
+    }
+    
+    /// Balance of an SPL token account, in whole tokens
+    async fn token_balance(&self, vault: &Pubkey) -> Result<f64> {
+        let request = json!({
+            "jsonrpc": "2.0",
+            "id": 1,
+            "method": "getTokenAccountBalance",
+            "params": [vault.to_string()],
+        });
+        let response = self.client.post(&self.base_url).json(&request).timeout(self.timeout).send().await?;
+        
+        if !response.status().is_success() {
+            return Err(anyhow::anyhow!("Solana RPC error: {}", response.status()));
+        }
+        
+        parse_token_balance(&response.json().await?)
+    }
+}
+
+/// Base and quote vault addresses from a `"<base vault>:<quote vault>"` pool id
+fn parse_pool(pool: &str) -> Result<(Pubkey, Pubkey)> {
+    let (base, quote) = pool.split_once(':')
+        .ok_or_else(|| anyhow::anyhow!("Invalid DEX pool '{}': expected <base vault>:<quote vault>", pool))?;
+    Ok((Pubkey::from_str(base.trim())?, Pubkey::from_str(quote.trim())?))
+}
+
+/// Token amount from a `getTokenAccountBalance` response
+fn parse_token_balance(json: &Value) -> Result<f64> {
+    if let Some(error) = json.get("error") {
+        return Err(anyhow::anyhow!("Solana RPC error: {}", error["message"].as_str().unwrap_or_default()));
+    }
+    
+    Ok(json["result"]["value"]["uiAmountString"].as_str()
+        .ok_or_else(|| anyhow::anyhow!("Invalid token balance data"))?
+        .parse::<f64>()?)
+}
+
+#[async_trait]
+impl DataSource for DexSource {
+    async fn fetch_price(&self, asset: &str) -> Result<PriceData> {
+        let (base_vault, quote_vault) = self.get_pool(asset)?;
+        let (base, quote) = futures::try_join!(self.token_balance(&base_vault), self.token_balance(&quote_vault))?;
+        
+        if base <= 0.0 || quote <= 0.0 {
+            return Err(anyhow::anyhow!("DEX pool for {} has no liquidity", asset));
+        }
+        
+        // A constant-product pool's marginal price is the ratio of its reserves
+        Ok(PriceData::new(asset.to_string(), quote / base, "DEX".to_string())
+            .with_confidence(DEX_CONFIDENCE))
+    }
+    
+    fn name(&self) -> &str {
+        "DEX"
     }
     
     fn base_url(&self) -> &str {
         &self.base_url
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_asset_id_overrides() {
+        let overrides = HashMap::from([("pepe".to_string(), "pepe-token".to_string())]);
+        let coin_gecko = CoinGeckoSource::new(DataSourceFactory::create_shared_client()).with_asset_ids(overrides.clone());
+        assert_eq!(coin_gecko.get_coin_id("PEPE"), "pepe-token");
+        assert_eq!(coin_gecko.get_coin_id("BTC"), "bitcoin");
+        // Unmapped assets keep the old lowercase fallback
+        assert_eq!(coin_gecko.get_coin_id("WIF"), "wif");
+        // Aliases resolve before the lookup
+        assert_eq!(coin_gecko.get_coin_id("Bitcoin"), "bitcoin");
+        assert_eq!(coin_gecko.get_coin_id("$ether"), "ethereum");
+        let coin_gecko = coin_gecko.with_asset_registry(AssetRegistry::default()
+            .with_aliases(&HashMap::from([("frog".to_string(), "PEPE".to_string())])));
+        assert_eq!(coin_gecko.get_coin_id("frog"), "pepe-token");
+        
+        let binance = BinanceSource::new(DataSourceFactory::create_shared_client())
+            .with_asset_ids(HashMap::from([("BTC".to_string(), "BTCFDUSD".to_string())]));
+        assert_eq!(binance.get_symbol("btc"), "BTCFDUSD");
+        assert_eq!(binance.get_symbol("eth"), "ETHUSDT");
+    }
+
+    #[tokio::test]
+    async fn test_binance_order_book_price() {
+        let mut server = mockito::Server::new_async().await;
+        server.mock("GET", "/ticker/bookTicker")
+            .match_query(mockito::Matcher::UrlEncoded("symbol".to_string(), "BTCUSDT".to_string()))
+            .with_body(json!({ "symbol": "BTCUSDT", "bidPrice": "44990.00", "bidQty": "1.5", "askPrice": "45010.00", "askQty": "2.0" }).to_string())
+            .create_async()
+            .await;
+        let mut source = BinanceSource::new(DataSourceFactory::create_shared_client());
+        source.base_url = server.url();
+        
+        let price_data = source.fetch_order_book_price("BTC").await.unwrap();
+        assert_eq!(price_data.price, 45000.0);
+        assert_eq!((price_data.bid, price_data.ask), (Some(44990.0), Some(45010.0)));
+        assert_eq!(price_data.spread(), Some(20.0));
+        
+        // A crossed or half-empty book is rejected
+        assert!(parse_book_ticker(&json!({ "bidPrice": "45010.00", "askPrice": "44990.00" })).is_err());
+        assert!(parse_book_ticker(&json!({ "bidPrice": "45010.00" })).is_err());
+    }
+
+    #[tokio::test]
+    async fn test_dex_pool_price() {
+        let base_vault = Pubkey::new_unique();
+        let quote_vault = Pubkey::new_unique();
+        let mut server = mockito::Server::new_async().await;
+        for (vault, amount) in [(base_vault, "1000.5"), (quote_vault, "150075")] {
+            server.mock("POST", "/")
+                .match_body(mockito::Matcher::PartialJson(json!({ "method": "getTokenAccountBalance", "params": [vault.to_string()] })))
+                .with_body(json!({
+                    "jsonrpc": "2.0",
+                    "id": 1,
+                    "result": { "context": { "slot": 1 }, "value": { "amount": "0", "decimals": 6, "uiAmountString": amount } },
+                }).to_string())
+                .create_async()
+                .await;
+        }
+        let pool = format!("{}:{}", base_vault, quote_vault);
+        let source = DexSource::new(DataSourceFactory::create_shared_client(), &server.url())
+            .with_asset_ids(HashMap::from([("SOL".to_string(), pool)]));
+        
+        let price_data = source.fetch_price("sol").await.unwrap();
+        assert_eq!(price_data.price, 150.0);
+        assert_eq!(price_data.source, "DEX");
+        assert_eq!(price_data.confidence, DEX_CONFIDENCE);
+        
+        // No pool for the asset, or a malformed one
+        assert!(source.fetch_price("BTC").await.is_err());
+        assert!(parse_pool("not-a-pool").is_err());
+        assert!(parse_token_balance(&json!({ "error": { "code": -32602, "message": "Invalid param" } })).is_err());
+    }
+
+    #[tokio::test]
+    async fn test_binance_24hr_volume() {
+        let mut server = mockito::Server::new_async().await;
+        server.mock("GET", "/ticker/24hr")
+            .match_query(mockito::Matcher::UrlEncoded("symbol".to_string(), "SOLUSDT".to_string()))
+            .with_body(json!({
+                "symbol": "SOLUSDT",
+                "priceChange": "-1.20",
+                "lastPrice": "101.50",
+                "volume": "2500000.00",
+                "quoteVolume": "253750000.00",
+                "count": 812345
+            }).to_string())
+            .create_async()
+            .await;
+        let mut source = BinanceSource::new(DataSourceFactory::create_shared_client());
+        source.base_url = server.url();
+        
+        let price_data = source.fetch_price("SOL").await.unwrap();
+        assert_eq!(price_data.price, 101.5);
+        assert_eq!(price_data.volume_24h, Some(253_750_000.0));
+        
+        // Volume is optional, the price isn't
+        assert_eq!(parse_binance_24hr(&json!({ "lastPrice": "101.50" })).unwrap(), (101.5, None));
+        assert!(parse_binance_24hr(&json!({ "quoteVolume": "1.0" })).is_err());
+    }
+
+    #[test]
+    fn test_parse_gate_ticker() {
+        let json = json!([{
+            "currency_pair": "BTC_USDT",
+            "last": "45012.5",
+            "base_volume": "1200.5",
+            "quote_volume": "54037000.25"
+        }]);
+        assert_eq!(parse_gate_ticker(&json).unwrap(), (45012.5, Some(54037000.25)));
+        
+        assert!(parse_gate_ticker(&json!([])).is_err());
+    }
+
+    #[test]
+    fn test_parse_okx_ticker() {
+        let json = json!({
+            "code": "0",
+            "msg": "",
+            "data": [{ "instId": "SOL-USDT", "last": "101.37", "vol24h": "2500", "volCcy24h": "253425" }]
+        });
+        assert_eq!(parse_okx_ticker(&json).unwrap(), (101.37, Some(253425.0)));
+        
+        let error = json!({ "code": "51001", "msg": "Instrument ID does not exist", "data": [] });
+        assert!(parse_okx_ticker(&error).unwrap_err().to_string().contains("51001"));
+    }
+    
+    fn coin_market_cap(base_url: String, api_key: Option<&str>) -> CoinMarketCapSource {
+        let mut source = CoinMarketCapSource::new(DataSourceFactory::create_shared_client(), false);
+        source.base_url = base_url;
+        source.api_key = api_key.map(str::to_string);
+        source
+    }
+    
+    #[tokio::test]
+    async fn test_coin_market_cap_quote() {
+        let mut server = mockito::Server::new_async().await;
+        let mock = server.mock("GET", "/cryptocurrency/quotes/latest")
+            .match_query(mockito::Matcher::AllOf(vec![
+                mockito::Matcher::UrlEncoded("symbol".to_string(), "SOL".to_string()),
+                mockito::Matcher::UrlEncoded("convert".to_string(), "USD".to_string()),
+            ]))
+            .match_header("X-CMC_PRO_API_KEY", "test-key")
+            .with_body(json!({
+                "status": { "error_code": 0, "error_message": null },
+                "data": { "SOL": { "quote": { "USD": { "price": 101.5, "volume_24h": 2.5e9, "market_cap": 4.5e10 } } } }
+            }).to_string())
+            .create_async()
+            .await;
+        
+        let price_data = coin_market_cap(server.url(), Some("test-key")).fetch_price("sol").await.unwrap();
+        mock.assert_async().await;
+        assert_eq!(price_data.price, 101.5);
+        assert_eq!(price_data.volume_24h, Some(2.5e9));
+        assert_eq!(price_data.market_cap, Some(4.5e10));
+        assert!(!price_data.simulated);
+        
+        // Without a key the source is skipped rather than simulated
+        let err = coin_market_cap(server.url(), None).fetch_price("SOL").await.unwrap_err();
+        assert!(matches!(err.downcast_ref(), Some(CoinMarketCapError::MissingApiKey)));
+    }
+    
+    #[tokio::test]
+    async fn test_coin_market_cap_errors() {
+        let mut server = mockito::Server::new_async().await;
+        for (symbol, status, message) in [("BTC", 400, "Invalid value for \"symbol\""), ("ETH", 401, "API key missing."), ("SOL", 429, "Rate limit reached")] {
+            server.mock("GET", "/cryptocurrency/quotes/latest")
+                .match_query(mockito::Matcher::UrlEncoded("symbol".to_string(), symbol.to_string()))
+                .with_status(status)
+                .with_body(json!({ "status": { "error_code": 1002, "error_message": message } }).to_string())
+                .create_async()
+                .await;
+        }
+        server.mock("GET", "/cryptocurrency/quotes/latest")
+            .match_query(mockito::Matcher::UrlEncoded("symbol".to_string(), "ADA".to_string()))
+            .with_status(500)
+            .create_async()
+            .await;
+        
+        let source = coin_market_cap(server.url(), Some("test-key"));
+        let error_for = |asset: &'static str| {
+            let source = &source;
+            async move { source.fetch_price(asset).await.unwrap_err().downcast::<CoinMarketCapError>().unwrap() }
+        };
+        assert!(matches!(error_for("BTC").await, CoinMarketCapError::BadRequest(message) if message.contains("symbol")));
+        assert!(matches!(error_for("ETH").await, CoinMarketCapError::Unauthorized(message) if message == "API key missing."));
+        assert!(matches!(error_for("SOL").await, CoinMarketCapError::RateLimited(_)));
+        assert!(matches!(error_for("ADA").await, CoinMarketCapError::Api { status: 500, .. }));
+    }
+    
+    #[tokio::test]
+    async fn test_request_timeout() {
+        // Accepts connections but never answers
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        tokio::spawn(async move {
+            let mut held = Vec::new();
+            while let Ok((socket, _)) = listener.accept().await {
+                held.push(socket);
+            }
+        });
+        
+        let mut source = BinanceSource::new(DataSourceFactory::create_shared_client())
+            .with_timeout(Duration::from_millis(100));
+        source.base_url = format!("http://{}", addr);
+        
+        let started = std::time::Instant::now();
+        let err = source.fetch_price("BTC").await.unwrap_err();
+        assert!(err.downcast_ref::<reqwest::Error>().is_some_and(|e| e.is_timeout()), "{}", err);
+        assert!(started.elapsed() < DEFAULT_HTTP_TIMEOUT);
+    }
+    
+    /// Run with `cargo test -- --ignored --nocapture`; needs network access
+    #[tokio::test]
+    #[ignore]
+    async fn bench_shared_client_reuses_connections() {
+        let source = CoinGeckoSource::new(DataSourceFactory::create_shared_client());
+        let mut latencies = Vec::new();
+        for _ in 0..3 {
+            let started = std::time::Instant::now();
+            source.fetch_price("BTC").await.unwrap();
+            latencies.push(started.elapsed());
+        }
+        println!("latencies: {:?}", latencies);
+        // Only the first request pays for DNS, TCP and TLS setup
+        assert!(latencies[2] < latencies[0]);
+    }
+}
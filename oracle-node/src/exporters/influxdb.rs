@@ -0,0 +1,149 @@
+// InfluxDB v2 line-protocol export of consensus prices
+use anyhow::{Context, Result};
+use async_trait::async_trait;
+use reqwest::Client;
+use std::time::Duration;
+
+use super::Exporter;
+use crate::models::ConsensusResult;
+
+/// Measurement every consensus price is written to
+pub const MEASUREMENT: &str = "oracle_price";
+
+/// Writes each consensus result as a point to an InfluxDB bucket
+pub struct InfluxDbExporter {
+    client: Client,
+    write_url: String,
+    token: String,
+    org: String,
+    bucket: String,
+}
+
+impl InfluxDbExporter {
+    /// Exporter writing to the server at `url` (e.g. `http://localhost:8086`)
+    pub fn new(url: &str, token: String, org: String, bucket: String) -> Self {
+        let client = Client::builder()
+            .timeout(Duration::from_secs(10))
+            .build()
+            .expect("Failed to create HTTP client");
+
+        Self {
+            client,
+            write_url: format!("{}/api/v2/write", url.trim_end_matches('/')),
+            token,
+            org,
+            bucket,
+        }
+    }
+
+    /// Exporter configured by `INFLUXDB_URL`, `INFLUXDB_TOKEN`, `INFLUXDB_ORG` and `INFLUXDB_BUCKET`,
+    /// or `None` when `INFLUXDB_URL` is unset
+    pub fn from_env() -> Result<Option<Self>> {
+        let Some(url) = std::env::var("INFLUXDB_URL").ok().filter(|url| !url.is_empty()) else {
+            return Ok(None);
+        };
+        let required = |name: &str| std::env::var(name)
+            .ok()
+            .filter(|value| !value.is_empty())
+            .with_context(|| format!("INFLUXDB_URL is set but {} is not", name));
+
+        Ok(Some(Self::new(&url, required("INFLUXDB_TOKEN")?, required("INFLUXDB_ORG")?, required("INFLUXDB_BUCKET")?)))
+    }
+
+    /// `result` as a line-protocol point, timestamped in nanoseconds
+    pub fn line(result: &ConsensusResult) -> String {
+        format!(
+            "{},asset={} price={:?},confidence={:?},consensus_score={:?} {}",
+            MEASUREMENT,
+            escape_tag(&result.asset),
+            result.price,
+            result.confidence,
+            result.consensus_score,
+            result.timestamp.timestamp_nanos_opt().unwrap_or_default(),
+        )
+    }
+}
+
+#[async_trait]
+impl Exporter for InfluxDbExporter {
+    async fn export(&self, result: &ConsensusResult) -> Result<()> {
+        self.client.post(&self.write_url)
+            .query(&[("org", self.org.as_str()), ("bucket", self.bucket.as_str()), ("precision", "ns")])
+            .bearer_auth(&self.token)
+            .header("Content-Type", "text/plain; charset=utf-8")
+            .body(Self::line(result))
+            .send()
+            .await?
+            .error_for_status()?;
+        Ok(())
+    }
+
+    fn name(&self) -> &str {
+        "InfluxDB"
+    }
+}
+
+/// Escape the characters line protocol gives meaning to in tag values
+fn escape_tag(value: &str) -> String {
+    let mut escaped = String::with_capacity(value.len());
+    for c in value.chars() {
+        if matches!(c, ',' | '=' | ' ') {
+            escaped.push('\\');
+        }
+        escaped.push(c);
+    }
+    escaped
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::DateTime;
+
+    fn result() -> ConsensusResult {
+        let mut result = ConsensusResult::new("BTC".to_string(), 45000.0, vec!["CoinGecko".to_string()])
+            .with_confidence(0.92)
+            .with_consensus_score(0.88);
+        result.timestamp = DateTime::from_timestamp(1_716_000_000, 0).unwrap();
+        result
+    }
+
+    #[test]
+    fn test_line_protocol_format() {
+        assert_eq!(
+            InfluxDbExporter::line(&result()),
+            "oracle_price,asset=BTC price=45000.0,confidence=0.92,consensus_score=0.88 1716000000000000000"
+        );
+        assert_eq!(escape_tag("A B,C=D"), r"A\ B\,C\=D");
+    }
+
+    #[tokio::test]
+    async fn test_export_writes_point() {
+        let mut server = mockito::Server::new_async().await;
+        let mock = server.mock("POST", "/api/v2/write")
+            .match_query(mockito::Matcher::AllOf(vec![
+                mockito::Matcher::UrlEncoded("org".to_string(), "oracle".to_string()),
+                mockito::Matcher::UrlEncoded("bucket".to_string(), "prices".to_string()),
+                mockito::Matcher::UrlEncoded("precision".to_string(), "ns".to_string()),
+            ]))
+            .match_header("authorization", "Bearer secret")
+            .match_body("oracle_price,asset=BTC price=45000.0,confidence=0.92,consensus_score=0.88 1716000000000000000")
+            .with_status(204)
+            .expect(1)
+            .create_async()
+            .await;
+
+        let exporter = InfluxDbExporter::new(&format!("{}/", server.url()), "secret".to_string(), "oracle".to_string(), "prices".to_string());
+        exporter.export(&result()).await.unwrap();
+        mock.assert_async().await;
+        mock.remove_async().await;
+
+        // Rejected writes are reported
+        server.mock("POST", "/api/v2/write")
+            .match_query(mockito::Matcher::Any)
+            .with_status(401)
+            .create_async()
+            .await;
+        assert!(exporter.export(&result()).await.is_err());
+    }
+}
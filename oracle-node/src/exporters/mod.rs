@@ -0,0 +1,24 @@
+// Telemetry exporters that receive every consensus result
+use anyhow::Result;
+use async_trait::async_trait;
+
+use crate::models::ConsensusResult;
+
+pub mod influxdb;
+
+pub use influxdb::InfluxDbExporter;
+
+#[async_trait]
+pub trait Exporter: Send + Sync {
+    async fn export(&self, result: &ConsensusResult) -> Result<()>;
+    fn name(&self) -> &str;
+}
+
+/// Every exporter configured through the environment (none when nothing is set)
+pub fn exporters_from_env() -> Result<Vec<Box<dyn Exporter>>> {
+    let mut exporters: Vec<Box<dyn Exporter>> = Vec::new();
+    if let Some(influxdb) = InfluxDbExporter::from_env()? {
+        exporters.push(Box::new(influxdb));
+    }
+    Ok(exporters)
+}
@@ -0,0 +1,204 @@
+// Background health checks of the data sources
+use dashmap::DashMap;
+use log::{info, warn};
+use serde::Serialize;
+use std::collections::{HashMap, VecDeque};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+use crate::data_sources::DataSource;
+
+/// Asset fetched from every source as a canary
+const CANARY_ASSET: &str = "BTC";
+
+/// Checks older than this no longer count toward a source's health
+const HEALTH_WINDOW: Duration = Duration::from_secs(5 * 60);
+
+/// Sources succeeding less often than this over the window are degraded
+const MIN_SUCCESS_RATE: f64 = 0.8;
+
+/// Current health of one source, as served by `/sources`
+#[derive(Debug, Clone, Serialize, PartialEq)]
+pub struct HealthStatus {
+    pub healthy: bool,
+    pub success_rate: f64,
+    pub p95_latency_ms: f64,
+    /// Checks within the rolling window
+    pub checks: usize,
+}
+
+struct CheckSample {
+    at: Instant,
+    latency: Duration,
+    success: bool,
+}
+
+pub type HealthStatuses = Arc<DashMap<String, HealthStatus>>;
+
+pub struct HealthMonitor {
+    sources: Vec<Arc<dyn DataSource>>,
+    interval_secs: u64,
+    statuses: HealthStatuses,
+    samples: Arc<Mutex<HashMap<String, VecDeque<CheckSample>>>>,
+}
+
+impl HealthMonitor {
+    pub fn new(sources: Vec<Arc<dyn DataSource>>, interval_secs: u64) -> Self {
+        Self {
+            sources,
+            interval_secs,
+            statuses: Arc::new(DashMap::new()),
+            samples: Arc::new(Mutex::new(HashMap::new())),
+        }
+    }
+
+    /// Shared view of the latest status per source
+    pub fn statuses(&self) -> HealthStatuses {
+        self.statuses.clone()
+    }
+
+    /// Spawn the background task checking every source each `interval_secs`
+    pub async fn run(&self) {
+        let monitor = Self {
+            sources: self.sources.clone(),
+            interval_secs: self.interval_secs,
+            statuses: self.statuses.clone(),
+            samples: self.samples.clone(),
+        };
+
+        tokio::spawn(async move {
+            let mut interval = tokio::time::interval(Duration::from_secs(monitor.interval_secs.max(1)));
+            loop {
+                interval.tick().await;
+                monitor.check_all().await;
+            }
+        });
+    }
+
+    /// Fetch the canary price from every source once and record the outcomes
+    async fn check_all(&self) {
+        let checks = self.sources.iter().map(|source| async move {
+            let started = Instant::now();
+            let success = source.fetch_price(CANARY_ASSET).await.is_ok();
+            (source.name().to_string(), started.elapsed(), success)
+        });
+
+        for (name, latency, success) in futures::future::join_all(checks).await {
+            self.record(&name, latency, success, Instant::now());
+        }
+    }
+
+    fn record(&self, source: &str, latency: Duration, success: bool, now: Instant) {
+        let status = {
+            let mut samples = self.samples.lock().unwrap();
+            let window = samples.entry(source.to_string()).or_default();
+            window.push_back(CheckSample { at: now, latency, success });
+            while window.front().is_some_and(|s| now.duration_since(s.at) > HEALTH_WINDOW) {
+                window.pop_front();
+            }
+            summarize(window)
+        };
+
+        let was_healthy = self.statuses.get(source).is_none_or(|s| s.healthy);
+        if was_healthy && !status.healthy {
+            warn!("Source {} degraded: {:.0}% of checks succeeded in the last {} minutes (p95 latency {:.0} ms)",
+                  source, status.success_rate * 100.0, HEALTH_WINDOW.as_secs() / 60, status.p95_latency_ms);
+        } else if !was_healthy && status.healthy {
+            info!("Source {} recovered", source);
+        }
+
+        self.statuses.insert(source.to_string(), status);
+    }
+}
+
+fn summarize(window: &VecDeque<CheckSample>) -> HealthStatus {
+    let checks = window.len();
+    let successes = window.iter().filter(|s| s.success).count();
+    let success_rate = if checks == 0 { 1.0 } else { successes as f64 / checks as f64 };
+
+    let mut latencies: Vec<f64> = window.iter().map(|s| s.latency.as_secs_f64() * 1000.0).collect();
+    latencies.sort_by(f64::total_cmp);
+    // Nearest-rank percentile
+    let p95_latency_ms = match latencies.len() {
+        0 => 0.0,
+        n => latencies[((n as f64 * 0.95).ceil() as usize).clamp(1, n) - 1],
+    };
+
+    HealthStatus {
+        healthy: success_rate >= MIN_SUCCESS_RATE,
+        success_rate,
+        p95_latency_ms,
+        checks,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::PriceData;
+    use async_trait::async_trait;
+
+    struct FixedSource {
+        name: &'static str,
+        fails: bool,
+    }
+
+    #[async_trait]
+    impl DataSource for FixedSource {
+        async fn fetch_price(&self, asset: &str) -> anyhow::Result<PriceData> {
+            if self.fails {
+                return Err(anyhow::anyhow!("unreachable"));
+            }
+            Ok(PriceData::new(asset.to_string(), 45000.0, self.name.to_string()))
+        }
+
+        fn name(&self) -> &str {
+            self.name
+        }
+
+        fn base_url(&self) -> &str {
+            "http://localhost"
+        }
+    }
+
+    #[tokio::test]
+    async fn test_check_marks_failing_source_unhealthy() {
+        let monitor = HealthMonitor::new(vec![
+            Arc::new(FixedSource { name: "Up", fails: false }),
+            Arc::new(FixedSource { name: "Down", fails: true }),
+        ], 60);
+
+        monitor.check_all().await;
+
+        let statuses = monitor.statuses();
+        assert!(statuses.get("Up").unwrap().healthy);
+        assert_eq!(statuses.get("Up").unwrap().checks, 1);
+        assert!(!statuses.get("Down").unwrap().healthy);
+        assert_eq!(statuses.get("Down").unwrap().success_rate, 0.0);
+    }
+
+    #[test]
+    fn test_rolling_window_success_rate_and_p95() {
+        let monitor = HealthMonitor::new(Vec::new(), 60);
+        let start = Instant::now();
+
+        // An old failure ages out of the window
+        monitor.record("Binance", Duration::from_millis(900), false, start);
+        for i in 1..=20 {
+            let at = start + HEALTH_WINDOW + Duration::from_secs(i);
+            monitor.record("Binance", Duration::from_millis(10 * i), i > 3, at);
+        }
+
+        let status = monitor.statuses().get("Binance").unwrap().clone();
+        assert_eq!(status.checks, 20);
+        assert!((status.success_rate - 0.85).abs() < 1e-9);
+        assert!(status.healthy);
+        assert_eq!(status.p95_latency_ms, 190.0);
+
+        // 17 of 22 is below 80%
+        for i in 21..=22 {
+            monitor.record("Binance", Duration::from_millis(10), false, start + HEALTH_WINDOW + Duration::from_secs(i));
+        }
+        assert!(!monitor.statuses().get("Binance").unwrap().healthy);
+    }
+}
@@ -0,0 +1,20 @@
+// Price Oracle Node components, shared by the node binary and the integration tests
+pub mod data_sources;
+pub mod consensus;
+pub mod validator;
+pub mod solana_client;
+pub mod models;
+pub mod config;
+pub mod alerts;
+pub mod reliability;
+pub mod store;
+pub mod health;
+pub mod status;
+pub mod breaker;
+pub mod stale;
+pub mod ring_buffer;
+pub mod ws_server;
+pub mod exporters;
+pub mod asset_registry;
+pub mod analytics;
+pub mod logging;
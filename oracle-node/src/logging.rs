@@ -0,0 +1,22 @@
+// Log setup shared by the node and the API binaries
+use std::io::Write;
+
+/// Set up logging at `default_filter` unless `RUST_LOG` says otherwise; `LOG_FORMAT=json`
+/// emits one JSON object per line instead of plain text
+pub fn init_logging(default_filter: &str) {
+    let mut builder = env_logger::Builder::from_env(env_logger::Env::default().default_filter_or(default_filter));
+    
+    if std::env::var("LOG_FORMAT").is_ok_and(|format| format.eq_ignore_ascii_case("json")) {
+        builder.format(|buf, record| {
+            let line = serde_json::json!({
+                "timestamp": chrono::Utc::now().to_rfc3339(),
+                "level": record.level().to_string(),
+                "target": record.target(),
+                "message": record.args().to_string(),
+            });
+            writeln!(buf, "{}", line)
+        });
+    }
+    
+    builder.init();
+}
@@ -1,24 +1,75 @@
 // Price Oracle Node - A decentralized price aggregation oracle for Solana
 use clap::{Parser, Subcommand};
-use log::{info, error};
-use std::time::Duration;
+use futures::future::join_all;
+use dashmap::DashMap;
+use log::{info, error, warn};
+use std::path::PathBuf;
+use std::str::FromStr;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
 use tokio::time::sleep;
 
-mod data_sources;
-mod consensus;
-mod validator;
-mod solana_client;
-mod models;
+use price_oracle_node::data_sources::{CoinGeckoSource, CoinMarketCapSource, BinanceSource, GateSource, OkxSource, DexSource, DataSource, DataSourceFactory};
+use price_oracle_node::consensus::{ConsensusEngine, ConsensusError};
+use price_oracle_node::validator::PriceValidator;
+use price_oracle_node::solana_client::{list_price_accounts, mainnet_allowed_by_env, MultiNetworkClient, RetryPolicy};
+use price_oracle_node::models::{ConsensusParams, ConsensusResult, NetworkKind, OracleConfig};
+use price_oracle_node::reliability::ReliabilityTracker;
+use price_oracle_node::store::PriceStore;
+use price_oracle_node::alerts::AlertEngine;
+use price_oracle_node::health::HealthMonitor;
+use price_oracle_node::status;
+use price_oracle_node::config::validate_asset_category;
+use price_oracle_node::breaker::CircuitBreaker;
+use price_oracle_node::stale::StaleFallback;
+use price_oracle_node::ws_server::WebSocketServer;
+use price_oracle_node::exporters::{exporters_from_env, Exporter};
+use price_oracle_node::logging::init_logging;
+use solana_client::rpc_client::RpcClient;
+use solana_sdk::{commitment_config::CommitmentConfig, pubkey::Pubkey};
 
-use data_sources::{CoinGeckoSource, CoinMarketCapSource, BinanceSource, DataSource};
-use consensus::ConsensusEngine;
-use validator::PriceValidator;
-use solana_client::SolanaOracleClient;
-use models::ConsensusResult;
+/// File where source reliability stats are persisted between runs
+const RELIABILITY_PATH: &str = "source_reliability.json";
+
+/// SQLite database holding the history of consensus results
+const PRICE_DB_PATH: &str = "price_history.db";
 
 #[derive(Parser)]
 #[command(author, version, about, long_about = None)]
 struct Cli {
+    /// Config file (.toml or .json); command-line arguments take precedence
+    #[arg(short, long, global = true)]
+    config: Option<PathBuf>,
+    
+    /// Allow sources without credentials to return fabricated prices (development only)
+    #[arg(long, global = true)]
+    allow_simulated: bool,
+    
+    /// Networks to submit to at once, e.g. `devnet,mainnet`; replaces the RPC URL for submission
+    #[arg(long, global = true, value_delimiter = ',')]
+    networks: Vec<NetworkKind>,
+    
+    /// Allow submitting to mainnet-beta (also allowed by `ALLOW_MAINNET=1`)
+    #[arg(long = "i-understand-mainnet", global = true)]
+    i_understand_mainnet: bool,
+    
+    /// When every source fails, serve the last good price marked stale with reduced confidence,
+    /// for at most `MAX_STALE_CYCLES` failed cycles in a row
+    #[arg(long, global = true)]
+    serve_stale: bool,
+    
+    /// Seconds a data source request may take; per-source [http_timeouts] in the config still apply
+    #[arg(long, global = true, value_parser = clap::value_parser!(u64).range(1..))]
+    http_timeout: Option<u64>,
+    
+    /// Seconds all sources get to answer within an update cycle; slower ones are left out of it [default: 10]
+    #[arg(long, global = true, value_parser = clap::value_parser!(u64).range(1..))]
+    source_timeout: Option<u64>,
+    
+    /// Fractional move from the last price treated as a possible flash crash (e.g. 0.2 for 20%)
+    #[arg(long, global = true, value_parser = parse_threshold)]
+    flash_crash_threshold: Option<f64>,
+    
     #[command(subcommand)]
     command: Commands,
 }
@@ -27,28 +78,48 @@ struct Cli {
 enum Commands {
     /// Start the oracle node
     Start {
-        /// Asset to track (e.g., BTC, SOL, ETH)
-        #[arg(short, long, default_value = "BTC")]
-        asset: String,
+        /// Asset to track (e.g., BTC, SOL, ETH) [default: BTC]
+        #[arg(short, long)]
+        asset: Option<String>,
+        
+        /// Update interval in seconds [default: 30]
+        #[arg(short, long)]
+        interval: Option<u64>,
         
-        /// Update interval in seconds
-        #[arg(short, long, default_value = "30")]
-        interval: u64,
+        /// Category to submit the asset under (L1, L2, DeFi, Stablecoin, Meme, Other) [default: Other]
+        #[arg(long, value_parser = parse_category)]
+        category: Option<String>,
         
-        /// Solana RPC URL
-        #[arg(long, default_value = "https://api.devnet.solana.com")]
-        rpc_url: String,
+        /// Solana RPC URL [default: https://api.devnet.solana.com]
+        #[arg(long)]
+        rpc_url: Option<String>,
         
         /// Program ID for the oracle program
         #[arg(long)]
         program_id: Option<String>,
+        
+        /// TOML file with price deviation alert rules
+        #[arg(long)]
+        alerts_config: Option<PathBuf>,
+        
+        /// Port to push prices to WebSocket subscribers on, 0 to disable [default: 9000]
+        #[arg(long)]
+        ws_port: Option<u16>,
+        
+        /// Exit after this many update cycles, 0 to run until stopped [default: 0]
+        #[arg(long, default_value_t = 0)]
+        cycles: u64,
     },
     
     /// Run a single price update
     Update {
-        /// Asset to update
-        #[arg(short, long, default_value = "BTC")]
-        asset: String,
+        /// Asset to update [default: BTC]
+        #[arg(short, long)]
+        asset: Option<String>,
+        
+        /// Category to submit the asset under (L1, L2, DeFi, Stablecoin, Meme, Other) [default: Other]
+        #[arg(long, value_parser = parse_category)]
+        category: Option<String>,
         
         /// Program ID for the oracle program
         #[arg(long)]
@@ -57,102 +128,309 @@ enum Commands {
     
     /// Test data sources
     TestSources {
-        /// Asset to test
-        #[arg(short, long, default_value = "BTC")]
-        asset: String,
+        /// Asset to test [default: BTC]
+        #[arg(short, long)]
+        asset: Option<String>,
+    },
+    
+    /// List the on-chain price accounts owned by the oracle program
+    ListAssets {
+        /// Program ID for the oracle program
+        #[arg(long)]
+        program_id: Option<String>,
+        
+        /// Solana RPC URL [default: https://api.devnet.solana.com]
+        #[arg(long)]
+        rpc_url: Option<String>,
     },
 }
 
 #[tokio::main]
 async fn main() -> anyhow::Result<()> {
-    env_logger::init();
+    // Without RUST_LOG only errors are logged, as env_logger does by default
+    init_logging("error");
     
     let cli = Cli::parse();
     
+    // Settings from the config file fill in anything not given on the command line
+    let mut config = match &cli.config {
+        Some(path) => {
+            info!("Loading config from {}", path.display());
+            OracleConfig::load(path)?
+        },
+        None => OracleConfig::default(),
+    };
+    config.allow_simulated |= cli.allow_simulated;
+    config.allow_mainnet |= cli.i_understand_mainnet;
+    config.serve_stale |= cli.serve_stale;
+    if !cli.networks.is_empty() {
+        config.networks = cli.networks;
+    }
+    config.http_timeout_secs = cli.http_timeout.unwrap_or(config.http_timeout_secs);
+    config.source_timeout_secs = cli.source_timeout.unwrap_or(config.source_timeout_secs);
+    config.flash_crash_threshold = cli.flash_crash_threshold.unwrap_or(config.flash_crash_threshold);
+    if config.allow_simulated {
+        warn!("Simulated prices are allowed; results using them are tagged [SIMULATED]");
+    }
+    
     match cli.command {
-        Commands::Start { asset, interval, rpc_url, program_id } => {
-            start_oracle_node(asset, interval, rpc_url, program_id).await?;
+        Commands::Start { asset, interval, category, rpc_url, program_id, alerts_config, ws_port, cycles } => {
+            config.asset = config.canonical_asset(&asset.unwrap_or(config.asset.clone()))?;
+            config.asset_category = category.unwrap_or(config.asset_category);
+            config.update_interval = interval.unwrap_or(config.update_interval);
+            config.rpc_url = rpc_url.unwrap_or(config.rpc_url);
+            config.program_id = program_id.or(config.program_id);
+            config.ws_port = ws_port.unwrap_or(config.ws_port);
+            let alerts = match alerts_config {
+                Some(path) => AlertEngine::load(path)?,
+                None => AlertEngine::new(Vec::new()),
+            };
+            start_oracle_node(config, alerts, cycles).await?;
         },
-        Commands::Update { asset, program_id } => {
-            run_single_update(asset, program_id).await?;
+        Commands::Update { asset, category, program_id } => {
+            config.asset = config.canonical_asset(&asset.unwrap_or(config.asset.clone()))?;
+            config.asset_category = category.unwrap_or(config.asset_category);
+            config.program_id = program_id.or(config.program_id);
+            run_single_update(config).await?;
         },
         Commands::TestSources { asset } => {
-            test_data_sources(asset).await?;
+            let asset = config.canonical_asset(asset.as_deref().unwrap_or(&config.asset))?;
+            test_data_sources(asset, &config).await?;
+        },
+        Commands::ListAssets { program_id, rpc_url } => {
+            config.program_id = program_id.or(config.program_id);
+            config.rpc_url = rpc_url.unwrap_or(config.rpc_url);
+            list_assets(&config)?;
         },
     }
     
     Ok(())
 }
 
-async fn start_oracle_node(
-    asset: String,
-    interval: u64,
-    rpc_url: String,
-    program_id: Option<String>,
-) -> anyhow::Result<()> {
+fn parse_category(category: &str) -> Result<String, String> {
+    validate_asset_category(category)
+        .map(|_| category.to_string())
+        .map_err(|e| e.to_string())
+}
+
+fn parse_threshold(threshold: &str) -> Result<f64, String> {
+    match threshold.parse::<f64>() {
+        Ok(value) if value > 0.0 && value.is_finite() => Ok(value),
+        _ => Err(format!("'{}' is not a positive number", threshold)),
+    }
+}
+
+/// Log suffix marking data that was fabricated rather than fetched
+fn simulated_tag(simulated: bool) -> &'static str {
+    if simulated { " [SIMULATED]" } else { "" }
+}
+
+/// Consensus engine using the thresholds from the node config
+fn consensus_engine_for(config: &OracleConfig) -> ConsensusEngine {
+    ConsensusEngine::with_params(ConsensusParams {
+        confidence_threshold: config.min_confidence,
+        price_variance_threshold: config.max_price_variance,
+        reject_high_variance: config.reject_high_variance,
+        rounds_kept: config.consensus_rounds_kept,
+        allow_single_source: config.allow_single_source,
+        single_source_confidence_cap: config.single_source_confidence_cap,
+        outlier_z_threshold: config.outlier_z_threshold,
+        ..ConsensusParams::default()
+    })
+}
+
+/// Every price source on one shared HTTP client, with the asset id overrides and timeouts from the config applied.
+/// The DEX source is only added when pools are configured under `[asset_ids.DEX]`
+fn data_sources_for(config: &OracleConfig) -> Vec<Arc<dyn DataSource>> {
+    let ids = &config.asset_ids;
+    let client = DataSourceFactory::create_shared_client();
+    let dex_pools = ids.for_source("DEX");
+    let dex: Option<Arc<dyn DataSource>> = (!dex_pools.is_empty()).then(|| {
+        Arc::new(DexSource::new(client.clone(), &config.rpc_url)
+            .with_asset_ids(dex_pools)
+            .with_timeout(config.http_timeout_for("DEX"))) as Arc<dyn DataSource>
+    });
+    let mut sources: Vec<Arc<dyn DataSource>> = vec![
+        Arc::new(CoinGeckoSource::new(client.clone())
+            .with_asset_ids(ids.for_source("CoinGecko"))
+            .with_asset_registry(config.asset_registry())
+            .with_timeout(config.http_timeout_for("CoinGecko"))),
+        Arc::new(CoinMarketCapSource::new(client.clone(), config.allow_simulated)
+            .with_asset_ids(ids.for_source("CoinMarketCap"))
+            .with_timeout(config.http_timeout_for("CoinMarketCap"))),
+        Arc::new(BinanceSource::new(client.clone())
+            .with_asset_ids(ids.for_source("Binance"))
+            .with_timeout(config.http_timeout_for("Binance"))),
+        Arc::new(GateSource::new(client.clone())
+            .with_asset_ids(ids.for_source("Gate.io"))
+            .with_timeout(config.http_timeout_for("Gate.io"))),
+        Arc::new(OkxSource::new(client)
+            .with_asset_ids(ids.for_source("OKX"))
+            .with_timeout(config.http_timeout_for("OKX"))),
+    ];
+    sources.extend(dex);
+    sources
+}
+
+/// Solana client for the configured networks, retrying submissions as configured
+fn solana_client_for(config: &OracleConfig) -> anyhow::Result<MultiNetworkClient> {
+    let retry_policy = RetryPolicy {
+        max_retries: config.submit_max_retries,
+        initial_backoff: Duration::from_millis(config.submit_retry_backoff_ms),
+    };
+    let allow_mainnet = config.allow_mainnet || mainnet_allowed_by_env();
+    for network in config.submit_networks() {
+        if !network.is_mainnet() {
+            info!("🌐 Solana cluster: {} ({})", network, network.rpc_url());
+        } else if allow_mainnet {
+            warn!("⚠️  Solana cluster: MAINNET ({}) - submissions spend real SOL", network.rpc_url());
+        } else {
+            warn!("⚠️  Solana cluster: MAINNET ({}) - submissions will be refused without --i-understand-mainnet or ALLOW_MAINNET=1", network.rpc_url());
+        }
+    }
+    MultiNetworkClient::new(
+        &config.submit_networks(),
+        config.program_id.clone(),
+        config.submit_required_networks(),
+        retry_policy,
+        config.allow_mainnet,
+    )
+}
+
+/// Run update cycles until stopped, or only `cycles` of them when non-zero
+async fn start_oracle_node(config: OracleConfig, mut alerts: AlertEngine, cycles: u64) -> anyhow::Result<()> {
+    let asset = &config.asset;
     info!("Starting Price Oracle Node for asset: {}", asset);
     
     // Initialize data sources
-    let coin_gecko = CoinGeckoSource::new();
-    let coin_market_cap = CoinMarketCapSource::new();
-    let binance = BinanceSource::new();
-    
-    let data_sources: Vec<Box<dyn DataSource>> = vec![
-        Box::new(coin_gecko),
-        Box::new(coin_market_cap),
-        Box::new(binance),
-    ];
+    let data_sources = data_sources_for(&config);
     
     // Initialize consensus engine
-    let consensus_engine = ConsensusEngine::new();
+    let consensus_engine = consensus_engine_for(&config);
+    
+    // Canary-check the sources in the background, independent of the update cycle
+    let statuses = if config.health_check_interval_secs > 0 {
+        let monitor = HealthMonitor::new(data_sources.clone(), config.health_check_interval_secs);
+        monitor.run().await;
+        monitor.statuses()
+    } else {
+        Arc::new(DashMap::new())
+    };
+    
+    // Push each consensus result to WebSocket subscribers
+    let price_updates = WebSocketServer::channel();
+    if config.ws_port > 0 {
+        let server = WebSocketServer::bind(&format!("0.0.0.0:{}", config.ws_port), price_updates.clone()).await?;
+        tokio::spawn(server.run());
+    }
+    
+    // Open the local price history
+    let store = PriceStore::open(PRICE_DB_PATH)?;
     
-    // Initialize price validator
-    let mut validator = PriceValidator::new();
+    // Pick up price history and source reliability where the last run left off
+    let (mut validator, mut tracker) = recover_state(&config, &store)?;
+    
+    if let Some(bind_address) = &config.status_bind_address {
+        tokio::spawn(status::serve(bind_address, statuses, validator.z_scores(), consensus_engine.rounds(), status::AdminKey::from_env())?);
+    }
+    
+    // Telemetry exporters configured through the environment
+    let exporters = exporters_from_env()?;
     
     // Initialize Solana client
-    let solana_client = SolanaOracleClient::new(&rpc_url, program_id)?;
+    let solana_client = solana_client_for(&config)?;
     
     info!("Oracle node initialized successfully");
-    info!("Update interval: {} seconds", interval);
+    info!("Update interval: {} seconds", config.update_interval);
+    info!("Price alert rules: {}", alerts.rule_count());
+    info!("Exporters: {}", exporters.iter().map(|exporter| exporter.name()).collect::<Vec<_>>().join(", "));
     info!("Oracle Public Key: {}", solana_client.get_oracle_pubkey());
     info!("Get SOL from faucet: https://faucet.solana.com/");
     
+    // Backs off to a slower cadence after repeated failures instead of hammering dead upstreams
+    let mut breaker = CircuitBreaker::new(config.breaker_failure_threshold, Duration::from_secs(config.breaker_backoff_secs));
+    
+    let (mut succeeded, mut failed) = (0u64, 0u64);
+    
+    // Last good result, served as stale while every source is down (with --serve-stale)
+    let mut fallback = StaleFallback::new(config.serve_stale);
+    
     // Main oracle loop
     loop {
-        match run_price_update(&asset, &data_sources, &consensus_engine, &mut validator, &solana_client).await {
+        match run_price_update(&config, &data_sources, &consensus_engine, &mut validator, &mut tracker, &store, &exporters, &solana_client).await {
             Ok(result) => {
-                info!("Price update successful: {} = ${:.2} (confidence: {:.2})", 
-                      result.asset, result.price, result.confidence);
+                info!("Price update successful: {} = ${:.2} (confidence: {:.2}){}", 
+                      result.asset, result.price, result.confidence, simulated_tag(result.simulated));
+                if breaker.record_success() {
+                    info!("Circuit breaker reset; resuming updates every {} seconds", config.update_interval);
+                }
+                alerts.check(&result).await;
+                fallback.record_success(&result);
+                // Fails only when nobody is subscribed
+                let _ = price_updates.send(result);
+                succeeded += 1;
             },
             Err(e) => {
                 error!("Price update failed: {}", e);
+                // Not resubmitted on chain: the account already holds this price, and the program
+                // rejects a timestamp that isn't newer than the stored one
+                if let Some(stale) = fallback.fallback_for(&e) {
+                    warn!("Every source failed; serving the last good price as STALE: {} = ${:.2} (confidence: {:.2}, priced at {})",
+                          stale.asset, stale.price, stale.confidence, stale.timestamp);
+                    let _ = price_updates.send(stale);
+                }
+                failed += 1;
+                if breaker.record_failure() {
+                    warn!("Circuit breaker tripped after {} consecutive failed updates; retrying every {} seconds until one succeeds",
+                          breaker.consecutive_failures(), config.breaker_backoff_secs.max(config.update_interval));
+                }
             }
         }
         
-        sleep(Duration::from_secs(interval)).await;
+        if cycles > 0 && succeeded + failed >= cycles {
+            break;
+        }
+        sleep(breaker.next_delay(Duration::from_secs(config.update_interval))).await;
     }
+    
+    info!("Finished {} update cycles: {} succeeded, {} failed", cycles, succeeded, failed);
+    println!("Update cycles: {}", cycles);
+    println!("Succeeded: {}", succeeded);
+    println!("Failed: {}", failed);
+    
+    Ok(())
 }
 
-async fn run_single_update(asset: String, program_id: Option<String>) -> anyhow::Result<()> {
+/// Validator seeded with the stored price history and reliability tracker with the stats saved
+/// after earlier update cycles, so a restarted node doesn't start cold
+fn recover_state(config: &OracleConfig, store: &PriceStore) -> anyhow::Result<(PriceValidator, ReliabilityTracker)> {
+    let validator = PriceValidator::load_from_store(store).with_flash_crash_threshold(config.flash_crash_threshold);
+    
+    let mut tracker = ReliabilityTracker::load_or_default(RELIABILITY_PATH)?;
+    match store.load_source_stats() {
+        Ok(stats) => info!("Restored reliability stats for {} sources", tracker.restore(stats)),
+        Err(e) => warn!("Failed to restore source reliability stats: {}", e),
+    }
+    
+    Ok((validator, tracker))
+}
+
+async fn run_single_update(config: OracleConfig) -> anyhow::Result<()> {
+    let asset = &config.asset;
     info!("Running single price update for: {}", asset);
     
     // Initialize components
-    let coin_gecko = CoinGeckoSource::new();
-    let coin_market_cap = CoinMarketCapSource::new();
-    let binance = BinanceSource::new();
-    
-    let data_sources: Vec<Box<dyn DataSource>> = vec![
-        Box::new(coin_gecko),
-        Box::new(coin_market_cap),
-        Box::new(binance),
-    ];
+    let data_sources = data_sources_for(&config);
     
-    let consensus_engine = ConsensusEngine::new();
-    let mut validator = PriceValidator::new();
-    let solana_client = SolanaOracleClient::new("https://api.devnet.solana.com", program_id)?;
+    let consensus_engine = consensus_engine_for(&config);
+    let mut validator = PriceValidator::new().with_flash_crash_threshold(config.flash_crash_threshold);
+    let mut tracker = ReliabilityTracker::load_or_default(RELIABILITY_PATH)?;
+    let store = PriceStore::open(PRICE_DB_PATH)?;
+    let exporters = exporters_from_env()?;
+    let solana_client = solana_client_for(&config)?;
     
     // Run update
-    let result = run_price_update(&asset, &data_sources, &consensus_engine, &mut validator, &solana_client).await?;
+    let result = run_price_update(&config, &data_sources, &consensus_engine, &mut validator, &mut tracker, &store, &exporters, &solana_client).await?;
     
     println!("Price Update Result:");
     println!("Asset: {}", result.asset);
@@ -160,64 +438,131 @@ async fn run_single_update(asset: String, program_id: Option<String>) -> anyhow:
     println!("Confidence: {:.2}", result.confidence);
     println!("Sources: {:?}", result.sources);
     println!("Consensus Score: {:.2}", result.consensus_score);
+    if result.simulated {
+        println!("WARNING: result includes SIMULATED prices");
+    }
+    if result.degraded {
+        println!("WARNING: DEGRADED result from a single source");
+    }
+    
+    // Stored consensus prices, now including this one, rather than the per-source prices the validator saw
+    let history = PriceValidator::load_from_store(&store);
+    if let Some(change) = history.price_change_pct(&result.asset) {
+        println!("Change: {:+.2}% since the last reading", change);
+    }
+    let readings_per_day = (86_400 / config.update_interval.max(1)) as usize;
+    let available = history.get_price_statistics(&result.asset).map_or(0, |stats| stats.count.saturating_sub(1));
+    let readings = readings_per_day.min(available);
+    if readings > 1 {
+        if let Some(change) = history.price_change_pct_over(&result.asset, readings) {
+            let window = if readings == readings_per_day { "24h".to_string() } else { format!("{} readings", readings) };
+            println!("Change ({}): {:+.2}%", window, change);
+        }
+    }
     
     Ok(())
 }
 
-async fn test_data_sources(asset: String) -> anyhow::Result<()> {
+async fn test_data_sources(asset: String, config: &OracleConfig) -> anyhow::Result<()> {
     info!("Testing data sources for asset: {}", asset);
     
-    let coin_gecko = CoinGeckoSource::new();
-    let coin_market_cap = CoinMarketCapSource::new();
-    let binance = BinanceSource::new();
+    let sources = data_sources_for(config);
     
-    let sources: Vec<(&str, Box<dyn DataSource>)> = vec![
-        ("CoinGecko", Box::new(coin_gecko)),
-        ("CoinMarketCap", Box::new(coin_market_cap)),
-        ("Binance", Box::new(binance)),
-    ];
+    let mut tracker = ReliabilityTracker::load_or_default(RELIABILITY_PATH)?;
+    // Stored history, to show how far each source's price is from the recent mean
+    let validator = PriceValidator::load_from_store(&PriceStore::open(PRICE_DB_PATH)?);
     
-    for (name, source) in sources {
+    for source in sources {
+        let started = Instant::now();
         match source.fetch_price(&asset).await {
             Ok(price_data) => {
-                println!("{}: ${:.2} (confidence: {:.2})", 
-                         name, price_data.price, price_data.confidence);
+                tracker.record_success(source.name(), started.elapsed());
+                let z_score = validator.compute_z_score(&asset, price_data.price)
+                    .map_or("n/a".to_string(), |z| format!("{:+.2}", z));
+                println!("{}: ${:.2} (confidence: {:.2}, z-score: {}){}", 
+                         source.name(), price_data.price, price_data.confidence, z_score, simulated_tag(price_data.simulated));
             },
             Err(e) => {
-                println!("{}: Error - {}", name, e);
+                tracker.record_failure(source.name(), started.elapsed());
+                println!("{}: Error - {}", source.name(), e);
             }
         }
     }
     
+    println!();
+    println!("Source Reliability:");
+    println!("{:<16} {:>11} {:>13} {:>16}", "Source", "Reliability", "Success Rate", "Avg Response");
+    for entry in tracker.table() {
+        println!("{:<16} {:>11.2} {:>12.1}% {:>13.0} ms", 
+                 entry.source_name, entry.reliability_score, 
+                 entry.success_rate * 100.0, entry.avg_response_time);
+    }
+    
+    tracker.save()?;
+    
     Ok(())
 }
 
+#[allow(clippy::too_many_arguments)]
 async fn run_price_update(
-    asset: &str,
-    data_sources: &[Box<dyn DataSource>],
+    config: &OracleConfig,
+    data_sources: &[Arc<dyn DataSource>],
     consensus_engine: &ConsensusEngine,
     validator: &mut PriceValidator,
-    solana_client: &SolanaOracleClient,
+    tracker: &mut ReliabilityTracker,
+    store: &PriceStore,
+    exporters: &[Box<dyn Exporter>],
+    solana_client: &MultiNetworkClient,
 ) -> anyhow::Result<ConsensusResult> {
+    let asset = config.asset.as_str();
     info!("Fetching price data for {}", asset);
     
-    // Fetch prices from all sources
+    // Fetch prices from all sources concurrently, dropping any that miss the cycle deadline
+    let deadline = tokio::time::Instant::now() + config.source_timeout();
+    let fetches = data_sources.iter().map(|source| async move {
+        let started = Instant::now();
+        let result = tokio::time::timeout_at(deadline, source.fetch_price(asset)).await;
+        (source, result, started.elapsed())
+    });
+    
     let mut price_data_vec = Vec::new();
     
-    for source in data_sources {
-        match source.fetch_price(asset).await {
-            Ok(data) => {
-                info!("Fetched price from {}: ${:.2}", data.source, data.price);
+    for (source, result, elapsed) in join_all(fetches).await {
+        match result {
+            Ok(Ok(data)) => {
+                tracker.record_success(source.name(), elapsed);
+                info!("Fetched price from {}: ${:.2}{}", data.source, data.price, simulated_tag(data.simulated));
                 price_data_vec.push(data);
             },
-            Err(e) => {
+            Ok(Err(e)) => {
+                tracker.record_failure(source.name(), elapsed);
                 error!("Failed to fetch price from {}: {}", source.name(), e);
+            },
+            Err(_) => {
+                tracker.record_failure(source.name(), elapsed);
+                warn!("Timed out {}: no response within the {}s source timeout", 
+                      source.name(), config.source_timeout_secs);
             }
         }
     }
     
+    if let Err(e) = tracker.save() {
+        warn!("Failed to persist source reliability: {}", e);
+    }
+    if let Err(e) = store.save_source_stats(tracker.table()) {
+        warn!("Failed to store source reliability stats: {}", e);
+    }
+    
+    // Weight each source by the operator's override and its tracked reliability
+    let price_data_vec: Vec<_> = price_data_vec.into_iter()
+        .map(|data| {
+            let weight = config.source_weights.weight_for(&data.source) * tracker.weight_for(&data.source);
+            data.with_weight(weight)
+        })
+        .collect();
+    
     if price_data_vec.is_empty() {
-        return Err(anyhow::anyhow!("No price data available from any source"));
+        return Err(ConsensusError::NoPriceData.into());
     }
     
     // Validate prices
@@ -226,14 +571,61 @@ async fn run_price_update(
     // Run consensus
     let consensus_result = consensus_engine.run_consensus(&validated_prices)?;
     
-    info!("Consensus reached: ${:.2} (confidence: {:.2})", 
-          consensus_result.price, consensus_result.confidence);
+    info!("Consensus reached: ${:.2} (confidence: {:.2}){}", 
+          consensus_result.price, consensus_result.confidence, simulated_tag(consensus_result.simulated));
+    if consensus_result.degraded {
+        warn!("Only {} reported a valid price; publishing it as a degraded single-source result", consensus_result.sources.join(", "));
+    }
+    
+    if let Err(e) = store.record(&consensus_result) {
+        warn!("Failed to store price history: {}", e);
+    }
+    
+    for exporter in exporters {
+        if let Err(e) = exporter.export(&consensus_result).await {
+            warn!("Failed to export to {}: {}", exporter.name(), e);
+        }
+    }
     
     // Submit to Solana (if configured)
-    if let Err(e) = solana_client.submit_price(&consensus_result).await {
+    if let Err(e) = solana_client.submit_price(&consensus_result, &config.asset_category).await {
         error!("Failed to submit to Solana: {}", e);
         // Don't fail the entire update if Solana submission fails
     }
     
     Ok(consensus_result)
 }
+
+fn list_assets(config: &OracleConfig) -> anyhow::Result<()> {
+    let program_id = config.program_id.as_deref()
+        .ok_or_else(|| anyhow::anyhow!("No program ID configured; pass --program-id or set program_id in the config"))?;
+    let program_id = Pubkey::from_str(program_id)?;
+    let rpc_client = RpcClient::new_with_commitment(config.rpc_url.clone(), CommitmentConfig::confirmed());
+    
+    let accounts = list_price_accounts(&rpc_client, &program_id)?;
+    if accounts.is_empty() {
+        println!("No price accounts found for program {}.", program_id);
+        println!("Create one for an asset with the oracle-publisher CLI's `create-account` command.");
+        return Ok(());
+    }
+    
+    println!("{:<44} {:<16} {:<10} {:>16} {:>10} {:<25} {:>7}",
+             "Account", "Asset", "Category", "Last Price", "Confidence", "Last Update", "Version");
+    for account in &accounts {
+        match &account.payload {
+            Some(payload) if payload.is_initialized => {
+                let updated = chrono::DateTime::from_timestamp(payload.timestamp, 0)
+                    .filter(|_| payload.timestamp > 0)
+                    .map_or("-".to_string(), |t| t.to_rfc3339());
+                println!("{:<44} {:<16} {:<10} {:>16.4} {:>10.2} {:<25} {:>7}",
+                         account.address, payload.asset, payload.asset_category, payload.price, payload.confidence, updated, payload.version);
+            },
+            Some(_) => println!("{:<44} {:<16}", account.address, "[uninitialized]"),
+            None => println!("{:<44} {:<16}", account.address, "[corrupt]"),
+        }
+    }
+    println!();
+    println!("{} account(s)", accounts.len());
+    
+    Ok(())
+}
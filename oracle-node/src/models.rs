@@ -1,6 +1,15 @@
 // Data models for the price oracle
 use serde::{Deserialize, Serialize};
 use chrono::{DateTime, Utc};
+use std::collections::HashMap;
+use uuid::Uuid;
+
+use crate::consensus::DEFAULT_OUTLIER_Z_THRESHOLD;
+use crate::validator::DEFAULT_FLASH_CRASH_THRESHOLD;
+use crate::ws_server::DEFAULT_WS_PORT;
+use price_oracle_program::{SentimentLabel, DEFAULT_ASSET_CATEGORY};
+
+use crate::analytics::sentiment_impact;
 
 /// Price data from a single source
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -12,6 +21,22 @@ pub struct PriceData {
     pub source: String,
     pub volume_24h: Option<f64>,
     pub market_cap: Option<f64>,
+    /// Consensus weight multiplier applied on top of confidence (e.g. source reliability)
+    #[serde(default = "default_weight")]
+    pub weight: f64,
+    /// Fabricated rather than fetched; only produced with `--allow-simulated`
+    #[serde(default)]
+    pub simulated: bool,
+    /// Best bid, from sources that report their order book
+    #[serde(default)]
+    pub bid: Option<f64>,
+    /// Best ask, from sources that report their order book
+    #[serde(default)]
+    pub ask: Option<f64>,
+}
+
+fn default_weight() -> f64 {
+    1.0
 }
 
 /// Consensus result from multiple sources
@@ -25,21 +50,206 @@ pub struct ConsensusResult {
     pub consensus_score: f64,
     pub price_variance: f64,
     pub outlier_count: usize,
+    /// Names of the sources flagged as outliers
+    #[serde(default)]
+    pub outlier_sources: Vec<String>,
+    /// True when any input price was simulated
+    #[serde(default)]
+    pub simulated: bool,
+    /// Volume-weighted average price, set whenever every source reported volume
+    #[serde(default)]
+    pub vwap_price: Option<f64>,
+    /// True when VWAP was requested but some source had no volume, so the default method was used
+    #[serde(default)]
+    pub vwap_unavailable: bool,
+    /// Each source's reported price, flagged when it was excluded as an outlier
+    #[serde(default)]
+    pub source_prices: Vec<SourcePrice>,
+    /// True when the price came from a single source instead of a consensus
+    #[serde(default)]
+    pub degraded: bool,
+    /// True when this is an earlier result republished because every source failed
+    #[serde(default)]
+    pub stale: bool,    /// Expected price direction from the asset's sentiment, see `analytics::sentiment_impact`;
+    /// set only where sentiment data is available alongside the price
+    #[serde(default)]
+    pub sentiment_impact: Option<f64>,
+}
+
+/// A single source's contribution to a consensus result
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SourcePrice {
+    pub source: String,
+    pub price: f64,
+    pub outlier: bool,
+}
+
+/// One source's price as it entered a consensus round
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ConsensusInput {
+    pub source: String,
+    pub price: f64,
+    pub confidence: f64,
+    /// Share of the final price contributed by this source (0 when excluded)
+    pub weight: f64,
+    /// Left out of the final price, as an outlier or by trimming
+    pub excluded: bool,
+}
+
+/// Record of a single `run_consensus` call, kept for auditing
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ConsensusRound {
+    pub round_id: Uuid,
+    pub asset: String,
+    pub timestamp: DateTime<Utc>,
+    pub inputs: Vec<ConsensusInput>,
+    /// Indices into `inputs` flagged as statistical outliers
+    pub outliers: Vec<usize>,
+    pub final_price: f64,
+    pub method: ConsensusMethod,
 }
 
 /// Oracle configuration
 #[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
 pub struct OracleConfig {
     pub asset: String,
+    /// Category the asset is submitted under, one of the program's `ASSET_CATEGORIES`
+    pub asset_category: String,
     pub update_interval: u64,
     pub rpc_url: String,
     pub program_id: Option<String>,
     pub min_confidence: f64,
     pub max_price_variance: f64,
+    /// Refuse to publish when sources spread further than `max_price_variance` (std dev / mean)
+    pub reject_high_variance: bool,
+    /// Let sources fall back to fabricated prices (development only)
+    pub allow_simulated: bool,
+    /// Operator overrides of how much each source is trusted
+    pub source_weights: SourceWeightConfig,
+    /// Per-source asset id overrides, merged over each source's built-in mapping
+    pub asset_ids: AssetIdConfig,
+    /// Extra aliases resolved to canonical asset symbols, alongside the built-in ones
+    pub asset_aliases: AssetAliasConfig,
+    /// Fraction a price may move from the last accepted one before it is treated as a possible flash crash
+    pub flash_crash_threshold: f64,
+    /// Seconds a source's HTTP request may take
+    pub http_timeout_secs: u64,
+    /// Seconds every source gets to answer within an update cycle; slower ones are left out of it
+    pub source_timeout_secs: u64,
+    /// Per-source overrides of `http_timeout_secs`
+    pub http_timeouts: SourceTimeoutConfig,
+    /// Resubmissions of a Solana transaction after a transient failure
+    pub submit_max_retries: u32,
+    /// Backoff before the first resubmission, doubling after each one
+    pub submit_retry_backoff_ms: u64,
+    /// Seconds between background canary checks of each source (0 disables them)
+    pub health_check_interval_secs: u64,
+    /// Address for the node's status endpoints such as `/sources` (unset disables them)
+    pub status_bind_address: Option<String>,
+    /// Port pushing consensus prices to WebSocket subscribers (0 disables it)
+    pub ws_port: u16,
+    /// Consecutive failed update cycles before the node backs off (0 disables the breaker)
+    pub breaker_failure_threshold: u32,
+    /// Seconds between update cycles while the breaker is tripped
+    pub breaker_backoff_secs: u64,
+    /// Recent consensus rounds kept for `/consensus/rounds`
+    pub consensus_rounds_kept: usize,
+    /// Publish a lone source's price, flagged as degraded, when consensus lacks sources
+    pub allow_single_source: bool,
+    /// Highest confidence a single-source price is published with
+    pub single_source_confidence_cap: f64,
+    /// Z-score above which a source's price is excluded as an outlier
+    pub outlier_z_threshold: f64,
+    /// Clusters to submit to at once; empty submits only to `rpc_url`
+    pub networks: Vec<NetworkKind>,
+    /// Networks a submission must reach to count as successful; empty requires all of them
+    pub required_networks: Vec<NetworkKind>,
+    /// Submit to mainnet-beta; without it (or `ALLOW_MAINNET=1`) mainnet submissions are refused
+    pub allow_mainnet: bool,
+    /// Serve the last good result to subscribers, marked stale with reduced confidence, when every source fails
+    pub serve_stale: bool,
+}
+
+/// Solana cluster, written as `devnet`, `testnet`, `mainnet` or an RPC URL
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(try_from = "String", into = "String")]
+pub enum NetworkKind {
+    Devnet,
+    Testnet,
+    Mainnet,
+    Custom(String),
+}
+
+/// Per-source multipliers from the `[source_weights]` config section, keyed by source name
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct SourceWeightConfig(pub HashMap<String, f64>);
+
+/// `[http_timeouts]` config section: source name -> request timeout in seconds
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct SourceTimeoutConfig(pub HashMap<String, u64>);
+
+/// `[asset_ids.<Source>]` config tables: source name -> (asset symbol -> source-specific id)
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct AssetIdConfig(pub HashMap<String, HashMap<String, String>>);
+
+/// `[asset_aliases]` config section: alias -> canonical asset symbol
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct AssetAliasConfig(pub HashMap<String, String>);
+
+impl Default for OracleConfig {
+    fn default() -> Self {
+        Self {
+            asset: "BTC".to_string(),
+            asset_category: DEFAULT_ASSET_CATEGORY.to_string(),
+            update_interval: 30,
+            rpc_url: "https://api.devnet.solana.com".to_string(),
+            program_id: None,
+            min_confidence: 0.7,
+            max_price_variance: 0.05,
+            reject_high_variance: true,
+            allow_simulated: false,
+            source_weights: SourceWeightConfig::default(),
+            asset_ids: AssetIdConfig::default(),
+            asset_aliases: AssetAliasConfig::default(),
+            flash_crash_threshold: DEFAULT_FLASH_CRASH_THRESHOLD,
+            http_timeout_secs: 5,
+            source_timeout_secs: 10,
+            http_timeouts: SourceTimeoutConfig::default(),
+            submit_max_retries: 3,
+            submit_retry_backoff_ms: 500,
+            health_check_interval_secs: 60,
+            status_bind_address: None,
+            ws_port: DEFAULT_WS_PORT,
+            breaker_failure_threshold: 5,
+            breaker_backoff_secs: 300,
+            consensus_rounds_kept: 10,
+            allow_single_source: false,
+            single_source_confidence_cap: 0.4,
+            outlier_z_threshold: DEFAULT_OUTLIER_Z_THRESHOLD,
+            networks: Vec::new(),
+            required_networks: Vec::new(),
+            allow_mainnet: false,
+            serve_stale: false,
+        }
+    }
+}
+
+/// Alert rule: notify `webhook_url` when `asset` moves more than `threshold_pct` percent within `window_secs`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AlertConfig {
+    pub asset: String,
+    pub threshold_pct: f64,
+    pub window_secs: u64,
+    pub webhook_url: String,
 }
 
 /// Data source reliability score
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct SourceReliability {
     pub source_name: String,
     pub reliability_score: f64,
@@ -48,6 +258,19 @@ pub struct SourceReliability {
     pub last_update: DateTime<Utc>,
 }
 
+/// Aggregation method used to derive the consensus price
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub enum ConsensusMethod {
+    /// Confidence-weighted mean of the non-outlier prices
+    WeightedAverage,
+    /// Confidence-weighted mean after discarding the top and bottom `trim_pct` of sorted prices
+    TrimmedMean { trim_pct: f64 },
+    /// Mean weighted by each source's 24h volume (confidence when unknown), with per-source share capped
+    VolumeWeighted,
+    /// `sum(price * volume) / sum(volume)`; needs a 24h volume from every source
+    Vwap,
+}
+
 /// Consensus parameters
 #[derive(Debug, Clone)]
 pub struct ConsensusParams {
@@ -55,6 +278,43 @@ pub struct ConsensusParams {
     pub max_outlier_percentage: f64,
     pub confidence_threshold: f64,
     pub price_variance_threshold: f64,
+    /// Refuse a round whose non-outlier prices' coefficient of variation (std dev / mean)
+    /// exceeds `price_variance_threshold`
+    pub reject_high_variance: bool,
+    pub method: ConsensusMethod,
+    /// Recent rounds the engine keeps for auditing
+    pub rounds_kept: usize,
+    /// Fall back to a lone source's price, flagged as degraded, when fewer than `min_sources` are available
+    pub allow_single_source: bool,
+    /// Highest confidence a single-source result may have
+    pub single_source_confidence_cap: f64,
+    /// Z-score above which a price is flagged as an outlier
+    pub outlier_z_threshold: f64,
+    /// How each component counts towards [`ConsensusResult::health_score`]
+    pub health_weights: HealthWeights,
+}
+
+/// Weights of the components of [`ConsensusResult::health_score`]; they need not sum to 1
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct HealthWeights {
+    pub confidence: f64,
+    pub variance: f64,
+    pub outliers: f64,
+    pub sources: f64,
+    /// Non-outlier sources at which the source component is full
+    pub full_source_count: usize,
+}
+
+impl Default for HealthWeights {
+    fn default() -> Self {
+        Self {
+            confidence: 0.4,
+            variance: 0.25,
+            outliers: 0.2,
+            sources: 0.15,
+            full_source_count: 5,
+        }
+    }
 }
 
 impl Default for ConsensusParams {
@@ -64,6 +324,13 @@ impl Default for ConsensusParams {
             max_outlier_percentage: 0.3,
             confidence_threshold: 0.7,
             price_variance_threshold: 0.05, // 5% variance threshold
+            reject_high_variance: true,
+            method: ConsensusMethod::WeightedAverage,
+            rounds_kept: 10,
+            allow_single_source: false,
+            single_source_confidence_cap: 0.4,
+            outlier_z_threshold: DEFAULT_OUTLIER_Z_THRESHOLD,
+            health_weights: HealthWeights::default(),
         }
     }
 }
@@ -75,6 +342,8 @@ pub struct ValidationResult {
     pub reason: Option<String>,
     pub adjusted_price: Option<f64>,
     pub confidence_adjustment: f64,
+    /// Standard deviations the price lies from the asset's historical mean, once there is enough history
+    pub z_score: Option<f64>,
 }
 
 impl PriceData {
@@ -87,6 +356,10 @@ impl PriceData {
             source,
             volume_24h: None,
             market_cap: None,
+            weight: 1.0,
+            simulated: false,
+            bid: None,
+            ask: None,
         }
     }
     
@@ -104,6 +377,53 @@ impl PriceData {
         self.market_cap = Some(market_cap);
         self
     }
+    
+    pub fn with_weight(mut self, weight: f64) -> Self {
+        self.weight = weight.max(0.0);
+        self
+    }
+    
+    pub fn simulated(mut self) -> Self {
+        self.simulated = true;
+        self
+    }
+    
+    pub fn with_bid_ask(mut self, bid: f64, ask: f64) -> Self {
+        self.bid = Some(bid);
+        self.ask = Some(ask);
+        self
+    }
+    
+    /// Midpoint of the best bid and ask, or the price when either is unknown
+    pub fn mid_price(&self) -> f64 {
+        match (self.bid, self.ask) {
+            (Some(bid), Some(ask)) => (bid + ask) / 2.0,
+            _ => self.price,
+        }
+    }
+    
+    /// Best ask minus best bid, when both are known
+    pub fn spread(&self) -> Option<f64> {
+        Some(self.ask? - self.bid?)
+    }
+    
+    /// Spread in basis points of the mid-price
+    pub fn spread_bps(&self) -> Option<f64> {
+        let mid = self.mid_price();
+        self.spread().filter(|_| mid > 0.0).map(|spread| spread / mid * 10_000.0)
+    }
+}
+
+impl SourceReliability {
+    pub fn new(source_name: String) -> Self {
+        Self {
+            source_name,
+            reliability_score: 1.0,
+            success_rate: 1.0,
+            avg_response_time: 0.0,
+            last_update: Utc::now(),
+        }
+    }
 }
 
 impl ConsensusResult {
@@ -117,6 +437,14 @@ impl ConsensusResult {
             consensus_score: 0.8,
             price_variance: 0.0,
             outlier_count: 0,
+            outlier_sources: Vec::new(),
+            simulated: false,
+            vwap_price: None,
+            vwap_unavailable: false,
+            source_prices: Vec::new(),
+            degraded: false,
+            stale: false,
+            sentiment_impact: None,
         }
     }
     
@@ -135,8 +463,76 @@ impl ConsensusResult {
         self
     }
     
-    pub fn with_outliers(mut self, count: usize) -> Self {
-        self.outlier_count = count;
+    pub fn with_simulated(mut self, simulated: bool) -> Self {
+        self.simulated = simulated;
+        self
+    }
+    
+    pub fn with_outliers(mut self, sources: Vec<String>) -> Self {
+        self.outlier_count = sources.len();
+        self.outlier_sources = sources;
+        self
+    }
+    
+    pub fn with_source_prices(mut self, source_prices: Vec<SourcePrice>) -> Self {
+        self.source_prices = source_prices;
+        self
+    }
+    
+    pub fn with_vwap(mut self, vwap_price: Option<f64>, vwap_unavailable: bool) -> Self {
+        self.vwap_price = vwap_price;
+        self.vwap_unavailable = vwap_unavailable;
         self
     }
+    
+    pub fn with_degraded(mut self, degraded: bool) -> Self {
+        self.degraded = degraded;
+        self
+    }
+
+    /// Set `sentiment_impact` from the asset's sentiment
+    pub fn with_sentiment(mut self, label: SentimentLabel, confidence: f64) -> Self {
+        self.sentiment_impact = Some(sentiment_impact(label, confidence));
+        self
+    }
+
+    /// Overall health of the result from 0 to 100: the weighted mean of
+    /// - confidence,
+    /// - `1 - (std dev / price) / price_variance_threshold`, floored at 0,
+    /// - the share of sources that were not outliers,
+    /// - non-outlier sources over `full_source_count`, capped at 1,
+    ///
+    /// using `params.health_weights`
+    pub fn health_score(&self, params: &ConsensusParams) -> f64 {
+        let weights = &params.health_weights;
+        let total = weights.confidence + weights.variance + weights.outliers + weights.sources;
+        if total <= 0.0 {
+            return 0.0;
+        }
+
+        let confidence = self.confidence.clamp(0.0, 1.0);
+        let variance = if self.price > 0.0 && params.price_variance_threshold > 0.0 {
+            let relative_spread = self.price_variance.max(0.0).sqrt() / self.price;
+            1.0 - (relative_spread / params.price_variance_threshold).min(1.0)
+        } else {
+            0.0
+        };
+        let counted = self.sources.len().saturating_sub(self.outlier_count);
+        let outliers = if self.sources.is_empty() {
+            0.0
+        } else {
+            counted as f64 / self.sources.len() as f64
+        };
+        let sources = if weights.full_source_count == 0 {
+            1.0
+        } else {
+            counted.min(weights.full_source_count) as f64 / weights.full_source_count as f64
+        };
+
+        let score = weights.confidence * confidence
+            + weights.variance * variance
+            + weights.outliers * outliers
+            + weights.sources * sources;
+        (100.0 * score / total).clamp(0.0, 100.0)
+    }
 }
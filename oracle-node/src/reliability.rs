@@ -0,0 +1,154 @@
+// Source reliability tracking based on fetch outcomes
+use anyhow::Result;
+use chrono::Utc;
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+use crate::models::SourceReliability;
+
+/// Smoothing factor for the rolling success rate and response time
+const SMOOTHING: f64 = 0.1;
+
+/// Response time (ms) at which the latency penalty bottoms out
+const SLOW_RESPONSE_MS: f64 = 10_000.0;
+
+#[derive(Default)]
+pub struct ReliabilityTracker {
+    sources: HashMap<String, SourceReliability>,
+    path: Option<PathBuf>,
+}
+
+impl ReliabilityTracker {
+    /// Load the tracker from disk, starting empty if the file doesn't exist yet
+    pub fn load_or_default(path: &str) -> Result<Self> {
+        let sources = if Path::new(path).exists() {
+            let data = std::fs::read_to_string(path)?;
+            serde_json::from_str(&data)?
+        } else {
+            HashMap::new()
+        };
+
+        Ok(Self {
+            sources,
+            path: Some(PathBuf::from(path)),
+        })
+    }
+
+    /// Take over stats saved elsewhere for sources this tracker has older (or no) data on;
+    /// returns how many entries were taken
+    pub fn restore(&mut self, stats: Vec<SourceReliability>) -> usize {
+        let mut restored = 0;
+        for entry in stats {
+            let newer = match self.sources.get(&entry.source_name) {
+                Some(current) => entry.last_update > current.last_update,
+                None => true,
+            };
+            if newer {
+                self.sources.insert(entry.source_name.clone(), entry);
+                restored += 1;
+            }
+        }
+        restored
+    }
+
+    /// Persist the current table to the file it was loaded from (if any)
+    pub fn save(&self) -> Result<()> {
+        if let Some(path) = &self.path {
+            std::fs::write(path, serde_json::to_string_pretty(&self.sources)?)?;
+        }
+        Ok(())
+    }
+
+    pub fn record_success(&mut self, source: &str, response_time: Duration) {
+        self.record(source, true, response_time);
+    }
+
+    pub fn record_failure(&mut self, source: &str, response_time: Duration) {
+        self.record(source, false, response_time);
+    }
+
+    fn record(&mut self, source: &str, success: bool, response_time: Duration) {
+        let is_new = !self.sources.contains_key(source);
+        let entry = self.sources.entry(source.to_string())
+            .or_insert_with(|| SourceReliability::new(source.to_string()));
+
+        let outcome = if success { 1.0 } else { 0.0 };
+        let response_ms = response_time.as_secs_f64() * 1000.0;
+
+        if is_new {
+            entry.success_rate = outcome;
+            entry.avg_response_time = response_ms;
+        } else {
+            entry.success_rate = entry.success_rate * (1.0 - SMOOTHING) + outcome * SMOOTHING;
+            entry.avg_response_time = entry.avg_response_time * (1.0 - SMOOTHING) + response_ms * SMOOTHING;
+        }
+
+        // Slow sources lose up to half of their weight
+        let latency_factor = (1.0 - entry.avg_response_time / SLOW_RESPONSE_MS).clamp(0.5, 1.0);
+        entry.reliability_score = (entry.success_rate * latency_factor).clamp(0.0, 1.0);
+        entry.last_update = Utc::now();
+    }
+
+    /// Consensus weight for a source; unknown sources get full weight
+    pub fn weight_for(&self, source: &str) -> f64 {
+        self.sources.get(source)
+            .map(|r| r.reliability_score)
+            .unwrap_or(1.0)
+    }
+
+    /// Current reliability table, sorted by source name
+    pub fn table(&self) -> Vec<&SourceReliability> {
+        let mut entries: Vec<&SourceReliability> = self.sources.values().collect();
+        entries.sort_by(|a, b| a.source_name.cmp(&b.source_name));
+        entries
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_failures_lower_reliability() {
+        let mut tracker = ReliabilityTracker::default();
+
+        tracker.record_success("Good", Duration::from_millis(100));
+        tracker.record_success("Bad", Duration::from_millis(100));
+        for _ in 0..5 {
+            tracker.record_success("Good", Duration::from_millis(100));
+            tracker.record_failure("Bad", Duration::from_millis(100));
+        }
+
+        assert!(tracker.weight_for("Good") > 0.95);
+        assert!(tracker.weight_for("Bad") < tracker.weight_for("Good"));
+        assert_eq!(tracker.weight_for("Unknown"), 1.0);
+    }
+
+    #[test]
+    fn test_slow_source_penalized() {
+        let mut tracker = ReliabilityTracker::default();
+
+        tracker.record_success("Fast", Duration::from_millis(50));
+        tracker.record_success("Slow", Duration::from_secs(8));
+
+        assert!(tracker.weight_for("Slow") < tracker.weight_for("Fast"));
+        assert!(tracker.weight_for("Slow") >= 0.5);
+    }
+
+    #[test]
+    fn test_restore_keeps_newest_stats() {
+        let mut tracker = ReliabilityTracker::default();
+        tracker.record_success("Fresh", Duration::from_millis(100));
+
+        let mut stale = SourceReliability::new("Fresh".to_string());
+        stale.reliability_score = 0.1;
+        stale.last_update -= chrono::Duration::hours(1);
+        let mut restored = SourceReliability::new("Restored".to_string());
+        restored.reliability_score = 0.6;
+
+        assert_eq!(tracker.restore(vec![stale, restored]), 1);
+        assert_eq!(tracker.weight_for("Restored"), 0.6);
+        assert!(tracker.weight_for("Fresh") > 0.9);
+    }
+}
@@ -0,0 +1,66 @@
+// Fixed-capacity buffer keeping only the most recent items
+use std::collections::VecDeque;
+
+#[derive(Debug, Clone)]
+pub struct RingBuffer<T> {
+    items: VecDeque<T>,
+    capacity: usize,
+}
+
+impl<T> RingBuffer<T> {
+    /// Buffer holding at most `capacity` items (0 keeps nothing)
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            items: VecDeque::with_capacity(capacity),
+            capacity,
+        }
+    }
+
+    /// Append an item, dropping the oldest one when full
+    pub fn push(&mut self, item: T) {
+        if self.capacity == 0 {
+            return;
+        }
+        if self.items.len() == self.capacity {
+            self.items.pop_front();
+        }
+        self.items.push_back(item);
+    }
+
+    /// Items from oldest to newest
+    pub fn iter(&self) -> impl DoubleEndedIterator<Item = &T> {
+        self.items.iter()
+    }
+
+    pub fn len(&self) -> usize {
+        self.items.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.items.is_empty()
+    }
+
+    pub fn capacity(&self) -> usize {
+        self.capacity
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_push_drops_oldest() {
+        let mut buffer = RingBuffer::new(3);
+        for i in 0..5 {
+            buffer.push(i);
+        }
+        assert_eq!(buffer.len(), 3);
+        assert_eq!(buffer.iter().copied().collect::<Vec<_>>(), vec![2, 3, 4]);
+        assert_eq!(buffer.iter().next_back(), Some(&4));
+
+        let mut disabled = RingBuffer::new(0);
+        disabled.push(1);
+        assert!(disabled.is_empty());
+    }
+}
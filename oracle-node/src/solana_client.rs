@@ -1,26 +1,94 @@
 // Solana client for submitting price data to the blockchain
 use anyhow::Result;
+use solana_client::client_error::{ClientError, ClientErrorKind};
 use solana_client::rpc_client::RpcClient;
 use solana_sdk::{
     commitment_config::CommitmentConfig,
-    signature::{Keypair, Signer},
+    signature::{Keypair, Signature, Signer},
     pubkey::Pubkey,
-    transaction::Transaction,
+    transaction::{Transaction, TransactionError},
     instruction::{AccountMeta, Instruction},
 };
+use borsh::BorshDeserialize;
+use std::collections::HashMap;
 use std::str::FromStr;
+use std::sync::Arc;
+use std::time::Duration;
 
-use crate::models::ConsensusResult;
-use price_oracle_program::{PriceOracleInstruction, get_account_size};
+use crate::models::{ConsensusResult, NetworkKind};
+use price_oracle_program::{
+    PriceOracleInstruction, PricePayload, PricePayloadV1, SentimentLabel, SubmitPriceArgs, get_account_size, initialize_account_instruction, new_ed25519_instruction, DEFAULT_ASSET_CATEGORY, NO_SENTIMENT_LABEL, allowlist_address,
+};
+
+/// Env var holding the oracle's secret key; takes precedence over keypair files
+const KEYPAIR_ENV: &str = "ORACLE_KEYPAIR";
+
+/// Env var that, set to `1`, lets clients submit to mainnet-beta
+const ALLOW_MAINNET_ENV: &str = "ALLOW_MAINNET";
+
+/// Whether `ALLOW_MAINNET=1` is set
+pub fn mainnet_allowed_by_env() -> bool {
+    std::env::var(ALLOW_MAINNET_ENV).is_ok_and(|value| value.trim() == "1")
+}
+
+/// How transaction submission retries transient RPC failures
+#[derive(Debug, Clone, Copy)]
+pub struct RetryPolicy {
+    /// Resubmissions after the first attempt
+    pub max_retries: u32,
+    /// Wait before the first retry; doubles on each further one
+    pub initial_backoff: Duration,
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        Self {
+            max_retries: 3,
+            initial_backoff: Duration::from_millis(500),
+        }
+    }
+}
 
 pub struct SolanaOracleClient {
     rpc_client: RpcClient,
     program_id: Option<Pubkey>,
     keypair: Keypair,
+    retry_policy: RetryPolicy,
+    network: NetworkKind,
+    /// Submissions to mainnet-beta are refused unless this is set
+    allow_mainnet: bool,
 }
 
 impl SolanaOracleClient {
+    /// Parse a secret key given as base58 (as wallets export it) or a JSON byte array (as `solana-keygen` writes it)
+    fn keypair_from_secret(secret: &str) -> Result<Keypair> {
+        let secret = secret.trim();
+        let bytes: Vec<u8> = if secret.starts_with('[') {
+            serde_json::from_str(secret)
+                .map_err(|e| anyhow::anyhow!("{} is not a valid JSON byte array: {}", KEYPAIR_ENV, e))?
+        } else {
+            bs58::decode(secret).into_vec()
+                .map_err(|e| anyhow::anyhow!("{} is not valid base58: {}", KEYPAIR_ENV, e))?
+        };
+        
+        if bytes.len() != 64 {
+            return Err(anyhow::anyhow!(
+                "{} must decode to a 64-byte secret key, got {} bytes", KEYPAIR_ENV, bytes.len()
+            ));
+        }
+        
+        Keypair::from_bytes(&bytes)
+            .map_err(|e| anyhow::anyhow!("{} is not a valid keypair: {}", KEYPAIR_ENV, e))
+    }
+    
     fn load_or_create_keypair() -> Result<Keypair> {
+        // A key passed through the environment never touches the disk
+        if let Ok(secret) = std::env::var(KEYPAIR_ENV) {
+            let keypair = Self::keypair_from_secret(&secret)?;
+            println!("🔑 Using keypair from {}: {}", KEYPAIR_ENV, keypair.pubkey());
+            return Ok(keypair);
+        }
+        
         // Use Solana CLI keypair
         let solana_config_path = std::env::var("SOLANA_CONFIG_FILE")
             .unwrap_or_else(|_| format!("{}/.config/solana/id.json", std::env::var("HOME").unwrap()));
@@ -55,6 +123,13 @@ impl SolanaOracleClient {
     }
     
     pub fn new(rpc_url: &str, program_id: Option<String>) -> Result<Self> {
+        // Load or generate keypair for this oracle node
+        let keypair = Self::load_or_create_keypair()?;
+        
+        Self::with_keypair(rpc_url, program_id, keypair)
+    }
+    
+    pub fn with_keypair(rpc_url: &str, program_id: Option<String>, keypair: Keypair) -> Result<Self> {
         let rpc_client = RpcClient::new_with_commitment(
             rpc_url.to_string(),
             CommitmentConfig::confirmed(),
@@ -66,29 +141,79 @@ impl SolanaOracleClient {
             None
         };
         
-        // Load or generate keypair for this oracle node
-        let keypair = Self::load_or_create_keypair()?;
-        
         Ok(Self {
             rpc_client,
             program_id,
             keypair,
+            retry_policy: RetryPolicy::default(),
+            network: NetworkKind::from_rpc_url(rpc_url),
+            allow_mainnet: mainnet_allowed_by_env(),
         })
     }
     
-    pub async fn submit_price(&self, consensus_result: &ConsensusResult) -> Result<()> {
+    pub fn with_retry_policy(mut self, retry_policy: RetryPolicy) -> Self {
+        self.retry_policy = retry_policy;
+        self
+    }
+    
+    /// Also allow mainnet submissions when `allowed`, e.g. from `--i-understand-mainnet`;
+    /// `ALLOW_MAINNET=1` allows them regardless
+    pub fn with_mainnet_allowed(mut self, allowed: bool) -> Self {
+        self.allow_mainnet |= allowed;
+        self
+    }
+    
+    /// Cluster the RPC URL points at
+    pub fn network(&self) -> &NetworkKind {
+        &self.network
+    }
+    
+    /// Sign and send a transaction, retrying transient RPC failures with a fresh
+    /// blockhash and exponential backoff. Errors from the program itself are returned at once.
+    async fn send_with_retry(&self, instructions: &[Instruction]) -> Result<Signature> {
+        let mut attempt = 0;
+        loop {
+            let result = match self.rpc_client.get_latest_blockhash() {
+                Ok(recent_blockhash) => {
+                    let transaction = Transaction::new_signed_with_payer(
+                        instructions,
+                        Some(&self.keypair.pubkey()),
+                        &[&self.keypair],
+                        recent_blockhash,
+                    );
+                    self.rpc_client.send_and_confirm_transaction(&transaction)
+                },
+                Err(e) => Err(e),
+            };
+            
+            match result {
+                Ok(signature) => return Ok(signature),
+                Err(e) if attempt < self.retry_policy.max_retries && is_retriable(&e) => {
+                    let backoff = self.retry_policy.initial_backoff * 2u32.saturating_pow(attempt);
+                    attempt += 1;
+                    log::warn!("Transaction failed ({}), retry {}/{} in {:?}", 
+                              e, attempt, self.retry_policy.max_retries, backoff);
+                    tokio::time::sleep(backoff).await;
+                },
+                Err(e) => return Err(e.into()),
+            }
+        }
+    }
+    
+    pub async fn submit_price(&self, consensus_result: &ConsensusResult, asset_category: &str) -> Result<()> {
         if self.program_id.is_none() {
             log::info!("No program ID configured, skipping Solana submission");
             return Ok(());
         }
         
         let program_id = self.program_id.unwrap();
+        self.check_mainnet_allowed()?;
         
         log::info!("Submitting price to Solana: {} = ${:.2}", 
                   consensus_result.asset, consensus_result.price);
         
         // REAL blockchain submission
-        self.submit_to_blockchain(consensus_result, program_id).await?;
+        self.submit_to_blockchain(consensus_result, asset_category, program_id).await?;
         
         Ok(())
     }
@@ -96,6 +221,7 @@ impl SolanaOracleClient {
     async fn submit_to_blockchain(
         &self, 
         consensus_result: &ConsensusResult, 
+        asset_category: &str,
         program_id: Pubkey
     ) -> Result<()> {
         log::info!("🚀 REAL BLOCKCHAIN SUBMISSION to Solana program: {}", program_id);
@@ -125,67 +251,89 @@ impl SolanaOracleClient {
             }
         }
         
-        // Sign the price data with our oracle keypair
-        let price_data = format!("{}{}{}{}", 
-            consensus_result.asset, 
-            consensus_result.price, 
-            consensus_result.timestamp.timestamp(),
-            consensus_result.confidence
-        );
-        
-        let signature = self.keypair.sign_message(price_data.as_bytes());
-        let signer_pubkey = self.keypair.pubkey().to_bytes();
-        
-        // Create the instruction data
-        let instruction = PriceOracleInstruction::SubmitPrice {
+        let args = SubmitPriceArgs {
             asset: consensus_result.asset.clone(),
             price: consensus_result.price,
             confidence: consensus_result.confidence,
             timestamp: consensus_result.timestamp.timestamp(),
             sources: consensus_result.sources.clone(),
             consensus_score: consensus_result.consensus_score,
-            signature: signature.as_ref().to_vec(),
-            signer: signer_pubkey,
+            signature: Vec::new(),
+            signer: [0; 32],
+            asset_category: asset_category.to_string(),
+            degraded: consensus_result.degraded,
+            sentiment_label: NO_SENTIMENT_LABEL,
         };
         
-        // Serialize the instruction
-        let instruction_data = borsh::to_vec(&instruction)?;
+        log::info!("📤 Submitting transaction...");
+        let signature = self.send_submission(program_id, oracle_account, args).await?;
         
-        // Create the instruction
+        log::info!("🎉 SUCCESS! Transaction submitted: {}", signature);
+        log::info!("🔗 View on Solana Explorer: https://explorer.solana.com/tx/{}", signature);
+        log::info!("📊 Price data: {} = ${:.2} (confidence: {:.2})", 
+                  consensus_result.asset, consensus_result.price, consensus_result.confidence);
+        
+        Ok(())
+    }
+    
+    /// Resubmit `current`, this oracle's latest price, with `sentiment_label` attached and
+    /// stamped `timestamp`, which must be newer than the price. Returns the transaction signature.
+    pub async fn submit_sentiment(&self, current: &PricePayload, sentiment_label: SentimentLabel, timestamp: i64) -> Result<Signature> {
+        let program_id = self.program_id
+            .ok_or_else(|| anyhow::anyhow!("No program ID configured"))?;
+        self.check_mainnet_allowed()?;
+        
+        let oracle_account = self.get_oracle_account_address(&current.asset, program_id);
+        log::info!("Submitting {} sentiment for {} to {}", sentiment_label, current.asset, oracle_account);
+        self.send_submission(program_id, oracle_account, sentiment_submission(current, sentiment_label, timestamp)).await
+    }
+    
+    /// Refuse mainnet-beta unless it was explicitly allowed
+    fn check_mainnet_allowed(&self) -> Result<()> {
+        if self.network.is_mainnet() && !self.allow_mainnet {
+            return Err(anyhow::anyhow!(
+                "Refusing to submit to mainnet ({}): pass --i-understand-mainnet or set {}=1",
+                self.network.rpc_url(), ALLOW_MAINNET_ENV
+            ));
+        }
+        Ok(())
+    }
+    
+    /// Sign `args` with the oracle keypair and send them to `oracle_account`, behind the
+    /// Ed25519 verification the program checks them against
+    async fn send_submission(&self, program_id: Pubkey, oracle_account: Pubkey, mut args: SubmitPriceArgs) -> Result<Signature> {
+        let message = args.signed_message();
+        let signature = self.keypair.sign_message(message.as_bytes());
+        args.signature = signature.as_ref().to_vec();
+        args.signer = self.keypair.pubkey().to_bytes();
+        
+        // The Ed25519 precompile checks the signature; the program checks it covered this submission
+        let verify_ix = new_ed25519_instruction(&args.signer, signature.as_ref(), message.as_bytes());
         let submit_ix = Instruction {
             program_id,
             accounts: vec![
                 AccountMeta::new(oracle_account, false),
                 AccountMeta::new(self.keypair.pubkey(), true),
+                AccountMeta::new_readonly(allowlist_address(&program_id).0, false),
+                AccountMeta::new_readonly(solana_sdk::sysvar::instructions::id(), false),
             ],
-            data: instruction_data,
+            data: borsh::to_vec(&PriceOracleInstruction::from(args))?,
         };
         
-        // Create and send transaction
-        let recent_blockhash = self.rpc_client.get_latest_blockhash()?;
-        let transaction = Transaction::new_signed_with_payer(
-            &[submit_ix],
-            Some(&self.keypair.pubkey()),
-            &[&self.keypair],
-            recent_blockhash,
-        );
-        
-        log::info!("📤 Submitting transaction...");
-        let signature = self.rpc_client.send_and_confirm_transaction(&transaction)?;
-        
-        log::info!("🎉 SUCCESS! Transaction submitted: {}", signature);
-        log::info!("🔗 View on Solana Explorer: https://explorer.solana.com/tx/{}", signature);
-        log::info!("📊 Price data: {} = ${:.2} (confidence: {:.2})", 
-                  consensus_result.asset, consensus_result.price, consensus_result.confidence);
-        
-        Ok(())
+        self.send_with_retry(&[verify_ix, submit_ix]).await
     }
     
     fn get_oracle_account_address(&self, asset: &str, program_id: Pubkey) -> Pubkey {
         // Generate deterministic account address based on asset and oracle pubkey
-        let oracle_pubkey = self.keypair.pubkey();
-        let seed = format!("oracle_{}", asset);
-        Pubkey::create_with_seed(&oracle_pubkey, &seed, &program_id).unwrap()
+        price_account_address(&self.keypair.pubkey(), asset, &program_id).unwrap()
+    }
+    
+    /// Fetch and decode this oracle's on-chain price account for `asset`
+    pub fn read_price_account(&self, asset: &str) -> Result<PricePayload> {
+        let program_id = self.program_id
+            .ok_or_else(|| anyhow::anyhow!("No program ID configured"))?;
+        let address = price_account_address(&self.keypair.pubkey(), asset, &program_id)?;
+        read_price_account(&self.rpc_client, &address)
     }
     
     pub fn get_oracle_pubkey(&self) -> Pubkey {
@@ -225,22 +373,10 @@ impl SolanaOracleClient {
         );
         
         // Initialize account instruction
-        let init_ix = Instruction {
-            program_id,
-            accounts: vec![AccountMeta::new(oracle_account, false)],
-            data: borsh::to_vec(&PriceOracleInstruction::InitializeAccount)?,
-        };
+        let init_ix = initialize_account_instruction(&program_id, &oracle_account, &self.keypair.pubkey());
         
         // Create and send transaction
-        let recent_blockhash = self.rpc_client.get_latest_blockhash()?;
-        let transaction = Transaction::new_signed_with_payer(
-            &[create_account_ix, init_ix],
-            Some(&self.keypair.pubkey()),
-            &[&self.keypair],
-            recent_blockhash,
-        );
-        
-        let signature = self.rpc_client.send_and_confirm_transaction(&transaction)?;
+        let signature = self.send_with_retry(&[create_account_ix, init_ix]).await?;
         
         log::info!("✅ Created oracle account: {}", oracle_account);
         log::info!("🔗 Transaction signature: {}", signature);
@@ -259,4 +395,504 @@ impl SolanaOracleClient {
     }
 }
 
+/// Whether a failed submission may succeed if resent: expired blockhashes and
+/// transport/RPC node trouble are retried, anything the runtime or program rejected is not
+fn is_retriable(error: &ClientError) -> bool {
+    match error.get_transaction_error() {
+        Some(TransactionError::BlockhashNotFound) => true,
+        Some(_) => false,
+        None => matches!(
+            error.kind(),
+            ClientErrorKind::Io(_) | ClientErrorKind::Reqwest(_) | ClientErrorKind::RpcError(_)
+        ),
+    }
+}
+
 // Helper trait removed - using borsh::to_vec directly
+
+/// An account owned by the oracle program, with its payload if it could be decoded
+/// Submits every price to several clusters at once, one `SolanaOracleClient` per network,
+/// all signing with the same oracle keypair. The program is expected under the same ID on each.
+pub struct MultiNetworkClient {
+    networks: HashMap<NetworkKind, Arc<SolanaOracleClient>>,
+    required_networks: Vec<NetworkKind>,
+}
+
+impl MultiNetworkClient {
+    pub fn new(
+        networks: &[NetworkKind],
+        program_id: Option<String>,
+        required_networks: Vec<NetworkKind>,
+        retry_policy: RetryPolicy,
+        allow_mainnet: bool,
+    ) -> Result<Self> {
+        if networks.is_empty() {
+            return Err(anyhow::anyhow!("At least one network is required"));
+        }
+        if let Some(missing) = required_networks.iter().find(|network| !networks.contains(network)) {
+            return Err(anyhow::anyhow!("Required network {} is not in the network list", missing));
+        }
+        
+        let keypair = SolanaOracleClient::load_or_create_keypair()?;
+        let mut clients = HashMap::new();
+        for network in networks {
+            let keypair = Keypair::from_bytes(&keypair.to_bytes())?;
+            let client = SolanaOracleClient::with_keypair(network.rpc_url(), program_id.clone(), keypair)?
+                .with_retry_policy(retry_policy)
+                .with_mainnet_allowed(allow_mainnet);
+            clients.insert(network.clone(), Arc::new(client));
+        }
+        
+        Ok(Self {
+            networks: clients,
+            required_networks,
+        })
+    }
+    
+    pub fn get_oracle_pubkey(&self) -> Pubkey {
+        self.networks.values().next()
+            .expect("MultiNetworkClient has at least one network")
+            .get_oracle_pubkey()
+    }
+    
+    /// Submit to every network concurrently. Failures on optional networks are only logged;
+    /// the submission fails if any required network did not accept it.
+    pub async fn submit_price(&self, consensus_result: &ConsensusResult, asset_category: &str) -> Result<()> {
+        let handles: Vec<_> = self.networks.iter()
+            .map(|(network, client)| {
+                let client = client.clone();
+                let consensus_result = consensus_result.clone();
+                let asset_category = asset_category.to_string();
+                let handle = tokio::spawn(async move { client.submit_price(&consensus_result, &asset_category).await });
+                (network.clone(), handle)
+            })
+            .collect();
+        
+        let mut failed = Vec::new();
+        for (network, handle) in handles {
+            let result = handle.await
+                .unwrap_or_else(|e| Err(anyhow::anyhow!("submission task failed: {}", e)));
+            if let Err(e) = result {
+                log::error!("Submission of {} to {} failed: {}", consensus_result.asset, network, e);
+                failed.push(network);
+            }
+        }
+        
+        let failed_required: Vec<String> = self.required_networks.iter()
+            .filter(|network| failed.contains(network))
+            .map(|network| network.to_string())
+            .collect();
+        if !failed_required.is_empty() {
+            return Err(anyhow::anyhow!("Submission failed on required networks: {}", failed_required.join(", ")));
+        }
+        
+        Ok(())
+    }
+}
+
+pub struct PriceAccount {
+    pub address: Pubkey,
+    /// `None` when the account data is malformed
+    pub payload: Option<PricePayload>,
+}
+
+/// The unsigned `SubmitPrice` that restates `current` with `sentiment_label` attached at
+/// `timestamp`. The account doesn't keep the degraded flag, so it is submitted unset.
+pub fn sentiment_submission(current: &PricePayload, sentiment_label: SentimentLabel, timestamp: i64) -> SubmitPriceArgs {
+    let asset_category = if current.asset_category.is_empty() {
+        DEFAULT_ASSET_CATEGORY.to_string()
+    } else {
+        current.asset_category.clone()
+    };
+    SubmitPriceArgs {
+        asset: current.asset.clone(),
+        price: current.price,
+        confidence: current.confidence,
+        timestamp,
+        sources: current.sources.clone(),
+        consensus_score: current.consensus_score,
+        signature: Vec::new(),
+        signer: [0; 32],
+        asset_category,
+        degraded: false,
+        sentiment_label: sentiment_label as u8,
+    }
+}
+
+/// Address of the price account `authority` keeps for `asset`, seeded with `oracle_<ASSET>`
+pub fn price_account_address(authority: &Pubkey, asset: &str, program_id: &Pubkey) -> Result<Pubkey> {
+    Ok(Pubkey::create_with_seed(authority, &format!("oracle_{}", asset), program_id)?)
+}
+
+/// Fetch and decode the price account at `address`; fails unless it holds an initialized payload
+pub fn read_price_account(rpc_client: &RpcClient, address: &Pubkey) -> Result<PricePayload> {
+    let data = rpc_client.get_account_data(address)?;
+    let payload = decode_price_payload(&data)
+        .ok_or_else(|| anyhow::anyhow!("Price account {} holds malformed data", address))?;
+    if !payload.is_initialized {
+        return Err(anyhow::anyhow!("Price account {} is not initialized", address));
+    }
+    Ok(payload)
+}
+
+/// Fetch every account owned by the program, sorted by asset (undecodable accounts last)
+pub fn list_price_accounts(rpc_client: &RpcClient, program_id: &Pubkey) -> Result<Vec<PriceAccount>> {
+    let mut accounts: Vec<PriceAccount> = rpc_client.get_program_accounts(program_id)?
+        .into_iter()
+        .map(|(address, account)| PriceAccount {
+            address,
+            payload: decode_price_payload(&account.data),
+        })
+        .collect();
+    
+    accounts.sort_by_key(|account| {
+        let asset = account.payload.as_ref().map(|payload| payload.asset.clone());
+        (asset.is_none(), asset, account.address)
+    });
+    Ok(accounts)
+}
+
+/// Decode a price account's data, ignoring the spare room accounts are allocated with.
+/// Accounts not yet migrated decode with the fields they predate defaulted: a nonce of 0
+/// (version 1), the default asset category (versions 1 and 2), unpaused (versions 1 to 3) and
+/// without a sentiment label (versions 1 to 4).
+pub fn decode_price_payload(data: &[u8]) -> Option<PricePayload> {
+    if matches!(data.get(1), Some(1..=4)) {
+        let mut rest = data;
+        let v1 = PricePayloadV1::deserialize(&mut rest).ok()?;
+        let nonce = if v1.version >= 2 { u64::deserialize(&mut rest).ok()? } else { 0 };
+        let asset_category = if v1.version >= 3 {
+            String::deserialize(&mut rest).ok()?
+        } else {
+            DEFAULT_ASSET_CATEGORY.to_string()
+        };
+        let paused = if v1.version >= 4 { bool::deserialize(&mut rest).ok()? } else { false };
+        return Some(PricePayload {
+            is_initialized: v1.is_initialized,
+            version: v1.version,
+            asset: v1.asset,
+            price: v1.price,
+            confidence: v1.confidence,
+            timestamp: v1.timestamp,
+            sources: v1.sources,
+            consensus_score: v1.consensus_score,
+            signature: v1.signature,
+            signer: v1.signer,
+            nonce,
+            asset_category,
+            paused,
+            sentiment_label: NO_SENTIMENT_LABEL,
+        });
+    }
+    PricePayload::deserialize(&mut &data[..]).ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use base64::Engine as _;
+    use solana_client::client_error::Result as ClientResult;
+    use solana_client::rpc_client::RpcClientConfig;
+    use solana_client::rpc_request::RpcRequest;
+    use solana_client::rpc_sender::{RpcSender, RpcTransportStats};
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::sync::Arc;
+    
+    /// Minimal RPC node: confirms every transaction, after failing the first
+    /// `failures` submissions with `error`
+    struct FlakySender {
+        failures: AtomicUsize,
+        error: TransactionError,
+        blockhash_requests: Arc<AtomicUsize>,
+        submissions: Arc<AtomicUsize>,
+    }
+    
+    #[async_trait::async_trait]
+    impl RpcSender for FlakySender {
+        async fn send(&self, request: RpcRequest, params: serde_json::Value) -> ClientResult<serde_json::Value> {
+            let context = serde_json::json!({ "slot": 1 });
+            Ok(match request {
+                RpcRequest::GetVersion => serde_json::json!({ "solana-core": "1.18.26" }),
+                RpcRequest::GetLatestBlockhash => {
+                    let count = self.blockhash_requests.fetch_add(1, Ordering::SeqCst);
+                    let blockhash = solana_sdk::hash::hash(&count.to_le_bytes());
+                    serde_json::json!({
+                        "context": context,
+                        "value": { "blockhash": blockhash.to_string(), "lastValidBlockHeight": 100 },
+                    })
+                },
+                RpcRequest::SendTransaction => {
+                    self.submissions.fetch_add(1, Ordering::SeqCst);
+                    if self.failures.load(Ordering::SeqCst) > 0 {
+                        self.failures.fetch_sub(1, Ordering::SeqCst);
+                        return Err(self.error.clone().into());
+                    }
+                    // The first signature follows the one-byte signature count
+                    let encoded = params[0].as_str().unwrap();
+                    let bytes = base64::engine::general_purpose::STANDARD.decode(encoded).unwrap();
+                    serde_json::json!(Signature::try_from(&bytes[1..65]).unwrap().to_string())
+                },
+                RpcRequest::GetBalance => serde_json::json!({ "context": context, "value": 10_000_000_000u64 }),
+                // Every oracle account already exists
+                RpcRequest::GetAccountInfo => serde_json::json!({
+                    "context": context,
+                    "value": {
+                        "data": ["", "base64"],
+                        "owner": Pubkey::default().to_string(),
+                        "lamports": 1_000_000,
+                        "executable": false,
+                        "rentEpoch": 0,
+                        "space": 0,
+                    },
+                }),
+                RpcRequest::GetSignatureStatuses => serde_json::json!({
+                    "context": context,
+                    "value": [{ "slot": 1, "confirmations": null, "err": null, "status": { "Ok": null }, "confirmationStatus": "finalized" }],
+                }),
+                other => panic!("unexpected RPC request {}", other),
+            })
+        }
+        
+        fn get_transport_stats(&self) -> RpcTransportStats {
+            RpcTransportStats::default()
+        }
+        
+        fn url(&self) -> String {
+            "mock".to_string()
+        }
+    }
+    
+    /// Client backed by a `FlakySender`, returning its blockhash and submission counters
+    fn flaky_client(failures: usize, error: TransactionError) -> (SolanaOracleClient, Arc<AtomicUsize>, Arc<AtomicUsize>) {
+        let blockhash_requests = Arc::new(AtomicUsize::new(0));
+        let submissions = Arc::new(AtomicUsize::new(0));
+        let sender = FlakySender {
+            failures: AtomicUsize::new(failures),
+            error,
+            blockhash_requests: blockhash_requests.clone(),
+            submissions: submissions.clone(),
+        };
+        let client = SolanaOracleClient {
+            rpc_client: RpcClient::new_sender(sender, RpcClientConfig::with_commitment(CommitmentConfig::confirmed())),
+            program_id: None,
+            keypair: Keypair::new(),
+            retry_policy: RetryPolicy { max_retries: 3, initial_backoff: Duration::from_millis(1) },
+            network: NetworkKind::Devnet,
+            allow_mainnet: false,
+        };
+        (client, blockhash_requests, submissions)
+    }
+    
+    fn transfer_to_self(client: &SolanaOracleClient) -> Instruction {
+        let payer = client.keypair.pubkey();
+        solana_sdk::system_instruction::transfer(&payer, &payer, 1)
+    }
+    
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_expired_blockhash_is_retried() {
+        let (client, blockhash_requests, submissions) = flaky_client(1, TransactionError::BlockhashNotFound);
+        
+        client.send_with_retry(&[transfer_to_self(&client)]).await.unwrap();
+        
+        assert_eq!(submissions.load(Ordering::SeqCst), 2);
+        // The resubmission is signed over a fresh blockhash
+        assert_eq!(blockhash_requests.load(Ordering::SeqCst), 2);
+    }
+    
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_program_error_is_not_retried() {
+        let error = TransactionError::InstructionError(0, solana_sdk::instruction::InstructionError::InvalidArgument);
+        let (client, _, submissions) = flaky_client(1, error);
+        
+        assert!(client.send_with_retry(&[transfer_to_self(&client)]).await.is_err());
+        assert_eq!(submissions.load(Ordering::SeqCst), 1);
+    }
+    
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_mainnet_submission_needs_confirmation() {
+        let error = TransactionError::InstructionError(0, solana_sdk::instruction::InstructionError::InvalidArgument);
+        let (mut client, _, submissions) = flaky_client(usize::MAX, error);
+        client.program_id = Some(Pubkey::new_unique());
+        client.network = NetworkKind::from_rpc_url("https://api.mainnet-beta.solana.com/");
+        let result = ConsensusResult::new("SOL".to_string(), 150.0, vec!["Binance".to_string()]);
+        
+        let err = client.submit_price(&result, "L1").await.unwrap_err();
+        assert!(err.to_string().contains("--i-understand-mainnet"), "{}", err);
+        assert_eq!(submissions.load(Ordering::SeqCst), 0);
+        
+        // Once allowed the submission is sent (and rejected by the stubbed program)
+        let client = client.with_mainnet_allowed(true);
+        let err = client.submit_price(&result, "L1").await.unwrap_err();
+        assert!(!err.to_string().contains("--i-understand-mainnet"), "{}", err);
+        assert_eq!(submissions.load(Ordering::SeqCst), 1);
+    }
+    
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_submit_sentiment_restates_price() {
+        let current = decode_price_payload(&payload_bytes(1, "BTC")).unwrap();
+        let args = sentiment_submission(&current, SentimentLabel::Negative, 1_700_000_060);
+        assert_eq!((args.asset.as_str(), args.price, args.confidence, args.consensus_score), ("BTC", 45000.0, 0.9, 0.95));
+        assert_eq!((args.timestamp, args.asset_category.as_str()), (1_700_000_060, DEFAULT_ASSET_CATEGORY));
+        assert_eq!(SentimentLabel::from_u8(args.sentiment_label), Some(SentimentLabel::Negative));
+        
+        let (mut client, _, submissions) = flaky_client(0, TransactionError::BlockhashNotFound);
+        client.program_id = Some(Pubkey::new_unique());
+        client.submit_sentiment(&current, SentimentLabel::Negative, 1_700_000_060).await.unwrap();
+        assert_eq!(submissions.load(Ordering::SeqCst), 1);
+        
+        client.network = NetworkKind::from_rpc_url("https://api.mainnet-beta.solana.com/");
+        assert!(client.submit_sentiment(&current, SentimentLabel::Negative, 1_700_000_120).await.is_err());
+        assert_eq!(submissions.load(Ordering::SeqCst), 1);
+    }
+    
+    #[test]
+    fn test_keypair_from_base58_and_json() {
+        let keypair = Keypair::new();
+        
+        let from_base58 = SolanaOracleClient::keypair_from_secret(&keypair.to_base58_string()).unwrap();
+        assert_eq!(from_base58.pubkey(), keypair.pubkey());
+        
+        let json = serde_json::to_string(&keypair.to_bytes().to_vec()).unwrap();
+        let from_json = SolanaOracleClient::keypair_from_secret(&json).unwrap();
+        assert_eq!(from_json.pubkey(), keypair.pubkey());
+    }
+    
+    #[test]
+    fn test_keypair_wrong_length_rejected() {
+        // A bare 32-byte public key is not a secret key
+        let short = bs58::encode(Keypair::new().pubkey().to_bytes()).into_string();
+        let err = SolanaOracleClient::keypair_from_secret(&short).unwrap_err();
+        assert!(err.to_string().contains("64-byte"));
+        
+        assert!(SolanaOracleClient::keypair_from_secret("not-base58-0OIl").is_err());
+    }
+    
+    fn payload_bytes(version: u8, asset: &str) -> Vec<u8> {
+        let v1 = PricePayloadV1 {
+            is_initialized: true,
+            version,
+            asset: asset.to_string(),
+            price: 45000.0,
+            confidence: 0.9,
+            timestamp: 1_700_000_000,
+            sources: vec!["CoinGecko".to_string()],
+            consensus_score: 0.95,
+            signature: vec![1; 64],
+            signer: [2; 32],
+        };
+        borsh::to_vec(&v1).unwrap()
+    }
+    
+    #[test]
+    fn test_decode_price_payload_versions() {
+        let mut v2 = payload_bytes(2, "BTC");
+        v2.extend_from_slice(&7u64.to_le_bytes());
+        v2.resize(v2.len() + 256, 0);
+        let payload = decode_price_payload(&v2).unwrap();
+        assert_eq!((payload.asset.as_str(), payload.version, payload.nonce), ("BTC", 2, 7));
+        assert_eq!(payload.asset_category, DEFAULT_ASSET_CATEGORY);
+        
+        let mut v3 = payload_bytes(3, "UNI");
+        v3.extend_from_slice(&8u64.to_le_bytes());
+        v3.extend_from_slice(&borsh::to_vec(&"DeFi".to_string()).unwrap());
+        // Bytes left past the category by an earlier, longer payload don't matter before v4
+        v3.push(0xff);
+        v3.resize(v3.len() + 256, 0);
+        let payload = decode_price_payload(&v3).unwrap();
+        assert_eq!((payload.version, payload.nonce, payload.asset_category.as_str()), (3, 8, "DeFi"));
+        assert!(!payload.paused);
+        
+        let mut v4 = payload_bytes(4, "UNI");
+        v4.extend_from_slice(&9u64.to_le_bytes());
+        v4.extend_from_slice(&borsh::to_vec(&"DeFi".to_string()).unwrap());
+        v4.push(1);
+        // Nor do bytes past `paused` before v5
+        v4.push(0xff);
+        let payload = decode_price_payload(&v4).unwrap();
+        assert!(payload.paused);
+        assert_eq!(payload.sentiment_label, NO_SENTIMENT_LABEL);
+        
+        let mut v5 = payload_bytes(5, "SOL");
+        v5.extend_from_slice(&10u64.to_le_bytes());
+        v5.extend_from_slice(&borsh::to_vec(&"L1".to_string()).unwrap());
+        v5.extend_from_slice(&[0, 4]);
+        v5.resize(v5.len() + 256, 0);
+        assert_eq!(decode_price_payload(&v5).unwrap().sentiment_label, 4);
+        
+        let payload = decode_price_payload(&payload_bytes(1, "SOL")).unwrap();
+        assert_eq!((payload.asset.as_str(), payload.version, payload.nonce), ("SOL", 1, 0));
+        
+        // A freshly allocated account decodes as uninitialized
+        assert!(!decode_price_payload(&[0; 300]).unwrap().is_initialized);
+        
+        // A string length running past the end of the data is malformed
+        let mut corrupt = payload_bytes(2, "ETH");
+        corrupt[2..6].copy_from_slice(&u32::MAX.to_le_bytes());
+        assert!(decode_price_payload(&corrupt).is_none());
+    }
+    
+    #[test]
+    fn test_read_price_account() {
+        let program_id = Pubkey::new_unique();
+        let mut data = payload_bytes(1, "BTC");
+        data.resize(data.len() + 64, 0);
+        let account = |data: &[u8]| serde_json::json!({
+            "context": { "slot": 1 },
+            "value": {
+                "data": [base64::engine::general_purpose::STANDARD.encode(data), "base64"],
+                "owner": program_id.to_string(),
+                "lamports": 1_000_000,
+                "executable": false,
+                "rentEpoch": 0,
+                "space": data.len(),
+            },
+        });
+        let client_with = |data: &[u8]| SolanaOracleClient {
+            rpc_client: RpcClient::new_mock_with_mocks("succeeds", HashMap::from([(RpcRequest::GetAccountInfo, account(data))])),
+            program_id: Some(program_id),
+            keypair: Keypair::new(),
+            retry_policy: RetryPolicy::default(),
+            network: NetworkKind::Devnet,
+            allow_mainnet: false,
+        };
+        
+        let payload = client_with(&data).read_price_account("BTC").unwrap();
+        assert_eq!((payload.asset.as_str(), payload.price, payload.version), ("BTC", 45000.0, 1));
+        
+        let err = client_with(&[0; 300]).read_price_account("BTC").unwrap_err();
+        assert!(err.to_string().contains("not initialized"), "{}", err);
+        
+        let mut client = client_with(&data);
+        client.program_id = None;
+        assert!(client.read_price_account("BTC").is_err());
+    }
+    
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_multi_network_required_networks() {
+        let error = TransactionError::InstructionError(0, solana_sdk::instruction::InstructionError::InvalidArgument);
+        let program_id = Some(Pubkey::new_unique());
+        let (mut devnet, _, devnet_submissions) = flaky_client(0, error.clone());
+        let (mut mainnet, _, mainnet_submissions) = flaky_client(usize::MAX, error);
+        devnet.program_id = program_id;
+        mainnet.program_id = program_id;
+        
+        let mut client = MultiNetworkClient {
+            networks: HashMap::from([
+                (NetworkKind::Devnet, Arc::new(devnet)),
+                (NetworkKind::Mainnet, Arc::new(mainnet)),
+            ]),
+            required_networks: vec![NetworkKind::Devnet],
+        };
+        let result = ConsensusResult::new("SOL".to_string(), 150.0, vec!["Binance".to_string()]);
+        
+        // Mainnet rejects the price, but only devnet is required
+        client.submit_price(&result, "L1").await.unwrap();
+        assert_eq!(devnet_submissions.load(Ordering::SeqCst), 1);
+        assert_eq!(mainnet_submissions.load(Ordering::SeqCst), 1);
+        
+        client.required_networks = vec![NetworkKind::Devnet, NetworkKind::Mainnet];
+        let err = client.submit_price(&result, "L1").await.unwrap_err();
+        assert!(err.to_string().contains("mainnet"));
+        assert!(!err.to_string().contains("devnet"));
+    }
+}
@@ -0,0 +1,112 @@
+// Last-good consensus, republished as stale when every source fails (`--serve-stale`)
+use crate::consensus::ConsensusError;
+use crate::models::ConsensusResult;
+
+/// Share of the last good confidence a stale result is published with
+pub const STALE_CONFIDENCE_FACTOR: f64 = 0.5;
+
+/// Consecutive failed cycles the last good result is served for before the node publishes nothing
+pub const MAX_STALE_CYCLES: u32 = 5;
+
+pub struct StaleFallback {
+    enabled: bool,
+    last_good: Option<ConsensusResult>,
+    stale_cycles: u32,
+}
+
+impl StaleFallback {
+    pub fn new(enabled: bool) -> Self {
+        Self {
+            enabled,
+            last_good: None,
+            stale_cycles: 0,
+        }
+    }
+
+    /// Remember a successful result and start counting stale cycles afresh; stale ones are never kept
+    pub fn record_success(&mut self, result: &ConsensusResult) {
+        if !result.stale {
+            self.last_good = Some(result.clone());
+            self.stale_cycles = 0;
+        }
+    }
+
+    /// The last good result to publish after a failed cycle: only when serving stale data is
+    /// enabled, the cycle failed because no source returned a price and fewer than
+    /// `MAX_STALE_CYCLES` failed cycles in a row have been served already. It is marked stale and
+    /// its confidence scaled by `STALE_CONFIDENCE_FACTOR`, but keeps the timestamp it was priced at
+    pub fn fallback_for(&mut self, error: &anyhow::Error) -> Option<ConsensusResult> {
+        if !self.enabled || !matches!(error.downcast_ref(), Some(ConsensusError::NoPriceData)) {
+            return None;
+        }
+        if self.stale_cycles >= MAX_STALE_CYCLES {
+            return None;
+        }
+        let mut result = self.last_good.clone()?;
+        self.stale_cycles += 1;
+        result.confidence *= STALE_CONFIDENCE_FACTOR;
+        result.stale = true;
+        Some(result)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn last_good() -> ConsensusResult {
+        ConsensusResult::new("BTC".to_string(), 45000.0, vec!["Binance".to_string(), "OKX".to_string()])
+            .with_confidence(0.9)
+    }
+
+    #[test]
+    fn test_all_sources_down_serves_stale_only_when_enabled() {
+        let all_down: anyhow::Error = ConsensusError::NoPriceData.into();
+
+        let mut disabled = StaleFallback::new(false);
+        disabled.record_success(&last_good());
+        assert!(disabled.fallback_for(&all_down).is_none());
+
+        let mut fallback = StaleFallback::new(true);
+        // Nothing to fall back on before the first good cycle
+        assert!(fallback.fallback_for(&all_down).is_none());
+
+        fallback.record_success(&last_good());
+        let stale = fallback.fallback_for(&all_down).unwrap();
+        assert!(stale.stale);
+        assert_eq!(stale.price, 45000.0);
+        assert!((stale.confidence - 0.45).abs() < 1e-9);
+        // Consumers can tell how old the price is
+        assert_eq!(stale.timestamp, fallback.last_good.as_ref().unwrap().timestamp);
+
+        // Stale results don't replace the last good one, so confidence doesn't keep shrinking
+        fallback.record_success(&stale);
+        assert!((fallback.fallback_for(&all_down).unwrap().confidence - 0.45).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_stale_serving_is_capped_until_a_fresh_result() {
+        let all_down: anyhow::Error = ConsensusError::NoPriceData.into();
+        let mut fallback = StaleFallback::new(true);
+        fallback.record_success(&last_good());
+
+        for _ in 0..MAX_STALE_CYCLES {
+            let stale = fallback.fallback_for(&all_down).unwrap();
+            fallback.record_success(&stale);
+        }
+        assert!(fallback.fallback_for(&all_down).is_none());
+
+        fallback.record_success(&last_good());
+        assert!(fallback.fallback_for(&all_down).is_some());
+    }
+
+    #[test]
+    fn test_other_failures_are_not_served_stale() {
+        let mut fallback = StaleFallback::new(true);
+        fallback.record_success(&last_good());
+
+        let outliers: anyhow::Error = ConsensusError::InsufficientEffectiveSources { remaining: 1, minimum: 2 }.into();
+        assert!(fallback.fallback_for(&outliers).is_none());
+        assert!(fallback.fallback_for(&anyhow::anyhow!("Too many outliers")).is_none());
+    }
+}
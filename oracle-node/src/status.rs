@@ -0,0 +1,327 @@
+// HTTP status endpoints of the oracle node
+use actix_web::dev::Server;
+use actix_web::{get, web, App, HttpRequest, HttpResponse, HttpServer, Responder};
+use log::info;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::collections::BTreeMap;
+use uuid::Uuid;
+
+use crate::consensus::ConsensusRounds;
+use crate::health::{HealthStatus, HealthStatuses};
+use crate::models::{ConsensusMethod, ConsensusRound};
+use crate::validator::SourceZScores;
+
+/// Env var holding the key `/consensus/debug` requires in `X-Admin-Key`
+pub const ADMIN_KEY_ENV: &str = "ORACLE_ADMIN_KEY";
+
+/// Key guarding the debug endpoints (`None` disables them)
+#[derive(Clone, Default)]
+pub struct AdminKey(Option<String>);
+
+impl AdminKey {
+    pub fn new(key: Option<String>) -> Self {
+        Self(key.filter(|key| !key.is_empty()))
+    }
+
+    /// Key from `ORACLE_ADMIN_KEY`
+    pub fn from_env() -> Self {
+        Self::new(std::env::var(ADMIN_KEY_ENV).ok())
+    }
+
+    /// Check the request's `X-Admin-Key` header; `Err` explains the rejection
+    fn authorize(&self, req: &HttpRequest) -> Result<(), String> {
+        let Some(expected) = &self.0 else {
+            return Err(format!("Endpoint is disabled ({} not set)", ADMIN_KEY_ENV));
+        };
+        let provided = req.headers()
+            .get("X-Admin-Key")
+            .and_then(|value| value.to_str().ok())
+            .ok_or_else(|| "Missing X-Admin-Key header".to_string())?;
+
+        // Compare digests so the check doesn't leak the key through timing
+        if Sha256::digest(provided.trim()) == Sha256::digest(expected) {
+            Ok(())
+        } else {
+            Err("Invalid X-Admin-Key".to_string())
+        }
+    }
+}
+
+/// Query parameters for `/consensus/rounds`
+#[derive(Deserialize)]
+struct RoundsQuery {
+    asset: Option<String>,
+    #[serde(default = "default_rounds_limit")]
+    limit: usize,
+}
+
+fn default_rounds_limit() -> usize {
+    10
+}
+
+/// Query parameters for `/consensus/debug`
+#[derive(Deserialize)]
+struct DebugQuery {
+    asset: String,
+}
+
+/// Internals of the latest consensus round for an asset
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ConsensusDebug {
+    pub asset: String,
+    pub round_id: Uuid,
+    pub sources: Vec<DebugSource>,
+    /// Sources flagged as statistical outliers
+    pub outliers: Vec<String>,
+    pub method: ConsensusMethod,
+    pub result_price: f64,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct DebugSource {
+    pub name: String,
+    pub price: f64,
+    pub weight: f64,
+    pub excluded: bool,
+}
+
+impl From<&ConsensusRound> for ConsensusDebug {
+    fn from(round: &ConsensusRound) -> Self {
+        Self {
+            asset: round.asset.clone(),
+            round_id: round.round_id,
+            sources: round.inputs.iter()
+                .map(|input| DebugSource {
+                    name: input.source.clone(),
+                    price: input.price,
+                    weight: input.weight,
+                    excluded: input.excluded,
+                })
+                .collect(),
+            outliers: round.outliers.iter()
+                .filter_map(|&i| round.inputs.get(i))
+                .map(|input| input.source.clone())
+                .collect(),
+            method: round.method,
+            result_price: round.final_price,
+        }
+    }
+}
+
+/// What `/sources` reports for one data source
+#[derive(Debug, Default, Serialize)]
+pub struct SourceStatus {
+    /// From the background checks, when they run
+    #[serde(flatten)]
+    pub health: Option<HealthStatus>,
+    /// Latest Z-score of the source's price against each asset's history
+    #[serde(skip_serializing_if = "BTreeMap::is_empty")]
+    pub z_scores: BTreeMap<String, f64>,
+}
+
+/// Health of each data source from the background checks, with the Z-scores of its latest prices
+#[get("/sources")]
+async fn list_sources(statuses: web::Data<HealthStatuses>, z_scores: Option<web::Data<SourceZScores>>) -> impl Responder {
+    let mut sources: BTreeMap<String, SourceStatus> = statuses.iter()
+        .map(|entry| (entry.key().clone(), SourceStatus { health: Some(entry.value().clone()), ..SourceStatus::default() }))
+        .collect();
+    for entry in z_scores.iter().flat_map(|z_scores| z_scores.iter()) {
+        sources.entry(entry.key().clone()).or_default().z_scores = entry.value().clone();
+    }
+    HttpResponse::Ok().json(sources)
+}
+
+/// Most recent consensus rounds first, optionally for one asset
+#[get("/consensus/rounds")]
+async fn list_rounds(query: web::Query<RoundsQuery>, rounds: web::Data<ConsensusRounds>) -> impl Responder {
+    let rounds = rounds.lock().unwrap();
+    let recent: Vec<&ConsensusRound> = rounds.iter()
+        .rev()
+        .filter(|round| match &query.asset {
+            Some(asset) => round.asset.eq_ignore_ascii_case(asset),
+            None => true,
+        })
+        .take(query.limit)
+        .collect();
+    HttpResponse::Ok().json(recent)
+}
+
+/// Source prices, weights and outliers of the asset's latest consensus round; requires `X-Admin-Key`
+#[get("/consensus/debug")]
+async fn consensus_debug(
+    req: HttpRequest,
+    query: web::Query<DebugQuery>,
+    rounds: web::Data<ConsensusRounds>,
+    admin_key: web::Data<AdminKey>,
+) -> impl Responder {
+    if let Err(message) = admin_key.authorize(&req) {
+        return HttpResponse::Unauthorized().json(message);
+    }
+    let latest = rounds.lock().unwrap()
+        .iter()
+        .rev()
+        .find(|round| round.asset.eq_ignore_ascii_case(&query.asset))
+        .map(ConsensusDebug::from);
+    match latest {
+        Some(debug) => HttpResponse::Ok().json(debug),
+        None => HttpResponse::NotFound().json(format!("No consensus round has run for {}", query.asset)),
+    }
+}
+
+/// Bind the status endpoints to `bind_address`; the returned server runs once spawned
+pub fn serve(
+    bind_address: &str,
+    statuses: HealthStatuses,
+    z_scores: SourceZScores,
+    rounds: ConsensusRounds,
+    admin_key: AdminKey,
+) -> std::io::Result<Server> {
+    let server = HttpServer::new(move || {
+        App::new()
+            .app_data(web::Data::new(statuses.clone()))
+            .app_data(web::Data::new(z_scores.clone()))
+            .app_data(web::Data::new(rounds.clone()))
+            .app_data(web::Data::new(admin_key.clone()))
+            .service(list_sources)
+            .service(list_rounds)
+            .service(consensus_debug)
+    })
+    .workers(1)
+    .disable_signals()
+    .bind(bind_address)?
+    .run();
+
+    info!("Status server listening on http://{}", bind_address);
+    Ok(server)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::consensus::ConsensusEngine;
+    use crate::models::PriceData;
+    use actix_web::test as actix_test;
+    use dashmap::DashMap;
+    use std::sync::Arc;
+
+    #[actix_web::test]
+    async fn test_sources_lists_statuses() {
+        let statuses: HealthStatuses = Arc::new(DashMap::new());
+        statuses.insert("Binance".to_string(), HealthStatus {
+            healthy: false,
+            success_rate: 0.5,
+            p95_latency_ms: 120.0,
+            checks: 4,
+        });
+
+        let z_scores: SourceZScores = Arc::new(DashMap::new());
+        z_scores.insert("Binance".to_string(), BTreeMap::from([("BTC".to_string(), 1.5)]));
+        z_scores.insert("OKX".to_string(), BTreeMap::from([("BTC".to_string(), -0.25)]));
+
+        let app = actix_test::init_service(
+            App::new()
+                .app_data(web::Data::new(statuses))
+                .app_data(web::Data::new(z_scores))
+                .service(list_sources),
+        ).await;
+        let req = actix_test::TestRequest::get().uri("/sources").to_request();
+        let response: serde_json::Value = actix_test::call_and_read_body_json(&app, req).await;
+
+        assert_eq!(response["Binance"]["healthy"], false);
+        assert_eq!(response["Binance"]["p95_latency_ms"], 120.0);
+        assert_eq!(response["Binance"]["z_scores"]["BTC"], 1.5);
+        // Sources validated without a health check yet still show their Z-scores
+        assert_eq!(response["OKX"], serde_json::json!({ "z_scores": { "BTC": -0.25 } }));
+    }
+
+    #[actix_web::test]
+    async fn test_rounds_newest_first() {
+        let engine = ConsensusEngine::new();
+        for (asset, price) in [("BTC", 45000.0), ("ETH", 3000.0), ("BTC", 46000.0)] {
+            let price_data = vec![
+                PriceData::new(asset.to_string(), price, "Source1".to_string()),
+                PriceData::new(asset.to_string(), price, "Source2".to_string()),
+            ];
+            engine.run_consensus(&price_data).unwrap();
+        }
+
+        let app = actix_test::init_service(
+            App::new()
+                .app_data(web::Data::new(engine.rounds()))
+                .service(list_rounds),
+        ).await;
+        let req = actix_test::TestRequest::get().uri("/consensus/rounds?asset=btc&limit=5").to_request();
+        let response: serde_json::Value = actix_test::call_and_read_body_json(&app, req).await;
+
+        let prices: Vec<f64> = response.as_array().unwrap().iter()
+            .map(|round| round["final_price"].as_f64().unwrap())
+            .collect();
+        assert_eq!(prices, vec![46000.0, 45000.0]);
+        assert_eq!(response[0]["inputs"][0]["source"], "Source1");
+
+        let req = actix_test::TestRequest::get().uri("/consensus/rounds?limit=1").to_request();
+        let response: serde_json::Value = actix_test::call_and_read_body_json(&app, req).await;
+        assert_eq!(response.as_array().unwrap().len(), 1);
+    }
+
+    #[actix_web::test]
+    async fn test_consensus_debug_requires_admin_key() {
+        let engine = ConsensusEngine::new();
+        let app = actix_test::init_service(
+            App::new()
+                .app_data(web::Data::new(engine.rounds()))
+                .app_data(web::Data::new(AdminKey::new(Some("s3cret".to_string()))))
+                .service(consensus_debug),
+        ).await;
+        let debug = |key: Option<&str>| {
+            let req = actix_test::TestRequest::get().uri("/consensus/debug?asset=btc");
+            match key {
+                Some(key) => req.insert_header(("X-Admin-Key", key)).to_request(),
+                None => req.to_request(),
+            }
+        };
+
+        assert_eq!(actix_test::call_service(&app, debug(None)).await.status(), 401);
+        assert_eq!(actix_test::call_service(&app, debug(Some("wrong"))).await.status(), 401);
+        assert_eq!(actix_test::call_service(&app, debug(Some("s3cret"))).await.status(), 404);
+
+        let price_data: Vec<PriceData> = [("Source1", 45000.0), ("Source2", 45010.0), ("Source3", 45020.0)]
+            .into_iter()
+            .map(|(source, price)| PriceData::new("BTC".to_string(), price, source.to_string()))
+            .collect();
+        let result = engine.run_consensus(&price_data).unwrap();
+
+        let response: ConsensusDebug = actix_test::call_and_read_body_json(&app, debug(Some("s3cret"))).await;
+        assert_eq!(response.asset, "BTC");
+        assert_eq!(response.result_price, result.price);
+        assert_eq!(response.method, ConsensusMethod::WeightedAverage);
+        assert_eq!(response.sources.iter().map(|source| source.name.as_str()).collect::<Vec<_>>(), vec!["Source1", "Source2", "Source3"]);
+        assert!(response.sources.iter().all(|source| !source.excluded && source.weight > 0.0));
+        assert!(response.outliers.is_empty());
+
+        // Without a configured key the endpoint stays closed
+        let disabled = actix_test::init_service(
+            App::new()
+                .app_data(web::Data::new(engine.rounds()))
+                .app_data(web::Data::new(AdminKey::new(Some(String::new()))))
+                .service(consensus_debug),
+        ).await;
+        assert_eq!(actix_test::call_service(&disabled, debug(Some(""))).await.status(), 401);
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_server_runs_on_tokio() {
+        let port = std::net::TcpListener::bind("127.0.0.1:0").unwrap().local_addr().unwrap().port();
+        let bind_address = format!("127.0.0.1:{}", port);
+        let server = serve(&bind_address, Arc::new(DashMap::new()), Arc::new(DashMap::new()), ConsensusEngine::new().rounds(), AdminKey::default()).unwrap();
+        let handle = server.handle();
+        tokio::spawn(server);
+
+        let response = reqwest::get(format!("http://{}/sources", bind_address)).await.unwrap();
+        assert_eq!(response.status(), 200);
+        assert_eq!(response.text().await.unwrap(), "{}");
+
+        handle.stop(false).await;
+    }
+}
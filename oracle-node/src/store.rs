@@ -0,0 +1,221 @@
+// Local SQLite history of consensus results
+use anyhow::Result;
+use chrono::DateTime;
+use rusqlite::{params, Connection};
+use std::path::Path;
+
+use crate::models::{ConsensusResult, SourceReliability};
+
+const SCHEMA: &str = "
+    CREATE TABLE IF NOT EXISTS price_history (
+        id INTEGER PRIMARY KEY AUTOINCREMENT,
+        timestamp INTEGER NOT NULL,
+        asset TEXT NOT NULL,
+        price REAL NOT NULL,
+        confidence REAL NOT NULL,
+        sources TEXT NOT NULL,
+        consensus_score REAL NOT NULL
+    );
+    CREATE INDEX IF NOT EXISTS idx_price_history_asset_time
+        ON price_history (asset, timestamp);
+    CREATE TABLE IF NOT EXISTS source_prices (
+        history_id INTEGER NOT NULL REFERENCES price_history (id),
+        source TEXT NOT NULL,
+        price REAL NOT NULL,
+        outlier INTEGER NOT NULL
+    );
+    CREATE INDEX IF NOT EXISTS idx_source_prices_history
+        ON source_prices (history_id);
+    CREATE TABLE IF NOT EXISTS source_stats (
+        source TEXT PRIMARY KEY,
+        reliability_score REAL NOT NULL,
+        success_rate REAL NOT NULL,
+        avg_response_time REAL NOT NULL,
+        last_update INTEGER NOT NULL
+    );
+";
+
+/// Create the node's tables on `conn` where they don't exist yet, for other processes that share
+/// the history database, such as the API
+pub fn create_schema(conn: &Connection) -> Result<()> {
+    conn.execute_batch(SCHEMA)?;
+    Ok(())
+}
+
+pub struct PriceStore {
+    conn: Connection,
+}
+
+impl PriceStore {
+    /// Open (or create) the history database at `path`
+    pub fn open<P: AsRef<Path>>(path: P) -> Result<Self> {
+        Self::init(Connection::open(path)?)
+    }
+
+    /// Throwaway in-memory store, used by tests
+    #[cfg(test)]
+    pub fn in_memory() -> Result<Self> {
+        Self::init(Connection::open_in_memory()?)
+    }
+
+    fn init(conn: Connection) -> Result<Self> {
+        create_schema(&conn)?;
+        Ok(Self { conn })
+    }
+
+    /// Append a consensus result, and each source's price behind it, to the history
+    pub fn record(&self, result: &ConsensusResult) -> Result<()> {
+        let tx = self.conn.unchecked_transaction()?;
+        tx.execute(
+            "INSERT INTO price_history (timestamp, asset, price, confidence, sources, consensus_score)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6)",
+            params![
+                result.timestamp.timestamp(),
+                result.asset,
+                result.price,
+                result.confidence,
+                result.sources.join(","),
+                result.consensus_score,
+            ],
+        )?;
+        let history_id = tx.last_insert_rowid();
+        for source_price in &result.source_prices {
+            tx.execute(
+                "INSERT INTO source_prices (history_id, source, price, outlier) VALUES (?1, ?2, ?3, ?4)",
+                params![history_id, source_price.source, source_price.price, source_price.outlier],
+            )?;
+        }
+        tx.commit()?;
+        Ok(())
+    }
+
+    /// Every asset with stored history, sorted
+    pub fn assets(&self) -> Result<Vec<String>> {
+        let mut stmt = self.conn.prepare("SELECT DISTINCT asset FROM price_history ORDER BY asset")?;
+        let assets = stmt.query_map([], |row| row.get(0))?.collect::<rusqlite::Result<_>>()?;
+        Ok(assets)
+    }
+
+    /// The last `limit` consensus prices of `asset`, oldest first
+    pub fn recent_prices(&self, asset: &str, limit: usize) -> Result<Vec<f64>> {
+        let mut stmt = self.conn.prepare(
+            "SELECT price FROM price_history WHERE asset = ?1 ORDER BY timestamp DESC, id DESC LIMIT ?2",
+        )?;
+        let mut prices: Vec<f64> = stmt.query_map(params![asset, limit as i64], |row| row.get(0))?
+            .collect::<rusqlite::Result<_>>()?;
+        prices.reverse();
+        Ok(prices)
+    }
+
+    /// Replace the stored reliability stats of each of these sources
+    pub fn save_source_stats<'a>(&self, stats: impl IntoIterator<Item = &'a SourceReliability>) -> Result<()> {
+        let tx = self.conn.unchecked_transaction()?;
+        for entry in stats {
+            tx.execute(
+                "INSERT OR REPLACE INTO source_stats (source, reliability_score, success_rate, avg_response_time, last_update)
+                 VALUES (?1, ?2, ?3, ?4, ?5)",
+                params![
+                    entry.source_name,
+                    entry.reliability_score,
+                    entry.success_rate,
+                    entry.avg_response_time,
+                    entry.last_update.timestamp(),
+                ],
+            )?;
+        }
+        tx.commit()?;
+        Ok(())
+    }
+
+    /// Reliability stats saved by earlier update cycles
+    pub fn load_source_stats(&self) -> Result<Vec<SourceReliability>> {
+        let mut stmt = self.conn.prepare(
+            "SELECT source, reliability_score, success_rate, avg_response_time, last_update FROM source_stats ORDER BY source",
+        )?;
+        let stats = stmt.query_map([], |row| {
+            Ok(SourceReliability {
+                source_name: row.get(0)?,
+                reliability_score: row.get(1)?,
+                success_rate: row.get(2)?,
+                avg_response_time: row.get(3)?,
+                last_update: DateTime::from_timestamp(row.get(4)?, 0).unwrap_or_default(),
+            })
+        })?;
+        Ok(stats.collect::<rusqlite::Result<_>>()?)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::SourcePrice;
+
+    #[test]
+    fn test_record_appends_row() {
+        let store = PriceStore::in_memory().unwrap();
+        let result = ConsensusResult::new("BTC".to_string(), 45000.0, vec!["CoinGecko".into(), "Binance".into()]);
+
+        store.record(&result).unwrap();
+        store.record(&result).unwrap();
+
+        let (count, sources): (i64, String) = store.conn
+            .query_row("SELECT COUNT(*), MAX(sources) FROM price_history WHERE asset = 'BTC'", [], |row| {
+                Ok((row.get(0)?, row.get(1)?))
+            })
+            .unwrap();
+        assert_eq!(count, 2);
+        assert_eq!(sources, "CoinGecko,Binance");
+    }
+
+    #[test]
+    fn test_record_keeps_source_prices() {
+        let store = PriceStore::in_memory().unwrap();
+        let result = ConsensusResult::new("SOL".to_string(), 100.0, vec!["CoinGecko".into(), "OKX".into()])
+            .with_source_prices(vec![
+                SourcePrice { source: "CoinGecko".to_string(), price: 100.5, outlier: false },
+                SourcePrice { source: "OKX".to_string(), price: 120.0, outlier: true },
+            ]);
+
+        store.record(&result).unwrap();
+
+        let rows: Vec<(String, f64, bool)> = store.conn
+            .prepare("SELECT source, price, outlier FROM source_prices WHERE history_id = 1 ORDER BY source")
+            .unwrap()
+            .query_map([], |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?)))
+            .unwrap()
+            .collect::<rusqlite::Result<_>>()
+            .unwrap();
+        assert_eq!(rows, vec![("CoinGecko".to_string(), 100.5, false), ("OKX".to_string(), 120.0, true)]);
+    }
+
+    #[test]
+    fn test_recent_prices_oldest_first() {
+        let store = PriceStore::in_memory().unwrap();
+        for (asset, price) in [("BTC", 45000.0), ("ETH", 3000.0), ("BTC", 45100.0), ("BTC", 45200.0)] {
+            store.record(&ConsensusResult::new(asset.to_string(), price, vec!["CoinGecko".into()])).unwrap();
+        }
+
+        assert_eq!(store.assets().unwrap(), vec!["BTC".to_string(), "ETH".to_string()]);
+        assert_eq!(store.recent_prices("BTC", 2).unwrap(), vec![45100.0, 45200.0]);
+        assert_eq!(store.recent_prices("BTC", 10).unwrap(), vec![45000.0, 45100.0, 45200.0]);
+        assert!(store.recent_prices("SOL", 10).unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_source_stats_round_trip() {
+        let store = PriceStore::in_memory().unwrap();
+        let mut binance = SourceReliability::new("Binance".to_string());
+        binance.success_rate = 0.5;
+        binance.last_update = DateTime::from_timestamp(1_716_000_000, 0).unwrap();
+
+        store.save_source_stats([&binance, &SourceReliability::new("OKX".to_string())]).unwrap();
+        binance.reliability_score = 0.4;
+        store.save_source_stats([&binance]).unwrap();
+
+        let stats = store.load_source_stats().unwrap();
+        assert_eq!(stats.len(), 2);
+        assert_eq!(stats[0].source_name, "Binance");
+        assert_eq!((stats[0].reliability_score, stats[0].success_rate), (0.4, 0.5));
+        assert_eq!(stats[0].last_update, binance.last_update);
+    }
+}
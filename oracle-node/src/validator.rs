@@ -1,13 +1,36 @@
 // Price validation and quality assessment
 use anyhow::Result;
-use std::collections::HashMap;
+use dashmap::DashMap;
+use std::collections::{BTreeMap, HashMap};
+use std::sync::Arc;
 
 use crate::models::{PriceData, ValidationResult};
+use crate::store::PriceStore;
+
+/// A source further than this from the cycle's median price is flagged as an outlier
+const OUTLIER_DEVIATION: f64 = 0.02;
+/// Fraction of a source's outlier score carried into the next cycle
+const OUTLIER_DECAY: f64 = 0.5;
+/// Confidence multiplier per unit of outlier score
+const OUTLIER_PENALTY: f64 = 0.8;
+/// Move from the last accepted price, as a fraction of it, treated as a possible flash crash
+pub const DEFAULT_FLASH_CRASH_THRESHOLD: f64 = 0.20;
+/// Confidence multiplier for a price that looks like a flash crash
+const FLASH_CRASH_CONFIDENCE: f64 = 0.3;
+/// Prices of an asset needed before its history is used for statistics
+const MIN_HISTORY_FOR_STATS: usize = 3;
+
+/// Latest Z-score of each source's price per asset: source -> (asset -> Z-score), as served by `/sources`
+pub type SourceZScores = Arc<DashMap<String, BTreeMap<String, f64>>>;
 
 pub struct PriceValidator {
     // Historical price data for validation
     price_history: HashMap<String, Vec<f64>>,
     max_history_size: usize,
+    // Decaying count of recent outlier flags per source
+    outlier_scores: HashMap<String, f64>,
+    flash_crash_threshold: f64,
+    z_scores: SourceZScores,
 }
 
 impl PriceValidator {
@@ -15,15 +38,75 @@ impl PriceValidator {
         Self {
             price_history: HashMap::new(),
             max_history_size: 100,
+            outlier_scores: HashMap::new(),
+            flash_crash_threshold: DEFAULT_FLASH_CRASH_THRESHOLD,
+            z_scores: Arc::new(DashMap::new()),
+        }
+    }
+    
+    /// Validator whose price history starts with the last `max_history_size` stored prices of
+    /// each asset, so a restarted node doesn't validate its first updates without context
+    pub fn load_from_store(store: &PriceStore) -> Self {
+        let mut validator = Self::new();
+        let assets = match store.assets() {
+            Ok(assets) => assets,
+            Err(e) => {
+                log::warn!("Failed to read stored price history: {}", e);
+                return validator;
+            }
+        };
+        for asset in assets {
+            match store.recent_prices(&asset, validator.max_history_size) {
+                Ok(prices) => {
+                    log::info!("Restored {} price history entries for {}", prices.len(), asset);
+                    validator.price_history.insert(asset, prices);
+                },
+                Err(e) => log::warn!("Failed to restore price history for {}: {}", asset, e),
+            }
+        }
+        validator
+    }
+    
+    pub fn with_flash_crash_threshold(mut self, threshold: f64) -> Self {
+        self.flash_crash_threshold = threshold;
+        self
+    }
+    
+    /// Shared view of the latest Z-score per source and asset
+    pub fn z_scores(&self) -> SourceZScores {
+        self.z_scores.clone()
+    }
+    
+    /// Standard deviations `price` lies from the mean of the asset's price history, negative below it.
+    /// `None` until the asset has enough history, or when the history doesn't vary at all
+    pub fn compute_z_score(&self, asset: &str, price: f64) -> Option<f64> {
+        z_score(price, self.price_history.get(asset)?)
+    }
+    
+    /// Whether a price moved more than the flash crash threshold away from the last one in `history`
+    pub fn detect_flash_crash(&self, new_price: f64, history: &[f64]) -> bool {
+        match history.last() {
+            Some(&last) if last > 0.0 => (last - new_price).abs() / last > self.flash_crash_threshold,
+            _ => false,
         }
     }
     
     pub fn validate_prices(&mut self, price_data: &[PriceData]) -> Result<Vec<PriceData>> {
         let mut validated_prices = Vec::new();
+        let median = Self::cycle_median(price_data);
         
         for data in price_data {
-            match self.validate_single_price(data) {
+            let validation = self.validate_single_price(data);
+            
+            // Penalties from this cycle's flag only apply from the next cycle on
+            let flagged = median.is_some_and(|m| (data.price - m).abs() / m > OUTLIER_DEVIATION);
+            self.record_outlier_flag(&data.source, flagged);
+            
+            match validation {
                 Ok(validation) => {
+                    if let Some(z_score) = validation.z_score {
+                        self.z_scores.entry(data.source.clone()).or_default().insert(data.asset.clone(), z_score);
+                    }
                     if validation.is_valid {
                         let mut validated_data = data.clone();
                         
@@ -61,12 +144,23 @@ impl PriceValidator {
     
     fn validate_single_price(&self, price_data: &PriceData) -> Result<ValidationResult> {
         // Basic price validation
+        if !price_data.price.is_finite() {
+            return Ok(ValidationResult {
+                is_valid: false,
+                reason: Some("Price is not a finite number".to_string()),
+                adjusted_price: None,
+                confidence_adjustment: 0.0,
+                z_score: None,
+            });
+        }
+        
         if price_data.price <= 0.0 {
             return Ok(ValidationResult {
                 is_valid: false,
                 reason: Some("Price must be positive".to_string()),
                 adjusted_price: None,
                 confidence_adjustment: 0.0,
+                z_score: None,
             });
         }
         
@@ -76,12 +170,27 @@ impl PriceValidator {
                 reason: Some("Price too high (possible error)".to_string()),
                 adjusted_price: None,
                 confidence_adjustment: 0.0,
+                z_score: None,
             });
         }
         
+        // Sources that keep disagreeing with their peers are trusted less
+        let outlier_adjustment = self.outlier_adjustment(&price_data.source);
+        
         // Check against historical data if available
         if let Some(history) = self.price_history.get(&price_data.asset) {
-            if let Some(validation) = self.validate_against_history(price_data, history) {
+            // Kept, since the move may be real, but trusted much less
+            if self.detect_flash_crash(price_data.price, history) {
+                return Ok(ValidationResult {
+                    is_valid: true,
+                    reason: Some("Potential flash crash".to_string()),
+                    adjusted_price: None,
+                    confidence_adjustment: FLASH_CRASH_CONFIDENCE * outlier_adjustment,
+                    z_score: z_score(price_data.price, history),
+                });
+            }
+            if let Some(mut validation) = self.validate_against_history(price_data, history) {
+                validation.confidence_adjustment *= outlier_adjustment;
                 return Ok(validation);
             }
         }
@@ -93,6 +202,7 @@ impl PriceValidator {
                 reason: Some("Confidence too low".to_string()),
                 adjusted_price: None,
                 confidence_adjustment: 0.0,
+                z_score: None,
             });
         }
         
@@ -101,21 +211,50 @@ impl PriceValidator {
             is_valid: true,
             reason: None,
             adjusted_price: None,
-            confidence_adjustment: 1.0,
+            confidence_adjustment: outlier_adjustment,
+            z_score: self.compute_z_score(&price_data.asset, price_data.price),
+        })
+    }
+    
+    /// Confidence multiplier for a source, shrinking with its recent outlier flags
+    fn outlier_adjustment(&self, source: &str) -> f64 {
+        let score = self.outlier_scores.get(source).copied().unwrap_or(0.0);
+        OUTLIER_PENALTY.powf(score)
+    }
+    
+    /// Decay the source's outlier score and add this cycle's flag
+    fn record_outlier_flag(&mut self, source: &str, flagged: bool) {
+        let score = self.outlier_scores.entry(source.to_string()).or_insert(0.0);
+        *score = *score * OUTLIER_DECAY + if flagged { 1.0 } else { 0.0 };
+    }
+    
+    /// Median of the cycle's positive prices; needs three sources to tell who disagrees
+    fn cycle_median(price_data: &[PriceData]) -> Option<f64> {
+        let mut prices: Vec<f64> = price_data.iter()
+            .map(|d| d.price)
+            .filter(|p| *p > 0.0)
+            .collect();
+        if prices.len() < 3 {
+            return None;
+        }
+        
+        prices.sort_by(f64::total_cmp);
+        let mid = prices.len() / 2;
+        Some(if prices.len().is_multiple_of(2) {
+            (prices[mid - 1] + prices[mid]) / 2.0
+        } else {
+            prices[mid]
         })
     }
     
     fn validate_against_history(&self, price_data: &PriceData, history: &[f64]) -> Option<ValidationResult> {
-        if history.len() < 3 {
+        if history.len() < MIN_HISTORY_FOR_STATS {
             return None; // Not enough history
         }
         
         // Calculate historical statistics
-        let mean = history.iter().sum::<f64>() / history.len() as f64;
-        let variance = history.iter()
-            .map(|x| (x - mean).powi(2))
-            .sum::<f64>() / history.len() as f64;
-        let std_dev = variance.sqrt();
+        let (mean, std_dev) = mean_std_dev(history);
+        let z_score = z_score(price_data.price, history);
         
         // Check for extreme price movements (> 3 standard deviations)
         let price_diff = (price_data.price - mean).abs();
@@ -128,6 +267,7 @@ impl PriceValidator {
                                     (price_diff / mean) * 100.0)),
                 adjusted_price: None,
                 confidence_adjustment: 0.7, // Reduce confidence for extreme movements
+                z_score,
             });
         }
         
@@ -138,6 +278,7 @@ impl PriceValidator {
                 reason: Some("Suspiciously small price movement".to_string()),
                 adjusted_price: None,
                 confidence_adjustment: 0.8,
+                z_score,
             });
         }
         
@@ -180,6 +321,41 @@ impl PriceValidator {
             }
         })
     }
+    
+    /// Percentage change of the latest stored price from the one before it (3.2 for +3.2%)
+    pub fn price_change_pct(&self, asset: &str) -> Option<f64> {
+        self.price_change_pct_over(asset, 1)
+    }
+    
+    /// Percentage change of the latest stored price from the one `readings` entries earlier;
+    /// `None` when the history is that short or the earlier price is zero
+    pub fn price_change_pct_over(&self, asset: &str, readings: usize) -> Option<f64> {
+        let history = self.price_history.get(asset)?;
+        if readings == 0 || history.len() <= readings {
+            return None;
+        }
+        let latest = history[history.len() - 1];
+        let earlier = history[history.len() - 1 - readings];
+        (earlier != 0.0).then(|| (latest - earlier) / earlier * 100.0)
+    }
+}
+
+/// Mean and population standard deviation of a price history
+fn mean_std_dev(history: &[f64]) -> (f64, f64) {
+    let mean = history.iter().sum::<f64>() / history.len() as f64;
+    let variance = history.iter()
+        .map(|x| (x - mean).powi(2))
+        .sum::<f64>() / history.len() as f64;
+    (mean, variance.sqrt())
+}
+
+/// Z-score of `price` against `history`, see [`PriceValidator::compute_z_score`]
+fn z_score(price: f64, history: &[f64]) -> Option<f64> {
+    if history.len() < MIN_HISTORY_FOR_STATS {
+        return None;
+    }
+    let (mean, std_dev) = mean_std_dev(history);
+    (std_dev > 0.0).then(|| (price - mean) / std_dev)
 }
 
 #[derive(Debug, Clone)]
@@ -222,6 +398,45 @@ mod tests {
         assert_eq!(result[0].price, 45000.0);
     }
     
+    #[test]
+    fn test_load_from_store_seeds_history() {
+        let store = PriceStore::in_memory().unwrap();
+        for i in 0..150 {
+            let result = crate::models::ConsensusResult::new("BTC".to_string(), 45000.0 + i as f64, vec!["Test".to_string()]);
+            store.record(&result).unwrap();
+        }
+        
+        let validator = PriceValidator::load_from_store(&store);
+        let stats = validator.get_price_statistics("BTC").unwrap();
+        assert_eq!(stats.count, 100);
+        assert_eq!((stats.min, stats.max), (45050.0, 45149.0));
+        assert!(validator.get_price_statistics("ETH").is_none());
+        
+        // A restored history already catches a flash crash on the first update
+        assert!(validator.detect_flash_crash(30000.0, &validator.price_history["BTC"]));
+    }
+    
+    #[test]
+    fn test_price_change_pct() {
+        let mut validator = PriceValidator::new();
+        assert_eq!(validator.price_change_pct("BTC"), None);
+        
+        validator.price_history.insert("BTC".to_string(), vec![100.0]);
+        assert_eq!(validator.price_change_pct("BTC"), None);
+        
+        validator.price_history.insert("BTC".to_string(), vec![100.0, 110.0, 99.0, 102.168]);
+        assert!((validator.price_change_pct("BTC").unwrap() - 3.2).abs() < 1e-9);
+        assert!((validator.price_change_pct_over("BTC", 2).unwrap() + 7.12).abs() < 1e-9);
+        assert!((validator.price_change_pct_over("BTC", 3).unwrap() - 2.168).abs() < 1e-9);
+        assert_eq!(validator.price_change_pct_over("BTC", 4), None);
+        assert_eq!(validator.price_change_pct_over("BTC", 0), None);
+        assert_eq!(validator.price_change_pct("ETH"), None);
+        
+        // No percentage from a zero price
+        validator.price_history.insert("BTC".to_string(), vec![0.0, 5.0]);
+        assert_eq!(validator.price_change_pct("BTC"), None);
+    }
+    
     #[test]
     fn test_validate_negative_price() {
         let mut validator = PriceValidator::new();
@@ -245,4 +460,82 @@ mod tests {
         let result = validator.validate_prices(&price_data);
         assert!(result.is_err());
     }
+    
+    #[test]
+    fn test_repeat_outlier_loses_confidence() {
+        let mut validator = PriceValidator::new();
+        let cycle = |deviant_price: f64| vec![
+            PriceData::new("BTC".to_string(), 45000.0, "Steady1".to_string()).with_confidence(0.9),
+            PriceData::new("BTC".to_string(), 45000.0, "Steady2".to_string()).with_confidence(0.9),
+            PriceData::new("BTC".to_string(), deviant_price, "Deviant".to_string()).with_confidence(0.9),
+        ];
+        let deviant_confidence = |validated: &[PriceData]| {
+            validated.iter().find(|d| d.source == "Deviant").unwrap().confidence
+        };
+        
+        // The first disagreement only counts against later cycles
+        let mut previous = deviant_confidence(&validator.validate_prices(&cycle(47250.0)).unwrap());
+        assert!((previous - 0.9).abs() < 1e-9);
+        
+        for _ in 0..3 {
+            let validated = validator.validate_prices(&cycle(47250.0)).unwrap();
+            let confidence = deviant_confidence(&validated);
+            assert!(confidence < previous);
+            previous = confidence;
+            
+            let steady = validated.iter().find(|d| d.source == "Steady1").unwrap();
+            assert!((steady.confidence - 0.9).abs() < 1e-9);
+        }
+        
+        // Agreeing again earns the confidence back
+        for _ in 0..2 {
+            validator.validate_prices(&cycle(45000.0)).unwrap();
+        }
+        let recovered = deviant_confidence(&validator.validate_prices(&cycle(45000.0)).unwrap());
+        assert!(recovered > previous);
+    }
+    
+    #[test]
+    fn test_z_score() {
+        let mut validator = PriceValidator::new();
+        assert_eq!(validator.compute_z_score("BTC", 45000.0), None);
+        
+        // Mean 100, standard deviation 2
+        validator.price_history.insert("BTC".to_string(), vec![98.0, 102.0, 98.0, 102.0]);
+        assert!((validator.compute_z_score("BTC", 105.0).unwrap() - 2.5).abs() < 1e-9);
+        assert!((validator.compute_z_score("BTC", 97.0).unwrap() + 1.5).abs() < 1e-9);
+        
+        // A flat history has no spread to measure against
+        validator.price_history.insert("ETH".to_string(), vec![3000.0; 5]);
+        assert_eq!(validator.compute_z_score("ETH", 3100.0), None);
+        
+        // Validation carries it and remembers the latest one per source
+        let validated = validator.validate_prices(&[PriceData::new("BTC".to_string(), 101.0, "Test".to_string())]).unwrap();
+        assert_eq!(validated.len(), 1);
+        assert!((validator.z_scores().get("Test").unwrap()["BTC"] - 0.5).abs() < 1e-9);
+        let validation = validator.validate_single_price(&PriceData::new("SOL".to_string(), 150.0, "Test".to_string())).unwrap();
+        assert_eq!(validation.z_score, None);
+    }
+    
+    #[test]
+    fn test_flash_crash_reduces_confidence() {
+        let mut validator = PriceValidator::new();
+        let cycle = |price: f64| vec![PriceData::new("SOL".to_string(), price, "Test".to_string()).with_confidence(0.9)];
+        for _ in 0..5 {
+            validator.validate_prices(&cycle(100.0)).unwrap();
+        }
+        
+        // A 25% drop is kept, at reduced confidence
+        let validated = validator.validate_prices(&cycle(75.0)).unwrap();
+        assert_eq!(validated[0].price, 75.0);
+        assert!((validated[0].confidence - 0.9 * 0.3).abs() < 1e-9);
+        
+        let history = [100.0, 100.0, 100.0];
+        assert!(validator.detect_flash_crash(75.0, &history));
+        assert!(!validator.detect_flash_crash(85.0, &history));
+        assert!(!validator.detect_flash_crash(75.0, &[]));
+        
+        let lenient = PriceValidator::new().with_flash_crash_threshold(0.3);
+        assert!(!lenient.detect_flash_crash(75.0, &history));
+    }
 }
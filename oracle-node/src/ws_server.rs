@@ -0,0 +1,165 @@
+// WebSocket push of consensus prices to subscribed clients
+use chrono::{DateTime, Utc};
+use futures::{SinkExt, StreamExt};
+use log::{debug, info, warn};
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+use std::net::SocketAddr;
+use tokio::net::{TcpListener, TcpStream};
+use tokio::sync::broadcast::{self, error::RecvError};
+use tokio_tungstenite::tungstenite::Message;
+
+use crate::models::ConsensusResult;
+
+/// Port the WebSocket server listens on unless configured otherwise
+pub const DEFAULT_WS_PORT: u16 = 9000;
+
+/// Updates a client may fall behind by before the oldest ones are dropped
+pub const CLIENT_BACKLOG: usize = 100;
+
+/// Message a client sends to receive updates for some assets
+#[derive(Debug, Deserialize)]
+struct Subscription {
+    subscribe: Vec<String>,
+}
+
+/// What subscribers receive for each consensus result
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PriceUpdate {
+    pub asset: String,
+    pub price: f64,
+    pub confidence: f64,
+    pub timestamp: DateTime<Utc>,
+    /// Set when the last good price was resubmitted because every source failed
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub stale: bool,
+}
+
+impl From<&ConsensusResult> for PriceUpdate {
+    fn from(result: &ConsensusResult) -> Self {
+        Self {
+            asset: result.asset.clone(),
+            price: result.price,
+            confidence: result.confidence,
+            timestamp: result.timestamp,
+            stale: result.stale,
+        }
+    }
+}
+
+/// Pushes every consensus result sent on its channel to the clients subscribed to the asset
+pub struct WebSocketServer {
+    listener: TcpListener,
+    updates: broadcast::Sender<ConsensusResult>,
+}
+
+impl WebSocketServer {
+    /// Channel to publish consensus results on, holding `CLIENT_BACKLOG` per client
+    pub fn channel() -> broadcast::Sender<ConsensusResult> {
+        broadcast::channel(CLIENT_BACKLOG).0
+    }
+
+    pub async fn bind(address: &str, updates: broadcast::Sender<ConsensusResult>) -> std::io::Result<Self> {
+        let listener = TcpListener::bind(address).await?;
+        info!("WebSocket server listening on ws://{}", listener.local_addr()?);
+        Ok(Self { listener, updates })
+    }
+
+    pub fn local_addr(&self) -> std::io::Result<SocketAddr> {
+        self.listener.local_addr()
+    }
+
+    /// Accept clients until the listener fails, serving each on its own task
+    pub async fn run(self) {
+        loop {
+            match self.listener.accept().await {
+                Ok((stream, peer)) => {
+                    let updates = self.updates.subscribe();
+                    tokio::spawn(async move {
+                        if let Err(e) = serve_client(stream, updates).await {
+                            debug!("WebSocket client {} dropped: {}", peer, e);
+                        }
+                    });
+                },
+                Err(e) => warn!("Failed to accept WebSocket connection: {}", e),
+            }
+        }
+    }
+}
+
+async fn serve_client(stream: TcpStream, mut updates: broadcast::Receiver<ConsensusResult>) -> anyhow::Result<()> {
+    let socket = tokio_tungstenite::accept_async(stream).await?;
+    let (mut sink, mut incoming) = socket.split();
+    // Asset symbols, uppercased
+    let mut subscribed: HashSet<String> = HashSet::new();
+
+    loop {
+        tokio::select! {
+            message = incoming.next() => match message {
+                Some(Ok(Message::Text(text))) => match serde_json::from_str::<Subscription>(&text) {
+                    Ok(subscription) => {
+                        subscribed.extend(subscription.subscribe.iter().map(|asset| asset.to_uppercase()));
+                    },
+                    Err(e) => {
+                        let error = serde_json::json!({ "error": format!("Invalid subscription: {}", e) });
+                        sink.send(Message::Text(error.to_string())).await?;
+                    },
+                },
+                Some(Ok(Message::Close(_))) | None => return Ok(()),
+                // Pings are answered by tungstenite itself
+                Some(Ok(_)) => {},
+                Some(Err(e)) => return Err(e.into()),
+            },
+            update = updates.recv() => match update {
+                Ok(result) if subscribed.contains(&result.asset.to_uppercase()) => {
+                    let update = serde_json::to_string(&PriceUpdate::from(&result))?;
+                    sink.send(Message::Text(update)).await?;
+                },
+                Ok(_) => {},
+                Err(RecvError::Lagged(skipped)) => {
+                    warn!("WebSocket client fell {} updates behind; dropped the oldest", skipped);
+                },
+                Err(RecvError::Closed) => return Ok(()),
+            },
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+    use tokio_tungstenite::connect_async;
+
+    #[tokio::test]
+    async fn test_subscribers_receive_their_assets() {
+        let updates = WebSocketServer::channel();
+        let server = WebSocketServer::bind("127.0.0.1:0", updates.clone()).await.unwrap();
+        let url = format!("ws://{}", server.local_addr().unwrap());
+        tokio::spawn(server.run());
+
+        let (mut client, _) = connect_async(&url).await.unwrap();
+        client.send(Message::Text(r#"{"subscribe": ["btc"]}"#.to_string())).await.unwrap();
+        client.send(Message::Text("not json".to_string())).await.unwrap();
+        let error = client.next().await.unwrap().unwrap().into_text().unwrap();
+        assert!(error.contains("Invalid subscription"));
+
+        // The error reply shows the subscription was processed before anything is published
+        let eth = ConsensusResult::new("ETH".to_string(), 2500.0, vec!["Binance".to_string()]);
+        let btc = ConsensusResult::new("BTC".to_string(), 45000.0, vec!["Binance".to_string()]).with_confidence(0.92);
+        updates.send(eth).unwrap();
+        updates.send(btc.clone()).unwrap();
+
+        let message = tokio::time::timeout(Duration::from_secs(5), client.next()).await.unwrap().unwrap().unwrap();
+        let update: PriceUpdate = serde_json::from_str(&message.into_text().unwrap()).unwrap();
+        assert_eq!(update, PriceUpdate::from(&btc));
+
+        // A disconnected client no longer counts as a receiver
+        client.close(None).await.unwrap();
+        tokio::time::timeout(Duration::from_secs(5), async {
+            while updates.receiver_count() > 0 {
+                tokio::time::sleep(Duration::from_millis(10)).await;
+            }
+        }).await.unwrap();
+    }
+}
@@ -0,0 +1,76 @@
+// Property tests for consensus and price validation
+use price_oracle_node::consensus::ConsensusEngine;
+use price_oracle_node::models::PriceData;
+use price_oracle_node::validator::PriceValidator;
+use proptest::prelude::*;
+
+/// Same rule as the engine: more than 2.5 standard deviations from the mean
+fn non_outlier_prices(prices: &[f64]) -> Vec<f64> {
+    let mean = prices.iter().sum::<f64>() / prices.len() as f64;
+    let std_dev = (prices.iter().map(|p| (p - mean).powi(2)).sum::<f64>() / prices.len() as f64).sqrt();
+    prices.iter()
+        .copied()
+        .filter(|p| std_dev == 0.0 || (p - mean).abs() / std_dev <= 2.5)
+        .collect()
+}
+
+fn price_data_strategy() -> impl Strategy<Value = Vec<PriceData>> {
+    prop::collection::vec((0.01f64..=1_000_000.0, 0.0f64..=1.0), 0..12).prop_map(|entries| {
+        entries.into_iter()
+            .enumerate()
+            .map(|(i, (price, confidence))| {
+                PriceData::new("BTC".to_string(), price, format!("Source{}", i)).with_confidence(confidence)
+            })
+            .collect()
+    })
+}
+
+/// Any `f64`, with the awkward values made likely
+fn any_price() -> impl Strategy<Value = f64> {
+    prop_oneof![
+        Just(f64::NAN),
+        Just(f64::INFINITY),
+        Just(f64::NEG_INFINITY),
+        Just(0.0),
+        Just(-0.0),
+        -1_000.0f64..0.0,
+        0.01f64..=2_000_000.0,
+        any::<f64>(),
+    ]
+}
+
+proptest! {
+    #[test]
+    fn consensus_price_stays_within_non_outlier_range(price_data in price_data_strategy()) {
+        let engine = ConsensusEngine::new();
+
+        if let Ok(result) = engine.run_consensus(&price_data) {
+            let prices: Vec<f64> = price_data.iter().map(|p| p.price).collect();
+            let included = non_outlier_prices(&prices);
+            let min = included.iter().copied().fold(f64::INFINITY, f64::min);
+            let max = included.iter().copied().fold(f64::NEG_INFINITY, f64::max);
+            let tolerance = max * 1e-9;
+
+            prop_assert!(result.price >= min - tolerance && result.price <= max + tolerance,
+                         "price {} outside [{}, {}]", result.price, min, max);
+            prop_assert!((0.0..=1.0).contains(&result.confidence));
+        }
+    }
+
+    #[test]
+    fn validation_never_passes_invalid_prices(prices in prop::collection::vec(any_price(), 1..8)) {
+        let mut validator = PriceValidator::new();
+        let price_data: Vec<PriceData> = prices.iter()
+            .enumerate()
+            .map(|(i, price)| PriceData::new("BTC".to_string(), *price, format!("Source{}", i)))
+            .collect();
+
+        if let Ok(validated) = validator.validate_prices(&price_data) {
+            for data in validated {
+                prop_assert!(data.price.is_finite() && data.price > 0.0 && data.price <= 1_000_000.0,
+                             "invalid price {} passed validation", data.price);
+                prop_assert!((0.0..=1.0).contains(&data.confidence));
+            }
+        }
+    }
+}
@@ -0,0 +1,219 @@
+// Decoding of on-chain `PricePayload` accounts for inspection
+use borsh::BorshDeserialize;
+use chrono::DateTime;
+use price_oracle_program::{PricePayload, PricePayloadV1, SentimentLabel, DEFAULT_ASSET_CATEGORY, NO_SENTIMENT_LABEL};
+use serde::Serialize;
+use std::error::Error;
+
+pub type AccountResult<T> = Result<T, Box<dyn Error>>;
+
+/// Human-readable view of a stored `PricePayload`
+#[derive(Serialize, Debug, Clone, PartialEq)]
+pub struct AccountView {
+    pub asset: String,
+    pub price: f64,
+    pub confidence: f64,
+    /// RFC 3339, UTC
+    pub timestamp: String,
+    pub sources: Vec<String>,
+    pub consensus_score: f64,
+    pub nonce: u64,
+    pub version: u8,
+    pub asset_category: String,
+    pub paused: bool,
+    /// Label of a sentiment submission, e.g. `VERY_POSITIVE`; absent for prices
+    pub sentiment_label: Option<String>,
+    /// Hex-encoded public key of the oracle that signed the payload
+    pub signer: String,
+}
+
+/// Decode an account's data, accepting older layouts (reported with a nonce of 0, the default
+/// category, unpaused and without a label, as after migration) and ignoring the zero padding
+/// past the payload
+pub fn decode_payload(data: &[u8]) -> AccountResult<AccountView> {
+    if data.first().is_none_or(|&initialized| initialized == 0) {
+        return Err("account is not initialized".into());
+    }
+
+    let mut rest = data;
+    let payload = match data.get(1) {
+        Some(1..=4) => {
+            let v1 = PricePayloadV1::deserialize(&mut rest)?;
+            let nonce = if v1.version >= 2 { u64::deserialize(&mut rest)? } else { 0 };
+            let asset_category = if v1.version >= 3 {
+                String::deserialize(&mut rest)?
+            } else {
+                DEFAULT_ASSET_CATEGORY.to_string()
+            };
+            let paused = if v1.version >= 4 { bool::deserialize(&mut rest)? } else { false };
+            PricePayload {
+                is_initialized: v1.is_initialized,
+                version: v1.version,
+                asset: v1.asset,
+                price: v1.price,
+                confidence: v1.confidence,
+                timestamp: v1.timestamp,
+                sources: v1.sources,
+                consensus_score: v1.consensus_score,
+                signature: v1.signature,
+                signer: v1.signer,
+                nonce,
+                asset_category,
+                paused,
+                sentiment_label: NO_SENTIMENT_LABEL,
+            }
+        },
+        _ => PricePayload::deserialize(&mut rest)?,
+    };
+
+    let timestamp = DateTime::from_timestamp(payload.timestamp, 0)
+        .ok_or_else(|| format!("timestamp {} is out of range", payload.timestamp))?
+        .to_rfc3339();
+
+    Ok(AccountView {
+        asset: payload.asset,
+        price: payload.price,
+        confidence: payload.confidence,
+        timestamp,
+        sources: payload.sources,
+        consensus_score: payload.consensus_score,
+        nonce: payload.nonce,
+        version: payload.version,
+        asset_category: payload.asset_category,
+        paused: payload.paused,
+        sentiment_label: SentimentLabel::from_u8(payload.sentiment_label).map(String::from),
+        signer: hex::encode(payload.signer),
+    })
+}
+
+/// Multi-line summary of a decoded account for `inspect`, with the timestamp as a readable date
+pub fn describe(view: &AccountView) -> String {
+    let date = DateTime::parse_from_rfc3339(&view.timestamp)
+        .map(|timestamp| timestamp.format("%Y-%m-%d %H:%M:%S UTC").to_string())
+        .unwrap_or_else(|_| view.timestamp.clone());
+    let mut lines = vec![
+        format!("Asset:       {} ({})", view.asset, view.asset_category),
+        format!("Price:       {}", view.price),
+        format!("Confidence:  {:.2}", view.confidence),
+        format!("Updated:     {}", date),
+        format!("Sources:     {}", view.sources.join(", ")),
+        format!("Signer:      {}", view.signer),
+        format!("Version:     {} (nonce {})", view.version, view.nonce),
+    ];
+    if let Some(label) = &view.sentiment_label {
+        lines.push(format!("Sentiment:   {}", label));
+    }
+    if view.paused {
+        lines.push("Status:      PAUSED".to_string());
+    }
+    lines.join("\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use borsh::BorshSerialize;
+
+    fn payload(version: u8) -> PricePayloadV1 {
+        PricePayloadV1 {
+            is_initialized: true,
+            version,
+            asset: "SOL".to_string(),
+            price: 101.5,
+            confidence: 0.92,
+            timestamp: 1_700_000_000,
+            sources: vec!["CoinGecko".to_string(), "Binance".to_string()],
+            consensus_score: 0.88,
+            signature: vec![7; 64],
+            signer: [1; 32],
+        }
+    }
+
+    #[test]
+    fn test_decode_padded_v2_account() {
+        let mut data = payload(2).try_to_vec().unwrap();
+        data.extend_from_slice(&42u64.to_le_bytes());
+        data.resize(data.len() + 256, 0);
+
+        let view = decode_payload(&data).unwrap();
+        assert_eq!(view.asset, "SOL");
+        assert_eq!(view.timestamp, "2023-11-14T22:13:20+00:00");
+        assert_eq!(view.sources, vec!["CoinGecko", "Binance"]);
+        assert_eq!(view.nonce, 42);
+        assert_eq!(view.version, 2);
+        assert_eq!(view.asset_category, DEFAULT_ASSET_CATEGORY);
+        assert_eq!(view.signer, "01".repeat(32));
+    }
+
+    #[test]
+    fn test_decode_v3_account() {
+        let mut data = payload(3).try_to_vec().unwrap();
+        data.extend_from_slice(&7u64.to_le_bytes());
+        data.extend_from_slice(&"L1".to_string().try_to_vec().unwrap());
+        data.resize(data.len() + 256, 0);
+
+        let view = decode_payload(&data).unwrap();
+        assert_eq!((view.nonce, view.version), (7, 3));
+        assert_eq!(view.asset_category, "L1");
+        assert!(!view.paused);
+    }
+
+    #[test]
+    fn test_decode_paused_v4_account() {
+        let mut data = payload(4).try_to_vec().unwrap();
+        data.extend_from_slice(&7u64.to_le_bytes());
+        data.extend_from_slice(&"L1".to_string().try_to_vec().unwrap());
+        data.push(1);
+        data.resize(data.len() + 256, 0);
+
+        let view = decode_payload(&data).unwrap();
+        assert_eq!((view.nonce, view.version), (7, 4));
+        assert!(view.paused);
+        assert_eq!(view.sentiment_label, None);
+    }
+
+    #[test]
+    fn test_decode_v5_sentiment_label() {
+        let mut data = payload(5).try_to_vec().unwrap();
+        data.extend_from_slice(&7u64.to_le_bytes());
+        data.extend_from_slice(&"L1".to_string().try_to_vec().unwrap());
+        data.extend_from_slice(&[0, SentimentLabel::VeryNegative as u8]);
+        data.resize(data.len() + 256, 0);
+
+        let view = decode_payload(&data).unwrap();
+        assert_eq!(view.version, 5);
+        assert_eq!(view.sentiment_label.as_deref(), Some("VERY_NEGATIVE"));
+    }
+
+    #[test]
+    fn test_decode_v1_account() {
+        let data = payload(1).try_to_vec().unwrap();
+
+        let view = decode_payload(&data).unwrap();
+        assert_eq!(view.version, 1);
+        assert_eq!(view.nonce, 0);
+        assert_eq!(view.price, 101.5);
+    }
+
+    #[test]
+    fn test_describe_account() {
+        let mut data = payload(4).try_to_vec().unwrap();
+        data.extend_from_slice(&7u64.to_le_bytes());
+        data.extend_from_slice(&"L1".to_string().try_to_vec().unwrap());
+        data.push(1);
+
+        let description = describe(&decode_payload(&data).unwrap());
+        assert!(description.contains("Asset:       SOL (L1)"));
+        assert!(description.contains("Updated:     2023-11-14 22:13:20 UTC"));
+        assert!(description.contains("Sources:     CoinGecko, Binance"));
+        assert!(description.contains(&format!("Signer:      {}", "01".repeat(32))));
+        assert!(description.contains("PAUSED"));
+        assert!(!description.contains("Sentiment"));
+    }
+
+    #[test]
+    fn test_uninitialized_account_rejected() {
+        let err = decode_payload(&[0; 128]).unwrap_err();
+        assert!(err.to_string().contains("not initialized"));
+    }
+}
@@ -0,0 +1,95 @@
+// Devnet/localnet airdrops for testing
+use solana_client::client_error::{ClientError, ClientErrorKind};
+use solana_client::rpc_client::RpcClient;
+use solana_sdk::{commitment_config::CommitmentConfig, pubkey::Pubkey, signature::Signature};
+use std::error::Error;
+use std::io;
+
+pub type AirdropResult<T> = Result<T, Box<dyn Error>>;
+
+/// Attempts made when the RPC node times out
+pub const MAX_ATTEMPTS: usize = 3;
+
+/// Refuse anything but devnet or a local validator, so a mistyped URL never hits mainnet
+pub fn ensure_test_cluster(rpc_url: &str) -> AirdropResult<()> {
+    let url = rpc_url.to_lowercase();
+    if url.contains("devnet") || url.contains("localhost") || url.contains("127.0.0.1") {
+        Ok(())
+    } else {
+        Err(format!("Airdrops are only available on devnet or localhost, not {}", rpc_url).into())
+    }
+}
+
+/// Solana Explorer link for a transaction on the cluster behind `rpc_url`
+pub fn explorer_url(signature: &Signature, rpc_url: &str) -> String {
+    let cluster = if rpc_url.contains("devnet") {
+        "cluster=devnet".to_string()
+    } else {
+        format!("cluster=custom&customUrl={}", rpc_url)
+    };
+    format!("https://explorer.solana.com/tx/{}?{}", signature, cluster)
+}
+
+/// Request an airdrop and wait for it to confirm, retrying when the RPC node times out
+pub fn request_airdrop(rpc_client: &RpcClient, pubkey: &Pubkey, lamports: u64) -> AirdropResult<Signature> {
+    let mut attempt = 1;
+    loop {
+        let result = match rpc_client.request_airdrop(pubkey, lamports) {
+            Ok(signature) => rpc_client
+                .poll_for_signature_with_commitment(&signature, CommitmentConfig::confirmed())
+                .map(|_| signature),
+            Err(e) => Err(e),
+        };
+        
+        match result {
+            Ok(signature) => return Ok(signature),
+            Err(e) if attempt < MAX_ATTEMPTS && is_timeout(&e) => {
+                eprintln!("Airdrop attempt {}/{} timed out, retrying", attempt, MAX_ATTEMPTS);
+                attempt += 1;
+            },
+            Err(e) => return Err(e.into()),
+        }
+    }
+}
+
+fn is_timeout(err: &ClientError) -> bool {
+    match err.kind() {
+        ClientErrorKind::Reqwest(e) => e.is_timeout(),
+        ClientErrorKind::Io(e) => e.kind() == io::ErrorKind::TimedOut,
+        _ => false,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_only_test_clusters_allowed() {
+        assert!(ensure_test_cluster("https://api.devnet.solana.com").is_ok());
+        assert!(ensure_test_cluster("http://localhost:8899").is_ok());
+        assert!(ensure_test_cluster("http://127.0.0.1:8899").is_ok());
+
+        let err = ensure_test_cluster("https://api.mainnet-beta.solana.com").unwrap_err();
+        assert!(err.to_string().contains("only available on devnet or localhost"));
+    }
+
+    #[test]
+    fn test_explorer_url_names_cluster() {
+        let signature = Signature::default();
+        assert_eq!(
+            explorer_url(&signature, "https://api.devnet.solana.com"),
+            format!("https://explorer.solana.com/tx/{}?cluster=devnet", signature),
+        );
+        assert!(explorer_url(&signature, "http://localhost:8899")
+            .ends_with("?cluster=custom&customUrl=http://localhost:8899"));
+    }
+
+    #[test]
+    fn test_timeouts_are_retried() {
+        let timeout = ClientError::from(io::Error::new(io::ErrorKind::TimedOut, "timed out"));
+        let refused = ClientError::from(io::Error::new(io::ErrorKind::ConnectionRefused, "refused"));
+        assert!(is_timeout(&timeout));
+        assert!(!is_timeout(&refused));
+    }
+}
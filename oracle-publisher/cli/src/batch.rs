@@ -0,0 +1,100 @@
+// Bulk submission of a directory of signed files
+use solana_sdk::{instruction::Instruction, message::Message, packet::PACKET_DATA_SIZE, pubkey::Pubkey};
+use std::fs;
+use std::io;
+use std::ops::Range;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+/// Blockhashes are valid for roughly a minute, so fetch a new one well before that
+pub const BLOCKHASH_REFRESH: Duration = Duration::from_secs(30);
+
+/// The `*.json` files directly inside `dir`, sorted by name
+pub fn json_files(dir: &Path) -> io::Result<Vec<PathBuf>> {
+    let mut files = Vec::new();
+    for entry in fs::read_dir(dir)? {
+        let path = entry?.path();
+        if path.is_file() && path.extension() == Some("json".as_ref()) {
+            files.push(path);
+        }
+    }
+    files.sort();
+    Ok(files)
+}
+
+/// Serialized size of a transaction carrying `instructions`, paid for by `payer`
+pub fn transaction_size(instructions: &[Instruction], payer: &Pubkey) -> usize {
+    let message = Message::new(instructions, Some(payer));
+    let signatures = message.header.num_required_signatures as usize;
+    // One byte for the (short) signature count, the signatures, then the message
+    1 + signatures * 64 + message.serialize().len()
+}
+
+/// Group consecutive submissions, each one or more instructions kept together, into as few
+/// transactions as fit in a packet each. A submission too large on its own still gets a
+/// transaction, and fails when sent.
+pub fn pack(submissions: &[Vec<Instruction>], payer: &Pubkey) -> Vec<Range<usize>> {
+    let mut groups = Vec::new();
+    let mut start = 0;
+    for end in 1..=submissions.len() {
+        if end - start > 1 && transaction_size(&submissions[start..end].concat(), payer) > PACKET_DATA_SIZE {
+            groups.push(start..end - 1);
+            start = end - 1;
+        }
+    }
+    if start < submissions.len() {
+        groups.push(start..submissions.len());
+    }
+    groups
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use solana_sdk::instruction::AccountMeta;
+
+    fn instruction(program_id: Pubkey, account: Pubkey, signer: Pubkey, len: usize) -> Instruction {
+        Instruction {
+            program_id,
+            accounts: vec![AccountMeta::new(account, false), AccountMeta::new_readonly(signer, true)],
+            data: vec![7; len],
+        }
+    }
+
+    #[test]
+    fn test_json_files_sorted() {
+        let dir = tempfile::tempdir().unwrap();
+        for name in ["b.json", "a.json", "notes.txt"] {
+            fs::write(dir.path().join(name), "{}").unwrap();
+        }
+        fs::create_dir(dir.path().join("nested.json")).unwrap();
+
+        let names: Vec<String> = json_files(dir.path()).unwrap().iter()
+            .map(|path| path.file_name().unwrap().to_string_lossy().into_owned())
+            .collect();
+        assert_eq!(names, vec!["a.json", "b.json"]);
+    }
+
+    #[test]
+    fn test_pack_respects_packet_size() {
+        let (program_id, account, payer) = (Pubkey::new_unique(), Pubkey::new_unique(), Pubkey::new_unique());
+        let small: Vec<Vec<Instruction>> = (0..10).map(|_| vec![instruction(program_id, account, payer, 300)]).collect();
+
+        let groups = pack(&small, &payer);
+        assert!(groups.len() > 1 && groups.len() < small.len());
+        assert_eq!(groups.first().unwrap().start, 0);
+        assert_eq!(groups.last().unwrap().end, small.len());
+        for group in &groups {
+            assert!(transaction_size(&small[group.clone()].concat(), &payer) <= PACKET_DATA_SIZE);
+        }
+
+        // A submission's instructions are never split across transactions
+        let pairs: Vec<Vec<Instruction>> = (0..4).map(|_| vec![instruction(program_id, account, payer, 250); 2]).collect();
+        assert_eq!(pack(&pairs, &payer), vec![0..2, 2..4]);
+
+        // An oversized submission is sent on its own
+        let oversized = vec![vec![instruction(program_id, account, payer, 2_000)], vec![instruction(program_id, account, payer, 10)]];
+        assert_eq!(pack(&oversized, &payer), vec![0..1, 1..2]);
+        assert!(pack(&[], &payer).is_empty());
+    }
+}
@@ -0,0 +1,240 @@
+// Export of the oracle node's price history to CSV or JSON
+use chrono::{DateTime, NaiveDate, Utc};
+use clap::ValueEnum;
+use flate2::write::GzEncoder;
+use flate2::Compression;
+use rusqlite::{params, Connection, OpenFlags};
+use serde::{Deserialize, Serialize};
+use std::error::Error;
+use std::fs::File;
+use std::io::{BufWriter, Write};
+use std::path::Path;
+
+pub type ExportResult<T> = Result<T, Box<dyn Error>>;
+
+/// Output format for the export subcommand
+#[derive(ValueEnum, Clone, Copy, Debug, PartialEq)]
+pub enum ExportFormat {
+    Csv,
+    Json,
+}
+
+/// One exported row; field order matches the CSV header
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct ExportRecord {
+    pub timestamp: String,
+    pub asset: String,
+    pub price: f64,
+    pub confidence: f64,
+    pub source: String,
+    pub consensus_score: f64,
+}
+
+/// Read an asset's history from the `price_history` table written by the node's `PriceStore`
+pub fn load_records(
+    db_path: &Path,
+    asset: &str,
+    from: Option<&str>,
+    to: Option<&str>,
+) -> ExportResult<Vec<ExportRecord>> {
+    let from = from.map(|s| parse_bound(s, false)).transpose()?;
+    let to = to.map(|s| parse_bound(s, true)).transpose()?;
+
+    let conn = Connection::open_with_flags(db_path, OpenFlags::SQLITE_OPEN_READ_ONLY)?;
+    let mut stmt = conn.prepare(
+        "SELECT timestamp, asset, price, confidence, sources, consensus_score
+         FROM price_history
+         WHERE asset = ?1 AND timestamp >= ?2 AND timestamp <= ?3
+         ORDER BY timestamp, id",
+    )?;
+
+    let rows = stmt.query_map(
+        params![
+            asset.to_uppercase(),
+            from.map_or(i64::MIN, |t| t.timestamp()),
+            to.map_or(i64::MAX, |t| t.timestamp()),
+        ],
+        |row| {
+            let timestamp: i64 = row.get(0)?;
+            Ok(ExportRecord {
+                timestamp: DateTime::from_timestamp(timestamp, 0)
+                    .unwrap_or_default()
+                    .to_rfc3339(),
+                asset: row.get(1)?,
+                price: row.get(2)?,
+                confidence: row.get(3)?,
+                source: row.get(4)?,
+                consensus_score: row.get(5)?,
+            })
+        },
+    )?;
+
+    Ok(rows.collect::<rusqlite::Result<Vec<_>>>()?)
+}
+
+/// Parse an RFC 3339 timestamp or a plain `YYYY-MM-DD` date.
+/// Dates cover the whole day, so `end_of_day` picks its last second.
+fn parse_bound(value: &str, end_of_day: bool) -> ExportResult<DateTime<Utc>> {
+    if let Ok(timestamp) = DateTime::parse_from_rfc3339(value) {
+        return Ok(timestamp.with_timezone(&Utc));
+    }
+
+    let date = NaiveDate::parse_from_str(value, "%Y-%m-%d")
+        .map_err(|_| format!("Invalid date '{}': expected YYYY-MM-DD or RFC 3339", value))?;
+    let time = if end_of_day {
+        date.and_hms_opt(23, 59, 59)
+    } else {
+        date.and_hms_opt(0, 0, 0)
+    };
+
+    Ok(time.ok_or("Invalid time of day")?.and_utc())
+}
+
+/// Write the records to `output`, gzip-compressed if requested
+pub fn write_records(
+    records: &[ExportRecord],
+    format: ExportFormat,
+    output: &Path,
+    compress: bool,
+) -> ExportResult<()> {
+    let file = BufWriter::new(File::create(output)?);
+
+    if compress {
+        let mut encoder = GzEncoder::new(file, Compression::default());
+        write_formatted(records, format, &mut encoder)?;
+        encoder.finish()?.flush()?;
+    } else {
+        let mut file = file;
+        write_formatted(records, format, &mut file)?;
+        file.flush()?;
+    }
+
+    Ok(())
+}
+
+fn write_formatted<W: Write>(records: &[ExportRecord], format: ExportFormat, writer: W) -> ExportResult<()> {
+    match format {
+        ExportFormat::Csv => {
+            // RFC 4180: CRLF line endings, quoting only where needed
+            let mut csv_writer = csv::WriterBuilder::new()
+                .terminator(csv::Terminator::CRLF)
+                .from_writer(writer);
+            for record in records {
+                csv_writer.serialize(record)?;
+            }
+            // Emit the header even when there is nothing to export
+            if records.is_empty() {
+                csv_writer.write_record(["timestamp", "asset", "price", "confidence", "source", "consensus_score"])?;
+            }
+            csv_writer.flush()?;
+        }
+        ExportFormat::Json => {
+            serde_json::to_writer_pretty(writer, records)?;
+        }
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use flate2::read::GzDecoder;
+    use std::io::Read;
+
+    fn sample_db(dir: &Path) -> std::path::PathBuf {
+        let db_path = dir.join("price_history.db");
+        let conn = Connection::open(&db_path).unwrap();
+        conn.execute_batch(
+            "CREATE TABLE price_history (
+                id INTEGER PRIMARY KEY AUTOINCREMENT,
+                timestamp INTEGER NOT NULL,
+                asset TEXT NOT NULL,
+                price REAL NOT NULL,
+                confidence REAL NOT NULL,
+                sources TEXT NOT NULL,
+                consensus_score REAL NOT NULL
+            );",
+        )
+        .unwrap();
+
+        // 2025-05-14 12:00, 2025-05-15 12:00 and 2025-05-16 12:00 UTC
+        for (timestamp, price) in [(1747224000, 45000.0), (1747310400, 45500.5), (1747396800, 46000.0)] {
+            conn.execute(
+                "INSERT INTO price_history (timestamp, asset, price, confidence, sources, consensus_score)
+                 VALUES (?1, 'BTC', ?2, 0.9, 'CoinGecko,Binance', 0.85)",
+                params![timestamp, price],
+            )
+            .unwrap();
+        }
+        conn.execute(
+            "INSERT INTO price_history (timestamp, asset, price, confidence, sources, consensus_score)
+             VALUES (1747310400, 'ETH', 2500.0, 0.8, 'Binance', 0.8)",
+            [],
+        )
+        .unwrap();
+
+        db_path
+    }
+
+    #[test]
+    fn test_csv_export_round_trip() {
+        let dir = tempfile::tempdir().unwrap();
+        let db_path = sample_db(dir.path());
+        let output = dir.path().join("btc.csv");
+
+        let records = load_records(&db_path, "btc", None, None).unwrap();
+        assert_eq!(records.len(), 3);
+        write_records(&records, ExportFormat::Csv, &output, false).unwrap();
+
+        let contents = std::fs::read_to_string(&output).unwrap();
+        assert!(contents.starts_with("timestamp,asset,price,confidence,source,consensus_score\r\n"));
+        // The comma-separated source list must be quoted
+        assert!(contents.contains("\"CoinGecko,Binance\""));
+
+        let mut reader = csv::Reader::from_path(&output).unwrap();
+        let parsed: Vec<ExportRecord> = reader.deserialize().map(|r| r.unwrap()).collect();
+        assert_eq!(parsed, records);
+    }
+
+    #[test]
+    fn test_json_export_round_trip_with_date_range() {
+        let dir = tempfile::tempdir().unwrap();
+        let db_path = sample_db(dir.path());
+        let output = dir.path().join("btc.json");
+
+        let records = load_records(&db_path, "BTC", Some("2025-05-15"), Some("2025-05-15")).unwrap();
+        assert_eq!(records.len(), 1);
+        assert_eq!(records[0].price, 45500.5);
+        write_records(&records, ExportFormat::Json, &output, false).unwrap();
+
+        let parsed: Vec<ExportRecord> = serde_json::from_str(&std::fs::read_to_string(&output).unwrap()).unwrap();
+        assert_eq!(parsed, records);
+    }
+
+    #[test]
+    fn test_compressed_export() {
+        let dir = tempfile::tempdir().unwrap();
+        let db_path = sample_db(dir.path());
+        let output = dir.path().join("btc.csv.gz");
+
+        let records = load_records(&db_path, "BTC", None, None).unwrap();
+        write_records(&records, ExportFormat::Csv, &output, true).unwrap();
+
+        let mut decompressed = String::new();
+        GzDecoder::new(File::open(&output).unwrap())
+            .read_to_string(&mut decompressed)
+            .unwrap();
+        let parsed: Vec<ExportRecord> = csv::Reader::from_reader(decompressed.as_bytes())
+            .deserialize()
+            .map(|r| r.unwrap())
+            .collect();
+        assert_eq!(parsed, records);
+    }
+
+    #[test]
+    fn test_invalid_date_rejected() {
+        assert!(parse_bound("15/05/2025", false).is_err());
+        assert!(parse_bound("2025-05-15T00:00:00Z", false).is_ok());
+    }
+}
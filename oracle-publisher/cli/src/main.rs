@@ -3,26 +3,46 @@ use clap::{Parser, Subcommand};
 use solana_client::rpc_client::RpcClient;
 use solana_sdk::{
     commitment_config::CommitmentConfig,
-    signature::{read_keypair_file, Keypair, Signer},
+    native_token::{lamports_to_sol, sol_to_lamports},
+    signature::{read_keypair_file, write_keypair_file, Keypair, Signer},
     pubkey::Pubkey,
     system_instruction::create_account,
+    sysvar,
     transaction::Transaction,
     instruction::{AccountMeta, Instruction},
 };
-use solana_cli_config::Config;
+use solana_cli_config::{Config, CONFIG_FILE};
 use std::fs::File;
 use std::io::Read;
+use std::path::PathBuf;
 use std::str::FromStr;
+use std::time::{Duration, Instant};
 use serde::{Serialize, Deserialize};
-use sha2::{Sha256, Digest};
-use ed25519_dalek::{Keypair as DalekKeypair, Signer as DalekSigner};
-use rand::rngs::OsRng;
-use borsh::BorshSerialize;
+use ed25519_dalek::{PublicKey, Signature, Verifier};
+use price_oracle_node::asset_registry::AssetRegistry;
 use price_oracle_program::{
     PriceOracleInstruction,
+    allowlist_address,
     get_account_size,
+    initialize_account_instruction,
+    new_ed25519_instruction,
+    set_oracle_authorized_instruction,
+    set_paused_instruction,
+    signed_price_message,
+    update_authority_instruction,
+    DEFAULT_ASSET_CATEGORY,
+    NO_SENTIMENT_LABEL,
 };
 
+mod account;
+mod airdrop;
+mod batch;
+mod export;
+mod self_test;
+mod watch;
+
+use export::ExportFormat;
+
 // Define the price payload structure
 #[derive(Serialize, Deserialize, Debug, Clone)]
 struct PriceData {
@@ -32,12 +52,32 @@ struct PriceData {
     timestamp: i64,
     sources: Vec<String>,
     consensus_score: f64,
+    /// One of the program's `ASSET_CATEGORIES`
+    #[serde(default = "default_asset_category")]
+    asset_category: String,
+    /// Price came from a single source rather than a consensus
+    #[serde(default)]
+    degraded: bool,
+}
+
+fn default_asset_category() -> String {
+    DEFAULT_ASSET_CATEGORY.to_string()
+}
+
+impl PriceData {
+    /// The message the oracle signs, as the program rebuilds it to check the signature
+    fn signed_message(&self) -> String {
+        signed_price_message(
+            &self.asset, self.price, self.timestamp, self.confidence, self.consensus_score, &self.sources,
+            &self.asset_category, self.degraded, NO_SENTIMENT_LABEL,
+        )
+    }
 }
 
 // Define the structure for signed data
 #[derive(Serialize, Deserialize, Debug)]
-struct SignedSentimentData {
-    data: SentimentData,
+struct SignedPriceData {
+    data: PriceData,
     signature: Vec<u8>,
     signer: Vec<u8>,
 }
@@ -67,9 +107,9 @@ enum Commands {
         output: String,
     },
     
-    /// Sign sentiment data
+    /// Sign price data with the keypair (--keypair)
     Sign {
-        /// Input JSON file containing sentiment data
+        /// Input JSON file containing price data
         #[arg(short, long)]
         input: String,
         
@@ -78,48 +118,249 @@ enum Commands {
         output: String,
     },
     
-    /// Create a new account to store sentiment data
+    /// Create and initialize a new account to store price data
     CreateAccount {
-        /// The tweet ID to estimate account size
+        /// The Solana program ID that will own the account
         #[arg(short, long)]
-        tweet_id: String,
+        program_id: String,
         
-        /// The text content to estimate account size
+        /// The asset symbol, to size the account
         #[arg(short, long)]
-        text: String,
+        asset: String,
         
-        /// The username to estimate account size
+        /// Comma-separated source names, to size the account
+        #[arg(short, long, value_delimiter = ',')]
+        sources: Vec<String>,
+    },
+    
+    /// Submit signed price data to Solana
+    Submit {
+        /// Input file containing the signed price data
         #[arg(short, long)]
-        username: String,
+        input: String,
         
-        /// The date to estimate account size
+        /// The Solana program ID
         #[arg(short, long)]
-        date: String,
+        program_id: String,
         
-        /// The source to estimate account size
+        /// The account to store the price data
         #[arg(short, long)]
-        source: String,
+        account: String,
     },
     
-    /// Submit signed sentiment data to Solana
-    Submit {
-        /// Input file containing the signed sentiment data
+    /// Submit every signed `*.json` file in a directory, oldest first
+    SubmitBatch {
+        /// Directory containing the signed price files
         #[arg(short, long)]
-        input: String,
+        input_dir: PathBuf,
         
         /// The Solana program ID
         #[arg(short, long)]
         program_id: String,
         
-        /// The account to store the sentiment data
+        /// The account to store the price data
+        #[arg(short, long)]
+        account: String,
+        
+        /// Pack as many submissions into each transaction as fit
+        #[arg(long)]
+        pack: bool,
+    },
+    
+    /// Export stored price history to CSV or JSON
+    Export {
+        /// Asset to export (e.g., BTC)
+        #[arg(short, long)]
+        asset: String,
+        
+        /// Output format
+        #[arg(short, long, value_enum, default_value = "csv")]
+        format: ExportFormat,
+        
+        /// Output file
+        #[arg(short, long)]
+        output: PathBuf,
+        
+        /// Only include records at or after this date (YYYY-MM-DD or RFC 3339)
+        #[arg(long)]
+        from: Option<String>,
+        
+        /// Only include records at or before this date (YYYY-MM-DD or RFC 3339)
+        #[arg(long)]
+        to: Option<String>,
+        
+        /// Gzip-compress the output
+        #[arg(long)]
+        compress: bool,
+        
+        /// Price history database written by the oracle node
+        #[arg(long, default_value = "price_history.db")]
+        db: PathBuf,
+    },
+    
+    /// Print the price data stored in an oracle account as JSON
+    ReadAccount {
+        /// The account to read
+        #[arg(short, long)]
+        account: String,
+        
+        /// The Solana RPC URL to read from
+        #[arg(long, default_value = "https://api.devnet.solana.com")]
+        rpc_url: String,
+        
+        /// Dump the raw account data as hex instead of decoding it
+        #[arg(long)]
+        raw: bool,
+    },
+    
+    /// Show the price data stored in an oracle account in readable form
+    Inspect {
+        /// The Solana program ID that should own the account
+        #[arg(short, long)]
+        program_id: String,
+        
+        /// The account to inspect
+        #[arg(short, long)]
+        account: String,
+    },
+    
+    /// Poll an oracle account until Ctrl-C, highlighting fields that changed since the last poll
+    WatchAccount {
+        /// The account to watch
+        #[arg(short, long)]
+        account: String,
+        
+        /// Seconds between polls
+        #[arg(short, long, default_value = "5")]
+        interval_secs: u64,
+        
+        /// The Solana RPC URL to read from
+        #[arg(long, default_value = "https://api.devnet.solana.com")]
+        rpc_url: String,
+        
+        /// Print one JSON object per poll instead of redrawing the terminal
+        #[arg(long)]
+        json: bool,
+    },
+    
+    /// Hand an oracle account to a new key; the transaction is signed by the current one (--keypair)
+    RotateKey {
+        /// The oracle account whose authority changes
+        #[arg(short, long)]
+        account: String,
+        
+        /// Keypair file of the new authority
+        #[arg(short, long)]
+        new_keypair: String,
+    },
+    
+    /// Reject price submissions to an oracle account until it is resumed; signed by its authority (--keypair)
+    PauseOracle {
+        /// The oracle account to pause
         #[arg(short, long)]
         account: String,
     },
+    
+    /// Accept price submissions to a paused oracle account again; signed by its authority (--keypair)
+    ResumeOracle {
+        /// The oracle account to resume
+        #[arg(short, long)]
+        account: String,
+    },
+    
+    /// Add an oracle to the program's allowlist; the first call must be signed by the program's
+    /// upgrade authority (--keypair), which becomes the allowlist's admin
+    AuthorizeOracle {
+        /// The Solana program ID
+        #[arg(short, long)]
+        program_id: String,
+        
+        /// Public key of the oracle allowed to submit prices
+        #[arg(short, long)]
+        oracle: String,
+    },
+    
+    /// Remove an oracle from the program's allowlist; signed by the allowlist's admin (--keypair)
+    RevokeOracle {
+        /// The Solana program ID
+        #[arg(short, long)]
+        program_id: String,
+        
+        /// Public key of the oracle to remove
+        #[arg(short, long)]
+        oracle: String,
+    },
+    
+    /// Check signing, verification and instruction encoding offline, printing PASS/FAIL per step
+    SelfTest,
+    
+    /// Request devnet (or local validator) SOL for the keypair
+    Airdrop {
+        /// Amount of SOL to request
+        #[arg(short, long, default_value = "1.0")]
+        amount_sol: f64,
+        
+        /// The Solana RPC URL; only devnet and localhost are accepted
+        #[arg(long, default_value = "https://api.devnet.solana.com")]
+        rpc_url: String,
+    },
 }
 
 fn main() {
     let cli = Cli::parse();
     
+    // Exporting only reads the local database, so it needs no RPC client or keypair
+    if let Commands::Export { asset, format, output, from, to, compress, db } = &cli.command {
+        // `bitcoin` and `$btc` export BTC's history; symbols the registry doesn't know are taken as given
+        let asset = &AssetRegistry::default().normalize(asset).unwrap_or_else(|| asset.to_uppercase());
+        let records = export::load_records(db, asset, from.as_deref(), to.as_deref())
+            .expect("Failed to read price history");
+        export::write_records(&records, *format, output, *compress)
+            .expect("Failed to write export");
+        
+        println!("Exported {} records for {} to {}", records.len(), asset, output.display());
+        return;
+    }
+    
+    // Reading an account is read-only as well
+    if let Commands::ReadAccount { account, rpc_url, raw } = &cli.command {
+        read_account(account, rpc_url, *raw);
+        return;
+    }
+    if let Commands::Inspect { program_id, account } = &cli.command {
+        inspect(account, program_id, &cli.url);
+        return;
+    }
+    if let Commands::WatchAccount { account, interval_secs, rpc_url, json } = &cli.command {
+        if *interval_secs == 0 {
+            eprintln!("The interval must be at least 1 second");
+            std::process::exit(1);
+        }
+        if let Err(e) = watch::run(&pubkey_from_str(account), rpc_url, Duration::from_secs(*interval_secs), *json) {
+            eprintln!("Failed to watch account: {}", e);
+            std::process::exit(1);
+        }
+        return;
+    }
+    
+    // The self-test generates its own keypair and never touches the network
+    if let Commands::SelfTest = &cli.command {
+        if !self_test::run() {
+            std::process::exit(1);
+        }
+        return;
+    }
+    
+    // A new keypair needs no existing one
+    if let Commands::GenerateKeypair { output } = &cli.command {
+        let keypair = Keypair::new();
+        write_keypair_file(&keypair, output).expect("Failed to write keypair to file");
+        
+        println!("Generated new keypair and saved to {}", output);
+        println!("Public key: {}", keypair.pubkey());
+        return;
+    }
+    
     // Get the RPC client
     let rpc_client = RpcClient::new_with_commitment(cli.url, CommitmentConfig::confirmed());
     
@@ -127,68 +368,38 @@ fn main() {
     let keypair = match cli.keypair {
         Some(keypair_path) => read_keypair_file(&keypair_path).expect("Failed to read keypair"),
         None => {
-            let config = Config::load(&Config::default_config_file_path()).expect("Failed to load Solana CLI config");
+            let config_file = CONFIG_FILE.as_ref().expect("Failed to find the Solana CLI config file");
+            let config = Config::load(config_file).expect("Failed to load Solana CLI config");
             read_keypair_file(&config.keypair_path).expect("Failed to read keypair from config")
         }
     };
     
     match cli.command {
-        Commands::GenerateKeypair { output } => {
-            // Generate a new keypair
-            let mut rng = OsRng{};
-            let dalek_keypair = DalekKeypair::generate(&mut rng);
-            
-            // Save the keypair to a file
-            let keypair_bytes = dalek_keypair.to_bytes().to_vec();
-            std::fs::write(&output, keypair_bytes).expect("Failed to write keypair to file");
-            
-            println!("Generated new keypair and saved to {}", output);
-            println!("Public key: {}", hex::encode(dalek_keypair.public.to_bytes()));
-        },
         Commands::Sign { input, output } => {
-            // Read the sentiment data from the input file
+            // Read the price data from the input file
             let mut file = File::open(&input).expect("Failed to open input file");
             let mut contents = String::new();
             file.read_to_string(&mut contents).expect("Failed to read input file");
             
-            let sentiment_data: SentimentData = serde_json::from_str(&contents)
-                .expect("Failed to parse sentiment data");
-            
-            // Canonicalize the JSON
-            let canonical_json = serde_json::to_string(&sentiment_data)
-                .expect("Failed to serialize sentiment data");
+            let price_data: PriceData = serde_json::from_str(&contents)
+                .expect("Failed to parse price data");
             
-            // Hash the canonical JSON using SHA-256
-            let mut hasher = Sha256::new();
-            hasher.update(canonical_json.as_bytes());
-            let hash = hasher.finalize();
-            
-            // Load ED25519 keypair for signing
-            let mut rng = OsRng{};
-            let dalek_keypair = DalekKeypair::generate(&mut rng); // In real-world, load from file
-            
-            // Sign the hash
-            let signature = dalek_keypair.sign(&hash);
-            
-            // Create the signed data structure
-            let signed_data = SignedSentimentData {
-                data: sentiment_data,
-                signature: signature.to_bytes().to_vec(),
-                signer: dalek_keypair.public.to_bytes().to_vec(),
-            };
+            let signed_data = sign_price(price_data, &keypair);
             
             // Write the signed data to the output file
             let signed_json = serde_json::to_string_pretty(&signed_data)
                 .expect("Failed to serialize signed data");
             std::fs::write(&output, signed_json).expect("Failed to write signed data to file");
             
-            println!("Signed sentiment data and saved to {}", output);
-            println!("Signature: {}", hex::encode(signature.to_bytes()));
-            println!("Signer: {}", hex::encode(dalek_keypair.public.to_bytes()));
+            println!("Signed price data and saved to {}", output);
+            println!("Signature: {}", hex::encode(&signed_data.signature));
+            println!("Signer: {}", keypair.pubkey());
         },
-        Commands::CreateAccount { tweet_id, text, username, date, source } => {
+        Commands::CreateAccount { program_id, asset, sources } => {
+            let program_id = pubkey_from_str(&program_id);
+            
             // Calculate the required account size
-            let account_size = get_account_size(&tweet_id, &text, &username, &date, &source);
+            let account_size = get_account_size(&asset, &sources);
             
             // Generate a new keypair for the account
             let account_keypair = Keypair::new();
@@ -197,20 +408,21 @@ fn main() {
             let rent = rpc_client.get_minimum_balance_for_rent_exemption(account_size)
                 .expect("Failed to get rent exemption");
             
-            // Create the account
+            // Create the account, owned by the program, and initialize it in the same transaction
             let create_account_ix = create_account(
                 &keypair.pubkey(),
                 &account_keypair.pubkey(),
                 rent,
                 account_size as u64,
-                &Pubkey::from_str("11111111111111111111111111111111").unwrap(), // Program ID placeholder
+                &program_id,
             );
+            let init_ix = initialize_account_instruction(&program_id, &account_keypair.pubkey(), &keypair.pubkey());
             
             // Build and send the transaction
             let blockhash = rpc_client.get_latest_blockhash()
                 .expect("Failed to get blockhash");
             let transaction = Transaction::new_signed_with_payer(
-                &[create_account_ix],
+                &[create_account_ix, init_ix],
                 Some(&keypair.pubkey()),
                 &[&keypair, &account_keypair],
                 blockhash,
@@ -231,49 +443,113 @@ fn main() {
             let account_pubkey = Pubkey::from_str(&account)
                 .expect("Invalid account");
             
-            // Read the signed sentiment data from the input file
+            // Read the signed price data from the input file
             let mut file = File::open(&input).expect("Failed to open input file");
             let mut contents = String::new();
             file.read_to_string(&mut contents).expect("Failed to read input file");
             
-            let signed_data: SignedSentimentData = serde_json::from_str(&contents)
+            let signed_data: SignedPriceData = serde_json::from_str(&contents)
                 .expect("Failed to parse signed data");
             
-            // Convert the signer from bytes to a Pubkey
-            let mut signer_bytes = [0u8; 32];
-            signer_bytes.copy_from_slice(&signed_data.signer);
+            let instructions = submit_instructions(signed_data, program_id, account_pubkey, &keypair.pubkey())
+                .expect("Invalid signed data");
             
-            // Create the instruction to submit the sentiment data
-            let submit_ix = SentimentInstruction::SubmitSentiment {
-                tweet_id: signed_data.data.id,
-                text: signed_data.data.text,
-                label: signed_data.data.label,
-                score: signed_data.data.score,
-                date: signed_data.data.date,
-                username: signed_data.data.username,
-                source: signed_data.data.source,
-                signature: signed_data.signature,
-                signer: signer_bytes,
-            };
+            // Build and send the transaction
+            let blockhash = rpc_client.get_latest_blockhash()
+                .expect("Failed to get blockhash");
+            let transaction = Transaction::new_signed_with_payer(
+                &instructions,
+                Some(&keypair.pubkey()),
+                &[&keypair],
+                blockhash,
+            );
             
-            // Serialize the instruction
-            let mut instruction_data = Vec::new();
-            submit_ix.serialize(&mut instruction_data)
-                .expect("Failed to serialize instruction");
+            let signature = rpc_client.send_and_confirm_transaction(&transaction)
+                .expect("Failed to send transaction");
+            
+            println!("Submitted price data to Solana");
+            println!("Transaction signature: {}", signature);
+        },
+        Commands::SubmitBatch { input_dir, program_id, account, pack } => {
+            let program_id = pubkey_from_str(&program_id);
+            let account_pubkey = pubkey_from_str(&account);
             
-            // Create the Solana instruction
-            let accounts = vec![
-                AccountMeta::new(account_pubkey, false),
-                AccountMeta::new_readonly(keypair.pubkey(), true),
-            ];
+            let files = batch::json_files(&input_dir).expect("Failed to read input directory");
+            let mut outcomes: Vec<(PathBuf, Result<String, String>)> = Vec::new();
+            let mut entries = Vec::new();
+            for path in files {
+                let parsed = std::fs::read_to_string(&path)
+                    .map_err(|e| e.to_string())
+                    .and_then(|contents| serde_json::from_str::<SignedPriceData>(&contents).map_err(|e| e.to_string()));
+                match parsed {
+                    Ok(signed_data) => entries.push((path, signed_data)),
+                    Err(e) => outcomes.push((path, Err(format!("not a signed file: {}", e)))),
+                }
+            }
             
-            let instruction = Instruction {
-                program_id,
-                accounts,
-                data: instruction_data,
+            // Submit in chronological order so each one is newer than the last
+            entries.sort_by_key(|(_, signed_data)| signed_data.data.timestamp);
+            let mut paths = Vec::new();
+            let mut submissions = Vec::new();
+            for (path, signed_data) in entries {
+                match submit_instructions(signed_data, program_id, account_pubkey, &keypair.pubkey()) {
+                    Ok(instructions) => {
+                        paths.push(path);
+                        submissions.push(instructions);
+                    },
+                    Err(e) => outcomes.push((path, Err(e))),
+                }
+            }
+            
+            let groups = if pack {
+                batch::pack(&submissions, &keypair.pubkey())
+            } else {
+                (0..submissions.len()).map(|i| i..i + 1).collect()
             };
+            println!("Submitting {} files in {} transactions", submissions.len(), groups.len());
+            
+            // One blockhash serves many transactions, until it gets close to expiring
+            let mut blockhash = rpc_client.get_latest_blockhash().expect("Failed to get blockhash");
+            let mut fetched_at = Instant::now();
+            for group in groups {
+                if fetched_at.elapsed() > batch::BLOCKHASH_REFRESH {
+                    blockhash = rpc_client.get_latest_blockhash().expect("Failed to get blockhash");
+                    fetched_at = Instant::now();
+                }
+                let transaction = Transaction::new_signed_with_payer(
+                    &submissions[group.clone()].concat(),
+                    Some(&keypair.pubkey()),
+                    &[&keypair],
+                    blockhash,
+                );
+                let result = rpc_client.send_and_confirm_transaction(&transaction)
+                    .map(|signature| signature.to_string())
+                    .map_err(|e| e.to_string());
+                outcomes.extend(paths[group].iter().map(|path| (path.clone(), result.clone())));
+            }
+            
+            let failed = outcomes.iter().filter(|(_, result)| result.is_err()).count();
+            for (path, result) in &outcomes {
+                match result {
+                    Ok(signature) => println!("  ok      {}  {}", path.display(), signature),
+                    Err(e) => println!("  failed  {}  {}", path.display(), e),
+                }
+            }
+            println!("Submitted {} of {} files", outcomes.len() - failed, outcomes.len());
+            if failed > 0 {
+                std::process::exit(1);
+            }
+        },
+        Commands::RotateKey { account, new_keypair } => {
+            let account_pubkey = pubkey_from_str(&account);
+            let new_keypair = read_keypair_file(&new_keypair).expect("Failed to read new keypair");
+            
+            // The instruction goes to the program owning the account
+            let program_id = rpc_client.get_account(&account_pubkey)
+                .expect("Failed to fetch account")
+                .owner;
+            let instruction = update_authority_instruction(&program_id, &account_pubkey, &keypair.pubkey(), &new_keypair.pubkey());
             
-            // Build and send the transaction
             let blockhash = rpc_client.get_latest_blockhash()
                 .expect("Failed to get blockhash");
             let transaction = Transaction::new_signed_with_payer(
@@ -286,12 +562,210 @@ fn main() {
             let signature = rpc_client.send_and_confirm_transaction(&transaction)
                 .expect("Failed to send transaction");
             
-            println!("Submitted sentiment data to Solana");
+            println!("Authority of {} moved from {} to {}", account_pubkey, keypair.pubkey(), new_keypair.pubkey());
             println!("Transaction signature: {}", signature);
         },
+        Commands::PauseOracle { account } => set_paused(&rpc_client, &keypair, &account, true),
+        Commands::ResumeOracle { account } => set_paused(&rpc_client, &keypair, &account, false),
+        Commands::AuthorizeOracle { program_id, oracle } => set_oracle_authorized(&rpc_client, &keypair, &program_id, &oracle, true),
+        Commands::RevokeOracle { program_id, oracle } => set_oracle_authorized(&rpc_client, &keypair, &program_id, &oracle, false),
+        Commands::GenerateKeypair { .. } => unreachable!("generate-keypair is handled before connecting"),
+        Commands::Export { .. } => unreachable!("export is handled before connecting"),
+        Commands::ReadAccount { .. } => unreachable!("read-account is handled before connecting"),
+        Commands::Inspect { .. } => unreachable!("inspect is handled before connecting"),
+        Commands::WatchAccount { .. } => unreachable!("watch-account is handled before connecting"),
+        Commands::SelfTest => unreachable!("self-test is handled before connecting"),
+        Commands::Airdrop { amount_sol, rpc_url } => {
+            if let Err(e) = airdrop::ensure_test_cluster(&rpc_url) {
+                eprintln!("{}", e);
+                std::process::exit(1);
+            }
+            if !(amount_sol.is_finite() && amount_sol > 0.0) {
+                eprintln!("Airdrop amount must be a positive number of SOL");
+                std::process::exit(1);
+            }
+            
+            let rpc_client = RpcClient::new_with_commitment(rpc_url.clone(), CommitmentConfig::confirmed());
+            let lamports = sol_to_lamports(amount_sol);
+            
+            let signature = airdrop::request_airdrop(&rpc_client, &keypair.pubkey(), lamports)
+                .unwrap_or_else(|e| {
+                    eprintln!("Airdrop failed: {}", e);
+                    std::process::exit(1);
+                });
+            let balance = rpc_client.get_balance(&keypair.pubkey())
+                .expect("Failed to get balance");
+            
+            println!("Airdropped {} SOL to {}", amount_sol, keypair.pubkey());
+            println!("New balance: {} SOL", lamports_to_sol(balance));
+            println!("Transaction: {}", airdrop::explorer_url(&signature, &rpc_url));
+        },
     }
 }
 
+// Fetch an oracle account and print its contents, exiting with 1 if it holds no price data
+fn read_account(account: &str, rpc_url: &str, raw: bool) {
+    let account_pubkey = pubkey_from_str(account);
+    let rpc_client = RpcClient::new_with_commitment(rpc_url.to_string(), CommitmentConfig::confirmed());
+    
+    let data = match rpc_client.get_account_data(&account_pubkey) {
+        Ok(data) => data,
+        Err(e) => {
+            eprintln!("Account {} not found: {}", account_pubkey, e);
+            std::process::exit(1);
+        }
+    };
+    
+    if raw {
+        println!("{}", hex::encode(&data));
+        return;
+    }
+    
+    match account::decode_payload(&data) {
+        Ok(view) => println!("{}", serde_json::to_string_pretty(&view).expect("Failed to serialize account data")),
+        Err(e) => {
+            eprintln!("Account {} holds no price data: {}", account_pubkey, e);
+            std::process::exit(1);
+        }
+    }
+}
+
+// Print an oracle account's price data, checking it belongs to the program
+fn inspect(account: &str, program_id: &str, rpc_url: &str) {
+    let account_pubkey = pubkey_from_str(account);
+    let program_id = pubkey_from_str(program_id);
+    let rpc_client = RpcClient::new_with_commitment(rpc_url.to_string(), CommitmentConfig::confirmed());
+    
+    let stored = match rpc_client.get_account(&account_pubkey) {
+        Ok(stored) => stored,
+        Err(e) => {
+            eprintln!("Account {} not found: {}", account_pubkey, e);
+            std::process::exit(1);
+        }
+    };
+    if stored.owner != program_id {
+        eprintln!("Account {} is owned by {}, not the oracle program {}", account_pubkey, stored.owner, program_id);
+        std::process::exit(1);
+    }
+    
+    match account::decode_payload(&stored.data) {
+        Ok(view) => {
+            println!("Account:     {}", account_pubkey);
+            println!("{}", account::describe(&view));
+        },
+        Err(e) => {
+            eprintln!("Account {} does not hold oracle price data ({}); it may be uninitialized or from another program version", account_pubkey, e);
+            std::process::exit(1);
+        }
+    }
+}
+
+// Pause or resume an oracle account, signing as its authority
+fn set_paused(rpc_client: &RpcClient, keypair: &Keypair, account: &str, paused: bool) {
+    let account_pubkey = pubkey_from_str(account);
+    
+    // The instruction goes to the program owning the account
+    let program_id = rpc_client.get_account(&account_pubkey)
+        .expect("Failed to fetch account")
+        .owner;
+    let instruction = set_paused_instruction(&program_id, &account_pubkey, &keypair.pubkey(), paused);
+    
+    let blockhash = rpc_client.get_latest_blockhash()
+        .expect("Failed to get blockhash");
+    let transaction = Transaction::new_signed_with_payer(
+        &[instruction],
+        Some(&keypair.pubkey()),
+        &[keypair],
+        blockhash,
+    );
+    
+    let signature = rpc_client.send_and_confirm_transaction(&transaction)
+        .expect("Failed to send transaction");
+    
+    println!("{} {}", if paused { "Paused" } else { "Resumed" }, account_pubkey);
+    println!("Transaction signature: {}", signature);
+}
+
+// Add an oracle to, or remove one from, the program's allowlist, signing as its admin
+fn set_oracle_authorized(rpc_client: &RpcClient, keypair: &Keypair, program_id: &str, oracle: &str, authorized: bool) {
+    let program_id = pubkey_from_str(program_id);
+    let oracle = pubkey_from_str(oracle);
+    let instruction = set_oracle_authorized_instruction(&program_id, &keypair.pubkey(), &oracle, authorized);
+    
+    let blockhash = rpc_client.get_latest_blockhash()
+        .expect("Failed to get blockhash");
+    let transaction = Transaction::new_signed_with_payer(
+        &[instruction],
+        Some(&keypair.pubkey()),
+        &[keypair],
+        blockhash,
+    );
+    
+    let signature = rpc_client.send_and_confirm_transaction(&transaction)
+        .expect("Failed to send transaction");
+    
+    println!("{} {}", if authorized { "Authorized oracle" } else { "Revoked oracle" }, oracle);
+    println!("Transaction signature: {}", signature);
+}
+
+// Sign the price data's `signed_price_message` with the keypair
+fn sign_price(price_data: PriceData, keypair: &Keypair) -> SignedPriceData {
+    let signature = keypair.sign_message(price_data.signed_message().as_bytes());
+    SignedPriceData {
+        data: price_data,
+        signature: signature.as_ref().to_vec(),
+        signer: keypair.pubkey().to_bytes().to_vec(),
+    }
+}
+
+// Check a signed file's signature against its data and signer
+fn verify_signed(signed_data: &SignedPriceData) -> Result<(), String> {
+    let public_key = PublicKey::from_bytes(&signed_data.signer)
+        .map_err(|e| format!("Invalid signer: {}", e))?;
+    let signature = Signature::from_bytes(&signed_data.signature)
+        .map_err(|e| format!("Invalid signature: {}", e))?;
+    public_key.verify(signed_data.data.signed_message().as_bytes(), &signature)
+        .map_err(|_| "Signature does not match the data".to_string())
+}
+
+// Build the instructions submitting one signed file to the oracle account: the Ed25519
+// verification of its signature, then the `SubmitPrice` the program checks against it
+fn submit_instructions(signed_data: SignedPriceData, program_id: Pubkey, account: Pubkey, submitter: &Pubkey) -> Result<Vec<Instruction>, String> {
+    let signer: [u8; 32] = signed_data.signer.as_slice().try_into()
+        .map_err(|_| format!("Signer is {} bytes, not 32", signed_data.signer.len()))?;
+    let message = signed_data.data.signed_message();
+    let verify_ix = new_ed25519_instruction(&signer, &signed_data.signature, message.as_bytes());
+    
+    let data = signed_data.data;
+    let submit_ix = PriceOracleInstruction::SubmitPrice {
+        asset: data.asset,
+        price: data.price,
+        confidence: data.confidence,
+        timestamp: data.timestamp,
+        sources: data.sources,
+        consensus_score: data.consensus_score,
+        signature: signed_data.signature,
+        signer,
+        asset_category: data.asset_category,
+        degraded: data.degraded,
+        sentiment_label: NO_SENTIMENT_LABEL,
+    };
+    
+    Ok(vec![
+        verify_ix,
+        Instruction {
+            program_id,
+            accounts: vec![
+                AccountMeta::new(account, false),
+                AccountMeta::new_readonly(*submitter, true),
+                AccountMeta::new_readonly(allowlist_address(&program_id).0, false),
+                AccountMeta::new_readonly(sysvar::instructions::id(), false),
+            ],
+            data: borsh::to_vec(&submit_ix).map_err(|e| e.to_string())?,
+        },
+    ])
+}
+
 // Helper function to parse a Pubkey from a string
 fn pubkey_from_str(s: &str) -> Pubkey {
     Pubkey::from_str(s).expect("Invalid pubkey")
@@ -0,0 +1,116 @@
+// Offline smoke test of the signing and instruction encoding paths
+use borsh::BorshDeserialize;
+use ed25519_dalek::{PublicKey, Signature, Verifier};
+use price_oracle_program::{check_ed25519_instruction, signed_price_message, PriceOracleInstruction};
+use solana_sdk::pubkey::Pubkey;
+use solana_sdk::signature::{Keypair, Signer};
+
+use crate::{sign_price, submit_instructions, verify_signed, PriceData};
+
+/// Run every step, printing PASS/FAIL for each; true when all passed
+pub fn run() -> bool {
+    let mut passed = true;
+    let mut step = |name: &str, result: Result<(), String>| {
+        match result {
+            Ok(()) => println!("PASS  {}", name),
+            Err(e) => {
+                println!("FAIL  {}: {}", name, e);
+                passed = false;
+            }
+        }
+    };
+    
+    let keypair = Keypair::new();
+    step("generate keypair", Ok(()));
+    
+    let signed = sign_price(sample_price(), &keypair);
+    step("sign price data", check(signed.signature.len() == 64, "signature is not 64 bytes"));
+    step("verify signature", verify_signed(&signed));
+    
+    let mut tampered = sign_price(sample_price(), &keypair);
+    tampered.data.price += 1.0;
+    step("reject tampered data", check(verify_signed(&tampered).is_err(), "tampered data verified"));
+    
+    step("encode and decode SubmitPrice with its Ed25519 verification", submit_price_round_trip(&keypair));
+    
+    passed
+}
+
+fn check(ok: bool, message: &str) -> Result<(), String> {
+    if ok { Ok(()) } else { Err(message.to_string()) }
+}
+
+fn sample_price() -> PriceData {
+    PriceData {
+        asset: "SOL".to_string(),
+        price: 172.5,
+        confidence: 0.92,
+        timestamp: 1_716_000_000,
+        sources: vec!["CoinGecko".to_string(), "Binance".to_string()],
+        consensus_score: 0.88,
+        asset_category: "L1".to_string(),
+        degraded: false,
+    }
+}
+
+// Build the instructions `submit` sends for a signed file, decode the SubmitPrice again and
+// check that the Ed25519 verification before it covers exactly the decoded fields, as the
+// program checks on-chain
+fn submit_price_round_trip(keypair: &Keypair) -> Result<(), String> {
+    let signed = sign_price(sample_price(), keypair);
+    let (program_id, account) = (Pubkey::new_unique(), Pubkey::new_unique());
+    let instructions = submit_instructions(signed, program_id, account, &keypair.pubkey())?;
+    let [verify_ix, submit_ix] = instructions.as_slice() else {
+        return Err(format!("expected 2 instructions, got {}", instructions.len()));
+    };
+    check(submit_ix.program_id == program_id, "SubmitPrice goes to another program")?;
+    check(submit_ix.accounts.len() == 4, "SubmitPrice does not pass the allowlist and instructions sysvar")?;
+    
+    let decoded = PriceOracleInstruction::try_from_slice(&submit_ix.data).map_err(|e| format!("deserialize: {}", e))?;
+    let reencoded = borsh::to_vec(&decoded).map_err(|e| format!("serialize: {}", e))?;
+    check(reencoded == submit_ix.data, "decoded instruction encodes differently")?;
+    
+    let PriceOracleInstruction::SubmitPrice {
+        asset, price, timestamp, confidence, consensus_score, sources, signature, signer, asset_category, degraded, sentiment_label,
+    } = decoded else {
+        return Err("decoded into a different instruction".to_string());
+    };
+    let message = signed_price_message(
+        &asset, price, timestamp, confidence, consensus_score, &sources, &asset_category, degraded, sentiment_label,
+    );
+    let public_key = PublicKey::from_bytes(&signer).map_err(|e| format!("signer: {}", e))?;
+    let parsed = Signature::from_bytes(&signature).map_err(|e| format!("signature: {}", e))?;
+    public_key.verify(message.as_bytes(), &parsed)
+        .map_err(|_| "signature no longer matches the decoded fields".to_string())?;
+    check_ed25519_instruction(verify_ix, &signer, &signature, message.as_bytes())
+        .map_err(|e| format!("Ed25519 verification does not cover the submission: {}", e))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use price_oracle_program::{DEFAULT_ASSET_CATEGORY, NO_SENTIMENT_LABEL};
+    
+    #[test]
+    fn test_self_test_passes() {
+        assert!(run());
+    }
+    
+    #[test]
+    fn test_verification_must_cover_the_submission() {
+        let keypair = Keypair::new();
+        let signed = sign_price(sample_price(), &keypair);
+        let signature = signed.signature.clone();
+        let instructions = submit_instructions(signed, Pubkey::new_unique(), Pubkey::new_unique(), &keypair.pubkey()).unwrap();
+        
+        let other = signed_price_message("SOL", 1.0, 1_716_000_000, 0.92, 0.88, &[], DEFAULT_ASSET_CATEGORY, false, NO_SENTIMENT_LABEL);
+        assert!(check_ed25519_instruction(&instructions[0], &keypair.pubkey().to_bytes(), &signature, other.as_bytes()).is_err());
+    }
+    
+    #[test]
+    fn test_verify_rejects_wrong_signer() {
+        let mut signed = sign_price(sample_price(), &Keypair::new());
+        signed.signer = Keypair::new().pubkey().to_bytes().to_vec();
+        assert!(verify_signed(&signed).is_err());
+    }
+}
@@ -0,0 +1,193 @@
+// Live view of an oracle account, refreshed on an interval
+use chrono::{DateTime, Utc};
+use colored::Colorize;
+use serde::Serialize;
+use solana_client::rpc_client::RpcClient;
+use solana_sdk::{commitment_config::CommitmentConfig, pubkey::Pubkey};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+
+use crate::account::{self, AccountResult, AccountView};
+
+/// Clears the terminal and moves the cursor home
+const CLEAR_SCREEN: &str = "\x1b[2J\x1b[H";
+
+/// How often the sleep between polls checks for Ctrl-C
+const STOP_CHECK: Duration = Duration::from_millis(100);
+
+/// Change since the previous poll
+#[derive(Serialize, Debug, Clone, PartialEq)]
+pub struct AccountDelta {
+    /// Price minus the previous poll's price (0 on the first poll)
+    pub price_change: f64,
+    /// Seconds since the account's timestamp
+    pub time_since_last_update: i64,
+}
+
+/// One poll as written with `--json`: the account's fields plus the delta
+#[derive(Serialize, Debug)]
+pub struct WatchLine<'a> {
+    pub account: String,
+    #[serde(flatten)]
+    pub view: &'a AccountView,
+    #[serde(flatten)]
+    pub delta: AccountDelta,
+}
+
+/// Names of the fields that differ between two polls
+pub fn changed_fields(previous: &AccountView, current: &AccountView) -> Vec<&'static str> {
+    let fields = [
+        ("asset", previous.asset != current.asset),
+        ("price", previous.price != current.price),
+        ("confidence", previous.confidence != current.confidence),
+        ("timestamp", previous.timestamp != current.timestamp),
+        ("sources", previous.sources != current.sources),
+        ("consensus_score", previous.consensus_score != current.consensus_score),
+        ("nonce", previous.nonce != current.nonce),
+        ("version", previous.version != current.version),
+        ("asset_category", previous.asset_category != current.asset_category),
+        ("paused", previous.paused != current.paused),
+        ("sentiment_label", previous.sentiment_label != current.sentiment_label),
+    ];
+    fields.into_iter().filter(|(_, changed)| *changed).map(|(name, _)| name).collect()
+}
+
+/// Delta of `current` against the previous poll, as of `now`
+pub fn delta(previous: Option<&AccountView>, current: &AccountView, now: DateTime<Utc>) -> AccountDelta {
+    let updated = DateTime::parse_from_rfc3339(&current.timestamp)
+        .map(|timestamp| timestamp.timestamp())
+        .unwrap_or_else(|_| now.timestamp());
+    AccountDelta {
+        price_change: previous.map_or(0.0, |previous| current.price - previous.price),
+        time_since_last_update: (now.timestamp() - updated).max(0),
+    }
+}
+
+/// The account as a field table, fields changed since the previous poll highlighted, then the delta row
+pub fn render(address: &Pubkey, view: &AccountView, previous: Option<&AccountView>, delta: &AccountDelta) -> String {
+    let changed = previous.map(|previous| changed_fields(previous, view)).unwrap_or_default();
+    let rows = [
+        ("asset", view.asset.clone()),
+        ("price", format!("{:.4}", view.price)),
+        ("confidence", format!("{:.2}", view.confidence)),
+        ("timestamp", view.timestamp.clone()),
+        ("sources", view.sources.join(", ")),
+        ("consensus_score", format!("{:.2}", view.consensus_score)),
+        ("nonce", view.nonce.to_string()),
+        ("version", view.version.to_string()),
+        ("asset_category", view.asset_category.clone()),
+        ("paused", view.paused.to_string()),
+        ("sentiment_label", view.sentiment_label.clone().unwrap_or_else(|| "-".to_string())),
+    ];
+
+    let mut out = format!("Account {}\n\n", address);
+    for (name, value) in rows {
+        let value = if changed.contains(&name) { value.yellow().bold().to_string() } else { value };
+        out.push_str(&format!("{:<16} {}\n", name, value));
+    }
+    let price_change = format!("{:+.4}", delta.price_change);
+    let price_change = if delta.price_change > 0.0 {
+        price_change.green().to_string()
+    } else if delta.price_change < 0.0 {
+        price_change.red().to_string()
+    } else {
+        price_change
+    };
+    out.push_str(&format!("\nprice_change {}   time_since_last_update {}s\n", price_change, delta.time_since_last_update));
+    out
+}
+
+/// Poll the account every `interval` until Ctrl-C, redrawing the table or, with `json`, printing
+/// one JSON object per poll. Failed polls are reported and retried on the next tick
+pub fn run(address: &Pubkey, rpc_url: &str, interval: Duration, json: bool) -> AccountResult<()> {
+    let stop = Arc::new(AtomicBool::new(false));
+    let handler_stop = stop.clone();
+    ctrlc::set_handler(move || handler_stop.store(true, Ordering::SeqCst))?;
+
+    let rpc_client = RpcClient::new_with_commitment(rpc_url.to_string(), CommitmentConfig::confirmed());
+    let mut previous: Option<AccountView> = None;
+    while !stop.load(Ordering::SeqCst) {
+        match poll(&rpc_client, address) {
+            Ok(view) => {
+                let delta = delta(previous.as_ref(), &view, Utc::now());
+                if json {
+                    let line = WatchLine { account: address.to_string(), view: &view, delta };
+                    println!("{}", serde_json::to_string(&line)?);
+                } else {
+                    print!("{}{}", CLEAR_SCREEN, render(address, &view, previous.as_ref(), &delta));
+                }
+                previous = Some(view);
+            },
+            Err(e) => eprintln!("Failed to read account {}: {}", address, e),
+        }
+        sleep_unless_stopped(interval, &stop);
+    }
+    Ok(())
+}
+
+fn poll(rpc_client: &RpcClient, address: &Pubkey) -> AccountResult<AccountView> {
+    account::decode_payload(&rpc_client.get_account_data(address)?)
+}
+
+// Sleep in short steps so Ctrl-C ends the watch without waiting out the interval
+fn sleep_unless_stopped(interval: Duration, stop: &AtomicBool) {
+    let mut remaining = interval;
+    while !remaining.is_zero() && !stop.load(Ordering::SeqCst) {
+        let step = remaining.min(STOP_CHECK);
+        std::thread::sleep(step);
+        remaining -= step;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn view(price: f64, timestamp: &str) -> AccountView {
+        AccountView {
+            asset: "SOL".to_string(),
+            price,
+            confidence: 0.92,
+            timestamp: timestamp.to_string(),
+            sources: vec!["CoinGecko".to_string(), "Binance".to_string()],
+            consensus_score: 0.88,
+            nonce: 1,
+            version: 5,
+            asset_category: "L1".to_string(),
+            paused: false,
+            sentiment_label: None,
+            signer: "01".repeat(32),
+        }
+    }
+
+    #[test]
+    fn test_changed_fields_and_delta() {
+        let now = DateTime::parse_from_rfc3339("2023-11-14T22:14:00+00:00").unwrap().with_timezone(&Utc);
+        let first = view(101.5, "2023-11-14T22:13:20+00:00");
+        let mut second = view(100.0, "2023-11-14T22:13:50+00:00");
+        second.nonce = 2;
+
+        assert_eq!(changed_fields(&first, &second), vec!["price", "timestamp", "nonce"]);
+        assert!(changed_fields(&first, &first).is_empty());
+
+        assert_eq!(delta(None, &first, now), AccountDelta { price_change: 0.0, time_since_last_update: 40 });
+        assert_eq!(delta(Some(&first), &second, now), AccountDelta { price_change: -1.5, time_since_last_update: 10 });
+    }
+
+    #[test]
+    fn test_json_line_is_flat() {
+        let current = view(101.5, "2023-11-14T22:13:20+00:00");
+        let line = WatchLine {
+            account: Pubkey::default().to_string(),
+            view: &current,
+            delta: AccountDelta { price_change: 0.5, time_since_last_update: 3 },
+        };
+
+        let json: serde_json::Value = serde_json::to_value(&line).unwrap();
+        assert_eq!(json["price"], 101.5);
+        assert_eq!(json["price_change"], 0.5);
+        assert_eq!(json["time_since_last_update"], 3);
+        assert_eq!(json["account"], Pubkey::default().to_string());
+    }
+}
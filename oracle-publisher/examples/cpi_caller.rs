@@ -0,0 +1,60 @@
+// Minimal program that submits prices to the oracle through a CPI, signing as its own PDA.
+// Build with `cargo build --example cpi_caller --features cpi` from ../program.
+//
+// Accounts expected:
+// 0. [] The oracle program
+// 1. [writable] The oracle account storing the price
+// 2. [] This program's submitter PDA (seed `SUBMITTER_SEED`), which must be on the allowlist
+// 3. [] The oracle allowlist at `allowlist_address`
+// 4. [] The instructions sysvar; the transaction must verify the price's Ed25519 signature
+//    just before this program's instruction
+//
+// Instruction data: a borsh `SubmitPriceArgs`, whose `signer` is the submitter PDA
+//
+// Before the first submission, the oracle program must accept the PDA as the account's submitter:
+// 1. The allowlist admin adds the PDA with `set_oracle_authorized_instruction`
+// 2. The price account's current authority hands it to the PDA with `update_authority_instruction`;
+//    until then the oracle program rejects the PDA with `UnauthorizedSigner`
+// `cpi::tests::test_cpi_submit_needs_authority_rotated_to_pda` runs both steps.
+use borsh::BorshDeserialize;
+use price_oracle_program::cpi::{cpi_submit_price, CpiContext, SubmitPrice, SubmitPriceArgs};
+use solana_program::{
+    account_info::{next_account_info, AccountInfo},
+    entrypoint,
+    entrypoint::ProgramResult,
+    msg,
+    program_error::ProgramError,
+    pubkey::Pubkey,
+};
+
+/// Seed of the PDA this program submits prices as
+const SUBMITTER_SEED: &[u8] = b"oracle_submitter";
+
+entrypoint!(process_instruction);
+
+fn process_instruction(program_id: &Pubkey, accounts: &[AccountInfo], instruction_data: &[u8]) -> ProgramResult {
+    let args = SubmitPriceArgs::try_from_slice(instruction_data)?;
+
+    let account_info_iter = &mut accounts.iter();
+    let oracle_program = next_account_info(account_info_iter)?;
+    let price_account = next_account_info(account_info_iter)?;
+    let submitter = next_account_info(account_info_iter)?;
+    let allowlist = next_account_info(account_info_iter)?;
+    let instructions = next_account_info(account_info_iter)?;
+
+    let (expected, bump) = Pubkey::find_program_address(&[SUBMITTER_SEED], program_id);
+    if submitter.key != &expected {
+        msg!("Expected the submitter PDA {}, got {}", expected, submitter.key);
+        return Err(ProgramError::InvalidSeeds);
+    }
+
+    let accounts = SubmitPrice {
+        price_account: price_account.clone(),
+        submitter: submitter.clone(),
+        allowlist: allowlist.clone(),
+        instructions: instructions.clone(),
+    };
+    let bump = [bump];
+    let signer_seeds: &[&[&[u8]]] = &[&[SUBMITTER_SEED, &bump]];
+    cpi_submit_price(CpiContext::new_with_signer(oracle_program.clone(), accounts, signer_seeds), args)
+}
@@ -0,0 +1,24 @@
+// Fuzz target for borsh deserialization of `PricePayload`, as done by the CLI
+// and the API when reading oracle accounts.
+//
+// Setup (from `oracle-publisher/`, needs a nightly toolchain):
+//
+//     cargo install cargo-fuzz
+//     cargo +nightly fuzz run fuzz_price_payload_deser
+//
+// Arbitrary bytes must either decode or return an error; a panic, abort or
+// oversized allocation from a forged length prefix is a bug.
+#![no_main]
+
+use borsh::BorshDeserialize;
+use libfuzzer_sys::fuzz_target;
+use price_oracle_program::{PricePayload, PricePayloadV1};
+
+fuzz_target!(|data: &[u8]| {
+    // Exact decoding, as a freshly serialized payload would be read
+    let _ = PricePayload::try_from_slice(data);
+
+    // Prefix decoding, as account data with trailing padding is read
+    let _ = PricePayload::deserialize(&mut &data[..]);
+    let _ = PricePayloadV1::deserialize(&mut &data[..]);
+});
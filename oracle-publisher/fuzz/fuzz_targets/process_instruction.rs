@@ -0,0 +1,116 @@
+// Fuzz target for the on-chain program's `process_instruction`.
+//
+// Setup (from `oracle-publisher/`, needs a nightly toolchain):
+//
+//     cargo install cargo-fuzz
+//     cargo +nightly fuzz run process_instruction
+//
+// The program is built with its `fuzzing` feature, which drops the entrypoint so
+// it links into the fuzzer binary. Each input is passed verbatim as instruction
+// data against a freshly initialized, program-owned price account, a signing
+// submitter on the oracle allowlist, and an instructions sysvar placing the input
+// after an Ed25519 instruction, which covers the input's own signer, signature and
+// message when it decodes as a `SubmitPrice`. The `Clock` sysvar is stubbed to
+// `NOW`, so submissions timestamped near it get past the clock check to the field
+// validation, signature check and store. Any panic or abort is a bug: malformed
+// instructions must come back as a `ProgramError`.
+#![no_main]
+
+use std::sync::Once;
+
+use borsh::{BorshDeserialize, BorshSerialize};
+use libfuzzer_sys::fuzz_target;
+use price_oracle_program::{
+    allowlist_address, get_account_size, new_ed25519_instruction, process_instruction, signed_price_message,
+    OracleAllowlist, PriceOracleInstruction,
+};
+use solana_program::{
+    account_info::AccountInfo,
+    clock::Clock,
+    ed25519_program,
+    entrypoint::SUCCESS,
+    instruction::Instruction,
+    program_stubs::{set_syscall_stubs, SyscallStubs},
+    pubkey::Pubkey,
+    sysvar::{self, instructions::{construct_instructions_data, store_current_index, BorrowedInstruction}},
+};
+
+/// Cluster time reported by the stubbed `Clock` sysvar
+const NOW: i64 = 1_700_000_000;
+
+struct FixedClock;
+
+impl SyscallStubs for FixedClock {
+    fn sol_get_clock_sysvar(&self, var_addr: *mut u8) -> u64 {
+        let clock = Clock { unix_timestamp: NOW, ..Clock::default() };
+        unsafe { *(var_addr as *mut Clock) = clock };
+        SUCCESS
+    }
+}
+
+static CLOCK: Once = Once::new();
+
+/// Ed25519 verification of a `SubmitPrice`'s own signature over its fields, or an empty one
+/// for any other input
+fn verification_for(instruction_data: &[u8]) -> Instruction {
+    match PriceOracleInstruction::try_from_slice(instruction_data) {
+        Ok(PriceOracleInstruction::SubmitPrice {
+            asset, price, confidence, timestamp, sources, consensus_score, signature, signer, asset_category, degraded, sentiment_label,
+        }) => {
+            let message = signed_price_message(
+                &asset, price, timestamp, confidence, consensus_score, &sources, &asset_category, degraded, sentiment_label,
+            );
+            new_ed25519_instruction(&signer, &signature, message.as_bytes())
+        },
+        _ => Instruction { program_id: ed25519_program::id(), accounts: Vec::new(), data: Vec::new() },
+    }
+}
+
+fuzz_target!(|instruction_data: &[u8]| {
+    CLOCK.call_once(|| {
+        set_syscall_stubs(Box::new(FixedClock));
+    });
+
+    let program_id = Pubkey::new_from_array([1; 32]);
+    let account_key = Pubkey::new_from_array([2; 32]);
+    let submitter_key = Pubkey::new_from_array([3; 32]);
+    let system_program = Pubkey::default();
+
+    // Room for a payload with a handful of sources, like the CLI allocates
+    let sources = vec!["CoinGecko".to_string(), "Binance".to_string()];
+    let mut account_lamports = 1_000_000;
+    let mut account_data = vec![0u8; get_account_size("BTC", &sources)];
+    let mut submitter_lamports = 1_000_000;
+    let mut submitter_data = Vec::new();
+
+    // An allowlist listing the submitter, so submissions reach past the oracle check
+    let allowlist_key = allowlist_address(&program_id).0;
+    let allowlist = OracleAllowlist { is_initialized: true, admin: [4; 32], oracles: vec![submitter_key.to_bytes()] };
+    let mut allowlist_lamports = 1_000_000;
+    let mut allowlist_data = allowlist.try_to_vec().unwrap();
+    allowlist_data.resize(OracleAllowlist::ACCOUNT_SIZE, 0);
+
+    // The transaction as the program sees it: an Ed25519 verification, then the input
+    let (instructions_key, sysvar_owner) = (sysvar::instructions::id(), sysvar::id());
+    let mut instructions_lamports = 0;
+    let verification = verification_for(instruction_data);
+    let mut instructions_data = construct_instructions_data(&[
+        BorrowedInstruction { program_id: &verification.program_id, accounts: Vec::new(), data: &verification.data },
+        BorrowedInstruction { program_id: &program_id, accounts: Vec::new(), data: instruction_data },
+    ]);
+    store_current_index(&mut instructions_data, 1);
+
+    let accounts = [
+        AccountInfo::new(&account_key, false, true, &mut account_lamports, &mut account_data, &program_id, false, 0),
+        AccountInfo::new(&submitter_key, true, false, &mut submitter_lamports, &mut submitter_data, &system_program, false, 0),
+        AccountInfo::new(&allowlist_key, false, false, &mut allowlist_lamports, &mut allowlist_data, &program_id, false, 0),
+        AccountInfo::new(&instructions_key, false, false, &mut instructions_lamports, &mut instructions_data, &sysvar_owner, false, 0),
+    ];
+
+    // Start from an account initialized with the submitter as its authority, so submissions
+    // reach past the initialization and authority checks
+    let initialize = PriceOracleInstruction::InitializeAccount.try_to_vec().unwrap();
+    process_instruction(&program_id, &accounts[..2], &initialize).unwrap();
+
+    let _ = process_instruction(&program_id, &accounts, instruction_data);
+});
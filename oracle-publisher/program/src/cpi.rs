@@ -0,0 +1,213 @@
+// Submitting prices from another program, shaped after Anchor's generated `cpi` module.
+//
+// The program only takes submissions from the price account's authority, and only when it is on
+// the oracle allowlist. A calling program submitting as its own PDA therefore can't do so until
+// the allowlist admin has added the PDA (`set_oracle_authorized_instruction`) and the account's
+// current authority has handed the account to it (`update_authority_instruction`).
+use solana_program::{
+    account_info::AccountInfo,
+    entrypoint::ProgramResult,
+    instruction::{AccountMeta, Instruction},
+    program::invoke_signed,
+};
+use crate::PriceOracleInstruction;
+pub use crate::SubmitPriceArgs;
+
+/// The oracle program to invoke, the accounts it expects and, when one of those accounts is a
+/// PDA of the calling program, the seeds it signs with
+pub struct CpiContext<'a, 'info, T> {
+    pub program: AccountInfo<'info>,
+    pub accounts: T,
+    pub signer_seeds: &'a [&'a [&'a [u8]]],
+}
+
+impl<'a, 'info, T> CpiContext<'a, 'info, T> {
+    pub fn new(program: AccountInfo<'info>, accounts: T) -> Self {
+        Self { program, accounts, signer_seeds: &[] }
+    }
+
+    pub fn new_with_signer(program: AccountInfo<'info>, accounts: T, signer_seeds: &'a [&'a [&'a [u8]]]) -> Self {
+        Self { program, accounts, signer_seeds }
+    }
+
+    pub fn with_signer(mut self, signer_seeds: &'a [&'a [&'a [u8]]]) -> Self {
+        self.signer_seeds = signer_seeds;
+        self
+    }
+}
+
+/// Accounts of a `SubmitPrice`, in the order the program reads them:
+/// 0. `price_account` [writable] The oracle account storing the price, owned by the oracle program
+/// 1. `submitter` [signer] The oracle submitting it: the account's authority once it has one, and
+///    on the allowlist. A PDA of the calling program signs through `signer_seeds`
+/// 2. `allowlist` [] The oracle allowlist at `allowlist_address`
+/// 3. `instructions` [] The instructions sysvar. The program checks the Ed25519 verification just
+///    before the caller's top-level instruction, so the caller's transaction must carry one
+#[derive(Clone)]
+pub struct SubmitPrice<'info> {
+    pub price_account: AccountInfo<'info>,
+    pub submitter: AccountInfo<'info>,
+    pub allowlist: AccountInfo<'info>,
+    pub instructions: AccountInfo<'info>,
+}
+
+impl<'info> SubmitPrice<'info> {
+    pub fn to_account_metas(&self) -> Vec<AccountMeta> {
+        vec![
+            AccountMeta::new(*self.price_account.key, false),
+            AccountMeta::new_readonly(*self.submitter.key, true),
+            AccountMeta::new_readonly(*self.allowlist.key, false),
+            AccountMeta::new_readonly(*self.instructions.key, false),
+        ]
+    }
+
+    pub fn to_account_infos(&self) -> Vec<AccountInfo<'info>> {
+        vec![self.price_account.clone(), self.submitter.clone(), self.allowlist.clone(), self.instructions.clone()]
+    }
+}
+
+/// `SubmitPrice` instruction for `ctx`, as `cpi_submit_price` invokes it
+pub fn submit_price_instruction(ctx: &CpiContext<'_, '_, SubmitPrice>, args: SubmitPriceArgs) -> Result<Instruction, std::io::Error> {
+    Ok(Instruction {
+        program_id: *ctx.program.key,
+        accounts: ctx.accounts.to_account_metas(),
+        data: borsh::to_vec(&PriceOracleInstruction::from(args))?,
+    })
+}
+
+/// Submit a price to the oracle program from another program, signing for a PDA submitter with
+/// `ctx.signer_seeds`. The submitter must already be the price account's authority and on the
+/// allowlist, and `args.signer` must be that authority too
+pub fn cpi_submit_price<'info>(ctx: CpiContext<'_, 'info, SubmitPrice<'info>>, args: SubmitPriceArgs) -> ProgramResult {
+    let instruction = submit_price_instruction(&ctx, args)?;
+    let mut account_infos = ctx.accounts.to_account_infos();
+    account_infos.push(ctx.program.clone());
+    invoke_signed(&instruction, &account_infos, ctx.signer_seeds)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::{FixedClock, NOW};
+    use crate::{
+        allowlist_address, new_ed25519_instruction, process_instruction, signed_price_message, update_authority_instruction,
+        OracleAllowlist, PriceOracleError, PricePayload, NO_SENTIMENT_LABEL,
+    };
+    use borsh::BorshDeserialize;
+    use solana_program::{
+        program_error::ProgramError,
+        program_stubs::set_syscall_stubs,
+        pubkey::Pubkey,
+        sysvar::{self, instructions::{construct_instructions_data, store_current_index, BorrowedInstruction}},
+    };
+
+    #[test]
+    fn test_submit_price_instruction() {
+        let program_id = Pubkey::new_unique();
+        let (price_key, submitter_key, allowlist_key) = (Pubkey::new_unique(), Pubkey::new_unique(), allowlist_address(&program_id).0);
+        let (instructions_key, sysvar_owner) = (sysvar::instructions::id(), sysvar::id());
+        let (mut program_lamports, mut price_lamports, mut submitter_lamports, mut allowlist_lamports, mut instructions_lamports) = (0, 0, 0, 0, 0);
+        let (mut program_data, mut price_data, mut submitter_data, mut allowlist_data, mut instructions_data) = (vec![], vec![], vec![], vec![], vec![]);
+        let accounts = SubmitPrice {
+            price_account: AccountInfo::new(&price_key, false, true, &mut price_lamports, &mut price_data, &program_id, false, 0),
+            submitter: AccountInfo::new(&submitter_key, true, false, &mut submitter_lamports, &mut submitter_data, &program_id, false, 0),
+            allowlist: AccountInfo::new(&allowlist_key, false, false, &mut allowlist_lamports, &mut allowlist_data, &program_id, false, 0),
+            instructions: AccountInfo::new(&instructions_key, false, false, &mut instructions_lamports, &mut instructions_data, &sysvar_owner, false, 0),
+        };
+        let program = AccountInfo::new(&program_id, false, false, &mut program_lamports, &mut program_data, &program_id, true, 0);
+        let args = SubmitPriceArgs {
+            asset: "SOL".to_string(),
+            price: 150.25,
+            confidence: 0.9,
+            timestamp: 1_700_000_000,
+            sources: vec!["Binance".to_string(), "OKX".to_string()],
+            consensus_score: 0.85,
+            signature: vec![7; 64],
+            signer: submitter_key.to_bytes(),
+            asset_category: "L1".to_string(),
+            degraded: false,
+            sentiment_label: NO_SENTIMENT_LABEL,
+        };
+
+        let ctx = CpiContext::new(program, accounts);
+        let instruction = submit_price_instruction(&ctx, args.clone()).unwrap();
+        assert_eq!(instruction.program_id, program_id);
+        assert_eq!(instruction.accounts, vec![
+            AccountMeta::new(price_key, false),
+            AccountMeta::new_readonly(submitter_key, true),
+            AccountMeta::new_readonly(allowlist_key, false),
+            AccountMeta::new_readonly(instructions_key, false),
+        ]);
+        assert_eq!(ctx.accounts.to_account_infos().len(), 4);
+        // The program reads exactly what a client-built `SubmitPrice` would carry
+        let decoded = PriceOracleInstruction::try_from_slice(&instruction.data).unwrap();
+        assert_eq!(borsh::to_vec(&decoded).unwrap(), borsh::to_vec(&PriceOracleInstruction::from(args.clone())).unwrap());
+        let PriceOracleInstruction::SubmitPrice { asset, price, .. } = decoded else { panic!("expected SubmitPrice") };
+        assert_eq!((asset.as_str(), price), ("SOL", 150.25));
+    }
+    
+    #[test]
+    fn test_cpi_submit_needs_authority_rotated_to_pda() {
+        set_syscall_stubs(Box::new(FixedClock));
+        
+        let (program_id, caller_id, oracle) = (Pubkey::new_unique(), Pubkey::new_unique(), Pubkey::new_unique());
+        let pda = Pubkey::find_program_address(&[b"oracle_submitter"], &caller_id).0;
+        let (price_key, allowlist_key, system_program) = (Pubkey::new_unique(), allowlist_address(&program_id).0, Pubkey::default());
+        let (instructions_key, sysvar_owner) = (sysvar::instructions::id(), sysvar::id());
+        let (mut program_lamports, mut price_lamports, mut pda_lamports, mut oracle_lamports, mut allowlist_lamports, mut instructions_lamports) = (0, 0, 0, 0, 0, 0);
+        let (mut program_data, mut pda_data, mut oracle_data) = (vec![], vec![], vec![]);
+        let mut price_data = vec![0; 512];
+        let mut allowlist_data = borsh::to_vec(&OracleAllowlist { is_initialized: true, admin: [9; 32], oracles: vec![pda.to_bytes()] }).unwrap();
+        allowlist_data.resize(OracleAllowlist::ACCOUNT_SIZE, 0);
+        
+        let args = SubmitPriceArgs {
+            asset: "SOL".to_string(),
+            price: 150.25,
+            confidence: 0.9,
+            timestamp: NOW,
+            sources: vec!["Binance".to_string()],
+            consensus_score: 0.85,
+            signature: vec![7; 64],
+            signer: pda.to_bytes(),
+            asset_category: "L1".to_string(),
+            degraded: false,
+            sentiment_label: NO_SENTIMENT_LABEL,
+        };
+        let message = signed_price_message(
+            &args.asset, args.price, args.timestamp, args.confidence, args.consensus_score, &args.sources, &args.asset_category, args.degraded, args.sentiment_label,
+        );
+        let verification = new_ed25519_instruction(&args.signer, &args.signature, message.as_bytes());
+        let mut instructions_data = construct_instructions_data(&[
+            BorrowedInstruction { program_id: &verification.program_id, accounts: Vec::new(), data: &verification.data },
+            BorrowedInstruction { program_id: &caller_id, accounts: Vec::new(), data: &[] },
+        ]);
+        store_current_index(&mut instructions_data, 1);
+        
+        // What `invoke_signed` hands the oracle program: the PDA signs through the caller's seeds
+        let price_account = AccountInfo::new(&price_key, false, true, &mut price_lamports, &mut price_data, &program_id, false, 0);
+        let oracle_account = AccountInfo::new(&oracle, true, false, &mut oracle_lamports, &mut oracle_data, &system_program, false, 0);
+        let ctx = CpiContext::new(
+            AccountInfo::new(&program_id, false, false, &mut program_lamports, &mut program_data, &program_id, true, 0),
+            SubmitPrice {
+                price_account: price_account.clone(),
+                submitter: AccountInfo::new(&pda, true, false, &mut pda_lamports, &mut pda_data, &system_program, false, 0),
+                allowlist: AccountInfo::new(&allowlist_key, false, false, &mut allowlist_lamports, &mut allowlist_data, &program_id, false, 0),
+                instructions: AccountInfo::new(&instructions_key, false, false, &mut instructions_lamports, &mut instructions_data, &sysvar_owner, false, 0),
+            },
+        );
+        let submit = submit_price_instruction(&ctx, args).unwrap();
+        let submit_as_pda = || process_instruction(&program_id, &ctx.accounts.to_account_infos(), &submit.data);
+        
+        // The oracle that created the account is its authority, so the PDA is turned away
+        let initialize = crate::initialize_account_instruction(&program_id, &price_key, &oracle);
+        process_instruction(&program_id, &[price_account.clone(), oracle_account.clone()], &initialize.data).unwrap();
+        assert_eq!(submit_as_pda(), Err(ProgramError::Custom(PriceOracleError::UnauthorizedSigner as u32)));
+        
+        // Once the oracle hands the account to the PDA, the caller's submissions go through
+        let rotate = update_authority_instruction(&program_id, &price_key, &oracle, &pda);
+        process_instruction(&program_id, &[price_account.clone(), oracle_account], &rotate.data).unwrap();
+        submit_as_pda().unwrap();
+        let payload = PricePayload::deserialize(&mut &price_account.data.borrow()[..]).unwrap();
+        assert_eq!((payload.signer, payload.price), (pda.to_bytes(), 150.25));
+    }
+}
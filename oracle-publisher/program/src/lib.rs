@@ -1,24 +1,288 @@
 // Price Oracle Program - A Solana program to store aggregated price data on-chain
 use solana_program::{
     account_info::{next_account_info, AccountInfo},
+    clock::Clock,
     entrypoint,
+    ed25519_program,
     entrypoint::ProgramResult,
+    instruction::{AccountMeta, Instruction},
     msg,
+    program::invoke_signed,
     program_error::ProgramError,
     pubkey::Pubkey,
-    borsh::try_from_slice_unchecked,
+    bpf_loader_upgradeable::{self, UpgradeableLoaderState},
     program_pack::IsInitialized,
+    program_utils::limited_deserialize,
+    system_instruction,
+    sysvar::{self, rent::Rent, Sysvar},
 };
 use borsh::{BorshDeserialize, BorshSerialize};
 use std::mem::size_of;
 use sha2::{Sha256, Digest};
 
+/// Calling the program from another program (`cpi` feature)
+#[cfg(feature = "cpi")]
+pub mod cpi;
+
 // Declare the program's entrypoint
+#[cfg(not(feature = "no-entrypoint"))]
 entrypoint!(process_instruction);
 
+/// Current on-chain layout version of `PricePayload`
+pub const PAYLOAD_VERSION: u8 = 5;
+
+/// How far (in seconds) a submission's timestamp may differ from the cluster clock
+pub const MAX_CLOCK_SKEW_SECS: i64 = 300;
+
+/// Categories an asset may be submitted under, for category-level aggregation
+pub const ASSET_CATEGORIES: [&str; 6] = ["L1", "L2", "DeFi", "Stablecoin", "Meme", "Other"];
+
+/// Most sources a submission may name
+pub const MAX_SOURCES: usize = 10;
+
+/// `PricePayload::signer` of an account with no authority, which nobody may submit to
+pub const NO_AUTHORITY: [u8; 32] = [0; 32];
+
+/// Seed of the oracle allowlist's address (see `allowlist_address`)
+pub const ALLOWLIST_SEED: &[u8] = b"oracle_allowlist";
+
+/// Most oracles the allowlist can hold
+pub const MAX_AUTHORIZED_ORACLES: usize = 32;
+
+/// Address and bump of the program's oracle allowlist, which every `SubmitPrice` passes
+pub fn allowlist_address(program_id: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[ALLOWLIST_SEED], program_id)
+}
+
+/// Address of the program's ProgramData account, which records its upgrade authority
+pub fn program_data_address(program_id: &Pubkey) -> Pubkey {
+    Pubkey::find_program_address(&[program_id.as_ref()], &bpf_loader_upgradeable::id()).0
+}
+
+/// Category given to accounts written before categories existed
+pub const DEFAULT_ASSET_CATEGORY: &str = "Other";
+
+pub fn is_valid_asset_category(category: &str) -> bool {
+    ASSET_CATEGORIES.contains(&category)
+}
+
+/// `PricePayload::sentiment_label` of a plain price submission, which carries no label
+pub const NO_SENTIMENT_LABEL: u8 = 0;
+
+/// Sentiment label stored with a sentiment submission, as its `u8` discriminant on-chain and
+/// as an uppercase string (`POSITIVE`, `VERY_NEGATIVE`, ...) everywhere else
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(try_from = "String", into = "String"))]
+#[repr(u8)]
+pub enum SentimentLabel {
+    Positive = 1,
+    Negative = 2,
+    Neutral = 3,
+    VeryPositive = 4,
+    VeryNegative = 5,
+}
+
+impl SentimentLabel {
+    pub const ALL: [SentimentLabel; 5] = [
+        SentimentLabel::VeryPositive,
+        SentimentLabel::Positive,
+        SentimentLabel::Neutral,
+        SentimentLabel::Negative,
+        SentimentLabel::VeryNegative,
+    ];
+    
+    pub fn as_str(self) -> &'static str {
+        match self {
+            SentimentLabel::Positive => "POSITIVE",
+            SentimentLabel::Negative => "NEGATIVE",
+            SentimentLabel::Neutral => "NEUTRAL",
+            SentimentLabel::VeryPositive => "VERY_POSITIVE",
+            SentimentLabel::VeryNegative => "VERY_NEGATIVE",
+        }
+    }
+    
+    /// Label for a stored discriminant; `None` for `NO_SENTIMENT_LABEL` and unknown values
+    pub fn from_u8(value: u8) -> Option<Self> {
+        Self::ALL.into_iter().find(|label| *label as u8 == value)
+    }
+}
+
+impl std::fmt::Display for SentimentLabel {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl std::str::FromStr for SentimentLabel {
+    type Err = String;
+    
+    /// Case-insensitive; `very positive` and `very-positive` read as `VERY_POSITIVE`
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let normalized = s.trim().to_ascii_uppercase().replace([' ', '-'], "_");
+        Self::ALL.into_iter().find(|label| label.as_str() == normalized).ok_or_else(|| {
+            let allowed: Vec<&str> = Self::ALL.iter().map(|label| label.as_str()).collect();
+            format!("Unknown sentiment label '{}', expected one of {}", s, allowed.join(", "))
+        })
+    }
+}
+
+impl TryFrom<String> for SentimentLabel {
+    type Error = String;
+    
+    fn try_from(label: String) -> Result<Self, Self::Error> {
+        label.parse()
+    }
+}
+
+impl From<SentimentLabel> for String {
+    fn from(label: SentimentLabel) -> Self {
+        label.as_str().to_string()
+    }
+}
+
+/// Decimal places of the price and confidence in the signed message
+pub const MESSAGE_DECIMALS: usize = 8;
+
+/// Message an oracle signs for a submission:
+/// `asset|price|timestamp|confidence|consensus_score|source,source,...|asset_category|degraded|sentiment_label`.
+/// Price, confidence and consensus score are fixed to `MESSAGE_DECIMALS` places and the sources
+/// sorted, so signers and verifiers produce the same bytes whatever order the sources are listed
+/// in. `degraded` is `true` or `false` and `sentiment_label` the label's discriminant (or
+/// `NO_SENTIMENT_LABEL`).
+#[allow(clippy::too_many_arguments)]
+pub fn signed_price_message(
+    asset: &str,
+    price: f64,
+    timestamp: i64,
+    confidence: f64,
+    consensus_score: f64,
+    sources: &[String],
+    asset_category: &str,
+    degraded: bool,
+    sentiment_label: u8,
+) -> String {
+    let mut sources: Vec<&str> = sources.iter().map(String::as_str).collect();
+    sources.sort_unstable();
+    format!(
+        "{}|{:.*}|{}|{:.*}|{:.*}|{}|{}|{}|{}",
+        asset, MESSAGE_DECIMALS, price, timestamp, MESSAGE_DECIMALS, confidence,
+        MESSAGE_DECIMALS, consensus_score, sources.join(","), asset_category, degraded, sentiment_label
+    )
+}
+
+/// Whether a source name can appear in the signed message without making it ambiguous
+pub fn is_valid_source_name(source: &str) -> bool {
+    !source.is_empty() && !source.contains([',', '|'])
+}
+
+/// Size of one `Ed25519SignatureOffsets` entry in an Ed25519 program instruction
+const ED25519_OFFSETS_SIZE: usize = 14;
+
+/// Ed25519 program instruction verifying `signature` by `signer` over `message`, which must
+/// directly precede every `SubmitPrice`
+pub fn new_ed25519_instruction(signer: &[u8; 32], signature: &[u8], message: &[u8]) -> Instruction {
+    let public_key_offset = 2 + ED25519_OFFSETS_SIZE;
+    let signature_offset = public_key_offset + signer.len();
+    let message_offset = signature_offset + signature.len();
+    
+    let mut data = vec![1, 0];
+    // Every offset refers to this instruction's own data
+    for value in [signature_offset, u16::MAX as usize, public_key_offset, u16::MAX as usize, message_offset, message.len(), u16::MAX as usize] {
+        data.extend_from_slice(&(value as u16).to_le_bytes());
+    }
+    data.extend_from_slice(signer);
+    data.extend_from_slice(signature);
+    data.extend_from_slice(message);
+    
+    Instruction { program_id: ed25519_program::id(), accounts: Vec::new(), data }
+}
+
+/// `InitializeAccount` instruction for `account`, making `authority`, which must sign, the only
+/// key allowed to submit to it
+pub fn initialize_account_instruction(program_id: &Pubkey, account: &Pubkey, authority: &Pubkey) -> Instruction {
+    Instruction {
+        program_id: *program_id,
+        accounts: vec![AccountMeta::new(*account, false), AccountMeta::new_readonly(*authority, true)],
+        data: borsh::to_vec(&PriceOracleInstruction::InitializeAccount).expect("a fixed-size instruction always serializes"),
+    }
+}
+
+/// `UpdateAuthority` instruction handing `account` from `authority`, which must sign, to `new_authority`
+pub fn update_authority_instruction(program_id: &Pubkey, account: &Pubkey, authority: &Pubkey, new_authority: &Pubkey) -> Instruction {
+    let instruction = PriceOracleInstruction::UpdateAuthority { new_authority: new_authority.to_bytes() };
+    Instruction {
+        program_id: *program_id,
+        accounts: vec![AccountMeta::new(*account, false), AccountMeta::new_readonly(*authority, true)],
+        data: borsh::to_vec(&instruction).expect("a fixed-size instruction always serializes"),
+    }
+}
+
+/// `Pause` (or `Resume` when `paused` is false) instruction for `account`, signed by its `authority`
+pub fn set_paused_instruction(program_id: &Pubkey, account: &Pubkey, authority: &Pubkey, paused: bool) -> Instruction {
+    let instruction = if paused { PriceOracleInstruction::Pause } else { PriceOracleInstruction::Resume };
+    Instruction {
+        program_id: *program_id,
+        accounts: vec![AccountMeta::new(*account, false), AccountMeta::new_readonly(*authority, true)],
+        data: borsh::to_vec(&instruction).expect("a fixed-size instruction always serializes"),
+    }
+}
+
+/// `AddAuthorizedOracle` (or `RemoveAuthorizedOracle` when `authorized` is false) instruction for
+/// `oracle`, signed by the allowlist's `admin` (the program's upgrade authority for the first one)
+pub fn set_oracle_authorized_instruction(program_id: &Pubkey, admin: &Pubkey, oracle: &Pubkey, authorized: bool) -> Instruction {
+    let oracle = oracle.to_bytes();
+    let instruction = if authorized {
+        PriceOracleInstruction::AddAuthorizedOracle { oracle }
+    } else {
+        PriceOracleInstruction::RemoveAuthorizedOracle { oracle }
+    };
+    Instruction {
+        program_id: *program_id,
+        accounts: vec![
+            AccountMeta::new(allowlist_address(program_id).0, false),
+            AccountMeta::new(*admin, true),
+            AccountMeta::new_readonly(solana_program::system_program::id(), false),
+            AccountMeta::new_readonly(program_data_address(program_id), false),
+        ],
+        data: borsh::to_vec(&instruction).expect("a fixed-size instruction always serializes"),
+    }
+}
+
+/// Check that `instruction` is an Ed25519 program instruction verifying exactly `signature` by
+/// `signer` over `message`. The runtime has already rejected the transaction if it didn't verify.
+pub fn check_ed25519_instruction(instruction: &Instruction, signer: &[u8; 32], signature: &[u8], message: &[u8]) -> ProgramResult {
+    if instruction.program_id != ed25519_program::id() {
+        msg!("Expected an Ed25519 signature verification before the submission");
+        return Err(PriceOracleError::InvalidSignature.into());
+    }
+    
+    let data = &instruction.data;
+    let offsets = data.get(2..2 + ED25519_OFFSETS_SIZE).filter(|_| data[0] == 1)
+        .ok_or(PriceOracleError::InvalidSignature)?;
+    let field = |i: usize| u16::from_le_bytes([offsets[2 * i], offsets[2 * i + 1]]) as usize;
+    // Data held by another instruction could say anything
+    if [field(1), field(3), field(6)].iter().any(|&index| index != u16::MAX as usize) {
+        msg!("Ed25519 verification must carry its own data");
+        return Err(PriceOracleError::InvalidSignature.into());
+    }
+    let slice = |offset: usize, len: usize| data.get(offset..offset + len);
+    
+    let verified = slice(field(2), signer.len()) == Some(&signer[..])
+        && slice(field(0), signature.len()) == Some(signature)
+        && slice(field(4), field(5)) == Some(message);
+    if !verified {
+        msg!("Ed25519 verification does not cover this submission's signer, signature and message");
+        return Err(PriceOracleError::InvalidSignature.into());
+    }
+    Ok(())
+}
+
 #[derive(BorshSerialize, BorshDeserialize, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct PricePayload {
     pub is_initialized: bool,         // Used to check if the account has been initialized
+    pub version: u8,                  // Layout version (see `PAYLOAD_VERSION`)
     pub asset: String,                // Asset symbol (e.g., "BTC", "SOL")
     pub price: f64,                   // Aggregated price
     pub confidence: f64,              // Confidence score (0.0 to 1.0)
@@ -26,7 +290,47 @@ pub struct PricePayload {
     pub sources: Vec<String>,         // Data sources used
     pub consensus_score: f64,         // Consensus score
     pub signature: Vec<u8>,           // Signature of the payload
-    pub signer: [u8; 32],            // The public key of the signer
+    pub signer: [u8; 32],            // The account's authority, set by `InitializeAccount`
+    pub nonce: u64,                   // Submission counter (added in v2)
+    pub asset_category: String,       // One of `ASSET_CATEGORIES` (added in v3)
+    pub paused: bool,                 // Submissions are rejected while set (added in v4)
+    pub sentiment_label: u8,          // `SentimentLabel` discriminant or `NO_SENTIMENT_LABEL` (added in v5)
+}
+
+/// Version 1 account layout, the prefix shared by every later version.
+/// Newer versions only append fields after `signer`.
+#[derive(BorshSerialize, BorshDeserialize, Debug)]
+pub struct PricePayloadV1 {
+    pub is_initialized: bool,
+    pub version: u8,
+    pub asset: String,
+    pub price: f64,
+    pub confidence: f64,
+    pub timestamp: i64,
+    pub sources: Vec<String>,
+    pub consensus_score: f64,
+    pub signature: Vec<u8>,
+    pub signer: [u8; 32],
+}
+
+/// Oracles allowed to submit prices, kept in the account at `allowlist_address`.
+/// Only listed oracles may submit, so nothing can be submitted until the account exists.
+#[derive(BorshSerialize, BorshDeserialize, Debug, Default)]
+pub struct OracleAllowlist {
+    pub is_initialized: bool,
+    /// Key allowed to add and remove oracles: the program's upgrade authority when the first
+    /// `AddAuthorizedOracle` created the list
+    pub admin: [u8; 32],
+    pub oracles: Vec<[u8; 32]>,
+}
+
+impl OracleAllowlist {
+    /// Account size with room for `MAX_AUTHORIZED_ORACLES` oracles
+    pub const ACCOUNT_SIZE: usize = 1 + 32 + 4 + 32 * MAX_AUTHORIZED_ORACLES;
+    
+    pub fn is_authorized(&self, oracle: &Pubkey) -> bool {
+        self.oracles.contains(&oracle.to_bytes())
+    }
 }
 
 // Implement the IsInitialized trait for PricePayload
@@ -53,6 +357,24 @@ pub enum PriceOracleError {
     
     #[error("Consensus failed")]
     ConsensusFailed,
+    
+    #[error("Unsupported account version")]
+    UnsupportedVersion,
+    
+    #[error("Stale or replayed submission")]
+    StaleSubmission,
+    
+    #[error("Signer is not the account's authority")]
+    UnauthorizedSigner,
+    
+    #[error("Oracle account is paused")]
+    Paused,
+    
+    #[error("Submitter is not an authorized oracle")]
+    UnauthorizedOracle,
+    
+    #[error("Oracle allowlist is full")]
+    AllowlistFull,
 }
 
 // Map the custom error to ProgramError
@@ -84,10 +406,11 @@ pub fn process_instruction(
             consensus_score,
             signature,
             signer,
+            asset_category,
+            degraded,
+            sentiment_label,
         } => {
-            process_submit_price(
-                program_id,
-                accounts,
+            let args = SubmitPriceArgs {
                 asset,
                 price,
                 confidence,
@@ -96,22 +419,41 @@ pub fn process_instruction(
                 consensus_score,
                 signature,
                 signer,
-            )
-        }
+                asset_category,
+                degraded,
+                sentiment_label,
+            };
+            process_submit_price(program_id, accounts, args)
+        },
+        PriceOracleInstruction::UpdateAuthority { new_authority } => {
+            process_update_authority(program_id, accounts, new_authority)
+        },
+        PriceOracleInstruction::Pause => process_set_paused(program_id, accounts, true),
+        PriceOracleInstruction::Resume => process_set_paused(program_id, accounts, false),
+        PriceOracleInstruction::AddAuthorizedOracle { oracle } => {
+            process_update_allowlist(program_id, accounts, oracle, true)
+        },
+        PriceOracleInstruction::RemoveAuthorizedOracle { oracle } => {
+            process_update_allowlist(program_id, accounts, oracle, false)
+        },
     }
 }
 
 // Program instruction enum
 #[derive(BorshSerialize, BorshDeserialize, Debug)]
 pub enum PriceOracleInstruction {
-    /// Initialize a new account
-    /// Accounts expected: [writable] The account to initialize
+    /// Initialize a new account, making the signer its authority
+    /// Accounts expected:
+    /// 0. [writable] The account to initialize
+    /// 1. [signer] The authority, the only key allowed to submit to the account
     InitializeAccount,
     
     /// Submit a new price payload
     /// Accounts expected: 
     /// 0. [writable] The account to store the price data
     /// 1. [signer] The account of the oracle submitting the data
+    /// 2. [] The oracle allowlist at `allowlist_address`
+    /// 3. [] The instructions sysvar, to check the Ed25519 verification that must precede it
     SubmitPrice {
         asset: String,
         price: f64,
@@ -119,9 +461,105 @@ pub enum PriceOracleInstruction {
         timestamp: i64,
         sources: Vec<String>,
         consensus_score: f64,
+        /// Ed25519 signature of `signed_price_message`, verified by the instruction just before
         signature: Vec<u8>,
+        /// Key `signature` is by; must be the account's authority
         signer: [u8; 32],
+        /// One of `ASSET_CATEGORIES`
+        asset_category: String,
+        /// Price came from a single source rather than a consensus; logged, not stored
+        degraded: bool,
+        /// `SentimentLabel` discriminant of a sentiment submission, `NO_SENTIMENT_LABEL` for a
+        /// price. Signed as the last field of
+        /// `asset|price|timestamp|confidence|consensus_score|source,source,...|asset_category|degraded|sentiment_label`
+        sentiment_label: u8,
+    },
+    
+    /// Hand the account to a new authority, which every later submission must be signed by
+    /// Accounts expected:
+    /// 0. [writable] The account whose authority changes
+    /// 1. [signer] The current authority
+    UpdateAuthority {
+        new_authority: [u8; 32],
+    },
+    
+    /// Reject submissions until `Resume`, keeping the stored price readable
+    /// Accounts expected:
+    /// 0. [writable] The account to pause
+    /// 1. [signer] The account's authority
+    Pause,
+    
+    /// Accept submissions again after `Pause`
+    /// Accounts expected:
+    /// 0. [writable] The account to resume
+    /// 1. [signer] The account's authority
+    Resume,
+    
+    /// Allow `oracle` to submit prices. The first call creates the allowlist and must be signed
+    /// by the program's upgrade authority, which becomes the admin; later calls must be signed
+    /// by that admin
+    /// Accounts expected:
+    /// 0. [writable] The oracle allowlist at `allowlist_address`
+    /// 1. [signer, writable] The admin, paying for the allowlist when it is created
+    /// 2. [] The system program
+    /// 3. [] The program's ProgramData account, read when the allowlist is created
+    AddAuthorizedOracle {
+        oracle: [u8; 32],
     },
+    
+    /// Stop `oracle` from submitting prices
+    /// Accounts expected:
+    /// 0. [writable] The oracle allowlist at `allowlist_address`
+    /// 1. [signer] The admin
+    RemoveAuthorizedOracle {
+        oracle: [u8; 32],
+    },
+}
+
+/// The fields of `PriceOracleInstruction::SubmitPrice`
+#[derive(BorshSerialize, BorshDeserialize, Debug, Clone, PartialEq)]
+pub struct SubmitPriceArgs {
+    pub asset: String,
+    pub price: f64,
+    pub confidence: f64,
+    pub timestamp: i64,
+    pub sources: Vec<String>,
+    pub consensus_score: f64,
+    pub signature: Vec<u8>,
+    pub signer: [u8; 32],
+    /// One of `ASSET_CATEGORIES`
+    pub asset_category: String,
+    pub degraded: bool,
+    /// `SentimentLabel` discriminant, or `NO_SENTIMENT_LABEL` for a price
+    pub sentiment_label: u8,
+}
+
+impl SubmitPriceArgs {
+    /// The `signed_price_message` the submission's signature must cover
+    pub fn signed_message(&self) -> String {
+        signed_price_message(
+            &self.asset, self.price, self.timestamp, self.confidence, self.consensus_score, &self.sources,
+            &self.asset_category, self.degraded, self.sentiment_label,
+        )
+    }
+}
+
+impl From<SubmitPriceArgs> for PriceOracleInstruction {
+    fn from(args: SubmitPriceArgs) -> Self {
+        PriceOracleInstruction::SubmitPrice {
+            asset: args.asset,
+            price: args.price,
+            confidence: args.confidence,
+            timestamp: args.timestamp,
+            sources: args.sources,
+            consensus_score: args.consensus_score,
+            signature: args.signature,
+            signer: args.signer,
+            asset_category: args.asset_category,
+            degraded: args.degraded,
+            sentiment_label: args.sentiment_label,
+        }
+    }
 }
 
 // Process account initialization
@@ -131,6 +569,7 @@ fn process_initialize_account(
 ) -> ProgramResult {
     let account_info_iter = &mut accounts.iter();
     let account = next_account_info(account_info_iter)?;
+    let authority = next_account_info(account_info_iter)?;
     
     // Check if the account is owned by the program
     if account.owner != program_id {
@@ -138,18 +577,22 @@ fn process_initialize_account(
         return Err(ProgramError::IncorrectProgramId);
     }
     
-    // Check if the account is already initialized
-    if account.data.borrow().len() > 0 {
-        let price_payload = try_from_slice_unchecked::<PricePayload>(&account.data.borrow())?;
-        if price_payload.is_initialized {
-            msg!("Account is already initialized");
-            return Err(PriceOracleError::AccountAlreadyInitialized.into());
-        }
+    if !authority.is_signer {
+        msg!("Authority did not sign the transaction");
+        return Err(ProgramError::MissingRequiredSignature);
+    }
+    
+    // A new account is zero-filled, so only the leading `is_initialized` byte is checked;
+    // deserializing the rest would fail on data that was never written
+    if account.data.borrow().first() == Some(&1) {
+        msg!("Account is already initialized");
+        return Err(PriceOracleError::AccountAlreadyInitialized.into());
     }
     
     // Create a new empty price payload
     let price_payload = PricePayload {
         is_initialized: true,
+        version: PAYLOAD_VERSION,
         asset: String::new(),
         price: 0.0,
         confidence: 0.0,
@@ -157,11 +600,15 @@ fn process_initialize_account(
         sources: Vec::new(),
         consensus_score: 0.0,
         signature: Vec::new(),
-        signer: [0; 32],
+        signer: authority.key.to_bytes(),
+        nonce: 0,
+        asset_category: String::new(),
+        paused: false,
+        sentiment_label: NO_SENTIMENT_LABEL,
     };
     
     // Serialize and store the price payload
-    price_payload.serialize(&mut *account.data.borrow_mut())?;
+    price_payload.serialize(&mut &mut account.data.borrow_mut()[..])?;
     
     msg!("Account initialized successfully");
     Ok(())
@@ -171,18 +618,27 @@ fn process_initialize_account(
 fn process_submit_price(
     program_id: &Pubkey,
     accounts: &[AccountInfo],
-    asset: String,
-    price: f64,
-    confidence: f64,
-    timestamp: i64,
-    sources: Vec<String>,
-    consensus_score: f64,
-    signature: Vec<u8>,
-    signer: [u8; 32],
+    args: SubmitPriceArgs,
 ) -> ProgramResult {
+    let message = args.signed_message();
+    let SubmitPriceArgs {
+        asset,
+        price,
+        confidence,
+        timestamp,
+        sources,
+        consensus_score,
+        signature,
+        signer,
+        asset_category,
+        degraded,
+        sentiment_label,
+    } = args;
     let account_info_iter = &mut accounts.iter();
     let account = next_account_info(account_info_iter)?;
     let submitter = next_account_info(account_info_iter)?;
+    let allowlist = next_account_info(account_info_iter)?;
+    let instructions = next_account_info(account_info_iter)?;
     
     // Check if the account is owned by the program
     if account.owner != program_id {
@@ -190,8 +646,11 @@ fn process_submit_price(
         return Err(ProgramError::IncorrectProgramId);
     }
     
+    // Bring older account layouts up to date before reading them
+    migrate_account(&mut account.data.borrow_mut())?;
+    
     // Check if the account is initialized
-    let mut price_payload = try_from_slice_unchecked::<PricePayload>(&account.data.borrow())?;
+    let mut price_payload = PricePayload::deserialize(&mut &account.data.borrow()[..])?;
     if !price_payload.is_initialized {
         msg!("Account is not initialized");
         return Err(PriceOracleError::UninitializedAccount.into());
@@ -203,6 +662,23 @@ fn process_submit_price(
         return Err(ProgramError::MissingRequiredSignature);
     }
     
+    check_authorized_oracle(program_id, allowlist, submitter.key)?;
+    
+    // Only the account's authority may submit
+    if submitter.key.to_bytes() != price_payload.signer {
+        msg!("Submitter {} is not the account's authority", submitter.key);
+        return Err(PriceOracleError::UnauthorizedSigner.into());
+    }
+    
+    if price_payload.paused {
+        msg!("Account is paused; submissions are rejected until it is resumed");
+        return Err(PriceOracleError::Paused.into());
+    }
+    
+    // Reject replays and submissions too far from the cluster clock
+    let now = Clock::get()?.unix_timestamp;
+    check_submission_timestamp(timestamp, price_payload.timestamp, now, MAX_CLOCK_SKEW_SECS)?;
+    
     // Validate price data
     if price <= 0.0 {
         msg!("Invalid price: {}", price);
@@ -214,9 +690,38 @@ fn process_submit_price(
         return Err(PriceOracleError::InvalidPriceData.into());
     }
     
-    // Verify the signature (in a real-world application, we would verify the signature here)
-    // For this implementation, we'll just log a message and save the signature
-    msg!("Signature verification would happen here in a production system");
+    if !is_valid_asset_category(&asset_category) {
+        msg!("Invalid asset category: {}", asset_category);
+        return Err(PriceOracleError::InvalidPriceData.into());
+    }
+    
+    if sources.len() > MAX_SOURCES {
+        msg!("Too many sources: {} (maximum: {})", sources.len(), MAX_SOURCES);
+        return Err(PriceOracleError::InvalidPriceData.into());
+    }
+    
+    if !sources.iter().all(|source| is_valid_source_name(source)) {
+        msg!("Invalid source name in {:?}", sources);
+        return Err(PriceOracleError::InvalidPriceData.into());
+    }
+    
+    if sentiment_label != NO_SENTIMENT_LABEL && SentimentLabel::from_u8(sentiment_label).is_none() {
+        msg!("Invalid sentiment label: {}", sentiment_label);
+        return Err(PriceOracleError::InvalidPriceData.into());
+    }
+    
+    if degraded {
+        msg!("Degraded submission: {} price from a single source ({})", asset, sources.join(","));
+    }
+    
+    // The stored signature must verify against the stored authority, not a key of the caller's choosing
+    if signer != price_payload.signer {
+        msg!("Submission is signed by {}, not the account's authority", Pubkey::new_from_array(signer));
+        return Err(PriceOracleError::InvalidSignature.into());
+    }
+    
+    // The signature covers every submitted field, so a relayer can't alter the attribution
+    verify_preceding_signature(instructions, &signer, &signature, message.as_bytes())?;
     
     // Update the price payload
     price_payload.asset = asset;
@@ -226,19 +731,330 @@ fn process_submit_price(
     price_payload.sources = sources;
     price_payload.consensus_score = consensus_score;
     price_payload.signature = signature;
-    price_payload.signer = signer;
+    price_payload.nonce = price_payload.nonce.wrapping_add(1);
+    price_payload.asset_category = asset_category;
+    price_payload.sentiment_label = sentiment_label;
     
     // Serialize and store the updated price payload
-    price_payload.serialize(&mut *account.data.borrow_mut())?;
+    price_payload.serialize(&mut &mut account.data.borrow_mut()[..])?;
     
     msg!("Price data submitted successfully");
     Ok(())
 }
 
+// Process a change of the account's authority
+fn process_update_authority(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    new_authority: [u8; 32],
+) -> ProgramResult {
+    let account_info_iter = &mut accounts.iter();
+    let account = next_account_info(account_info_iter)?;
+    let authority = next_account_info(account_info_iter)?;
+    
+    if account.owner != program_id {
+        msg!("Account doesn't belong to this program");
+        return Err(ProgramError::IncorrectProgramId);
+    }
+    
+    migrate_account(&mut account.data.borrow_mut())?;
+    
+    let mut price_payload = PricePayload::deserialize(&mut &account.data.borrow()[..])?;
+    if !price_payload.is_initialized {
+        msg!("Account is not initialized");
+        return Err(PriceOracleError::UninitializedAccount.into());
+    }
+    
+    if !authority.is_signer {
+        msg!("Authority did not sign the transaction");
+        return Err(ProgramError::MissingRequiredSignature);
+    }
+    
+    // An account without an authority has nothing to hand over
+    if price_payload.signer == NO_AUTHORITY || authority.key.to_bytes() != price_payload.signer {
+        msg!("{} is not the account's authority", authority.key);
+        return Err(PriceOracleError::UnauthorizedSigner.into());
+    }
+    
+    // Handing the account to nobody would lock it for good
+    if new_authority == NO_AUTHORITY {
+        msg!("New authority must be a public key");
+        return Err(ProgramError::InvalidArgument);
+    }
+    
+    price_payload.signer = new_authority;
+    price_payload.serialize(&mut &mut account.data.borrow_mut()[..])?;
+    
+    msg!("Authority updated to {}", Pubkey::new_from_array(new_authority));
+    Ok(())
+}
+
+// Process pausing or resuming the account's price updates
+fn process_set_paused(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    paused: bool,
+) -> ProgramResult {
+    let account_info_iter = &mut accounts.iter();
+    let account = next_account_info(account_info_iter)?;
+    let authority = next_account_info(account_info_iter)?;
+    
+    if account.owner != program_id {
+        msg!("Account doesn't belong to this program");
+        return Err(ProgramError::IncorrectProgramId);
+    }
+    
+    migrate_account(&mut account.data.borrow_mut())?;
+    
+    let mut price_payload = PricePayload::deserialize(&mut &account.data.borrow()[..])?;
+    if !price_payload.is_initialized {
+        msg!("Account is not initialized");
+        return Err(PriceOracleError::UninitializedAccount.into());
+    }
+    
+    if !authority.is_signer {
+        msg!("Authority did not sign the transaction");
+        return Err(ProgramError::MissingRequiredSignature);
+    }
+    
+    if price_payload.signer == NO_AUTHORITY || authority.key.to_bytes() != price_payload.signer {
+        msg!("{} is not the account's authority", authority.key);
+        return Err(PriceOracleError::UnauthorizedSigner.into());
+    }
+    
+    price_payload.paused = paused;
+    price_payload.serialize(&mut &mut account.data.borrow_mut()[..])?;
+    
+    msg!("Account {}", if paused { "paused" } else { "resumed" });
+    Ok(())
+}
+
+// Process adding an oracle to, or removing one from, the allowlist
+fn process_update_allowlist(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    oracle: [u8; 32],
+    add: bool,
+) -> ProgramResult {
+    let account_info_iter = &mut accounts.iter();
+    let allowlist = next_account_info(account_info_iter)?;
+    let admin = next_account_info(account_info_iter)?;
+    
+    let (expected, bump) = allowlist_address(program_id);
+    if allowlist.key != &expected {
+        msg!("Expected the oracle allowlist {}, got {}", expected, allowlist.key);
+        return Err(ProgramError::InvalidArgument);
+    }
+    
+    if !admin.is_signer {
+        msg!("Admin did not sign the transaction");
+        return Err(ProgramError::MissingRequiredSignature);
+    }
+    
+    if allowlist.data_is_empty() {
+        if !add {
+            msg!("There is no allowlist to remove an oracle from");
+            return Err(PriceOracleError::UninitializedAccount.into());
+        }
+        let system_program = next_account_info(account_info_iter)?;
+        let size = OracleAllowlist::ACCOUNT_SIZE;
+        invoke_signed(
+            &system_instruction::create_account(admin.key, allowlist.key, Rent::get()?.minimum_balance(size), size as u64, program_id),
+            &[admin.clone(), allowlist.clone(), system_program.clone()],
+            &[&[ALLOWLIST_SEED, &[bump]]],
+        )?;
+        msg!("Created the oracle allowlist");
+    }
+    
+    if allowlist.owner != program_id {
+        msg!("Allowlist doesn't belong to this program");
+        return Err(ProgramError::IncorrectProgramId);
+    }
+    
+    let mut list = OracleAllowlist::deserialize(&mut &allowlist.data.borrow()[..])?;
+    if !list.is_initialized {
+        if !add {
+            msg!("There is no allowlist to remove an oracle from");
+            return Err(PriceOracleError::UninitializedAccount.into());
+        }
+        // Fixed at deploy time: nobody but whoever deployed the program can take the list over
+        check_upgrade_authority(program_id, accounts.get(3), admin.key)?;
+        list.is_initialized = true;
+        list.admin = admin.key.to_bytes();
+        msg!("Allowlist admin set to {}", admin.key);
+    } else if admin.key.to_bytes() != list.admin {
+        msg!("{} is not the allowlist's admin", admin.key);
+        return Err(PriceOracleError::UnauthorizedSigner.into());
+    }
+    
+    let oracle_key = Pubkey::new_from_array(oracle);
+    if add {
+        if !list.is_authorized(&oracle_key) {
+            if list.oracles.len() >= MAX_AUTHORIZED_ORACLES {
+                msg!("Allowlist already holds {} oracles", MAX_AUTHORIZED_ORACLES);
+                return Err(PriceOracleError::AllowlistFull.into());
+            }
+            list.oracles.push(oracle);
+        }
+        msg!("Oracle {} authorized", oracle_key);
+    } else {
+        list.oracles.retain(|listed| *listed != oracle);
+        msg!("Oracle {} removed", oracle_key);
+    }
+    
+    list.serialize(&mut &mut allowlist.data.borrow_mut()[..])?;
+    Ok(())
+}
+
+/// Check that `admin` is the upgrade authority recorded in the program's ProgramData account
+fn check_upgrade_authority(program_id: &Pubkey, program_data: Option<&AccountInfo>, admin: &Pubkey) -> ProgramResult {
+    let program_data = program_data.ok_or(ProgramError::NotEnoughAccountKeys)?;
+    let expected = program_data_address(program_id);
+    if program_data.key != &expected || program_data.owner != &bpf_loader_upgradeable::id() {
+        msg!("Expected the program's ProgramData account {}, got {}", expected, program_data.key);
+        return Err(ProgramError::InvalidArgument);
+    }
+    
+    let limit = UpgradeableLoaderState::size_of_programdata_metadata() as u64;
+    let state: UpgradeableLoaderState = limited_deserialize(&program_data.data.borrow(), limit)
+        .map_err(|_| ProgramError::InvalidAccountData)?;
+    match state {
+        UpgradeableLoaderState::ProgramData { upgrade_authority_address: Some(authority), .. } if authority == *admin => Ok(()),
+        _ => {
+            msg!("{} is not the program's upgrade authority", admin);
+            Err(PriceOracleError::UnauthorizedSigner.into())
+        },
+    }
+}
+
+/// Check that `submitter` may submit: `allowlist` must be the program's allowlist account, and
+/// the submitter must be on it. Before the allowlist exists nobody may submit.
+fn check_authorized_oracle(program_id: &Pubkey, allowlist: &AccountInfo, submitter: &Pubkey) -> ProgramResult {
+    let (expected, _) = allowlist_address(program_id);
+    if allowlist.key != &expected {
+        msg!("Expected the oracle allowlist {}, got {}", expected, allowlist.key);
+        return Err(ProgramError::InvalidArgument);
+    }
+    
+    if allowlist.owner != program_id || allowlist.data_is_empty() {
+        msg!("The oracle allowlist has not been created; no oracle may submit yet");
+        return Err(PriceOracleError::UnauthorizedOracle.into());
+    }
+    let list = OracleAllowlist::deserialize(&mut &allowlist.data.borrow()[..])?;
+    if !list.is_initialized || !list.is_authorized(submitter) {
+        msg!("Submitter {} is not an authorized oracle", submitter);
+        return Err(PriceOracleError::UnauthorizedOracle.into());
+    }
+    Ok(())
+}
+
+/// Check the instruction before the current one verifies `signature` by `signer` over `message`
+fn verify_preceding_signature(instructions: &AccountInfo, signer: &[u8; 32], signature: &[u8], message: &[u8]) -> ProgramResult {
+    if instructions.key != &sysvar::instructions::id() {
+        msg!("Expected the instructions sysvar, got {}", instructions.key);
+        return Err(ProgramError::InvalidArgument);
+    }
+    
+    let current = sysvar::instructions::load_current_index_checked(instructions)?;
+    if current == 0 {
+        msg!("No Ed25519 verification precedes the submission");
+        return Err(PriceOracleError::InvalidSignature.into());
+    }
+    let verification = sysvar::instructions::load_instruction_at_checked(current as usize - 1, instructions)?;
+    check_ed25519_instruction(&verification, signer, signature, message)
+}
+
+/// Check that a submission is newer than the stored one and within `max_skew` seconds of `now`
+pub fn check_submission_timestamp(timestamp: i64, last_timestamp: i64, now: i64, max_skew: i64) -> ProgramResult {
+    if timestamp <= last_timestamp {
+        msg!("Stale submission: timestamp {} is not newer than stored {}", timestamp, last_timestamp);
+        return Err(PriceOracleError::StaleSubmission.into());
+    }
+    
+    if (timestamp - now).abs() > max_skew {
+        msg!("Stale submission: timestamp {} is more than {}s from cluster time {}", timestamp, max_skew, now);
+        return Err(PriceOracleError::StaleSubmission.into());
+    }
+    
+    Ok(())
+}
+
+/// Upgrade an account's data in place to the current `PAYLOAD_VERSION`.
+/// Migrations are applied one version at a time; uninitialized accounts are left untouched.
+pub fn migrate_account(data: &mut [u8]) -> ProgramResult {
+    if data.len() < 2 || data[0] == 0 {
+        return Ok(());
+    }
+    
+    loop {
+        match data[1] {
+            PAYLOAD_VERSION => return Ok(()),
+            1 => {
+                // v1 -> v2: append `nonce`, starting at 0
+                let end = layout_end(data, 1)?;
+                write_field(data, end, &0u64)?;
+                data[1] = 2;
+                msg!("Migrated account from v1 to v2");
+            },
+            2 => {
+                // v2 -> v3: append `asset_category`, starting as the default
+                let end = layout_end(data, 2)?;
+                write_field(data, end, &DEFAULT_ASSET_CATEGORY.to_string())?;
+                data[1] = 3;
+                msg!("Migrated account from v2 to v3");
+            },
+            3 => {
+                // v3 -> v4: append `paused`, starting unpaused
+                let end = layout_end(data, 3)?;
+                write_field(data, end, &false)?;
+                data[1] = 4;
+                msg!("Migrated account from v3 to v4");
+            },
+            4 => {
+                // v4 -> v5: append `sentiment_label`, starting without one
+                let end = layout_end(data, 4)?;
+                write_field(data, end, &NO_SENTIMENT_LABEL)?;
+                data[1] = 5;
+                msg!("Migrated account from v4 to v5");
+            },
+            version => {
+                msg!("Unsupported account version: {}", version);
+                return Err(PriceOracleError::UnsupportedVersion.into());
+            }
+        }
+    }
+}
+
+// Offset just past the last field of the given layout version
+fn layout_end(data: &[u8], version: u8) -> Result<usize, ProgramError> {
+    let mut rest = data;
+    PricePayloadV1::deserialize(&mut rest)?;
+    if version >= 2 {
+        u64::deserialize(&mut rest)?;
+    }
+    if version >= 3 {
+        String::deserialize(&mut rest)?;
+    }
+    if version >= 4 {
+        bool::deserialize(&mut rest)?;
+    }
+    if version >= 5 {
+        u8::deserialize(&mut rest)?;
+    }
+    Ok(data.len() - rest.len())
+}
+
+// Serialize a field into the account data at the given offset
+fn write_field<T: BorshSerialize>(data: &mut [u8], offset: usize, value: &T) -> ProgramResult {
+    let mut dest = data.get_mut(offset..).ok_or(ProgramError::AccountDataTooSmall)?;
+    value.serialize(&mut dest).map_err(|_| ProgramError::AccountDataTooSmall)
+}
+
 // Helper function to calculate required account size
 pub fn get_account_size(asset: &str, sources: &[String]) -> usize {
     let payload = PricePayload {
         is_initialized: true,
+        version: PAYLOAD_VERSION,
         asset: asset.to_string(),
         price: 0.0,
         confidence: 0.0,
@@ -247,6 +1063,11 @@ pub fn get_account_size(asset: &str, sources: &[String]) -> usize {
         consensus_score: 0.0,
         signature: Vec::new(),
         signer: [0; 32],
+        nonce: 0,
+        // The longest category
+        asset_category: "Stablecoin".to_string(),
+        paused: false,
+        sentiment_label: NO_SENTIMENT_LABEL,
     };
     
     let mut data = Vec::new();
@@ -254,4 +1075,672 @@ pub fn get_account_size(asset: &str, sources: &[String]) -> usize {
     
     // Add buffer space for the signature and any additional data
     data.len() + 256
-} 
\ No newline at end of file
+} 
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use solana_program::program_stubs::{set_syscall_stubs, SyscallStubs};
+    
+    /// Cluster time reported by the stubbed `Clock` sysvar
+    pub(crate) const NOW: i64 = 1_700_000_600;
+    
+    pub(crate) struct FixedClock;
+    
+    impl SyscallStubs for FixedClock {
+        fn sol_get_clock_sysvar(&self, var_addr: *mut u8) -> u64 {
+            let clock = Clock { unix_timestamp: NOW, ..Clock::default() };
+            unsafe { *(var_addr as *mut Clock) = clock };
+            solana_program::entrypoint::SUCCESS
+        }
+    }
+    
+    /// Authority of the accounts from `v1_account_data`
+    const AUTHORITY: [u8; 32] = [3; 32];
+    
+    fn submit_price(program_id: &Pubkey, data: &mut [u8], timestamp: i64) -> ProgramResult {
+        submit_price_in(program_id, data, timestamp, "L1", false)
+    }
+    
+    fn submit_price_in(program_id: &Pubkey, data: &mut [u8], timestamp: i64, asset_category: &str, degraded: bool) -> ProgramResult {
+        let instruction = submit_instruction(timestamp, asset_category, degraded);
+        process_submit(program_id, data, &instruction, None)
+    }
+    
+    fn submit_instruction(timestamp: i64, asset_category: &str, degraded: bool) -> PriceOracleInstruction {
+        PriceOracleInstruction::SubmitPrice {
+            asset: "BTC".to_string(),
+            price: 45000.0,
+            confidence: 0.9,
+            timestamp,
+            sources: vec!["CoinGecko".to_string()],
+            consensus_score: 0.85,
+            signature: vec![7; 64],
+            signer: AUTHORITY,
+            asset_category: asset_category.to_string(),
+            degraded,
+            sentiment_label: NO_SENTIMENT_LABEL,
+        }
+    }
+    
+    /// Run a submission by `AUTHORITY`, passing `instructions_sysvar` as the instructions sysvar's
+    /// data (by default a verification of the submission's own signature)
+    fn process_submit(
+        program_id: &Pubkey,
+        data: &mut [u8],
+        instruction: &PriceOracleInstruction,
+        instructions_sysvar: Option<&mut Vec<u8>>,
+    ) -> ProgramResult {
+        process_signed_by(program_id, data, instruction, &Pubkey::new_from_array(AUTHORITY), instructions_sysvar)
+    }
+    
+    /// Run an instruction signed by `submitter_key`, with the submitter on the oracle allowlist
+    fn process_signed_by(
+        program_id: &Pubkey,
+        data: &mut [u8],
+        instruction: &PriceOracleInstruction,
+        submitter_key: &Pubkey,
+        instructions_sysvar: Option<&mut Vec<u8>>,
+    ) -> ProgramResult {
+        let mut allowlist = allowlist_data(&[*submitter_key]);
+        process_with_allowlist(program_id, data, instruction, submitter_key, &mut allowlist, instructions_sysvar)
+    }
+    
+    /// Data of an initialized allowlist listing `oracles`
+    fn allowlist_data(oracles: &[Pubkey]) -> Vec<u8> {
+        let list = OracleAllowlist {
+            is_initialized: true,
+            admin: [9; 32],
+            oracles: oracles.iter().map(|oracle| oracle.to_bytes()).collect(),
+        };
+        let mut data = borsh::to_vec(&list).unwrap();
+        data.resize(OracleAllowlist::ACCOUNT_SIZE, 0);
+        data
+    }
+    
+    /// Run an instruction signed by `submitter_key`, passing `allowlist` as the allowlist
+    /// account's data (owned by the program unless empty)
+    fn process_with_allowlist(
+        program_id: &Pubkey,
+        data: &mut [u8],
+        instruction: &PriceOracleInstruction,
+        submitter_key: &Pubkey,
+        allowlist: &mut [u8],
+        instructions_sysvar: Option<&mut Vec<u8>>,
+    ) -> ProgramResult {
+        set_syscall_stubs(Box::new(FixedClock));
+        
+        let account_key = Pubkey::new_unique();
+        let system_program = Pubkey::default();
+        let allowlist_key = allowlist_address(program_id).0;
+        let allowlist_owner = if allowlist.is_empty() { system_program } else { *program_id };
+        let (sysvar_key, sysvar_owner) = (sysvar::instructions::id(), sysvar::id());
+        let (mut account_lamports, mut submitter_lamports, mut allowlist_lamports, mut sysvar_lamports) = (1_000_000, 1_000_000, 1_000_000, 0);
+        let mut submitter_data = Vec::new();
+        let mut verified = verified_sysvar(program_id, instruction);
+        let accounts = [
+            AccountInfo::new(&account_key, false, true, &mut account_lamports, data, program_id, false, 0),
+            AccountInfo::new(submitter_key, true, false, &mut submitter_lamports, &mut submitter_data, &system_program, false, 0),
+            AccountInfo::new(&allowlist_key, false, false, &mut allowlist_lamports, allowlist, &allowlist_owner, false, 0),
+            AccountInfo::new(&sysvar_key, false, false, &mut sysvar_lamports, instructions_sysvar.unwrap_or(&mut verified), &sysvar_owner, false, 0),
+        ];
+        
+        process_instruction(program_id, &accounts, &borsh::to_vec(instruction).unwrap())
+    }
+    
+    /// Instructions sysvar data for a transaction verifying a `SubmitPrice`'s signature over its
+    /// own fields before it (empty for other instructions)
+    fn verified_sysvar(program_id: &Pubkey, instruction: &PriceOracleInstruction) -> Vec<u8> {
+        let PriceOracleInstruction::SubmitPrice {
+            asset, price, confidence, timestamp, sources, consensus_score, signature, signer, asset_category, degraded, sentiment_label,
+        } = instruction else {
+            return Vec::new();
+        };
+        let message = signed_price_message(
+            asset, *price, *timestamp, *confidence, *consensus_score, sources, asset_category, *degraded, *sentiment_label,
+        );
+        instructions_sysvar(program_id, &new_ed25519_instruction(signer, signature, message.as_bytes()), instruction)
+    }
+    
+    /// Instructions sysvar data for a transaction of `verification` followed by the submission
+    fn instructions_sysvar(program_id: &Pubkey, verification: &Instruction, submission: &PriceOracleInstruction) -> Vec<u8> {
+        use sysvar::instructions::{construct_instructions_data, store_current_index, BorrowedInstruction};
+        
+        let submission = borsh::to_vec(submission).unwrap();
+        let mut data = construct_instructions_data(&[
+            BorrowedInstruction { program_id: &verification.program_id, accounts: Vec::new(), data: &verification.data },
+            BorrowedInstruction { program_id, accounts: Vec::new(), data: &submission },
+        ]);
+        store_current_index(&mut data, 1);
+        data
+    }
+    
+    fn assert_stale(result: ProgramResult) {
+        assert_eq!(result, Err(ProgramError::Custom(PriceOracleError::StaleSubmission as u32)));
+    }
+    
+    #[test]
+    fn test_replayed_submission_rejected() {
+        let program_id = Pubkey::new_unique();
+        let mut data = v1_account_data();
+        
+        submit_price(&program_id, &mut data, NOW).unwrap();
+        
+        // Replaying the same payload, or an older one, must fail
+        assert_stale(submit_price(&program_id, &mut data, NOW));
+        assert_stale(submit_price(&program_id, &mut data, NOW - 10));
+        
+        let payload = PricePayload::deserialize(&mut data.as_slice()).unwrap();
+        assert_eq!(payload.timestamp, NOW);
+        assert_eq!(payload.nonce, 1);
+        assert_eq!(payload.asset_category, "L1");
+        
+        submit_price(&program_id, &mut data, NOW + 10).unwrap();
+    }
+    
+    #[test]
+    fn test_unknown_asset_category_rejected() {
+        let program_id = Pubkey::new_unique();
+        let mut data = v1_account_data();
+        
+        for category in ["Defi", "", "NFT"] {
+            assert_eq!(
+                submit_price_in(&program_id, &mut data, NOW, category, false),
+                Err(ProgramError::Custom(PriceOracleError::InvalidPriceData as u32))
+            );
+        }
+        submit_price_in(&program_id, &mut data, NOW, "DeFi", false).unwrap();
+        
+        let payload = PricePayload::deserialize(&mut data.as_slice()).unwrap();
+        assert_eq!(payload.asset_category, "DeFi");
+    }
+    
+    #[test]
+    fn test_degraded_submission_accepted() {
+        let program_id = Pubkey::new_unique();
+        let mut data = v1_account_data();
+        
+        submit_price_in(&program_id, &mut data, NOW, "L1", true).unwrap();
+        
+        let payload = PricePayload::deserialize(&mut data.as_slice()).unwrap();
+        assert_eq!((payload.price, payload.timestamp), (45000.0, NOW));
+    }
+    
+    #[test]
+    fn test_sentiment_label_stored() {
+        let program_id = Pubkey::new_unique();
+        let mut data = v1_account_data();
+        let labeled = |timestamp: i64, label: u8| {
+            let mut instruction = submit_instruction(timestamp, "L1", false);
+            if let PriceOracleInstruction::SubmitPrice { sentiment_label, .. } = &mut instruction {
+                *sentiment_label = label;
+            }
+            instruction
+        };
+        
+        assert_eq!(
+            process_submit(&program_id, &mut data, &labeled(NOW, 6), None),
+            Err(ProgramError::Custom(PriceOracleError::InvalidPriceData as u32))
+        );
+        process_submit(&program_id, &mut data, &labeled(NOW, SentimentLabel::VeryNegative as u8), None).unwrap();
+        let payload = PricePayload::deserialize(&mut data.as_slice()).unwrap();
+        assert_eq!(SentimentLabel::from_u8(payload.sentiment_label), Some(SentimentLabel::VeryNegative));
+        
+        // A later price submission clears the label
+        process_submit(&program_id, &mut data, &labeled(NOW + 1, NO_SENTIMENT_LABEL), None).unwrap();
+        let payload = PricePayload::deserialize(&mut data.as_slice()).unwrap();
+        assert_eq!(payload.sentiment_label, NO_SENTIMENT_LABEL);
+    }
+    
+    #[test]
+    fn test_sentiment_label_strings() {
+        for label in SentimentLabel::ALL {
+            assert_eq!(label.to_string().parse::<SentimentLabel>(), Ok(label));
+            assert_eq!(SentimentLabel::from_u8(label as u8), Some(label));
+        }
+        assert_eq!("very positive".parse::<SentimentLabel>(), Ok(SentimentLabel::VeryPositive));
+        assert_eq!(" Negative ".parse::<SentimentLabel>(), Ok(SentimentLabel::Negative));
+        assert!("BULLISH".parse::<SentimentLabel>().unwrap_err().contains("VERY_NEGATIVE"));
+        assert_eq!(SentimentLabel::from_u8(NO_SENTIMENT_LABEL), None);
+    }
+    
+    #[test]
+    fn test_too_many_sources_rejected() {
+        let program_id = Pubkey::new_unique();
+        let mut data = v1_account_data();
+        let with_sources = |count: usize| {
+            let mut instruction = submit_instruction(NOW, "L1", false);
+            if let PriceOracleInstruction::SubmitPrice { sources, .. } = &mut instruction {
+                *sources = (0..count).map(|i| format!("Source{}", i)).collect();
+            }
+            instruction
+        };
+        
+        assert_eq!(
+            process_submit(&program_id, &mut data, &with_sources(MAX_SOURCES + 1), None),
+            Err(ProgramError::Custom(PriceOracleError::InvalidPriceData as u32))
+        );
+        let mut data = vec![0; get_account_size("BTC", &vec!["Source0".to_string(); MAX_SOURCES])];
+        data[..v1_account_data().len()].copy_from_slice(&v1_account_data());
+        process_submit(&program_id, &mut data, &with_sources(MAX_SOURCES), None).unwrap();
+    }
+    
+    fn assert_unauthorized(result: ProgramResult) {
+        assert_eq!(result, Err(ProgramError::Custom(PriceOracleError::UnauthorizedSigner as u32)));
+    }
+    
+    #[test]
+    fn test_only_authority_may_submit() {
+        let program_id = Pubkey::new_unique();
+        let mut data = v1_account_data();
+        let intruder = Pubkey::new_unique();
+        
+        assert_unauthorized(process_signed_by(&program_id, &mut data, &submit_instruction(NOW, "L1", false), &intruder, None));
+        submit_price(&program_id, &mut data, NOW).unwrap();
+        
+        // A fresh account belongs to the key that initialized it, not to its first submitter
+        let mut data = vec![0; get_account_size("BTC", &["CoinGecko".to_string()])];
+        initialize(&program_id, &mut data, &Pubkey::new_from_array(AUTHORITY)).unwrap();
+        assert_unauthorized(process_signed_by(&program_id, &mut data, &submit_instruction(NOW, "L1", false), &intruder, None));
+        let payload = PricePayload::deserialize(&mut data.as_slice()).unwrap();
+        assert_eq!(payload.signer, AUTHORITY);
+        submit_price(&program_id, &mut data, NOW).unwrap();
+    }
+    
+    /// Run `InitializeAccount` on the account's data, signed by `authority`
+    fn initialize(program_id: &Pubkey, data: &mut [u8], authority: &Pubkey) -> ProgramResult {
+        let instruction = initialize_account_instruction(program_id, &Pubkey::new_unique(), authority);
+        let (system_program, mut lamports, mut authority_lamports, mut authority_data) = (Pubkey::default(), 0, 0, Vec::new());
+        let accounts = [
+            AccountInfo::new(&instruction.accounts[0].pubkey, false, true, &mut lamports, data, program_id, false, 0),
+            AccountInfo::new(authority, true, false, &mut authority_lamports, &mut authority_data, &system_program, false, 0),
+        ];
+        process_instruction(program_id, &accounts, &instruction.data)
+    }
+    
+    #[test]
+    fn test_initialize_twice_rejected() {
+        let program_id = Pubkey::new_unique();
+        let authority = Pubkey::new_unique();
+        // Freshly allocated: the requested length, all zeros
+        let mut data = vec![0; get_account_size("BTC", &["CoinGecko".to_string()])];
+        
+        // The authority must sign
+        let mut instruction = initialize_account_instruction(&program_id, &Pubkey::new_unique(), &authority);
+        instruction.accounts.truncate(1);
+        let (account_key, mut lamports) = (instruction.accounts[0].pubkey, 0);
+        let account = AccountInfo::new(&account_key, false, true, &mut lamports, &mut data, &program_id, false, 0);
+        assert_eq!(process_instruction(&program_id, &[account], &instruction.data), Err(ProgramError::NotEnoughAccountKeys));
+        
+        initialize(&program_id, &mut data, &authority).unwrap();
+        assert_eq!(initialize(&program_id, &mut data, &authority), Err(PriceOracleError::AccountAlreadyInitialized.into()));
+        
+        let payload = PricePayload::deserialize(&mut data.as_slice()).unwrap();
+        assert!(payload.is_initialized);
+        assert_eq!((payload.version, payload.signer), (PAYLOAD_VERSION, authority.to_bytes()));
+    }
+    
+    /// Run an allowlist change signed by `admin` against the allowlist account's data, with
+    /// `admin` as the program's upgrade authority
+    fn update_allowlist(program_id: &Pubkey, allowlist: &mut [u8], admin: &Pubkey, oracle: &Pubkey, add: bool) -> ProgramResult {
+        update_allowlist_deployed_by(program_id, allowlist, admin, admin, oracle, add)
+    }
+    
+    /// Run an allowlist change signed by `admin` for a program whose upgrade authority is `deployer`
+    fn update_allowlist_deployed_by(
+        program_id: &Pubkey,
+        allowlist: &mut [u8],
+        admin: &Pubkey,
+        deployer: &Pubkey,
+        oracle: &Pubkey,
+        add: bool,
+    ) -> ProgramResult {
+        let instruction = set_oracle_authorized_instruction(program_id, admin, oracle, add);
+        let (mut allowlist_lamports, mut admin_lamports, mut system_lamports, mut program_data_lamports) = (1_000_000, 1_000_000, 1, 1);
+        let (mut admin_data, mut system_data) = (Vec::new(), Vec::new());
+        // `UpgradeableLoaderState::ProgramData` as bincode writes it: tag, slot, optional authority
+        let mut program_data = [3u32.to_le_bytes().as_slice(), &0u64.to_le_bytes(), &[1], &deployer.to_bytes()].concat();
+        let (system_program, loader) = (Pubkey::default(), bpf_loader_upgradeable::id());
+        let accounts = [
+            AccountInfo::new(&instruction.accounts[0].pubkey, false, true, &mut allowlist_lamports, allowlist, program_id, false, 0),
+            AccountInfo::new(admin, true, true, &mut admin_lamports, &mut admin_data, &system_program, false, 0),
+            AccountInfo::new(&instruction.accounts[2].pubkey, false, false, &mut system_lamports, &mut system_data, &system_program, true, 0),
+            AccountInfo::new(&instruction.accounts[3].pubkey, false, false, &mut program_data_lamports, &mut program_data, &loader, false, 0),
+        ];
+        process_instruction(program_id, &accounts, &instruction.data)
+    }
+    
+    #[test]
+    fn test_allowlist_restricts_submitters() {
+        let program_id = Pubkey::new_unique();
+        let mut data = v1_account_data();
+        let (admin, oracle) = (Pubkey::new_unique(), Pubkey::new_from_array(AUTHORITY));
+        let submit_with = |data: &mut Vec<u8>, allowlist: &mut Vec<u8>, timestamp: i64| {
+            process_with_allowlist(&program_id, data, &submit_instruction(timestamp, "L1", false), &oracle, allowlist, None)
+        };
+        
+        // Nobody may submit before the allowlist exists
+        assert_eq!(
+            submit_with(&mut data, &mut Vec::new(), NOW),
+            Err(ProgramError::Custom(PriceOracleError::UnauthorizedOracle as u32))
+        );
+        
+        // Allocated by the program on the first addition, which only the upgrade authority may
+        // sign; it becomes the admin
+        let mut allowlist = vec![0; OracleAllowlist::ACCOUNT_SIZE];
+        assert_unauthorized(update_allowlist_deployed_by(&program_id, &mut allowlist, &oracle, &admin, &oracle, true));
+        update_allowlist(&program_id, &mut allowlist, &admin, &Pubkey::new_unique(), true).unwrap();
+        assert_eq!(
+            submit_with(&mut data, &mut allowlist, NOW),
+            Err(ProgramError::Custom(PriceOracleError::UnauthorizedOracle as u32))
+        );
+        
+        assert_unauthorized(update_allowlist(&program_id, &mut allowlist, &oracle, &oracle, true));
+        update_allowlist(&program_id, &mut allowlist, &admin, &oracle, true).unwrap();
+        submit_with(&mut data, &mut allowlist, NOW).unwrap();
+        let list = OracleAllowlist::deserialize(&mut allowlist.as_slice()).unwrap();
+        assert_eq!((list.admin, list.oracles.len()), (admin.to_bytes(), 2));
+        
+        update_allowlist(&program_id, &mut allowlist, &admin, &oracle, false).unwrap();
+        assert_eq!(
+            submit_with(&mut data, &mut allowlist, NOW + 1),
+            Err(ProgramError::Custom(PriceOracleError::UnauthorizedOracle as u32))
+        );
+        
+        // Only the program's allowlist account is accepted
+        let mut accounts_data = (data.clone(), vec![0u8; 64]);
+        let instruction = borsh::to_vec(&submit_instruction(NOW + 1, "L1", false)).unwrap();
+        let (account_key, other_key, system_program) = (Pubkey::new_unique(), Pubkey::new_unique(), Pubkey::default());
+        let (sysvar_key, sysvar_owner) = (sysvar::instructions::id(), sysvar::id());
+        let (mut lamports, mut submitter_lamports, mut other_lamports, mut sysvar_lamports) = (1, 1, 1, 0);
+        let (mut submitter_data, mut sysvar_data) = (Vec::new(), Vec::new());
+        let accounts = [
+            AccountInfo::new(&account_key, false, true, &mut lamports, &mut accounts_data.0, &program_id, false, 0),
+            AccountInfo::new(&oracle, true, false, &mut submitter_lamports, &mut submitter_data, &system_program, false, 0),
+            AccountInfo::new(&other_key, false, false, &mut other_lamports, &mut accounts_data.1, &program_id, false, 0),
+            AccountInfo::new(&sysvar_key, false, false, &mut sysvar_lamports, &mut sysvar_data, &sysvar_owner, false, 0),
+        ];
+        assert_eq!(process_instruction(&program_id, &accounts, &instruction), Err(ProgramError::InvalidArgument));
+    }
+    
+    #[test]
+    fn test_allowlist_capacity() {
+        let program_id = Pubkey::new_unique();
+        let admin = Pubkey::new_unique();
+        let mut allowlist = vec![0; OracleAllowlist::ACCOUNT_SIZE];
+        
+        // Nothing to remove from before the first addition
+        assert_eq!(
+            update_allowlist(&program_id, &mut allowlist, &admin, &admin, false),
+            Err(ProgramError::Custom(PriceOracleError::UninitializedAccount as u32))
+        );
+        for _ in 0..MAX_AUTHORIZED_ORACLES {
+            update_allowlist(&program_id, &mut allowlist, &admin, &Pubkey::new_unique(), true).unwrap();
+        }
+        assert_eq!(
+            update_allowlist(&program_id, &mut allowlist, &admin, &Pubkey::new_unique(), true),
+            Err(ProgramError::Custom(PriceOracleError::AllowlistFull as u32))
+        );
+    }
+    
+    #[test]
+    fn test_update_authority() {
+        let program_id = Pubkey::new_unique();
+        let mut data = v1_account_data();
+        let (old, new) = (Pubkey::new_from_array(AUTHORITY), Pubkey::new_unique());
+        let rotate = |new_authority: &Pubkey| PriceOracleInstruction::UpdateAuthority { new_authority: new_authority.to_bytes() };
+        
+        let instruction = update_authority_instruction(&program_id, &Pubkey::new_unique(), &old, &new);
+        assert_eq!(instruction.data, borsh::to_vec(&rotate(&new)).unwrap());
+        assert!(instruction.accounts[1].is_signer && !instruction.accounts[1].is_writable);
+        
+        assert_unauthorized(process_signed_by(&program_id, &mut data, &rotate(&new), &new, None));
+        assert_eq!(
+            process_signed_by(&program_id, &mut data, &PriceOracleInstruction::UpdateAuthority { new_authority: NO_AUTHORITY }, &old, None),
+            Err(ProgramError::InvalidArgument)
+        );
+        process_signed_by(&program_id, &mut data, &rotate(&new), &old, None).unwrap();
+        
+        let payload = PricePayload::deserialize(&mut data.as_slice()).unwrap();
+        assert_eq!(payload.signer, new.to_bytes());
+        assert_eq!((payload.price, payload.version), (45000.0, PAYLOAD_VERSION));
+        
+        // The old key is locked out; the new one submits, signing as itself
+        assert_unauthorized(submit_price(&program_id, &mut data, NOW));
+        let mut submission = submit_instruction(NOW, "L1", false);
+        if let PriceOracleInstruction::SubmitPrice { signer, .. } = &mut submission {
+            *signer = new.to_bytes();
+        }
+        process_signed_by(&program_id, &mut data, &submission, &new, None).unwrap();
+        assert_unauthorized(process_signed_by(&program_id, &mut data, &rotate(&old), &old, None));
+    }
+    
+    #[test]
+    fn test_pause_and_resume() {
+        let program_id = Pubkey::new_unique();
+        let mut data = v1_account_data();
+        let authority = Pubkey::new_from_array(AUTHORITY);
+        
+        let instruction = set_paused_instruction(&program_id, &Pubkey::new_unique(), &authority, true);
+        assert_eq!(instruction.data, borsh::to_vec(&PriceOracleInstruction::Pause).unwrap());
+        assert!(instruction.accounts[1].is_signer && !instruction.accounts[1].is_writable);
+        
+        assert_unauthorized(process_signed_by(&program_id, &mut data, &PriceOracleInstruction::Pause, &Pubkey::new_unique(), None));
+        process_signed_by(&program_id, &mut data, &PriceOracleInstruction::Pause, &authority, None).unwrap();
+        assert_eq!(
+            submit_price(&program_id, &mut data, NOW),
+            Err(ProgramError::Custom(PriceOracleError::Paused as u32))
+        );
+        let payload = PricePayload::deserialize(&mut data.as_slice()).unwrap();
+        assert!(payload.paused);
+        assert_eq!((payload.price, payload.nonce), (45000.0, 0));
+        
+        process_signed_by(&program_id, &mut data, &PriceOracleInstruction::Resume, &authority, None).unwrap();
+        submit_price(&program_id, &mut data, NOW).unwrap();
+        let payload = PricePayload::deserialize(&mut data.as_slice()).unwrap();
+        assert!(!payload.paused);
+        assert_eq!(payload.nonce, 1);
+    }
+    
+    #[test]
+    fn test_tampered_submission_rejected() {
+        let program_id = Pubkey::new_unique();
+        let mut data = v1_account_data();
+        let submission = submit_instruction(NOW, "L1", false);
+        let PriceOracleInstruction::SubmitPrice { signature, signer, .. } = &submission else { unreachable!() };
+        
+        // Signed for CoinGecko, but a relayer claims Binance too, or changes the other signed fields
+        let message = signed_price_message("BTC", 45000.0, NOW, 0.9, 0.85, &["CoinGecko".to_string()], "L1", false, NO_SENTIMENT_LABEL);
+        let verification = new_ed25519_instruction(signer, signature, message.as_bytes());
+        let tamperings: [fn(&mut PriceOracleInstruction); 4] = [
+            |submission| if let PriceOracleInstruction::SubmitPrice { sources, .. } = submission { sources.push("Binance".to_string()) },
+            |submission| if let PriceOracleInstruction::SubmitPrice { asset_category, .. } = submission { *asset_category = "Meme".to_string() },
+            |submission| if let PriceOracleInstruction::SubmitPrice { degraded, .. } = submission { *degraded = true },
+            |submission| if let PriceOracleInstruction::SubmitPrice { sentiment_label, .. } = submission { *sentiment_label = SentimentLabel::Positive as u8 },
+        ];
+        for tamper in tamperings {
+            let mut tampered = submit_instruction(NOW, "L1", false);
+            tamper(&mut tampered);
+            let mut sysvar_data = instructions_sysvar(&program_id, &verification, &tampered);
+            assert_eq!(
+                process_submit(&program_id, &mut data, &tampered, Some(&mut sysvar_data)),
+                Err(ProgramError::Custom(PriceOracleError::InvalidSignature as u32))
+            );
+        }
+        
+        let mut sysvar_data = instructions_sysvar(&program_id, &verification, &submission);
+        process_submit(&program_id, &mut data, &submission, Some(&mut sysvar_data)).unwrap();
+        let payload = PricePayload::deserialize(&mut data.as_slice()).unwrap();
+        assert_eq!(payload.sources, vec!["CoinGecko".to_string()]);
+        
+        // Only an Ed25519 verification counts
+        let other = Instruction { program_id: Pubkey::new_unique(), ..verification };
+        let newer = submit_instruction(NOW + 1, "L1", false);
+        let mut sysvar_data = instructions_sysvar(&program_id, &other, &newer);
+        assert!(process_submit(&program_id, &mut data, &newer, Some(&mut sysvar_data)).is_err());
+    }
+    
+    #[test]
+    fn test_submission_signed_by_another_key_rejected() {
+        let program_id = Pubkey::new_unique();
+        let mut data = v1_account_data();
+        
+        // The authority submits, but the Ed25519 verification is by a throwaway key
+        let mut submission = submit_instruction(NOW, "L1", false);
+        if let PriceOracleInstruction::SubmitPrice { signer, .. } = &mut submission {
+            *signer = Pubkey::new_unique().to_bytes();
+        }
+        assert_eq!(
+            process_submit(&program_id, &mut data, &submission, None),
+            Err(ProgramError::Custom(PriceOracleError::InvalidSignature as u32))
+        );
+        let payload = PricePayload::deserialize(&mut data.as_slice()).unwrap();
+        assert_eq!(payload.timestamp, 1_700_000_000);
+    }
+    
+    #[test]
+    fn test_unverified_submission_rejected() {
+        use sysvar::instructions::{construct_instructions_data, BorrowedInstruction};
+        
+        let program_id = Pubkey::new_unique();
+        let mut data = v1_account_data();
+        let submission = submit_instruction(NOW, "L1", false);
+        
+        // No Ed25519 instruction before the submission
+        let encoded = borsh::to_vec(&submission).unwrap();
+        let mut sysvar_data = construct_instructions_data(&[BorrowedInstruction { program_id: &program_id, accounts: Vec::new(), data: &encoded }]);
+        assert_eq!(
+            process_submit(&program_id, &mut data, &submission, Some(&mut sysvar_data)),
+            Err(ProgramError::Custom(PriceOracleError::InvalidSignature as u32))
+        );
+        
+        // No instructions sysvar at all
+        let mut allowlist = allowlist_data(&[Pubkey::new_from_array(AUTHORITY)]);
+        let (account_key, submitter_key, allowlist_key) = (Pubkey::new_unique(), Pubkey::new_from_array(AUTHORITY), allowlist_address(&program_id).0);
+        let (system_program, mut lamports, mut submitter_lamports, mut allowlist_lamports, mut submitter_data) = (Pubkey::default(), 1, 1, 1, Vec::new());
+        let accounts = [
+            AccountInfo::new(&account_key, false, true, &mut lamports, &mut data, &program_id, false, 0),
+            AccountInfo::new(&submitter_key, true, false, &mut submitter_lamports, &mut submitter_data, &system_program, false, 0),
+            AccountInfo::new(&allowlist_key, false, false, &mut allowlist_lamports, &mut allowlist, &program_id, false, 0),
+        ];
+        assert_eq!(process_instruction(&program_id, &accounts, &encoded), Err(ProgramError::NotEnoughAccountKeys));
+    }
+    
+    #[test]
+    fn test_submission_outside_clock_skew_rejected() {
+        let program_id = Pubkey::new_unique();
+        let mut data = v1_account_data();
+        
+        assert_stale(submit_price(&program_id, &mut data, NOW + MAX_CLOCK_SKEW_SECS + 1));
+        assert!(check_submission_timestamp(NOW - MAX_CLOCK_SKEW_SECS, 0, NOW, MAX_CLOCK_SKEW_SECS).is_ok());
+        assert!(check_submission_timestamp(NOW - MAX_CLOCK_SKEW_SECS - 1, 0, NOW, MAX_CLOCK_SKEW_SECS).is_err());
+    }
+    
+    fn v1_account_data() -> Vec<u8> {
+        let v1 = PricePayloadV1 {
+            is_initialized: true,
+            version: 1,
+            asset: "BTC".to_string(),
+            price: 45000.0,
+            confidence: 0.9,
+            timestamp: 1_700_000_000,
+            sources: vec!["CoinGecko".to_string(), "Binance".to_string()],
+            consensus_score: 0.85,
+            signature: vec![7; 64],
+            signer: AUTHORITY,
+        };
+        
+        let mut data = borsh::to_vec(&v1).unwrap();
+        data.resize(data.len() + 256, 0);
+        data
+    }
+    
+    #[test]
+    fn test_migrate_v1_to_current() {
+        let mut data = v1_account_data();
+        
+        migrate_account(&mut data).unwrap();
+        
+        let payload = PricePayload::deserialize(&mut data.as_slice()).unwrap();
+        assert_eq!(payload.version, PAYLOAD_VERSION);
+        assert_eq!(payload.asset, "BTC");
+        assert_eq!(payload.price, 45000.0);
+        assert_eq!(payload.sources, vec!["CoinGecko".to_string(), "Binance".to_string()]);
+        assert_eq!(payload.signer, [3; 32]);
+        assert_eq!(payload.nonce, 0);
+        assert_eq!(payload.asset_category, DEFAULT_ASSET_CATEGORY);
+        assert!(!payload.paused);
+        assert_eq!(payload.sentiment_label, NO_SENTIMENT_LABEL);
+    }
+    
+    #[test]
+    fn test_migrate_v3_ignores_stale_trailing_bytes() {
+        let mut data = v1_account_data();
+        migrate_account(&mut data).unwrap();
+        // A v3 account whose earlier, longer payload left bytes past the category
+        data[1] = 3;
+        let end = layout_end(&data, 3).unwrap();
+        data[end] = 0xff;
+        
+        migrate_account(&mut data).unwrap();
+        let payload = PricePayload::deserialize(&mut data.as_slice()).unwrap();
+        assert_eq!(payload.version, PAYLOAD_VERSION);
+        assert!(!payload.paused);
+    }
+    
+    #[test]
+    fn test_migrate_is_idempotent() {
+        let mut data = v1_account_data();
+        
+        migrate_account(&mut data).unwrap();
+        let migrated = data.clone();
+        migrate_account(&mut data).unwrap();
+        
+        assert_eq!(data, migrated);
+    }
+    
+    #[test]
+    fn test_migrate_skips_uninitialized_account() {
+        let mut data = vec![0u8; 128];
+        
+        migrate_account(&mut data).unwrap();
+        
+        assert!(data.iter().all(|b| *b == 0));
+    }
+    
+    #[test]
+    fn test_migrate_rejects_unknown_version() {
+        let mut data = v1_account_data();
+        data[1] = 99;
+        
+        assert!(migrate_account(&mut data).is_err());
+    }
+    
+    #[test]
+    fn test_migrate_fails_without_room() {
+        let mut data = v1_account_data();
+        let end = layout_end(&data, 1).unwrap();
+        data.truncate(end);
+        
+        assert!(migrate_account(&mut data).is_err());
+    }
+    
+    #[test]
+    fn test_signed_price_message_is_fixed_precision() {
+        let sources = ["OKX".to_string(), "Binance".to_string()];
+        assert_eq!(
+            signed_price_message("SOL", 98.45, 1_700_000_000, 0.9, 0.85, &sources, "L1", false, NO_SENTIMENT_LABEL),
+            format!("SOL|98.45000000|1700000000|0.90000000|0.85000000|Binance,OKX|L1|false|{}", NO_SENTIMENT_LABEL)
+        );
+        // Values that differ only past the 8th decimal sign the same bytes
+        assert_eq!(
+            signed_price_message("SOL", 98.450000000001, 1_700_000_000, 0.9, 0.85, &sources, "L1", false, NO_SENTIMENT_LABEL),
+            signed_price_message("SOL", 98.45, 1_700_000_000, 0.9, 0.85, &sources, "L1", false, NO_SENTIMENT_LABEL)
+        );
+        assert_eq!(
+            signed_price_message("BTC", 45000.0, 1, 1.0, 1.0, &[], "L1", true, SentimentLabel::Neutral as u8),
+            format!("BTC|45000.00000000|1|1.00000000|1.00000000||L1|true|{}", SentimentLabel::Neutral as u8)
+        );
+        assert!(!is_valid_source_name("Coin|Gecko") && !is_valid_source_name("A,B") && is_valid_source_name("CoinGecko"));
+    }
+}